@@ -0,0 +1,113 @@
+use std::fmt::Write;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use json_rs::{minify, parse, to_string, to_string_pretty, validate, PushParser};
+
+fn small() -> String {
+    r#"{"id": 1, "name": "widget", "tags": ["a", "b"], "price": 9.99, "active": true}"#.to_string()
+}
+
+/// A search-results page shaped like the classic `twitter.json` benchmark:
+/// many statuses, each with a nested user, entity arrays and non-ASCII text.
+fn medium() -> String {
+    let mut out = String::from(r#"{"statuses": ["#);
+    for i in 0..200 {
+        if i > 0 {
+            out.push(',');
+        }
+        write!(
+            out,
+            r#"{{"created_at": "Sun Aug 31 00:29:15 +0000 2014", "id": {id}, "id_str": "{id}",
+              "text": "@aym0566x \n\u540d\u524d:\u524d\u7530\u3042\u3086\u307f #{i} 😀",
+              "truncated": false, "in_reply_to_status_id": null,
+              "entities": {{"hashtags": [], "urls": [],
+                "user_mentions": [{{"screen_name": "aym0566x", "id": 866260188, "indices": [0, 9]}}]}},
+              "user": {{"id": {uid}, "name": "\u3044\u3063\u3061\u3083\u3093", "screen_name": "ychan{i}",
+                "followers_count": {followers}, "verified": false, "lang": "ja",
+                "profile_background_color": "C0DEED", "geo_enabled": true}},
+              "retweet_count": {i}, "favorite_count": 0, "favorited": false, "lang": "ja",
+              "coordinates": {{"type": "Point", "coordinates": [-75.14310264, 40.05701649]}}}}"#,
+            id = 505874924095815681u64 + i,
+            uid = 1186275104 + i,
+            followers = i * 37 % 1000,
+        )
+        .unwrap();
+    }
+    out.push_str(r#"], "search_metadata": {"count": 200, "max_id": 505874924095815681}}"#);
+    out
+}
+
+fn deep_nesting() -> String {
+    "[".repeat(10_000) + &"]".repeat(10_000)
+}
+
+fn escaped_string() -> String {
+    format!(
+        "\"{}\"",
+        r#"line\n\t\"quoted\" \u00e9\ud83d\ude00 \\ "#.repeat(4_000)
+    )
+}
+
+fn numbers() -> String {
+    let items: Vec<String> = (0..50_000)
+        .map(|i| format!("{}", (i as f64) * 1.000_001 - 25_000.5))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+    vec![
+        ("small", small()),
+        ("medium", medium()),
+        ("deep_nesting", deep_nesting()),
+        ("escaped_string", escaped_string()),
+        ("numbers", numbers()),
+    ]
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, input) in inputs() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("parse", name), &input, |b, input| {
+            b.iter(|| parse(black_box(input)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("validate", name), &input, |b, input| {
+            b.iter(|| validate(black_box(input)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("minify", name), &input, |b, input| {
+            b.iter(|| minify(black_box(input)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("push_4k", name), &input, |b, input| {
+            b.iter(|| {
+                let mut parser = PushParser::new();
+                for chunk in black_box(input).as_bytes().chunks(4096) {
+                    parser.feed(chunk).unwrap();
+                }
+                parser.finish().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialize");
+    for (name, input) in inputs() {
+        let value = parse(&input).unwrap();
+        group.throughput(Throughput::Bytes(to_string(&value).len() as u64));
+        group.bench_with_input(BenchmarkId::new("to_string", name), &value, |b, value| {
+            b.iter(|| to_string(black_box(value)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("to_string_pretty", name),
+            &value,
+            |b, value| b.iter(|| to_string_pretty(black_box(value))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_serialize);
+criterion_main!(benches);
@@ -0,0 +1,377 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Fields, Generics, Ident,
+    LitStr, Path,
+};
+
+/// Derives `json_rs::ToJson`.
+///
+/// Structs with named fields become objects, tuple structs arrays, newtype
+/// structs their one field and unit structs `null`. Unit enum variants
+/// become their name as a string, and other variants an object with a
+/// single member named after the variant. `#[json(rename = "...")]` renames
+/// a field or variant, and fields marked `#[json(skip)]` are left out.
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_json(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `json_rs::FromJson`, reading the shapes [`ToJson`](macro@ToJson)
+/// writes.
+///
+/// A missing field is decoded from `null`, so `Option` fields may be left
+/// out. `#[json(default)]` fills a missing field with `Default::default()`
+/// and `#[json(default = "path")]` with the result of calling `path`;
+/// `#[json(skip)]` fields are never read and always filled that way.
+/// Unknown members are ignored.
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_json(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The `#[json(...)]` settings of a field or variant.
+#[derive(Default)]
+struct Attrs {
+    rename: Option<String>,
+    /// `Some(None)` for a plain `default`.
+    default: Option<Option<Path>>,
+    skip: bool,
+}
+
+impl Attrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Attrs> {
+        let mut out = Attrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    out.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("default") {
+                    out.default = Some(match meta.value() {
+                        Ok(value) => Some(value.parse::<LitStr>()?.parse()?),
+                        Err(_) => None,
+                    });
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `default` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+
+    fn name(&self, ident: &Ident) -> String {
+        self.rename.clone().unwrap_or_else(|| ident.to_string())
+    }
+
+    fn default_value(&self) -> Option<TokenStream2> {
+        match &self.default {
+            Some(Some(path)) => Some(quote!(#path())),
+            Some(None) => Some(quote!(::core::default::Default::default())),
+            None if self.skip => Some(quote!(::core::default::Default::default())),
+            None => None,
+        }
+    }
+}
+
+/// A field with its settings, its name (`_0` for a tuple field) and the
+/// variable a pattern binds it to.
+struct Field {
+    attrs: Attrs,
+    ident: Ident,
+    binding: Ident,
+}
+
+fn bounded(generics: &Generics, bound: Path) -> Generics {
+    let mut generics = generics.clone();
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let clause = generics.make_where_clause();
+    for param in params {
+        clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
+
+fn object(members: Vec<TokenStream2>) -> TokenStream2 {
+    quote! {
+        ::json_rs::Type::Object(::core::convert::From::from(
+            <::json_rs::Map<::json_rs::Key, ::json_rs::Type> as ::core::iter::FromIterator<_>>::from_iter(
+                [#(#members),*]
+            )
+        ))
+    }
+}
+
+fn member(name: &str, value: TokenStream2) -> TokenStream2 {
+    quote!((::core::convert::Into::into(#name), #value))
+}
+
+/// A pattern binding every one of `fields` to a `__field{i}` variable,
+/// and the fields themselves.
+fn bindings(fields: &Fields) -> syn::Result<(TokenStream2, Vec<Field>)> {
+    let mut bound = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = Attrs::parse(&field.attrs)?;
+        let ident = field
+            .ident
+            .clone()
+            .unwrap_or_else(|| format_ident!("_{}", i));
+        bound.push(Field {
+            attrs,
+            ident,
+            binding: format_ident!("__field{}", i),
+        });
+    }
+    let pattern = match fields {
+        Fields::Named(_) => {
+            let names = bound
+                .iter()
+                .map(|Field { ident, binding, .. }| quote!(#ident: #binding));
+            quote!({ #(#names),* })
+        }
+        Fields::Unnamed(_) => {
+            let names = bound.iter().map(|Field { binding, .. }| binding);
+            quote!(( #(#names),* ))
+        }
+        Fields::Unit => quote!(),
+    };
+    Ok((pattern, bound))
+}
+
+/// Encodes fields already bound to the `bindings` names.
+fn encode(fields: &Fields, bound: &[Field]) -> TokenStream2 {
+    let written: Vec<_> = bound
+        .iter()
+        .filter(|Field { attrs, .. }| !attrs.skip)
+        .collect();
+    match fields {
+        Fields::Named(_) => object(
+            written
+                .iter()
+                .map(
+                    |Field {
+                         attrs,
+                         ident,
+                         binding,
+                     }| {
+                        member(
+                            &attrs.name(ident),
+                            quote!(::json_rs::ToJson::to_json(#binding)),
+                        )
+                    },
+                )
+                .collect(),
+        ),
+        Fields::Unnamed(_) if bound.len() == 1 && written.len() == 1 => {
+            let binding = &written[0].binding;
+            quote!(::json_rs::ToJson::to_json(#binding))
+        }
+        Fields::Unnamed(_) => {
+            let items = written.iter().map(|Field { binding, .. }| binding);
+            quote! {
+                ::json_rs::Type::Array(::core::iter::FromIterator::from_iter([
+                    #(::json_rs::ToJson::to_json(#items)),*
+                ]))
+            }
+        }
+        Fields::Unit => quote!(::json_rs::Type::Null),
+    }
+}
+
+/// A `Result<Self, DecodeError>` expression building `constructor` from
+/// the value `value`.
+fn decode(fields: &Fields, constructor: TokenStream2, value: &Ident) -> syn::Result<TokenStream2> {
+    let (_, bound) = bindings(fields)?;
+    let err = |message: &str| quote!(::json_rs::DecodeError::new(#message));
+    match fields {
+        Fields::Named(_) => {
+            let expected = quote!(::json_rs::DecodeError::expected("an object", #value));
+            let inits = bound.iter().map(|Field { attrs, ident, .. }| {
+                let name = attrs.name(ident);
+                let missing = attrs.default_value().unwrap_or_else(|| {
+                    let missing = err(&format!("missing field `{}`", name));
+                    quote! {
+                        ::json_rs::FromJson::from_json(&::json_rs::Type::Null)
+                            .map_err(|_| #missing)?
+                    }
+                });
+                if attrs.skip {
+                    return quote!(#ident: #missing);
+                }
+                quote! {
+                    #ident: match __members.get(#name) {
+                        ::core::option::Option::Some(__value) => {
+                            ::json_rs::FromJson::from_json(__value).map_err(|err| err.within(#name))?
+                        }
+                        ::core::option::Option::None => #missing,
+                    }
+                }
+            });
+            Ok(quote! {{
+                let ::json_rs::Type::Object(__members) = #value else {
+                    return ::core::result::Result::Err(#expected);
+                };
+                ::core::result::Result::Ok(#constructor { #(#inits),* })
+            }})
+        }
+        Fields::Unnamed(_) if bound.len() == 1 && !bound[0].attrs.skip => Ok(quote! {
+            ::core::result::Result::Ok(#constructor(::json_rs::FromJson::from_json(#value)?))
+        }),
+        Fields::Unnamed(_) => {
+            let len = bound
+                .iter()
+                .filter(|Field { attrs, .. }| !attrs.skip)
+                .count();
+            let what = format!("an array of {} elements", len);
+            let expected = quote!(::json_rs::DecodeError::expected(#what, #value));
+            let wrong_len = err(&format!("expected an array of {} elements", len));
+            let mut index = 0usize;
+            let items = bound.iter().map(|Field { attrs, .. }| match attrs.default_value() {
+                Some(default) if attrs.skip => default,
+                _ => {
+                    let i = index;
+                    index += 1;
+                    let token = i.to_string();
+                    quote! {
+                        ::json_rs::FromJson::from_json(&__items[#i]).map_err(|err| err.within(#token))?
+                    }
+                }
+            });
+            let items: Vec<_> = items.collect();
+            Ok(quote! {{
+                let ::json_rs::Type::Array(__items) = #value else {
+                    return ::core::result::Result::Err(#expected);
+                };
+                if __items.len() != #len {
+                    return ::core::result::Result::Err(#wrong_len);
+                }
+                ::core::result::Result::Ok(#constructor(#(#items),*))
+            }})
+        }
+        Fields::Unit => {
+            let expected = err("expected null");
+            Ok(quote! {
+                match #value {
+                    ::json_rs::Type::Null => ::core::result::Result::Ok(#constructor),
+                    _ => ::core::result::Result::Err(#expected),
+                }
+            })
+        }
+    }
+}
+
+fn expand_to_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let generics = bounded(&input.generics, parse_quote!(::json_rs::ToJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, bound) = bindings(&data.fields)?;
+            let value = encode(&data.fields, &bound);
+            quote! {
+                let #ident #pattern = self;
+                #value
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let name = Attrs::parse(&variant.attrs)?.name(&variant.ident);
+                let variant_ident = &variant.ident;
+                let (pattern, bound) = bindings(&variant.fields)?;
+                let value = match variant.fields {
+                    Fields::Unit => {
+                        quote!(::json_rs::Type::String(::core::convert::Into::into(#name)))
+                    }
+                    ref fields => object(vec![member(&name, encode(fields, &bound))]),
+                };
+                arms.push(quote!(#ident::#variant_ident #pattern => #value));
+            }
+            quote! {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+        Data::Union(_) => return Err(Error::new(Span::call_site(), "unions cannot derive ToJson")),
+    };
+    Ok(quote! {
+        impl #impl_generics ::json_rs::ToJson for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn to_json(&self) -> ::json_rs::Type {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_from_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let generics = bounded(&input.generics, parse_quote!(::json_rs::FromJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let value = Ident::new("__value", Span::call_site());
+    let body = match &input.data {
+        Data::Struct(data) => decode(&data.fields, quote!(#ident), &value)?,
+        Data::Enum(data) => {
+            let mut units = Vec::new();
+            let mut members = Vec::new();
+            for variant in &data.variants {
+                let name = Attrs::parse(&variant.attrs)?.name(&variant.ident);
+                let variant_ident = &variant.ident;
+                if let Fields::Unit = variant.fields {
+                    units.push(quote!(#name => ::core::result::Result::Ok(#ident::#variant_ident)));
+                }
+                let decoded = decode(&variant.fields, quote!(#ident::#variant_ident), &value)?;
+                members.push(quote! {
+                    #name => (|| -> ::core::result::Result<Self, ::json_rs::DecodeError> {
+                        #decoded
+                    })()
+                    .map_err(|err| err.within(#name))
+                });
+            }
+            quote! {
+                match #value {
+                    ::json_rs::Type::String(__name) => match __name.as_str() {
+                        #(#units,)*
+                        _ => ::core::result::Result::Err(::json_rs::DecodeError::new("unknown variant")),
+                    },
+                    ::json_rs::Type::Object(__members) if __members.len() == 1 => {
+                        let (__name, #value) = __members.iter().next().unwrap();
+                        match &**__name {
+                            #(#members,)*
+                            _ => ::core::result::Result::Err(::json_rs::DecodeError::new("unknown variant")),
+                        }
+                    }
+                    _ => ::core::result::Result::Err(::json_rs::DecodeError::new(
+                        "expected a variant name or an object with a single member",
+                    )),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "unions cannot derive FromJson",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::json_rs::FromJson for #ident #ty_generics #where_clause {
+            fn from_json(#value: &::json_rs::Type) -> ::core::result::Result<Self, ::json_rs::DecodeError> {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,26 @@
+#![no_main]
+
+use json_rs::{minify, parse, to_string, validate, PushParser};
+use libfuzzer_sys::fuzz_target;
+
+// Arbitrary input must never panic, and every entry point must agree on
+// whether it is valid.
+fuzz_target!(|data: &[u8]| {
+    let mut push = PushParser::new();
+    let (mid_a, mid_b) = data.split_at(data.len() / 2);
+    let pushed = push
+        .feed(mid_a)
+        .and_then(|_| push.feed(mid_b))
+        .and_then(|_| push.finish());
+
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let parsed = parse(text);
+    assert_eq!(validate(text).is_ok(), parsed.is_ok());
+    assert_eq!(minify(text).is_ok(), parsed.is_ok());
+    assert_eq!(pushed.as_ref().ok(), parsed.as_ref().ok());
+    if let Ok(value) = parsed {
+        assert_eq!(parse(&to_string(&value)).unwrap(), value);
+    }
+});
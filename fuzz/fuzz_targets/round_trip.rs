@@ -0,0 +1,10 @@
+#![no_main]
+
+use json_rs::{parse, to_string, to_string_pretty, Type};
+use libfuzzer_sys::fuzz_target;
+
+// Whatever the serializer writes, the parser reads back as the same value.
+fuzz_target!(|value: Type| {
+    assert_eq!(parse(&to_string(&value)).unwrap(), value);
+    assert_eq!(parse(&to_string_pretty(&value)).unwrap(), value);
+});
@@ -0,0 +1,59 @@
+use crate::Type;
+
+impl Type {
+    /// Compares values structurally like `==`, except that two numbers are
+    /// equal when they differ by at most `epsilon`, absolutely or relative
+    /// to the larger of the two. Meant for tests that compare computed
+    /// floating-point results.
+    ///
+    /// Equal infinities are equal; `NaN` is equal to nothing.
+    pub fn approx_eq(&self, other: &Type, epsilon: f64) -> bool {
+        match (self, other) {
+            (Type::Number(a), Type::Number(b)) => {
+                if a == b {
+                    return true;
+                }
+                let (a, b) = (a.to_f64(), b.to_f64());
+                let diff = (a - b).abs();
+                diff <= epsilon || diff <= epsilon * a.abs().max(b.abs())
+            }
+            (Type::Array(a), Type::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.approx_eq(b, epsilon))
+            }
+            (Type::Object(a), Type::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, a)| {
+                        b.get(&**key as &str)
+                            .is_some_and(|b| a.approx_eq(b, epsilon))
+                    })
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Type};
+
+    #[test]
+    fn tolerates_rounding() {
+        let a = parse(r#"{"x": [0.30000000000000004, 1e20], "y": "s"}"#).unwrap();
+        let b = parse(r#"{"y": "s", "x": [0.3, 1.0000000001e20]}"#).unwrap();
+        assert!(a != b && a.approx_eq(&b, 1e-9));
+        assert!(!a.approx_eq(&b, 1e-12));
+        assert!(Type::Number(0.into()).approx_eq(&Type::Number(1e-10.into()), 1e-9));
+        assert!(!Type::Number(1.into()).approx_eq(&Type::Number(1.1.into()), 0.01));
+        assert!(
+            Type::Number(f64::INFINITY.into()).approx_eq(&Type::Number(f64::INFINITY.into()), 0.1)
+        );
+        assert!(!Type::Number(f64::NAN.into()).approx_eq(&Type::Number(f64::NAN.into()), 0.1));
+        assert!(!parse("[1, 2]")
+            .unwrap()
+            .approx_eq(&parse("[1]").unwrap(), 1.0));
+        assert!(!parse(r#"{"a": 1}"#)
+            .unwrap()
+            .approx_eq(&parse(r#"{"b": 1}"#).unwrap(), 1.0));
+        assert!(!Type::Number(1.into()).approx_eq(&Type::String("1".into()), 1.0));
+    }
+}
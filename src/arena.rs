@@ -0,0 +1,183 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+
+use crate::lexer::{decode_number, decode_string, Lexer, TokenKind};
+use crate::parser::Grammar;
+use crate::{JsonError, Map, Number, Type};
+
+/// A JSON value whose nodes live in a [`Bump`] arena.
+///
+/// Strings without escapes borrow straight from the input; everything else,
+/// including the arrays and member lists, is allocated in the arena and
+/// freed all at once when it is reset or dropped. Object members keep their
+/// document order, duplicates included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorrowedType<'a> {
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(&'a str),
+    Array(&'a [BorrowedType<'a>]),
+    Object(&'a [(&'a str, BorrowedType<'a>)]),
+}
+
+impl<'a> BorrowedType<'a> {
+    /// Looks up a member of an object. As when building a [`Type`], the last
+    /// of several equal keys wins.
+    pub fn get(&self, key: &str) -> Option<&'a BorrowedType<'a>> {
+        match self {
+            BorrowedType::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Copies the value out of the arena.
+    pub fn to_type(&self) -> Type {
+        match *self {
+            BorrowedType::Null => Type::Null,
+            BorrowedType::Boolean(b) => Type::Boolean(b),
+            BorrowedType::Number(n) => Type::Number(n),
+            BorrowedType::String(s) => Type::String(s.into()),
+            BorrowedType::Array(items) => Type::Array(items.iter().map(Self::to_type).collect()),
+            BorrowedType::Object(members) => {
+                let mut map = Map::default();
+                for (key, value) in members {
+                    map.insert((*key).into(), value.to_type());
+                }
+                Type::Object(Box::new(map))
+            }
+        }
+    }
+}
+
+enum Frame<'a> {
+    Array(BumpVec<'a, BorrowedType<'a>>),
+    Object(BumpVec<'a, (&'a str, BorrowedType<'a>)>, Option<&'a str>),
+}
+
+/// Parses `json` into a tree allocated in `arena`, for workloads that parse,
+/// read and discard many documents: one arena reset frees a whole tree
+/// without touching the global allocator per node. A leading UTF-8
+/// byte-order mark is skipped.
+pub fn parse_in<'a>(arena: &'a Bump, json: &'a str) -> Result<BorrowedType<'a>, JsonError> {
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    let mut grammar = Grammar::new();
+    let mut stack: Vec<Frame<'a>> = Vec::new();
+    let mut root = None;
+    while let Some(token) = lexer.next_token()? {
+        let is_key = grammar.expects_key();
+        grammar.accept(token)?;
+        let value = match token.kind {
+            TokenKind::BeginArray => {
+                stack.push(Frame::Array(BumpVec::new_in(arena)));
+                continue;
+            }
+            TokenKind::BeginObject => {
+                stack.push(Frame::Object(BumpVec::new_in(arena), None));
+                continue;
+            }
+            TokenKind::Colon | TokenKind::Comma => continue,
+            TokenKind::EndArray | TokenKind::EndObject => match stack.pop() {
+                Some(Frame::Array(items)) => BorrowedType::Array(items.into_bump_slice()),
+                Some(Frame::Object(members, _)) => BorrowedType::Object(members.into_bump_slice()),
+                None => unreachable!("the grammar balances containers"),
+            },
+            TokenKind::String => {
+                let raw = &json[token.start + 1..token.end - 1];
+                let s = if raw.contains('\\') {
+                    arena.alloc_str(&decode_string(raw.as_bytes(), token.start + 1)?)
+                } else {
+                    raw
+                };
+                if is_key {
+                    if let Some(Frame::Object(_, pending)) = stack.last_mut() {
+                        *pending = Some(s);
+                    }
+                    continue;
+                }
+                BorrowedType::String(s)
+            }
+            TokenKind::Number => BorrowedType::Number(decode_number(
+                &json.as_bytes()[token.start..token.end],
+                token.start,
+            )?),
+            TokenKind::True => BorrowedType::Boolean(true),
+            TokenKind::False => BorrowedType::Boolean(false),
+            TokenKind::Null => BorrowedType::Null,
+        };
+        match stack.last_mut() {
+            None => root = Some(value),
+            Some(Frame::Array(items)) => items.push(value),
+            Some(Frame::Object(members, pending)) => {
+                if let Some(key) = pending.take() {
+                    members.push((key, value));
+                }
+            }
+        }
+    }
+    root.ok_or(JsonError::UnexpectEof { offset: json.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_in, BorrowedType};
+    use crate::{parse, Expected, JsonError};
+    use bumpalo::Bump;
+
+    #[test]
+    fn borrows_from_the_input() {
+        let json = r#"{"plain": "text", "escaped": "a\nb", "list": [1, true, null], "plain": 2}"#;
+        let arena = Bump::new();
+        let value = parse_in(&arena, json).unwrap();
+
+        let BorrowedType::Object(members) = value else {
+            panic!("expected an object");
+        };
+        assert_eq!(members.len(), 4);
+        let BorrowedType::String(text) = members[0].1 else {
+            panic!("expected a string");
+        };
+        // Unescaped strings point into `json` itself.
+        assert!(json.as_bytes().as_ptr_range().contains(&text.as_ptr()));
+        assert_eq!(value.get("escaped"), Some(&BorrowedType::String("a\nb")));
+        assert_eq!(value.get("plain"), Some(&BorrowedType::Number(2.into())));
+        assert_eq!(value.to_type(), parse(json).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        let arena = Bump::new();
+        assert_eq!(
+            parse_in(&arena, "[1,]").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::VALUE
+            }
+        );
+        assert_eq!(
+            parse_in(&arena, "{\"a\": [").unwrap_err(),
+            JsonError::UnexpectEof { offset: 7 }
+        );
+        assert_eq!(
+            parse_in(&arena, "\"\\ud800\"").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn skips_bom() {
+        let arena = Bump::new();
+        let value = parse_in(&arena, "\u{feff}[\"a\"]").unwrap();
+        assert_eq!(value.to_type(), parse(r#"["a"]"#).unwrap());
+    }
+}
@@ -0,0 +1,241 @@
+use std::io::{self, Read};
+use std::str::FromStr;
+
+use crate::lexer::{Lexer, TokenKind, BOM};
+use crate::parser::Grammar;
+use crate::{parse_slice, Expected, JsonError, RawValue, Type};
+
+/// Reads a top-level JSON array from a reader and yields its elements one at
+/// a time, so an array far larger than memory can be processed element by
+/// element. Only the element being read is held in memory.
+///
+/// The iterator parses each element into a [`Type`];
+/// [`next_raw`](ArrayReader::next_raw) yields the unparsed text instead.
+/// Error offsets count bytes of the reader, and iteration stops after the
+/// first error. Anything but whitespace after the closing bracket is an
+/// error.
+pub struct ArrayReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Offset of `buf[0]` in the whole input.
+    base: usize,
+    /// How much of `buf` has been lexed.
+    pos: usize,
+    /// Where the element being read starts in `buf`.
+    start: usize,
+    grammar: Grammar,
+    /// Nesting depth, counting the array itself.
+    depth: usize,
+    eof: bool,
+    failed: bool,
+}
+
+impl<R: Read> ArrayReader<R> {
+    pub fn new(reader: R) -> Self {
+        ArrayReader {
+            reader,
+            buf: Vec::new(),
+            base: 0,
+            pos: 0,
+            start: 0,
+            grammar: Grammar::new(),
+            depth: 0,
+            eof: false,
+            failed: false,
+        }
+    }
+
+    /// Like [`next`](Iterator::next), but returns the element's text as it
+    /// was written, checked but not parsed.
+    pub fn next_raw(&mut self) -> Option<Result<RawValue, JsonError>> {
+        Some(self.scan()?.and_then(|(start, end)| {
+            let offset = self.base + start;
+            match core::str::from_utf8(&self.buf[start..end]) {
+                Ok(text) => RawValue::from_str(text).map_err(|err| err.shifted(offset)),
+                Err(err) => Err(JsonError::UnexpectToken {
+                    offset: offset + err.valid_up_to(),
+                    expected: Expected::NONE,
+                }),
+            }
+        }))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads on to the end of the next element and returns its range in
+    /// `buf`, or `None` once the array and the input have ended.
+    fn scan(&mut self) -> Option<Result<(usize, usize), JsonError>> {
+        if self.failed {
+            return None;
+        }
+        let result = loop {
+            if self.depth <= 1 {
+                // No element is in progress, so what was lexed is done with.
+                self.buf.drain(..self.pos);
+                self.base += self.pos;
+                self.pos = 0;
+            }
+            match self.lex() {
+                Ok(Some(range)) => break Ok(range),
+                Ok(None) if self.eof => {
+                    if self.grammar.is_done() {
+                        return None;
+                    }
+                    break Err(JsonError::UnexpectEof {
+                        offset: self.base + self.buf.len(),
+                    });
+                }
+                Ok(None) => {
+                    if let Err(err) = self.fill() {
+                        break Err(err.into());
+                    }
+                }
+                Err(err) => break Err(err),
+            }
+        };
+        self.failed = result.is_err();
+        Some(result)
+    }
+
+    /// Lexes the complete tokens in `buf` up to the end of the next element.
+    fn lex(&mut self) -> Result<Option<(usize, usize)>, JsonError> {
+        let at = self.pos;
+        let offset = self.base + at;
+        let input = &self.buf[at..];
+        // Wait for a whole byte-order mark before deciding to skip one.
+        if !self.eof && offset == 0 && input.len() < BOM.len() && BOM.starts_with(input) {
+            return Ok(None);
+        }
+        let lexer = if self.eof {
+            Lexer::new(input)
+        } else {
+            Lexer::partial(input)
+        };
+        let mut lexer = if offset == 0 { lexer.skip_bom() } else { lexer };
+        while let Some(token) = lexer.next_token().map_err(|err| err.shifted(offset))? {
+            if self.depth == 0 && !self.grammar.is_done() && token.kind != TokenKind::BeginArray {
+                return Err(JsonError::UnexpectToken {
+                    offset: offset + token.start,
+                    expected: Expected::of(&[TokenKind::BeginArray]),
+                });
+            }
+            self.grammar
+                .accept(token)
+                .map_err(|err| err.shifted(offset))?;
+            let (start, end) = (at + token.start, at + token.end);
+            let element = match token.kind {
+                TokenKind::BeginObject | TokenKind::BeginArray => {
+                    if self.depth == 1 {
+                        self.start = start;
+                    }
+                    self.depth += 1;
+                    None
+                }
+                TokenKind::EndObject | TokenKind::EndArray => {
+                    self.depth -= 1;
+                    (self.depth == 1).then_some((self.start, end))
+                }
+                TokenKind::Colon | TokenKind::Comma => None,
+                _ => (self.depth == 1).then_some((start, end)),
+            };
+            if element.is_some() {
+                self.pos = end;
+                return Ok(element);
+            }
+        }
+        self.pos = at + lexer.pos();
+        Ok(None)
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0; 8192];
+        loop {
+            match self.reader.read(&mut chunk) {
+                Ok(read) => {
+                    self.buf.extend_from_slice(&chunk[..read]);
+                    self.eof = read == 0;
+                    return Ok(());
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for ArrayReader<R> {
+    type Item = Result<Type, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.scan()?.and_then(|(start, end)| {
+            parse_slice(&self.buf[start..end]).map_err(|err| err.shifted(self.base + start))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::ArrayReader;
+    use crate::{parse, Expected, JsonError, TokenKind, Type};
+
+    /// Hands out its input a few bytes at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(3);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn yields_elements() {
+        let input = "\u{feff} [1, {\"a\": [2, \"]\"]}, \"x\", [], -12.5e1, null] \n";
+        let values: Vec<_> = ArrayReader::new(Trickle(input.as_bytes()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(Type::Array(values), parse(input).unwrap());
+
+        let mut reader = ArrayReader::new(input.as_bytes());
+        let mut raw = Vec::new();
+        while let Some(element) = reader.next_raw() {
+            raw.push(element.unwrap().into_string());
+        }
+        assert_eq!(
+            raw,
+            ["1", "{\"a\": [2, \"]\"]}", "\"x\"", "[]", "-12.5e1", "null"]
+        );
+        assert_eq!(ArrayReader::new(&b" [ ] "[..]).count(), 0);
+    }
+
+    #[test]
+    fn stops_at_errors() {
+        let results: Vec<_> = ArrayReader::new(Trickle(b"[1, 2,]")).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[2],
+            Err(JsonError::UnexpectToken {
+                offset: 6,
+                expected: Expected::VALUE
+            })
+        );
+        let results: Vec<_> = ArrayReader::new(Trickle(b"[1, [2")).collect();
+        assert_eq!(results[1], Err(JsonError::UnexpectEof { offset: 6 }));
+        assert_eq!(
+            ArrayReader::new(&b" {}"[..]).next(),
+            Some(Err(JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::of(&[TokenKind::BeginArray])
+            }))
+        );
+        let results: Vec<_> = ArrayReader::new(&b"[1] 2"[..]).collect();
+        assert_eq!(results[1].as_ref().unwrap_err().offset(), Some(4));
+        assert!(ArrayReader::new(&b""[..]).next().unwrap().is_err());
+    }
+}
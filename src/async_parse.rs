@@ -0,0 +1,62 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::push::PushParser;
+use crate::{JsonError, Type};
+
+/// Parses a single JSON value from an async reader, feeding the incremental
+/// parser as data arrives instead of buffering the whole payload first. A
+/// UTF-8 byte-order mark at the start is skipped.
+pub async fn parse_async<R: AsyncRead + Unpin>(mut reader: R) -> Result<Type, JsonError> {
+    let mut parser = PushParser::new();
+    let mut chunk = vec![0; 8 * 1024];
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return parser.finish();
+        }
+        parser.feed(&chunk[..n])?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+
+    use super::parse_async;
+    use crate::{parse, JsonError};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn parse_from_reader() {
+        let input = br#"{"items": [1, 2, 3], "next": null}"#;
+        assert_eq!(
+            block_on(parse_async(&input[..])).unwrap(),
+            parse(std::str::from_utf8(input).unwrap()).unwrap()
+        );
+        assert_eq!(
+            block_on(parse_async(&b"[1, 2"[..])).unwrap_err(),
+            JsonError::UnexpectEof { offset: 5 }
+        );
+    }
+
+    #[test]
+    fn skips_a_byte_order_mark() {
+        let expected = parse("[\"\u{feff}\"]").unwrap();
+        let input = "\u{feff}[\"\u{feff}\"]".as_bytes();
+        assert_eq!(block_on(parse_async(input)).unwrap(), expected);
+        let (head, tail) = input.split_at(1);
+        assert_eq!(block_on(parse_async(head.chain(tail))).unwrap(), expected);
+    }
+}
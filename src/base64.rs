@@ -0,0 +1,119 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{into_str, Type};
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn encode(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+        if pad {
+            for _ in chunk.len()..3 {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Encodes `bytes` in the URL-safe base64 alphabet without padding, the form
+/// RFC 8949 recommends for binary data converted to JSON.
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub(crate) fn base64url(bytes: &[u8]) -> String {
+    encode(bytes, URL_SAFE, false)
+}
+
+/// Decodes base64 in either the standard or the URL-safe alphabet, with or
+/// without padding; `None` if `text` is not base64.
+fn decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    let data = match text.len() % 4 {
+        0 if text.ends_with(b"==") => &text[..text.len() - 2],
+        0 if text.ends_with(b"=") => &text[..text.len() - 1],
+        _ => text,
+    };
+    if data.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    for chunk in data.chunks(4) {
+        let mut n = 0u32;
+        for (i, &byte) in chunk.iter().enumerate() {
+            let digit = match byte {
+                b'A'..=b'Z' => byte - b'A',
+                b'a'..=b'z' => byte - b'a' + 26,
+                b'0'..=b'9' => byte - b'0' + 52,
+                b'+' | b'-' => 62,
+                b'/' | b'_' => 63,
+                _ => return None,
+            };
+            n |= (digit as u32) << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+impl Type {
+    /// Decodes a string value holding base64, in the standard or the
+    /// URL-safe alphabet and with or without padding. `None` for other
+    /// values and for strings that are not base64.
+    pub fn as_base64_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Type::String(s) => decode(s),
+            _ => None,
+        }
+    }
+
+    /// A string holding `bytes` in standard, padded base64.
+    pub fn from_base64_bytes(bytes: &[u8]) -> Type {
+        Type::String(into_str(encode(bytes, STANDARD, true)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Type;
+
+    #[test]
+    fn round_trips_bytes() {
+        let bytes = [0xfb, 0xff, 0x00, b'a', b'b'];
+        let value = Type::from_base64_bytes(&bytes);
+        assert_eq!(value, Type::String("+/8AYWI=".into()));
+        assert_eq!(value.as_base64_bytes().unwrap(), bytes);
+        #[cfg(any(feature = "cbor", feature = "msgpack"))]
+        assert_eq!(super::base64url(&bytes), "-_8AYWI");
+        assert_eq!(
+            Type::String("-_8AYWI".into()).as_base64_bytes().unwrap(),
+            bytes
+        );
+        for len in 0..4 {
+            let bytes = &b"xyz"[..len];
+            assert_eq!(
+                Type::from_base64_bytes(bytes).as_base64_bytes().unwrap(),
+                bytes
+            );
+        }
+        for text in ["a", "ab=c", "ab!c", "abcde", "====="] {
+            assert_eq!(
+                Type::String(text.into()).as_base64_bytes(),
+                None,
+                "{}",
+                text
+            );
+        }
+        assert_eq!(Type::Null.as_base64_bytes(), None);
+    }
+}
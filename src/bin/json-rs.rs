@@ -0,0 +1,202 @@
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::ExitCode;
+
+use json_rs::{
+    diff, extract_pointer, highlight, minify, parse, prettify, render_diff, rust_types,
+    to_string_pretty, validate, Filter, HighlightKind, JsonError, LineIndex,
+};
+
+const USAGE: &str = "usage:
+  json-rs fmt [FILE]              pretty-print with two-space indentation
+  json-rs validate [FILE]         check syntax, reporting line and column
+  json-rs get POINTER [FILE]      print the value at a JSON Pointer
+  json-rs query FILTER [FILE]     print each output of a jq-style filter
+  json-rs minify [FILE]           strip insignificant whitespace
+  json-rs codegen [FILE]          print Rust types for documents shaped like FILE
+  json-rs diff OLD NEW            print how NEW differs from OLD; exits 1 if it does
+
+FILE defaults to standard input.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return usage(),
+    };
+    let (argument, rest) = match command {
+        "get" | "query" => match rest.split_first() {
+            Some((argument, rest)) => (Some(argument.as_str()), rest),
+            None => return usage(),
+        },
+        "fmt" | "validate" | "minify" | "codegen" => (None, rest),
+        "diff" => return diff_files(rest),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => return usage(),
+    };
+    let path = match rest {
+        [] => None,
+        [path] => Some(path.as_str()),
+        _ => return usage(),
+    };
+
+    let name = path.unwrap_or("<stdin>");
+    let input = match read_input(path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("json-rs: {}: {}", name, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let output = match command {
+        "fmt" => prettify(&input, "  ").map(Some),
+        "validate" => validate(&input).map(|_| None),
+        "minify" => minify(&input).map(Some),
+        "codegen" => parse(&input).map(|value| Some(rust_types(&[value], "Root"))),
+        "query" => {
+            let filter = match Filter::parse(argument.unwrap_or_default()) {
+                Ok(filter) => filter,
+                Err(err) => {
+                    eprintln!("json-rs: invalid filter: {}", err);
+                    return ExitCode::from(2);
+                }
+            };
+            match parse(&input).map(|value| filter.run(&value)) {
+                Ok(Ok(outputs)) if outputs.is_empty() => Ok(None),
+                Ok(Ok(outputs)) => Ok(Some(
+                    outputs
+                        .iter()
+                        .map(to_string_pretty)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )),
+                Ok(Err(err)) => {
+                    eprintln!("json-rs: {}: {}", name, err);
+                    return ExitCode::FAILURE;
+                }
+                Err(err) => Err(err),
+            }
+        }
+        _ => match extract_pointer(input.as_bytes(), argument.unwrap_or_default()) {
+            Ok(Some(value)) => Ok(Some(to_string_pretty(&value))),
+            Ok(None) => {
+                eprintln!(
+                    "json-rs: {}: no value at {:?}",
+                    name,
+                    argument.unwrap_or_default()
+                );
+                return ExitCode::FAILURE;
+            }
+            Err(err) => Err(err),
+        },
+    };
+    match output {
+        Ok(Some(text)) => {
+            let mut stdout = io::stdout().lock();
+            let text = if stdout.is_terminal() && command != "codegen" {
+                paint(&text)
+            } else {
+                text
+            };
+            if writeln!(stdout, "{}", text).is_err() {
+                return ExitCode::FAILURE;
+            }
+            ExitCode::SUCCESS
+        }
+        Ok(None) => ExitCode::SUCCESS,
+        Err(err) => {
+            report(name, &input, &err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn diff_files(paths: &[String]) -> ExitCode {
+    let [old, new] = paths else {
+        return usage();
+    };
+    let mut values = Vec::new();
+    for path in [old, new] {
+        let input = match fs::read_to_string(path) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("json-rs: {}: {}", path, err);
+                return ExitCode::FAILURE;
+            }
+        };
+        match parse(&input) {
+            Ok(value) => values.push(value),
+            Err(err) => {
+                report(path, &input, &err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let changes = diff(&values[0], &values[1]);
+    let mut stdout = io::stdout().lock();
+    let color = stdout.is_terminal();
+    if write!(stdout, "{}", render_diff(&changes, color)).is_err() {
+        return ExitCode::from(2);
+    }
+    if changes.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Colors JSON output for a terminal with ANSI escapes.
+fn paint(json: &str) -> String {
+    let mut out = String::with_capacity(json.len() * 2);
+    let mut prev = 0;
+    for span in highlight(json) {
+        let color = match span.kind {
+            HighlightKind::Key => "34",
+            HighlightKind::String => "32",
+            HighlightKind::Number => "36",
+            HighlightKind::Literal => "35",
+            HighlightKind::Comment => "90",
+            HighlightKind::Punctuation => continue,
+        };
+        out.push_str(&json[prev..span.start]);
+        out.push_str(&format!(
+            "\x1b[{}m{}\x1b[0m",
+            color,
+            &json[span.start..span.end]
+        ));
+        prev = span.end;
+    }
+    out.push_str(&json[prev..]);
+    out
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{}", USAGE);
+    ExitCode::from(2)
+}
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+/// Prints `err` as `name:line:column: message`, counting both from one.
+fn report(name: &str, input: &str, err: &JsonError) {
+    match err.offset() {
+        Some(offset) => {
+            let (line, column) = LineIndex::new(input).line_col(offset);
+            eprintln!("{}:{}:{}: {}", name, line, column, err);
+        }
+        None => eprintln!("{}: {}", name, err),
+    }
+}
@@ -0,0 +1,311 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::slice;
+
+use crate::lexer::{Lexer, Token};
+use crate::parser::{to_event, Event, Parser};
+use crate::{into_str, JsonError, Key, Map, Type};
+
+enum Partial {
+    Array(Vec<Type>),
+    Object(Map<Key, Type>, Option<Key>),
+}
+
+/// Assembles a [`Type`] from a well-formed event sequence, keeping unfinished
+/// containers on an explicit stack, so that nesting depth costs no call
+/// stack. For building one value out of a document otherwise only lexed:
+/// feed it the value's tokens with [`push_token`](TreeBuilder::push_token).
+///
+/// The builder does not check the grammar; events out of order give some
+/// value or none rather than an error.
+pub struct TreeBuilder {
+    stack: Vec<Partial>,
+    /// Every distinct key seen so far, handed out again for repeats.
+    #[cfg(feature = "arc-keys")]
+    keys: Map<Key, ()>,
+}
+
+impl Default for TreeBuilder {
+    fn default() -> Self {
+        TreeBuilder::new()
+    }
+}
+
+impl TreeBuilder {
+    pub fn new() -> Self {
+        TreeBuilder {
+            stack: Vec::new(),
+            #[cfg(feature = "arc-keys")]
+            keys: Map::default(),
+        }
+    }
+
+    #[cfg(not(feature = "arc-keys"))]
+    fn intern(&mut self, key: String) -> Key {
+        into_str(key)
+    }
+
+    #[cfg(feature = "arc-keys")]
+    fn intern(&mut self, key: String) -> Key {
+        if let Some((shared, _)) = self.keys.get_key_value(key.as_str()) {
+            return shared.clone();
+        }
+        let shared = Key::from(key);
+        self.keys.insert(shared.clone(), ());
+        shared
+    }
+
+    /// Drives `parser` over `lexer` until the root value is complete, or
+    /// returns `None` if the lexer runs out of tokens first. Tokens after the
+    /// root value are left unread.
+    pub(crate) fn build(
+        &mut self,
+        parser: &mut Parser,
+        lexer: &mut Lexer,
+    ) -> Result<Option<Type>, JsonError> {
+        while let Some(event) = parser.next_event(lexer)? {
+            if let Some(root) = self.push(event) {
+                return Ok(Some(root));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Consumes one token of `input`, the input of the [`Lexer`] it came
+    /// from, returning the root value once it is complete. A string is a key
+    /// where an object expects one. Errors are those of decoding the token.
+    pub fn push_token(&mut self, input: &[u8], token: Token) -> Result<Option<Type>, JsonError> {
+        let is_key = matches!(self.stack.last(), Some(Partial::Object(_, None)));
+        Ok(match to_event(input, token, is_key)? {
+            Some(event) => self.push(event),
+            None => None,
+        })
+    }
+
+    /// Consumes one event, returning the root value once it is complete.
+    pub fn push(&mut self, event: Event) -> Option<Type> {
+        let value = match event {
+            Event::StartObject => {
+                self.stack.push(Partial::Object(Map::default(), None));
+                return None;
+            }
+            Event::StartArray => {
+                self.stack.push(Partial::Array(Vec::new()));
+                return None;
+            }
+            Event::Key(key) => {
+                let key = self.intern(key);
+                if let Some(Partial::Object(_, pending)) = self.stack.last_mut() {
+                    *pending = Some(key);
+                }
+                return None;
+            }
+            Event::EndObject | Event::EndArray => match self.stack.pop()? {
+                Partial::Array(items) => Type::Array(items),
+                Partial::Object(members, _) => Type::Object(Box::new(members)),
+            },
+            Event::Null => Type::Null,
+            Event::Boolean(b) => Type::Boolean(b),
+            Event::Number(n) => Type::Number(n),
+            Event::String(s) => Type::String(into_str(s)),
+        };
+        self.push_value(value)
+    }
+
+    /// Adds a finished value where the next one goes, returning the root
+    /// value once it is complete.
+    pub fn push_value(&mut self, value: Type) -> Option<Type> {
+        match self.stack.last_mut() {
+            None => Some(value),
+            Some(Partial::Array(items)) => {
+                items.push(value);
+                None
+            }
+            Some(Partial::Object(members, pending)) => {
+                if let Some(key) = pending.take() {
+                    members.insert(key, value);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// The events of a [`Type`], from [`Type::events`].
+pub struct Events<'a> {
+    stack: Vec<Level<'a>>,
+    /// The value to open next: the root, or an object member's value after
+    /// its key.
+    pending: Option<&'a Type>,
+}
+
+type Members<'a> = <&'a Map<Key, Type> as IntoIterator>::IntoIter;
+
+/// The rest of an open container.
+enum Level<'a> {
+    Array(slice::Iter<'a, Type>),
+    Object(Members<'a>),
+}
+
+impl<'a> Events<'a> {
+    fn open(&mut self, value: &'a Type) -> Event {
+        match value {
+            Type::Null => Event::Null,
+            Type::Boolean(b) => Event::Boolean(*b),
+            Type::Number(n) => Event::Number(*n),
+            Type::String(s) => Event::String(s.to_string()),
+            Type::Array(items) => {
+                self.stack.push(Level::Array(items.iter()));
+                Event::StartArray
+            }
+            Type::Object(members) => {
+                self.stack.push(Level::Object(members.iter()));
+                Event::StartObject
+            }
+        }
+    }
+}
+
+impl Iterator for Events<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if let Some(value) = self.pending.take() {
+            return Some(self.open(value));
+        }
+        match self.stack.last_mut()? {
+            Level::Array(items) => match items.next() {
+                Some(item) => Some(self.open(item)),
+                None => {
+                    self.stack.pop();
+                    Some(Event::EndArray)
+                }
+            },
+            Level::Object(members) => match members.next() {
+                Some((key, value)) => {
+                    self.pending = Some(value);
+                    Some(Event::Key(key.to_string()))
+                }
+                None => {
+                    self.stack.pop();
+                    Some(Event::EndObject)
+                }
+            },
+        }
+    }
+}
+
+impl Type {
+    /// The value as the events a parser would produce for it, object
+    /// members in map order, for driving streaming serializers and
+    /// transcoders from a tree. A [`TreeBuilder`] turns them back into the
+    /// value. Nesting depth costs no call stack.
+    pub fn events(&self) -> Events<'_> {
+        Events {
+            stack: Vec::new(),
+            pending: Some(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "arc-keys")]
+    use alloc::sync::Arc;
+
+    use super::TreeBuilder;
+    use crate::{parse, Event, Lexer, TokenKind, Type};
+
+    #[test]
+    fn builds_from_tokens() {
+        // Lex up to the key "keep", then build its value.
+        let input = br#"{"skip": [1, 2, {"x": 3}], "keep": {"a": ["b", null], "c": {}}} trailing"#;
+        let mut lexer = Lexer::new(input);
+        loop {
+            let token = lexer.next_token().unwrap().unwrap();
+            if &input[token.start..token.end] == br#""keep""# {
+                break;
+            }
+        }
+        assert_eq!(lexer.next_token().unwrap().unwrap().kind, TokenKind::Colon);
+        let mut builder = TreeBuilder::new();
+        let value = loop {
+            let token = lexer.next_token().unwrap().unwrap();
+            if let Some(value) = builder.push_token(lexer.input(), token).unwrap() {
+                break value;
+            }
+        };
+        assert_eq!(value, parse(r#"{"a": ["b", null], "c": {}}"#).unwrap());
+
+        let mut builder = TreeBuilder::default();
+        assert_eq!(builder.push(Event::StartArray), None);
+        assert_eq!(builder.push(Event::Boolean(true)), None);
+        assert_eq!(builder.push_value(Type::Null), None);
+        assert_eq!(
+            builder.push(Event::EndArray),
+            Some(parse("[true, null]").unwrap())
+        );
+    }
+
+    #[test]
+    fn walks_events() {
+        let value = parse(r#"[{"a": [1, "x"]}, true, null, {}]"#).unwrap();
+        let events: Vec<Event> = value.events().collect();
+        assert_eq!(
+            events,
+            [
+                Event::StartArray,
+                Event::StartObject,
+                Event::Key("a".into()),
+                Event::StartArray,
+                Event::Number(1.into()),
+                Event::String("x".into()),
+                Event::EndArray,
+                Event::EndObject,
+                Event::Boolean(true),
+                Event::Null,
+                Event::StartObject,
+                Event::EndObject,
+                Event::EndArray,
+            ]
+        );
+        assert_eq!(Type::Null.events().collect::<Vec<_>>(), [Event::Null]);
+
+        let value = parse(r#"{"a": {"b": [[], {"c": "d"}]}, "e": 2.5}"#).unwrap();
+        let mut builder = TreeBuilder::new();
+        let rebuilt = value.events().find_map(|event| builder.push(event));
+        assert_eq!(rebuilt, Some(value));
+    }
+
+    #[cfg(feature = "arc-keys")]
+    #[test]
+    fn repeated_keys_share_an_allocation() {
+        let Type::Array(rows) = parse(r#"[{"id": 1}, {"id": 2}, {"id": 3}]"#).unwrap() else {
+            panic!("expected an array");
+        };
+        let keys: Vec<_> = rows
+            .iter()
+            .map(|row| match row {
+                Type::Object(members) => members.keys().next().unwrap().clone(),
+                _ => panic!("expected an object"),
+            })
+            .collect();
+        assert!(Arc::ptr_eq(&keys[0], &keys[1]));
+        assert!(Arc::ptr_eq(&keys[0], &keys[2]));
+    }
+
+    #[cfg(feature = "arc-keys")]
+    #[test]
+    fn clones_share_keys() {
+        let value = parse(r#"{"name": {"first": "a"}}"#).unwrap();
+        let copy = value.clone();
+        let (Type::Object(a), Type::Object(b)) = (&value, &copy) else {
+            panic!("expected objects");
+        };
+        assert!(Arc::ptr_eq(
+            a.keys().next().unwrap(),
+            b.keys().next().unwrap()
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::codegen::words;
+use crate::Type;
+
+/// A naming convention for object keys, for [`Type::rename_keys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `userId`
+    CamelCase,
+    /// `user_id`
+    SnakeCase,
+    /// `user-id`
+    KebabCase,
+    /// `UserId`
+    PascalCase,
+}
+
+impl Case {
+    /// Rewrites `key` in this case. Words are split at punctuation and at
+    /// lowercase-to-uppercase steps; a key with no letters or digits is kept
+    /// as is.
+    pub fn convert(self, key: &str) -> String {
+        let words = words(key);
+        if words.is_empty() {
+            return key.into();
+        }
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            let first: String = chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .collect();
+            first + &chars.as_str().to_lowercase()
+        };
+        let lower: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        match self {
+            Case::SnakeCase => lower.join("_"),
+            Case::KebabCase => lower.join("-"),
+            Case::PascalCase => words.iter().map(|word| capitalize(word)).collect(),
+            Case::CamelCase => {
+                let mut out = lower[0].clone();
+                for word in &words[1..] {
+                    out.push_str(&capitalize(word));
+                }
+                out
+            }
+        }
+    }
+}
+
+impl Type {
+    /// Converts every object key, at any depth, to `case`, e.g. to turn the
+    /// camelCase keys of a JavaScript API into snake_case. When two keys of
+    /// one object convert to the same key, the one iterated last wins.
+    pub fn rename_keys(&mut self, case: Case) {
+        match self {
+            Type::Array(items) => {
+                for item in items {
+                    item.rename_keys(case);
+                }
+            }
+            Type::Object(members) => {
+                for (key, mut value) in mem::take(&mut **members) {
+                    value.rename_keys(case);
+                    members.insert(case.convert(&key).as_str().into(), value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Case;
+    use crate::parse;
+
+    #[test]
+    fn converts_keys() {
+        for (key, camel, snake, kebab, pascal) in [
+            ("userId", "userId", "user_id", "user-id", "UserId"),
+            ("user_id", "userId", "user_id", "user-id", "UserId"),
+            ("User-ID", "userId", "user_id", "user-id", "UserId"),
+            (
+                "address2Line",
+                "address2Line",
+                "address2_line",
+                "address2-line",
+                "Address2Line",
+            ),
+            ("name", "name", "name", "name", "Name"),
+            ("$", "$", "$", "$", "$"),
+        ] {
+            assert_eq!(Case::CamelCase.convert(key), camel);
+            assert_eq!(Case::SnakeCase.convert(key), snake);
+            assert_eq!(Case::KebabCase.convert(key), kebab);
+            assert_eq!(Case::PascalCase.convert(key), pascal);
+        }
+    }
+
+    #[test]
+    fn renames_recursively() {
+        let mut value = parse(
+            r#"{"firstName": "a", "homeAddress": {"zipCode": 1}, "tagList": [{"tagId": 2}]}"#,
+        )
+        .unwrap();
+        value.rename_keys(Case::SnakeCase);
+        assert_eq!(
+            value,
+            parse(r#"{"first_name": "a", "home_address": {"zip_code": 1}, "tag_list": [{"tag_id": 2}]}"#)
+                .unwrap()
+        );
+        value.rename_keys(Case::KebabCase);
+        value.rename_keys(Case::CamelCase);
+        assert_eq!(
+            value,
+            parse(
+                r#"{"firstName": "a", "homeAddress": {"zipCode": 1}, "tagList": [{"tagId": 2}]}"#
+            )
+            .unwrap()
+        );
+    }
+}
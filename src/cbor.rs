@@ -0,0 +1,469 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::base64::base64url;
+use crate::{into_str, to_string, Map, Number, Type, MAX_DEPTH};
+
+/// An error from [`from_cbor`]: the input is not well-formed CBOR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CborError {
+    /// The byte offset of the offending data item.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for CborError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid CBOR at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl core::error::Error for CborError {}
+
+/// Encodes `value` as CBOR (RFC 8949).
+///
+/// Numbers that are integers in the 64-bit range are written as CBOR
+/// integers, other numbers as the shortest float that holds them exactly.
+/// Object members are written in key order, so equal values encode alike.
+pub fn to_cbor(value: &Type) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode(value, &mut out);
+    out
+}
+
+fn head(major: u8, arg: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if arg <= u8::MAX as u64 {
+        out.extend_from_slice(&[major | 24, arg as u8]);
+    } else if arg <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(arg as u16).to_be_bytes());
+    } else if arg <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(arg as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+fn encode(value: &Type, out: &mut Vec<u8>) {
+    match value {
+        Type::Null => out.push(0xf6),
+        Type::Boolean(false) => out.push(0xf4),
+        Type::Boolean(true) => out.push(0xf5),
+        Type::Number(n) => {
+            // 2^64, the first integer past the range of either integer kind.
+            const LIMIT: f64 = 18_446_744_073_709_551_616.0;
+            if let Some(n) = n.as_u64() {
+                head(0, n, out);
+                return;
+            }
+            if let Some(n) = n.as_i64() {
+                head(1, !n as u64, out);
+                return;
+            }
+            let n = n.to_f64();
+            if n % 1.0 == 0.0 && (-LIMIT..0.0).contains(&n) {
+                head(1, (-n) as u64 - 1, out);
+            } else if (n as f32) as f64 == n {
+                out.push(0xfa);
+                out.extend_from_slice(&(n as f32).to_be_bytes());
+            } else {
+                out.push(0xfb);
+                out.extend_from_slice(&n.to_be_bytes());
+            }
+        }
+        Type::String(s) => {
+            head(3, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Type::Array(items) => {
+            head(4, items.len() as u64, out);
+            for item in items {
+                encode(item, out);
+            }
+        }
+        Type::Object(members) => {
+            head(5, members.len() as u64, out);
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            for (key, member) in members {
+                head(3, key.len() as u64, out);
+                out.extend_from_slice(key.as_bytes());
+                encode(member, out);
+            }
+        }
+    }
+}
+
+/// Decodes one CBOR data item spanning all of `bytes`.
+///
+/// CBOR has more kinds of value than JSON; they are converted as RFC 8949
+/// section 6.1 suggests. Byte strings become unpadded base64url strings,
+/// bignums (tags 2 and 3) become numbers and other tags are dropped,
+/// `undefined`, other simple values and non-finite floats become `null`, and
+/// map keys that are not text become their compact JSON serialization.
+pub fn from_cbor(bytes: &[u8]) -> Result<Type, CborError> {
+    let mut decoder = Decoder {
+        bytes,
+        pos: 0,
+        depth: 0,
+    };
+    let value = decoder.item()?;
+    if decoder.pos < bytes.len() {
+        return Err(decoder.error(decoder.pos, "trailing bytes after the data item"));
+    }
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// How many arrays, maps and tags enclose the current item.
+    depth: usize,
+}
+
+/// Where an indefinite-length item ends.
+const BREAK: u8 = 0xff;
+
+impl Decoder<'_> {
+    fn error(&self, offset: usize, message: &'static str) -> CborError {
+        CborError { offset, message }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], CborError> {
+        let Some(bytes) = self.bytes.get(self.pos..).and_then(|rest| rest.get(..n)) else {
+            return Err(self.error(self.bytes.len(), "unexpected end of input"));
+        };
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    /// Reads an initial byte and its argument; the argument is `None` for an
+    /// indefinite length.
+    fn head(&mut self) -> Result<(u8, u8, Option<u64>), CborError> {
+        let start = self.pos;
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let arg = match info {
+            0..=23 => Some(info as u64),
+            24 => Some(self.take(1)?[0] as u64),
+            25 => Some(u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64),
+            26 => Some(u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64),
+            27 => Some(u64::from_be_bytes(self.take(8)?.try_into().unwrap())),
+            31 if major >= 2 && major != 6 => None,
+            _ => return Err(self.error(start, "reserved additional information")),
+        };
+        Ok((major, info, arg))
+    }
+
+    fn at_break(&mut self) -> bool {
+        if self.bytes.get(self.pos) == Some(&BREAK) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Bounds a declared length by the bytes left, each item taking at least
+    /// one, so a forged length cannot make us allocate.
+    fn len(&self, start: usize, arg: u64) -> Result<usize, CborError> {
+        let left = self.bytes.len() - self.pos;
+        match usize::try_from(arg) {
+            Ok(len) if len <= left => Ok(len),
+            _ => Err(self.error(start, "length exceeds the input")),
+        }
+    }
+
+    /// Reads the contents of a byte or text string with major type `major`.
+    fn string(&mut self, start: usize, major: u8, arg: Option<u64>) -> Result<Vec<u8>, CborError> {
+        let Some(arg) = arg else {
+            let mut out = Vec::new();
+            while !self.at_break() {
+                let chunk = self.pos;
+                match self.head()? {
+                    (m, _, Some(arg)) if m == major => {
+                        out.extend(self.string(chunk, m, Some(arg))?)
+                    }
+                    _ => return Err(self.error(chunk, "malformed indefinite-length string")),
+                }
+            }
+            return Ok(out);
+        };
+        let len = self.len(start, arg)?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn text(&mut self, start: usize, arg: Option<u64>) -> Result<String, CborError> {
+        String::from_utf8(self.string(start, 3, arg)?)
+            .map_err(|_| self.error(start, "text string is not valid UTF-8"))
+    }
+
+    /// Enters an array, map or tag, which may nest no deeper than JSON
+    /// does.
+    fn enter(&mut self, start: usize) -> Result<(), CborError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(start, "nesting too deep"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn item(&mut self) -> Result<Type, CborError> {
+        let start = self.pos;
+        let (major, info, arg) = self.head()?;
+        if matches!(major, 4 | 5) {
+            self.enter(start)?;
+        }
+        let value = match major {
+            0 => Type::Number(arg.unwrap().into()),
+            1 => Type::Number(match i64::try_from(arg.unwrap()) {
+                Ok(n) => Number::from(-1 - n),
+                Err(_) => Number::from(-1.0 - arg.unwrap() as f64),
+            }),
+            2 => Type::String(into_str(base64url(&self.string(start, 2, arg)?))),
+            3 => Type::String(into_str(self.text(start, arg)?)),
+            4 => {
+                let mut items = Vec::new();
+                match arg {
+                    Some(arg) => {
+                        for _ in 0..self.len(start, arg)? {
+                            items.push(self.item()?);
+                        }
+                    }
+                    None => {
+                        while !self.at_break() {
+                            items.push(self.item()?);
+                        }
+                    }
+                }
+                Type::Array(items)
+            }
+            5 => {
+                let mut members = Map::default();
+                let mut remaining = arg.map(|arg| self.len(start, arg)).transpose()?;
+                loop {
+                    match &mut remaining {
+                        Some(0) => break,
+                        Some(n) => *n -= 1,
+                        None if self.at_break() => break,
+                        None => {}
+                    }
+                    let key = match self.item()? {
+                        Type::String(key) => key,
+                        key => into_str(to_string(&key)),
+                    };
+                    let value = self.item()?;
+                    members.insert(key.as_str().into(), value);
+                }
+                Type::Object(Box::new(members))
+            }
+            6 => {
+                let tag = arg.unwrap();
+                let inner = self.pos;
+                match (tag, self.head()?) {
+                    (2 | 3, (2, _, arg)) => {
+                        let magnitude = self
+                            .string(inner, 2, arg)?
+                            .iter()
+                            .fold(0.0, |n, &byte| n * 256.0 + byte as f64);
+                        Type::Number(Number::from(if tag == 2 {
+                            magnitude
+                        } else {
+                            -1.0 - magnitude
+                        }))
+                    }
+                    _ => {
+                        self.pos = inner;
+                        self.enter(start)?;
+                        let value = self.item()?;
+                        self.depth -= 1;
+                        value
+                    }
+                }
+            }
+            _ => match (info, arg) {
+                (20, _) => Type::Boolean(false),
+                (21, _) => Type::Boolean(true),
+                (25, Some(bits)) => number(half(bits as u16)),
+                (26, Some(bits)) => number(f32::from_bits(bits as u32) as f64),
+                (27, Some(bits)) => number(f64::from_bits(bits)),
+                (31, _) => return Err(self.error(start, "unexpected break")),
+                _ => Type::Null,
+            },
+        };
+        if matches!(major, 4 | 5) {
+            self.depth -= 1;
+        }
+        Ok(value)
+    }
+}
+
+fn number(n: f64) -> Type {
+    if n.is_finite() {
+        Type::Number(n.into())
+    } else {
+        Type::Null
+    }
+}
+
+/// Widens an IEEE 754 half-precision float.
+fn half(bits: u16) -> f64 {
+    let bits = bits as u32;
+    let (sign, exponent, mantissa) = (bits >> 15, (bits >> 10) & 0x1f, bits & 0x3ff);
+    let magnitude = match exponent {
+        // Subnormal: the mantissa counts units of 2^-24.
+        0 => mantissa as f64 * 5.960_464_477_539_063e-8,
+        31 => f32::from_bits(0x7f80_0000 | mantissa << 13) as f64,
+        _ => f32::from_bits((exponent + 127 - 15) << 23 | mantissa << 13) as f64,
+    };
+    if sign == 1 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_cbor, to_cbor, CborError};
+    use crate::{parse, Type, MAX_DEPTH};
+
+    #[test]
+    fn encodes() {
+        for (json, cbor) in [
+            ("0", &[0x00][..]),
+            ("23", &[0x17]),
+            ("24", &[0x18, 0x18]),
+            ("1000", &[0x19, 0x03, 0xe8]),
+            ("-1", &[0x20]),
+            ("-1000", &[0x39, 0x03, 0xe7]),
+            ("1.5", &[0xfa, 0x3f, 0xc0, 0x00, 0x00]),
+            (
+                "1.1",
+                &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+            ),
+            ("-0.0", &[0xfa, 0x80, 0x00, 0x00, 0x00]),
+            (
+                "18446744073709551615",
+                &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+            ),
+            // 2^64, the first integer past the range of either integer kind.
+            ("18446744073709551616", &[0xfa, 0x5f, 0x80, 0x00, 0x00]),
+            ("null", &[0xf6]),
+            ("true", &[0xf5]),
+            (r#""a""#, &[0x61, 0x61]),
+            ("[1, [2]]", &[0x82, 0x01, 0x81, 0x02]),
+            (
+                r#"{"b": 1, "a": 2}"#,
+                &[0xa2, 0x61, 0x61, 0x02, 0x61, 0x62, 0x01],
+            ),
+        ] {
+            let value = parse(json).unwrap();
+            assert_eq!(to_cbor(&value), cbor, "{}", json);
+            assert_eq!(from_cbor(cbor), Ok(value), "{}", json);
+        }
+        assert_eq!(
+            to_cbor(&Type::Number((1u64 << 63).into())),
+            [0x1b, 0x80, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn decodes_cbor_only_values() {
+        let decode = |bytes: &[u8]| from_cbor(bytes).unwrap();
+        // Byte strings, definite and in chunks.
+        assert_eq!(
+            decode(&[0x43, 0xfb, 0xff, 0x01]),
+            Type::String("-_8B".into())
+        );
+        assert_eq!(
+            decode(&[0x5f, 0x41, 0xfb, 0x42, 0xff, 0x01, 0xff]),
+            Type::String("-_8B".into())
+        );
+        assert_eq!(
+            decode(&[0x7f, 0x61, 0x61, 0x62, 0x62, 0x63, 0xff]),
+            Type::String("abc".into())
+        );
+        assert_eq!(
+            decode(&[0x9f, 0x01, 0x9f, 0xff, 0xff]),
+            parse("[1, []]").unwrap()
+        );
+        assert_eq!(
+            decode(&[0xbf, 0x01, 0xf5, 0xf6, 0x02, 0xff]),
+            parse(r#"{"1": true, "null": 2}"#).unwrap()
+        );
+        // A date tag is dropped, bignums become numbers.
+        assert_eq!(
+            decode(&[0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0]),
+            Type::Number(1363896240.into())
+        );
+        assert_eq!(decode(&[0xc2, 0x42, 0x01, 0x00]), Type::Number(256.into()));
+        assert_eq!(
+            decode(&[0xc3, 0x42, 0x01, 0x00]),
+            Type::Number((-257).into())
+        );
+        // Half floats, infinity, undefined and other simple values.
+        assert_eq!(decode(&[0xf9, 0x3c, 0x00]), Type::Number(1.into()));
+        assert_eq!(decode(&[0xf9, 0xc4, 0x00]), Type::Number((-4).into()));
+        assert_eq!(
+            decode(&[0xf9, 0x00, 0x01]),
+            Type::Number(5.960464477539063e-8.into())
+        );
+        assert_eq!(decode(&[0xf9, 0x7c, 0x00]), Type::Null);
+        assert_eq!(decode(&[0xf7]), Type::Null);
+        assert_eq!(decode(&[0xf8, 0xff]), Type::Null);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let error = |offset, message| Err(CborError { offset, message });
+        assert_eq!(from_cbor(&[]), error(0, "unexpected end of input"));
+        assert_eq!(
+            from_cbor(&[0xa1, 0x01]),
+            error(2, "unexpected end of input")
+        );
+        assert_eq!(
+            from_cbor(&[0x83, 0x01, 0x02]),
+            error(0, "length exceeds the input")
+        );
+        assert_eq!(
+            from_cbor(&[0x1c]),
+            error(0, "reserved additional information")
+        );
+        assert_eq!(
+            from_cbor(&[0x01, 0x02]),
+            error(1, "trailing bytes after the data item")
+        );
+        assert_eq!(
+            from_cbor(&[0x62, 0xff, 0xfe]),
+            error(0, "text string is not valid UTF-8")
+        );
+        assert_eq!(from_cbor(&[0xff]), error(0, "unexpected break"));
+        assert_eq!(
+            from_cbor(&[0x5f, 0x61, 0x61, 0xff]),
+            error(1, "malformed indefinite-length string")
+        );
+        assert_eq!(
+            from_cbor(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            error(0, "length exceeds the input")
+        );
+        assert_eq!(
+            from_cbor(&[0x81; 200_000]),
+            error(MAX_DEPTH, "nesting too deep")
+        );
+        assert_eq!(
+            from_cbor(&[0xc6; 200_000]),
+            error(MAX_DEPTH, "nesting too deep")
+        );
+        let mut nested = vec![0x81; MAX_DEPTH - 1];
+        nested.push(0x80);
+        assert!(from_cbor(&nested).is_ok());
+    }
+}
@@ -0,0 +1,279 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::infer::{Fields, Shape};
+use crate::Type;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Generates Rust type definitions for documents shaped like `samples`,
+/// naming the top-level type `name`, to bootstrap typed bindings for an
+/// API.
+///
+/// The structs derive serde's `Serialize` and `Deserialize`, with
+/// `#[serde(rename)]` where a key is not a valid snake_case field name.
+/// Integers become `i64`, or `u64` if one is above `i64::MAX` and none is
+/// negative, or else `i128`, other numbers `f64`, keys missing from some
+/// samples or ever `null` become `Option`s, and values seen with several
+/// kinds, or never seen at all, are `serde_json::Value`.
+pub fn rust_types(samples: &[Type], name: &str) -> String {
+    let mut generator = Generator {
+        structs: Vec::new(),
+        names: Vec::new(),
+    };
+    let root = generator.type_of(&Shape::of(samples), &pascal_case(name));
+    let mut out = String::from("use serde::{Deserialize, Serialize};\n");
+    if generator.structs.is_empty() {
+        out.push_str(&format!("\npub type {} = {};\n", pascal_case(name), root));
+    }
+    for definition in generator.structs {
+        out.push('\n');
+        out.push_str(&definition);
+    }
+    out
+}
+
+struct Generator {
+    /// The struct definitions, outermost first.
+    structs: Vec<String>,
+    names: Vec<String>,
+}
+
+impl Generator {
+    fn type_of(&mut self, shape: &Shape, name: &str) -> String {
+        let kinds = [
+            shape.boolean,
+            shape.number.is_some(),
+            shape.string,
+            shape.array.is_some(),
+            shape.object.is_some(),
+        ];
+        let inner = if kinds.iter().filter(|&&kind| kind).count() != 1 {
+            "serde_json::Value".to_string()
+        } else if shape.boolean {
+            "bool".to_string()
+        } else if let Some((min, _, integer)) = shape.number {
+            let ty = match (integer, shape.unsigned) {
+                (false, _) => "f64",
+                (true, false) => "i64",
+                (true, true) if min >= 0.0 => "u64",
+                (true, true) => "i128",
+            };
+            ty.to_string()
+        } else if shape.string {
+            "String".to_string()
+        } else if let Some(items) = &shape.array {
+            if items.is_empty() {
+                "Vec<serde_json::Value>".to_string()
+            } else {
+                format!("Vec<{}>", self.type_of(items, &format!("{}Item", name)))
+            }
+        } else {
+            let fields = shape.object.as_ref().expect("one kind is set");
+            self.define(fields, name)
+        };
+        if shape.null && inner != "serde_json::Value" {
+            format!("Option<{}>", inner)
+        } else {
+            inner
+        }
+    }
+
+    /// Adds a struct for `fields`, returning its name.
+    fn define(&mut self, fields: &Fields, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut n = 1;
+        while self.names.contains(&unique) {
+            n += 1;
+            unique = format!("{}{}", name, n);
+        }
+        self.names.push(unique.clone());
+        let index = self.structs.len();
+        self.structs.push(String::new());
+
+        let mut out = format!(
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {} {{\n",
+            unique
+        );
+        // Distinct keys can map to one field name, as `a-b` and `aB` do.
+        let mut fields_seen: Vec<String> = Vec::new();
+        for (key, (seen, shape)) in &fields.fields {
+            let mut ty = self.type_of(shape, &pascal_case(key));
+            if *seen < fields.objects && !ty.starts_with("Option<") {
+                ty = format!("Option<{}>", ty);
+            }
+            let mut field = field_name(key);
+            let mut n = 1;
+            while fields_seen.contains(&field) {
+                n += 1;
+                field = format!("{}_{}", field_name(key).trim_start_matches("r#"), n);
+            }
+            fields_seen.push(field.clone());
+            if field.trim_start_matches("r#") != key {
+                out.push_str(&format!("    #[serde(rename = {:?})]\n", key));
+            }
+            out.push_str(&format!("    pub {}: {},\n", field, ty));
+        }
+        out.push_str("}\n");
+        self.structs[index] = out;
+        unique
+    }
+}
+
+/// Splits `key` into words at non-alphanumeric characters and at
+/// lowercase-to-uppercase steps, as in `userId` or `user_id`.
+pub(crate) fn words(key: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut prev_lower = false;
+    for ch in key.chars() {
+        if !ch.is_alphanumeric() {
+            words.push(String::new());
+            prev_lower = false;
+            continue;
+        }
+        if words.is_empty() || (ch.is_uppercase() && prev_lower) {
+            words.push(String::new());
+        }
+        prev_lower = ch.is_lowercase() || ch.is_numeric();
+        words.last_mut().unwrap().push(ch);
+    }
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+fn pascal_case(key: &str) -> String {
+    let name: String = words(key)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_uppercase().collect::<String>());
+            first.unwrap_or_default() + &chars.as_str().to_lowercase()
+        })
+        .collect();
+    match name.chars().next() {
+        None => "Field".to_string(),
+        Some(c) if c.is_numeric() => format!("T{}", name),
+        Some(_) => name,
+    }
+}
+
+fn field_name(key: &str) -> String {
+    let name = words(key)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match name.chars().next() {
+        None => "field".to_string(),
+        Some(c) if c.is_numeric() => format!("_{}", name),
+        Some(_) if name == "self" || name == "super" || name == "crate" => format!("{}_", name),
+        Some(_) if KEYWORDS.contains(&name.as_str()) => format!("r#{}", name),
+        Some(_) => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_name, pascal_case, rust_types};
+    use crate::parse;
+
+    #[test]
+    fn names() {
+        assert_eq!(field_name("userId"), "user_id");
+        assert_eq!(field_name("User-Name"), "user_name");
+        assert_eq!(field_name("type"), "r#type");
+        assert_eq!(field_name("2fa"), "_2fa");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(field_name("$"), "field");
+        assert_eq!(pascal_case("line_items"), "LineItems");
+        assert_eq!(pascal_case("HTTPCode"), "Httpcode");
+    }
+
+    #[test]
+    fn generates_structs() {
+        let samples = [
+            parse(r#"{"id": 1, "userName": "a", "type": "x", "tags": ["t"], "owner": {"id": 2}, "items": [{"price": 1.5}]}"#)
+                .unwrap(),
+            parse(r#"{"id": 2, "userName": null, "type": "y", "tags": [], "owner": {"id": "3"}, "items": [], "note": 5}"#)
+                .unwrap(),
+        ];
+        assert_eq!(
+            rust_types(&samples, "order"),
+            r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: i64,
+    pub items: Vec<ItemsItem>,
+    pub note: Option<i64>,
+    pub owner: Owner,
+    pub tags: Vec<String>,
+    pub r#type: String,
+    #[serde(rename = "userName")]
+    pub user_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemsItem {
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Owner {
+    pub id: serde_json::Value,
+}
+"#
+        );
+        assert_eq!(
+            rust_types(&[parse("[1, 2]").unwrap()], "ids"),
+            "use serde::{Deserialize, Serialize};\n\npub type Ids = Vec<i64>;\n"
+        );
+        for (json, ty) in [
+            ("[1, 18446744073709551615]", "u64"),
+            ("[-1, 9223372036854775808]", "i128"),
+            ("[-1, 9223372036854775807]", "i64"),
+        ] {
+            assert_eq!(
+                rust_types(&[parse(json).unwrap()], "ids"),
+                format!(
+                    "use serde::{{Deserialize, Serialize}};\n\npub type Ids = Vec<{}>;\n",
+                    ty
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn distinct_keys_get_distinct_fields() {
+        let samples =
+            [parse(r#"{"A B": 1, "a-b": 2, "aB": 3, "a_b": 4, "type": 5, "Type": 6}"#).unwrap()];
+        assert_eq!(
+            rust_types(&samples, "t"),
+            r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct T {
+    #[serde(rename = "A B")]
+    pub a_b: i64,
+    #[serde(rename = "Type")]
+    pub r#type: i64,
+    #[serde(rename = "a-b")]
+    pub a_b_2: i64,
+    #[serde(rename = "aB")]
+    pub a_b_3: i64,
+    #[serde(rename = "a_b")]
+    pub a_b_4: i64,
+    #[serde(rename = "type")]
+    pub type_2: i64,
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use alloc::string::{String, ToString};
+
+use crate::{Number, Type};
+
+impl Type {
+    /// The value as a number, converted loosely the way JavaScript's
+    /// `Number()` does, for cleaning data from upstreams that send numbers
+    /// as strings: `null` and `false` are 0, `true` is 1, and a string is
+    /// read as a decimal or as `0x`, `0o` or `0b` digits after trimming
+    /// whitespace, a blank one being 0.
+    ///
+    /// `None` where JavaScript would give `NaN` or an infinity, and for
+    /// arrays and objects.
+    pub fn coerce_number(&self) -> Option<Number> {
+        match self {
+            Type::Null => Some(Number::from(0)),
+            Type::Boolean(b) => Some(Number::from(*b as u8)),
+            Type::Number(n) => Some(*n),
+            Type::String(s) => number_from_str(s),
+            Type::Array(_) | Type::Object(_) => None,
+        }
+    }
+
+    /// The value as text, as JavaScript's `String()` gives it: a string as
+    /// it is, a number as its JSON, and `null`, `true` and `false` spelled
+    /// out. `None` for arrays and objects.
+    pub fn coerce_string(&self) -> Option<String> {
+        match self {
+            Type::Null => Some("null".into()),
+            Type::Boolean(b) => Some(b.to_string()),
+            Type::Number(n) => Some(n.to_string()),
+            Type::String(s) => Some(s.to_string()),
+            Type::Array(_) | Type::Object(_) => None,
+        }
+    }
+
+    /// The value as a boolean: `null` is false, and a number is true unless
+    /// it is zero. Unlike JavaScript, which takes any non-empty string as
+    /// true, a string must say `true` or `false`, in any case, or be a
+    /// number, which counts as above; a blank one is false.
+    ///
+    /// `None` for other strings, and for arrays and objects.
+    pub fn coerce_bool(&self) -> Option<bool> {
+        match self {
+            Type::Null => Some(false),
+            Type::Boolean(b) => Some(*b),
+            Type::Number(n) => Some(n.to_f64() != 0.0),
+            Type::String(s) => {
+                let s = s.trim();
+                if s.eq_ignore_ascii_case("true") {
+                    Some(true)
+                } else if s.eq_ignore_ascii_case("false") {
+                    Some(false)
+                } else {
+                    number_from_str(s).map(|n| n.to_f64() != 0.0)
+                }
+            }
+            Type::Array(_) | Type::Object(_) => None,
+        }
+    }
+
+    /// Whether the value counts as true, for filters and templates: all
+    /// values do except `null`, `false`, zero and NaN, the empty string,
+    /// and the empty array and object.
+    ///
+    /// Stricter than JavaScript, where empty arrays and objects are true,
+    /// and than the `jq` truthiness [`query`](crate::query) uses, where
+    /// only `null` and `false` are false.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Type::Null => false,
+            Type::Boolean(b) => *b,
+            Type::Number(n) => {
+                let n = n.to_f64();
+                n != 0.0 && !n.is_nan()
+            }
+            Type::String(s) => !s.is_empty(),
+            Type::Array(items) => !items.is_empty(),
+            Type::Object(members) => !members.is_empty(),
+        }
+    }
+}
+
+fn number_from_str(s: &str) -> Option<Number> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Some(Number::from(0));
+    }
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        let digits = s
+            .strip_prefix(prefix)
+            .or_else(|| s.strip_prefix(&*prefix.to_ascii_uppercase()));
+        if let Some(digits) = digits {
+            // `from_str_radix` would take a sign, which JavaScript does not.
+            if digits.starts_with(['+', '-']) {
+                return None;
+            }
+            return u64::from_str_radix(digits, radix).ok().map(Number::from);
+        }
+    }
+    // Rust also reads `inf`, `nan` and the like, which JavaScript does not.
+    if !s
+        .bytes()
+        .all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+    {
+        return None;
+    }
+    if let Ok(n) = s.parse::<u64>() {
+        return Some(Number::from(n));
+    }
+    if let Ok(n) = s.parse::<i64>() {
+        if n < 0 {
+            return Some(Number::from(n));
+        }
+    }
+    s.parse::<f64>()
+        .ok()
+        .filter(|f| f.is_finite())
+        .map(Number::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Number, Type};
+
+    fn string(s: &str) -> Type {
+        Type::String(s.into())
+    }
+
+    #[test]
+    fn coerces_numbers() {
+        for (s, n) in [
+            ("42", Number::from(42)),
+            (" -7 ", Number::from(-7)),
+            ("+1.5", Number::from(1.5)),
+            (".5", Number::from(0.5)),
+            ("1e3", Number::from(1000)),
+            ("0x1F", Number::from(31)),
+            ("0b101", Number::from(5)),
+            ("", Number::from(0)),
+            ("18446744073709551615", Number::from(u64::MAX)),
+        ] {
+            assert_eq!(string(s).coerce_number(), Some(n), "{:?}", s);
+        }
+        for s in ["abc", "1,000", "Infinity", "nan", "-0x1", "1e400", "0x"] {
+            assert_eq!(string(s).coerce_number(), None, "{:?}", s);
+        }
+        assert_eq!(Type::Null.coerce_number(), Some(Number::from(0)));
+        assert_eq!(Type::Boolean(true).coerce_number(), Some(Number::from(1)));
+        assert_eq!(parse("[1]").unwrap().coerce_number(), None);
+    }
+
+    #[test]
+    fn coerces_strings_and_bools() {
+        let value = parse(r#"[null, true, 2.5, "x", []]"#).unwrap();
+        let Type::Array(items) = &value else {
+            unreachable!()
+        };
+        let strings: Vec<_> = items.iter().map(Type::coerce_string).collect();
+        assert_eq!(
+            strings,
+            [
+                Some("null".into()),
+                Some("true".into()),
+                Some("2.5".into()),
+                Some("x".into()),
+                None
+            ]
+        );
+
+        assert_eq!(Type::Number(1.into()).coerce_bool(), Some(true));
+        assert_eq!(Type::Number(0.into()).coerce_bool(), Some(false));
+        assert_eq!(Type::Null.coerce_bool(), Some(false));
+        for (s, b) in [
+            ("TRUE", Some(true)),
+            (" false", Some(false)),
+            ("1", Some(true)),
+            ("0.0", Some(false)),
+            ("", Some(false)),
+            ("yes", None),
+        ] {
+            assert_eq!(string(s).coerce_bool(), b, "{:?}", s);
+        }
+        assert_eq!(parse("{}").unwrap().coerce_bool(), None);
+    }
+
+    #[test]
+    fn truthiness() {
+        let value =
+            parse(r#"[null, false, 0, -0.0, "", [], {}, true, 1, -0.5, " ", [0], {"a": null}]"#)
+                .unwrap();
+        let Type::Array(items) = &value else {
+            unreachable!()
+        };
+        let truthy: Vec<bool> = items.iter().map(Type::is_truthy).collect();
+        assert_eq!(
+            truthy,
+            [false, false, false, false, false, false, false, true, true, true, true, true, true]
+        );
+        assert!(!Type::Number(f64::NAN.into()).is_truthy());
+    }
+}
@@ -0,0 +1,144 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::Type;
+
+/// Settings for [`Type::equals_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompareOptions {
+    /// Whether arrays are compared as multisets: equal when their elements
+    /// can be paired off, in any order.
+    pub unordered_arrays: bool,
+    /// Whether an object on the right may have members the left one lacks,
+    /// so that the left value reads as the part of a response a test cares
+    /// about.
+    pub ignore_extra_keys: bool,
+}
+
+impl Type {
+    /// Compares values structurally like `==`, loosened as `options` says,
+    /// for contract tests against APIs that do not fix the order of their
+    /// arrays or that add fields over time. The options apply at every
+    /// depth.
+    ///
+    /// Unordered arrays take time quadratic in their length, or cubic when
+    /// extra keys are ignored too.
+    pub fn equals_with(&self, other: &Type, options: CompareOptions) -> bool {
+        match (self, other) {
+            (Type::Array(a), Type::Array(b)) => {
+                if a.len() != b.len() {
+                    false
+                } else if options.unordered_arrays {
+                    pairs_off(a, b, options)
+                } else {
+                    a.iter().zip(b).all(|(a, b)| a.equals_with(b, options))
+                }
+            }
+            (Type::Object(a), Type::Object(b)) => {
+                (options.ignore_extra_keys || a.len() == b.len())
+                    && a.iter().all(|(key, a)| {
+                        b.get(&**key as &str)
+                            .is_some_and(|b| a.equals_with(b, options))
+                    })
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// Whether each element of `a` can be given its own element of `b` that it
+/// equals.
+///
+/// Unless extra keys are ignored the comparison is an equivalence, so any
+/// element equal to another can stand in for it and pairing greedily finds
+/// a match if there is one. Ignoring extra keys makes it one-sided, where a
+/// greedy pairing could miss a match, so the pairs are found by augmenting
+/// paths instead.
+fn pairs_off(a: &[Type], b: &[Type], options: CompareOptions) -> bool {
+    if !options.ignore_extra_keys {
+        let mut unpaired: Vec<&Type> = b.iter().collect();
+        return a.iter().all(
+            |a| match unpaired.iter().position(|b| a.equals_with(b, options)) {
+                Some(j) => {
+                    unpaired.swap_remove(j);
+                    true
+                }
+                None => false,
+            },
+        );
+    }
+    let candidates: Vec<Vec<usize>> = a
+        .iter()
+        .map(|a| {
+            (0..b.len())
+                .filter(|&j| a.equals_with(&b[j], options))
+                .collect()
+        })
+        .collect();
+    let mut owner: Vec<Option<usize>> = vec![None; b.len()];
+    (0..a.len()).all(|i| {
+        let mut seen = vec![false; b.len()];
+        augment(i, &candidates, &mut owner, &mut seen)
+    })
+}
+
+fn augment(
+    i: usize,
+    candidates: &[Vec<usize>],
+    owner: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for &j in &candidates[i] {
+        if seen[j] {
+            continue;
+        }
+        seen[j] = true;
+        if owner[j].is_none_or(|other| augment(other, candidates, owner, seen)) {
+            owner[j] = Some(i);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompareOptions;
+    use crate::parse;
+
+    fn equals(a: &str, b: &str, unordered_arrays: bool, ignore_extra_keys: bool) -> bool {
+        let options = CompareOptions {
+            unordered_arrays,
+            ignore_extra_keys,
+        };
+        parse(a).unwrap().equals_with(&parse(b).unwrap(), options)
+    }
+
+    #[test]
+    fn compares_loosely() {
+        let a = r#"{"ids": [1, 2, 2], "user": {"name": "a"}}"#;
+        let b = r#"{"ids": [2, 1, 2], "user": {"name": "a", "age": 3}, "extra": []}"#;
+        assert!(equals(a, a, false, false));
+        assert!(!equals(a, b, false, false));
+        assert!(!equals(a, b, true, false));
+        assert!(!equals(a, b, false, true));
+        assert!(equals(a, b, true, true));
+        assert!(!equals(b, a, true, true));
+
+        assert!(!equals("[1, 2, 2]", "[1, 1, 2]", true, false));
+        assert!(!equals("[1, 2]", "[2, 1, 3]", true, true));
+        assert!(equals("[[1, 2], [3]]", "[[3], [2, 1]]", true, false));
+        // The first element on the left fits both on the right; a greedy
+        // pairing that gave it the first would strand the second.
+        assert!(equals(
+            r#"[{}, {"a": 1}]"#,
+            r#"[{"a": 1, "b": 2}, {"a": 2}]"#,
+            true,
+            true
+        ));
+        assert!(!equals(r#"{"a": 1}"#, r#"{"a": "1"}"#, false, true));
+
+        let many = format!("[{}]", vec!["[1, 2]"; 5000].join(", "));
+        assert!(equals(&many, &many, true, false));
+    }
+}
@@ -0,0 +1,350 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::hash::BuildHasher;
+
+use crate::{into_str, JsonPointer, Map, Type};
+
+/// Converts a value into a [`Type`]; the serde-free counterpart of
+/// `Serialize`.
+pub trait ToJson {
+    fn to_json(&self) -> Type;
+}
+
+/// Builds a value from a [`Type`]; the serde-free counterpart of
+/// `Deserialize`.
+pub trait FromJson: Sized {
+    fn from_json(value: &Type) -> Result<Self, DecodeError>;
+}
+
+/// An error from [`FromJson`]: the value at `pointer` does not have the
+/// expected shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub pointer: JsonPointer,
+    pub message: String,
+}
+
+impl DecodeError {
+    /// An error at the value being decoded.
+    pub fn new(message: impl Into<String>) -> Self {
+        DecodeError {
+            pointer: JsonPointer::root(),
+            message: message.into(),
+        }
+    }
+
+    /// An error for `found` not being `what`, naming the kind it is instead:
+    /// `expected a number, found string`.
+    pub fn expected(what: &str, found: &Type) -> Self {
+        DecodeError::new(alloc::format!("expected {}, found {}", what, found.kind()))
+    }
+
+    /// Moves the error one level down, for an error from decoding the member
+    /// or element `token` of the value.
+    pub fn within(self, token: impl Into<String>) -> Self {
+        let mut pointer = JsonPointer::root();
+        pointer.push(token);
+        for token in self.pointer.tokens() {
+            pointer.push(token.as_str());
+        }
+        DecodeError { pointer, ..self }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}': {}", self.pointer, self.message)
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+impl ToJson for Type {
+    fn to_json(&self) -> Type {
+        self.clone()
+    }
+}
+
+impl FromJson for Type {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        Ok(value.clone())
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Type {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Type {
+        (**self).to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Type {
+        Type::Boolean(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        match value {
+            Type::Boolean(b) => Ok(*b),
+            other => Err(DecodeError::expected("a boolean", other)),
+        }
+    }
+}
+
+macro_rules! integers {
+    ($($t:ty)*) => {$(
+        impl ToJson for $t {
+            fn to_json(&self) -> Type {
+                Type::Number((*self).into())
+            }
+        }
+
+        impl FromJson for $t {
+            fn from_json(value: &Type) -> Result<Self, DecodeError> {
+                match value {
+                    Type::Number(n) if n.to_f64() % 1.0 == 0.0 => n
+                        .as_i64()
+                        .map(i128::from)
+                        .or(n.as_u64().map(i128::from))
+                        .and_then(|n| <$t>::try_from(n).ok())
+                        .ok_or_else(|| DecodeError::new("integer out of range")),
+                    other => Err(DecodeError::expected("an integer", other)),
+                }
+            }
+        }
+    )*};
+}
+
+integers!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+/// Non-finite floats have no JSON form and become `null`.
+impl ToJson for f64 {
+    fn to_json(&self) -> Type {
+        if self.is_finite() {
+            Type::Number((*self).into())
+        } else {
+            Type::Null
+        }
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        match value {
+            Type::Number(n) => Ok(n.to_f64()),
+            other => Err(DecodeError::expected("a number", other)),
+        }
+    }
+}
+
+impl ToJson for f32 {
+    fn to_json(&self) -> Type {
+        f64::from(*self).to_json()
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        f64::from_json(value).map(|n| n as f32)
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Type {
+        Type::String(self.into())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Type {
+        Type::String(self.as_str().into())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        match value {
+            Type::String(s) => Ok(s.as_str().into()),
+            other => Err(DecodeError::expected("a string", other)),
+        }
+    }
+}
+
+impl ToJson for char {
+    fn to_json(&self) -> Type {
+        Type::String(into_str(self.to_string()))
+    }
+}
+
+impl FromJson for char {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        let s = String::from_json(value)?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Ok(ch),
+            _ => Err(DecodeError::new("expected a single character")),
+        }
+    }
+}
+
+/// `None` is `null`.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Type {
+        self.as_ref().map_or(Type::Null, T::to_json)
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        match value {
+            Type::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Type {
+        Type::Array(self.iter().map(T::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Type {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        let Type::Array(items) = value else {
+            return Err(DecodeError::expected("an array", value));
+        };
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| T::from_json(item).map_err(|err| err.within(i.to_string())))
+            .collect()
+    }
+}
+
+fn to_object<'a, K, V>(members: impl Iterator<Item = (&'a K, &'a V)>) -> Type
+where
+    K: AsRef<str> + 'a,
+    V: ToJson + 'a,
+{
+    let members: Map<_, _> = members
+        .map(|(key, value)| (key.as_ref().into(), value.to_json()))
+        .collect();
+    Type::Object(Box::new(members))
+}
+
+fn from_object<C, V>(value: &Type) -> Result<C, DecodeError>
+where
+    C: FromIterator<(String, V)>,
+    V: FromJson,
+{
+    let Type::Object(members) = value else {
+        return Err(DecodeError::expected("an object", value));
+    };
+    members
+        .iter()
+        .map(|(key, member)| match V::from_json(member) {
+            Ok(value) => Ok((String::from(&**key), value)),
+            Err(err) => Err(err.within(&**key)),
+        })
+        .collect()
+}
+
+impl<K: AsRef<str>, V: ToJson> ToJson for BTreeMap<K, V> {
+    fn to_json(&self) -> Type {
+        to_object(self.iter())
+    }
+}
+
+impl<V: FromJson> FromJson for BTreeMap<String, V> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        from_object(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: AsRef<str>, V: ToJson, S> ToJson for HashMap<K, V, S> {
+    fn to_json(&self) -> Type {
+        to_object(self.iter())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<V: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, V, S> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        from_object(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+
+    use super::{DecodeError, FromJson, ToJson};
+    use crate::{parse, Type};
+
+    #[test]
+    fn round_trips_std_types() {
+        let mut users = BTreeMap::new();
+        users.insert("ada", vec![Some(1u8), None]);
+        let json = users.to_json();
+        assert_eq!(json, parse(r#"{"ada": [1, null]}"#).unwrap());
+        let back: BTreeMap<String, Vec<Option<u8>>> = FromJson::from_json(&json).unwrap();
+        assert_eq!(back["ada"], [Some(1), None]);
+
+        assert_eq!(f64::NAN.to_json(), Type::Null);
+        assert_eq!("é".to_json(), Type::String("é".into()));
+        assert_eq!(char::from_json(&'x'.to_json()), Ok('x'));
+        assert_eq!(i64::from_json(&(-5i64).to_json()), Ok(-5));
+        assert_eq!(f32::from_json(&parse("0.5").unwrap()), Ok(0.5));
+        let flags: std::collections::HashMap<String, u8> =
+            FromJson::from_json(&parse(r#"{"a": 1}"#).unwrap()).unwrap();
+        assert_eq!(flags["a"], 1);
+    }
+
+    #[test]
+    fn reports_where_decoding_failed() {
+        let error = |json: &str| {
+            <Vec<BTreeMap<String, u8>>>::from_json(&parse(json).unwrap())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(error("{}"), "'': expected an array, found object");
+        assert_eq!(
+            error(r#"[{}, {"a/b": 1.5}]"#),
+            "'/1/a~1b': expected an integer, found number"
+        );
+        assert_eq!(error(r#"[{"a": 256}]"#), "'/0/a': integer out of range");
+        assert_eq!(error(r#"[{"a": -1}]"#), "'/0/a': integer out of range");
+        assert_eq!(
+            char::from_json(&parse(r#""ab""#).unwrap()),
+            Err(DecodeError::new("expected a single character"))
+        );
+        assert!(u64::from_json(&parse("1e30").unwrap()).is_err());
+    }
+}
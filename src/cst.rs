@@ -0,0 +1,413 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::lexer::{check_string, decode_string, Lexer, TokenKind};
+use crate::parser::Grammar;
+use crate::pointer::{parse_index, JsonPointer};
+use crate::ser::write_string;
+use crate::{parse_lexer, to_string, JsonError, Type};
+
+/// A lossless parse of a JSON document: printing it with `Display` gives back
+/// the input byte for byte.
+///
+/// Whitespace and comments are kept verbatim as the trivia before each token,
+/// and scalars keep their exact lexemes, so editing one node and printing the
+/// document changes only that node's text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cst {
+    pub root: CstNode,
+    /// Trivia after the root value.
+    pub trailing: String,
+}
+
+/// A value in a [`Cst`], with the trivia before its first token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstNode {
+    pub before: String,
+    pub kind: CstKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CstKind {
+    /// A string, number, `true`, `false` or `null`, exactly as written.
+    Scalar(String),
+    Array {
+        items: Vec<CstElement>,
+        /// Trivia before the `]`.
+        before_close: String,
+    },
+    Object {
+        members: Vec<CstMember>,
+        /// Trivia before the `}`.
+        before_close: String,
+    },
+}
+
+/// An array element, with the trivia before the comma that follows it, if
+/// there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstElement {
+    pub value: CstNode,
+    pub comma: Option<String>,
+}
+
+/// An object member. `key` is the key's lexeme, quotes and escapes included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstMember {
+    pub before_key: String,
+    pub key: String,
+    pub before_colon: String,
+    pub value: CstNode,
+    pub comma: Option<String>,
+}
+
+impl CstMember {
+    /// The key with its escapes decoded.
+    pub fn name(&self) -> Option<String> {
+        let raw = self.key.strip_prefix('"')?.strip_suffix('"')?;
+        decode_string(raw.as_bytes(), 1).ok()
+    }
+}
+
+impl CstNode {
+    /// Looks up a node by JSON Pointer; `None` if the pointer is malformed or
+    /// addresses nothing. As in a [`Type`], the last of several equal keys
+    /// wins.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut CstNode> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        pointer
+            .tokens()
+            .iter()
+            .try_fold(self, |node, token| match &mut node.kind {
+                CstKind::Scalar(_) => None,
+                CstKind::Array { items, .. } => items
+                    .get_mut(parse_index(token)?)
+                    .map(|item| &mut item.value),
+                CstKind::Object { members, .. } => members
+                    .iter_mut()
+                    .rev()
+                    .find(|member| member.name().as_deref() == Some(token.as_str()))
+                    .map(|member| &mut member.value),
+            })
+    }
+
+    /// Replaces the node's value with `value` in compact form, keeping the
+    /// trivia around it.
+    pub fn set(&mut self, value: &Type) {
+        self.kind = compact_kind(value);
+    }
+
+    /// Decodes the node, ignoring its trivia.
+    pub fn to_type(&self) -> Result<Type, JsonError> {
+        let text = self.to_string();
+        parse_lexer(Lexer::new(text.as_bytes()).with_comments())
+    }
+}
+
+/// The kind of a node printing `value` as [`to_string`] does.
+fn compact_kind(value: &Type) -> CstKind {
+    let node = |value| CstNode {
+        before: String::new(),
+        kind: compact_kind(value),
+    };
+    // Every element but the last is followed by a comma.
+    let comma = |i: usize, len: usize| (i + 1 < len).then(String::new);
+    match value {
+        Type::Array(items) => CstKind::Array {
+            items: items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| CstElement {
+                    value: node(item),
+                    comma: comma(i, items.len()),
+                })
+                .collect(),
+            before_close: String::new(),
+        },
+        Type::Object(members) => CstKind::Object {
+            members: members
+                .iter()
+                .enumerate()
+                .map(|(i, (key, member))| {
+                    let mut lexeme = String::new();
+                    write_string(&mut lexeme, key, false, false)
+                        .expect("writing to a String never fails");
+                    CstMember {
+                        before_key: String::new(),
+                        key: lexeme,
+                        before_colon: String::new(),
+                        value: node(member),
+                        comma: comma(i, members.len()),
+                    }
+                })
+                .collect(),
+            before_close: String::new(),
+        },
+        scalar => CstKind::Scalar(to_string(scalar)),
+    }
+}
+
+impl Cst {
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut CstNode> {
+        self.root.pointer_mut(pointer)
+    }
+
+    pub fn to_type(&self) -> Result<Type, JsonError> {
+        self.root.to_type()
+    }
+}
+
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.before)?;
+        match &self.kind {
+            CstKind::Scalar(lexeme) => f.write_str(lexeme),
+            CstKind::Array {
+                items,
+                before_close,
+            } => {
+                f.write_str("[")?;
+                for item in items {
+                    write!(f, "{}", item.value)?;
+                    if let Some(before) = &item.comma {
+                        write!(f, "{},", before)?;
+                    }
+                }
+                write!(f, "{}]", before_close)
+            }
+            CstKind::Object {
+                members,
+                before_close,
+            } => {
+                f.write_str("{")?;
+                for member in members {
+                    write!(
+                        f,
+                        "{}{}{}:{}",
+                        member.before_key, member.key, member.before_colon, member.value
+                    )?;
+                    if let Some(before) = &member.comma {
+                        write!(f, "{},", before)?;
+                    }
+                }
+                write!(f, "{}}}", before_close)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.root, self.trailing)
+    }
+}
+
+enum Frame {
+    Array(String, Vec<CstElement>),
+    /// Holds the key, and the trivia before it and before the colon, until
+    /// the member's value arrives.
+    Object(String, Vec<CstMember>, Option<(String, String, String)>),
+}
+
+/// Parses `json` into a [`Cst`]. `//` and `/* */` comments are accepted and
+/// kept, as config files that need lossless editing often have them.
+pub fn parse_cst(json: &str) -> Result<Cst, JsonError> {
+    let mut lexer = Lexer::new(json.as_bytes()).with_comments().skip_bom();
+    let mut grammar = Grammar::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root = None;
+    // End of the previous token; everything from there to the next token is
+    // trivia.
+    let mut prev = 0;
+    while let Some(token) = lexer.next_token()? {
+        let is_key = grammar.expects_key();
+        grammar.accept(token)?;
+        let before = json[prev..token.start].to_string();
+        let lexeme = &json[token.start..token.end];
+        prev = token.end;
+        let node = match token.kind {
+            TokenKind::BeginArray => {
+                stack.push(Frame::Array(before, Vec::new()));
+                continue;
+            }
+            TokenKind::BeginObject => {
+                stack.push(Frame::Object(before, Vec::new(), None));
+                continue;
+            }
+            TokenKind::Colon => {
+                if let Some(Frame::Object(_, _, Some(pending))) = stack.last_mut() {
+                    pending.2 = before;
+                }
+                continue;
+            }
+            TokenKind::Comma => {
+                match stack.last_mut() {
+                    Some(Frame::Array(_, items)) => {
+                        if let Some(item) = items.last_mut() {
+                            item.comma = Some(before);
+                        }
+                    }
+                    Some(Frame::Object(_, members, _)) => {
+                        if let Some(member) = members.last_mut() {
+                            member.comma = Some(before);
+                        }
+                    }
+                    None => {}
+                }
+                continue;
+            }
+            TokenKind::EndArray | TokenKind::EndObject => {
+                let node = match stack.pop() {
+                    Some(Frame::Array(open, items)) => CstNode {
+                        before: open,
+                        kind: CstKind::Array {
+                            items,
+                            before_close: before,
+                        },
+                    },
+                    Some(Frame::Object(open, members, _)) => CstNode {
+                        before: open,
+                        kind: CstKind::Object {
+                            members,
+                            before_close: before,
+                        },
+                    },
+                    None => unreachable!("the grammar balances containers"),
+                };
+                node
+            }
+            TokenKind::String => {
+                check_string(&lexeme.as_bytes()[1..lexeme.len() - 1], token.start + 1)?;
+                if is_key {
+                    if let Some(Frame::Object(_, _, pending)) = stack.last_mut() {
+                        *pending = Some((before, lexeme.to_string(), String::new()));
+                    }
+                    continue;
+                }
+                CstNode {
+                    before,
+                    kind: CstKind::Scalar(lexeme.to_string()),
+                }
+            }
+            TokenKind::Number | TokenKind::True | TokenKind::False | TokenKind::Null => CstNode {
+                before,
+                kind: CstKind::Scalar(lexeme.to_string()),
+            },
+        };
+        match stack.last_mut() {
+            None => root = Some(node),
+            Some(Frame::Array(_, items)) => items.push(CstElement {
+                value: node,
+                comma: None,
+            }),
+            Some(Frame::Object(_, members, pending)) => {
+                if let Some((before_key, key, before_colon)) = pending.take() {
+                    members.push(CstMember {
+                        before_key,
+                        key,
+                        before_colon,
+                        value: node,
+                        comma: None,
+                    });
+                }
+            }
+        }
+    }
+    let root = root.ok_or(JsonError::UnexpectEof { offset: json.len() })?;
+    Ok(Cst {
+        root,
+        trailing: json[prev..].to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_cst;
+    use crate::{minify_relaxed, parse, to_string, Expected, JsonError, Type, MAX_DEPTH};
+
+    const CONFIG: &str = r#"
+// Service settings.
+{
+    "name" : "api",   /* shown in logs */
+    "port": 8080,
+    "ratio": 1.50e0,
+    "tags": [ "a",
+              "b\u0021" ],
+    "empty": { }
+}
+"#;
+
+    #[test]
+    fn round_trips_exactly() {
+        for json in [
+            CONFIG,
+            " [ ] ",
+            "1",
+            "{\"a\":[1,[2,{}]]}\n",
+            "\"\\u00e9\" // end",
+        ] {
+            assert_eq!(parse_cst(json).unwrap().to_string(), json, "{}", json);
+        }
+        let cst = parse_cst(CONFIG).unwrap();
+        assert_eq!(
+            cst.to_type().unwrap(),
+            parse(&minify_relaxed(CONFIG).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn edits_touch_only_the_node() {
+        let mut cst = parse_cst(CONFIG).unwrap();
+        cst.pointer_mut("/port")
+            .unwrap()
+            .set(&Type::Number(9090.into()));
+        cst.pointer_mut("/tags/1")
+            .unwrap()
+            .set(&Type::String("c".into()));
+        assert_eq!(
+            cst.to_string(),
+            CONFIG
+                .replace("8080", "9090")
+                .replace(r#""b\u0021""#, r#""c""#)
+        );
+        assert_eq!(
+            cst.pointer_mut("/ratio").unwrap().to_type(),
+            Ok(Type::Number(1.5.into()))
+        );
+        assert!(cst.pointer_mut("/missing").is_none());
+
+        let value = parse(r#"{"a\"b": [1, {}, []], "c": null}"#).unwrap();
+        let mut deep = Type::Array(Vec::new());
+        for _ in 0..2 * MAX_DEPTH {
+            deep = Type::Array(vec![deep, value.clone()]);
+        }
+        for value in [value, deep] {
+            let mut cst = parse_cst(" [0] ").unwrap();
+            cst.root.set(&value);
+            assert_eq!(cst.to_string(), format!(" {} ", to_string(&value)));
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse_cst("[1,]").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::VALUE
+            }
+        );
+        assert_eq!(
+            parse_cst("\"\\x\"").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 2,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            parse_cst("  ").unwrap_err(),
+            JsonError::UnexpectEof { offset: 2 }
+        );
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, Read};
+
+use crate::{into_str, parse, to_string, JsonPointer, Key, Map, Type};
+
+/// An error from [`from_csv`] or [`to_csv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvError {
+    /// The CSV is malformed at `line`, counted from one.
+    Syntax {
+        line: usize,
+        message: &'static str,
+    },
+    /// The value at `pointer` cannot be written as a CSV table.
+    Unrepresentable {
+        pointer: JsonPointer,
+        message: &'static str,
+    },
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::Syntax { line, message } => {
+                write!(f, "invalid CSV at line {}: {}", line, message)
+            }
+            CsvError::Unrepresentable { pointer, message } => {
+                write!(f, "'{}': {}", pointer, message)
+            }
+            CsvError::Io(kind) => write!(f, "I/O error: {}", kind),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<io::Error> for CsvError {
+    fn from(err: io::Error) -> Self {
+        CsvError::Io(err.kind())
+    }
+}
+
+/// Writes an array of objects as CSV, one row per object under a header row.
+///
+/// Nested fields are flattened as by [`Type::flatten`], so `{"a": {"b": 1}}`
+/// fills the column `a.b`. The columns are the sorted union of every row's
+/// fields; a field missing from a row leaves its cell empty, and `null` is
+/// written as `null`. Anything but an array of objects is
+/// `CsvError::Unrepresentable`.
+pub fn to_csv(value: &Type) -> Result<String, CsvError> {
+    let Type::Array(items) = value else {
+        return Err(CsvError::Unrepresentable {
+            pointer: JsonPointer::root(),
+            message: "CSV needs an array of objects",
+        });
+    };
+    let mut rows = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        match item {
+            Type::Object(members) if members.is_empty() => rows.push(Map::default()),
+            Type::Object(_) => match item.flatten() {
+                Type::Object(fields) => rows.push(*fields),
+                _ => unreachable!("flatten always returns an object"),
+            },
+            _ => {
+                let mut pointer = JsonPointer::root();
+                pointer.push(i.to_string());
+                return Err(CsvError::Unrepresentable {
+                    pointer,
+                    message: "a CSV row must be an object",
+                });
+            }
+        }
+    }
+    let columns: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|row| row.keys().map(|key| &**key))
+        .collect();
+    let mut out = String::new();
+    if columns.is_empty() {
+        return Ok(out);
+    }
+    write_record(&mut out, columns.iter().copied());
+    for row in &rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| row.get(*column).map_or_else(String::new, cell))
+            .collect();
+        write_record(&mut out, cells.iter().map(String::as_str));
+    }
+    Ok(out)
+}
+
+fn cell(value: &Type) -> String {
+    match value {
+        Type::String(s) => s.as_str().into(),
+        value => to_string(value),
+    }
+}
+
+fn write_record<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push('\n');
+}
+
+/// Reads CSV with a header row into an array of objects, one per record.
+///
+/// Dotted headers are nested as by [`Type::unflatten`], so the output of
+/// [`to_csv`] reads back to the same shape. Cells that are `true`, `false`,
+/// `null`, a JSON number or an empty `[]` or `{}` take that type; any other
+/// cell is a string, and an empty cell leaves its field out of the row.
+/// Fields may be quoted, with `""` for a quote inside, and rows may end in
+/// `\n` or `\r\n`. Blank lines are skipped, and so is a UTF-8 byte-order
+/// mark at the start, which spreadsheet programs often write.
+pub fn from_csv<R: Read>(mut reader: R) -> Result<Type, CsvError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut records = Records::new(text.strip_prefix('\u{feff}').unwrap_or(&text));
+    let Some((_, header)) = records.next().transpose()? else {
+        return Ok(Type::Array(Vec::new()));
+    };
+    let mut rows = Vec::new();
+    for record in records {
+        let (line, fields) = record?;
+        if fields.len() > header.len() {
+            return Err(CsvError::Syntax {
+                line,
+                message: "the row has more fields than the header",
+            });
+        }
+        let mut row: Map<Key, Type> = Map::default();
+        for (name, field) in header.iter().zip(fields) {
+            if !field.is_empty() {
+                row.insert(name.as_str().into(), sniff(field));
+            }
+        }
+        rows.push(
+            Type::Object(Box::new(row))
+                .unflatten()
+                .map_err(|_| CsvError::Syntax {
+                    line,
+                    message: "one field of the row is nested inside another",
+                })?,
+        );
+    }
+    Ok(Type::Array(rows))
+}
+
+fn sniff(field: String) -> Type {
+    let is_literal = field.starts_with(|ch: char| ch.is_ascii_digit() || "-tfn[{".contains(ch))
+        && !field.ends_with(|ch: char| ch.is_ascii_whitespace());
+    match parse(&field) {
+        Ok(value @ (Type::Null | Type::Boolean(_) | Type::Number(_))) if is_literal => value,
+        Ok(Type::Array(items)) if is_literal && items.is_empty() => Type::Array(items),
+        Ok(Type::Object(members)) if is_literal && members.is_empty() => Type::Object(members),
+        _ => Type::String(into_str(field)),
+    }
+}
+
+/// Splits CSV text into records, each with the line it starts on.
+struct Records<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> Records<'a> {
+    fn new(text: &'a str) -> Self {
+        Records {
+            rest: text,
+            line: 1,
+        }
+    }
+
+    fn record(&mut self) -> Result<Vec<String>, CsvError> {
+        let mut fields = Vec::new();
+        loop {
+            let mut field = String::new();
+            if let Some(quoted) = self.rest.strip_prefix('"') {
+                self.rest = quoted;
+                loop {
+                    let Some(end) = self.rest.find('"') else {
+                        return Err(CsvError::Syntax {
+                            line: self.line,
+                            message: "unterminated quoted field",
+                        });
+                    };
+                    field.push_str(&self.rest[..end]);
+                    self.line += self.rest[..end].matches('\n').count();
+                    self.rest = &self.rest[end + 1..];
+                    match self.rest.strip_prefix('"') {
+                        Some(rest) => {
+                            field.push('"');
+                            self.rest = rest;
+                        }
+                        None => break,
+                    }
+                }
+            } else {
+                let end = self.rest.find([',', '\r', '\n']).unwrap_or(self.rest.len());
+                field.push_str(&self.rest[..end]);
+                if field.contains('"') {
+                    return Err(CsvError::Syntax {
+                        line: self.line,
+                        message: "quote inside an unquoted field",
+                    });
+                }
+                self.rest = &self.rest[end..];
+            }
+            fields.push(field);
+            let rest = self.rest.strip_prefix(',');
+            if let Some(rest) = rest {
+                self.rest = rest;
+                continue;
+            }
+            let rest = self
+                .rest
+                .strip_prefix("\r\n")
+                .or_else(|| self.rest.strip_prefix('\n'));
+            match rest {
+                Some(rest) => {
+                    self.rest = rest;
+                    self.line += 1;
+                }
+                None if self.rest.is_empty() => {}
+                None => {
+                    return Err(CsvError::Syntax {
+                        line: self.line,
+                        message: "expected a comma or the end of the row after a quoted field",
+                    })
+                }
+            }
+            return Ok(fields);
+        }
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<(usize, Vec<String>), CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let line = self.line;
+            match self.record() {
+                Ok(fields) if fields.len() == 1 && fields[0].is_empty() => {}
+                Ok(fields) => return Some(Ok((line, fields))),
+                Err(err) => {
+                    self.rest = "";
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_csv, to_csv, CsvError};
+    use crate::parse;
+
+    #[test]
+    fn writes_csv() {
+        let value = parse(
+            r#"[
+                {"id": 1, "name": "Ada", "tags": ["x", "y"], "address": {"city": "London"}},
+                {"id": 2, "name": "Smith, \"J\"", "note": null, "empty": []}
+            ]"#,
+        )
+        .unwrap();
+        let csv = to_csv(&value).unwrap();
+        assert_eq!(
+            csv,
+            "address.city,empty,id,name,note,tags.0,tags.1
+London,,1,Ada,,x,y
+,[],2,\"Smith, \"\"J\"\"\",null,,
+"
+        );
+        assert_eq!(from_csv(csv.as_bytes()), Ok(value));
+        assert_eq!(to_csv(&parse("[]").unwrap()), Ok(String::new()));
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        let error = |json: &str| to_csv(&parse(json).unwrap()).unwrap_err().to_string();
+        assert_eq!(error("{}"), "'': CSV needs an array of objects");
+        assert_eq!(
+            error(r#"[{"a": 1}, 2]"#),
+            "'/1': a CSV row must be an object"
+        );
+    }
+
+    #[test]
+    fn reads_csv() {
+        let csv = "name,age,active,score,raw\r\n\
+                   Ada,36,true,-1.5e2,\"multi\nline\"\r\n\
+                   \r\n\
+                   \"Bob\",007, false ,1,\"\"\n\
+                   Cy\n";
+        let expected = parse(
+            r#"[
+                {"name": "Ada", "age": 36, "active": true, "score": -150, "raw": "multi\nline"},
+                {"name": "Bob", "age": "007", "active": " false ", "score": 1},
+                {"name": "Cy"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(from_csv(csv.as_bytes()), Ok(expected));
+        assert_eq!(from_csv(&b""[..]), Ok(parse("[]").unwrap()));
+        assert_eq!(
+            from_csv("\u{feff}id\n1\n".as_bytes()),
+            Ok(parse(r#"[{"id": 1}]"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |csv: &str| from_csv(csv.as_bytes()).unwrap_err();
+        assert_eq!(
+            error("a\n\"x\ny\n"),
+            CsvError::Syntax {
+                line: 2,
+                message: "unterminated quoted field"
+            }
+        );
+        assert_eq!(
+            error("a\n1\n\"q\"x\n").to_string(),
+            "invalid CSV at line 3: expected a comma or the end of the row after a quoted field"
+        );
+        assert_eq!(
+            error("a\nx\"y\n").to_string(),
+            "invalid CSV at line 2: quote inside an unquoted field"
+        );
+        assert_eq!(
+            error("a,b\n1,2,3\n").to_string(),
+            "invalid CSV at line 2: the row has more fields than the header"
+        );
+        assert_eq!(
+            error("a,a.b\n1,2\n").to_string(),
+            "invalid CSV at line 2: one field of the row is nested inside another"
+        );
+        assert_eq!(
+            from_csv(&b"a\n\xff\n"[..]),
+            Err(CsvError::Io(std::io::ErrorKind::InvalidData))
+        );
+    }
+}
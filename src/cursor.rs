@@ -0,0 +1,175 @@
+use alloc::string::{String, ToString};
+
+use crate::pointer::parse_index;
+use crate::{JsonPointer, Type};
+
+/// A position in a document that can move around it and edit it in place,
+/// remembering the path from the root. Made by [`Type::cursor`].
+///
+/// Moves that lead nowhere return `false` and leave the cursor where it was.
+#[derive(Debug)]
+pub struct Cursor<'a> {
+    root: &'a mut Type,
+    pointer: JsonPointer,
+}
+
+impl Type {
+    /// A cursor at this value, as the root of what it can reach.
+    pub fn cursor(&mut self) -> Cursor<'_> {
+        Cursor {
+            root: self,
+            pointer: JsonPointer::root(),
+        }
+    }
+}
+
+impl<'a> Cursor<'a> {
+    /// The pointer from the root to the current value.
+    pub fn pointer(&self) -> &JsonPointer {
+        &self.pointer
+    }
+
+    pub fn value(&self) -> &Type {
+        self.root
+            .at_tokens(self.pointer.tokens())
+            .expect("the cursor points at a value")
+    }
+
+    pub fn value_mut(&mut self) -> &mut Type {
+        self.root
+            .tokens_mut(self.pointer.tokens())
+            .expect("the cursor points at a value")
+    }
+
+    /// Moves to the member `key` of the current object.
+    pub fn down_key(&mut self, key: &str) -> bool {
+        let found = matches!(self.value(), Type::Object(members) if members.contains_key(key));
+        if found {
+            self.pointer.push(key);
+        }
+        found
+    }
+
+    /// Moves to element `index` of the current array.
+    pub fn down_index(&mut self, index: usize) -> bool {
+        let found = matches!(self.value(), Type::Array(items) if index < items.len());
+        if found {
+            self.pointer.push(index.to_string());
+        }
+        found
+    }
+
+    /// Moves to the object or array holding the current value.
+    pub fn up(&mut self) -> bool {
+        self.pointer.pop().is_some()
+    }
+
+    /// Moves to the next element of the enclosing array, or the next member
+    /// of the enclosing object in its iteration order.
+    pub fn next_sibling(&mut self) -> bool {
+        let Some((last, path)) = self.pointer.tokens().split_last() else {
+            return false;
+        };
+        let next = match self.root.at_tokens(path) {
+            Some(Type::Object(members)) => members
+                .keys()
+                .skip_while(|key| ***key != **last)
+                .nth(1)
+                .map(|key| String::from(&**key)),
+            Some(Type::Array(items)) => parse_index(last)
+                .map(|index| index + 1)
+                .filter(|&index| index < items.len())
+                .map(|index| index.to_string()),
+            _ => None,
+        };
+        match next {
+            Some(token) => {
+                self.pointer.pop();
+                self.pointer.push(token);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves back to the root.
+    pub fn up_to_root(&mut self) {
+        self.pointer = JsonPointer::root();
+    }
+
+    /// Replaces the current value with `value`, returning the old one.
+    pub fn replace(&mut self, value: Type) -> Type {
+        core::mem::replace(self.value_mut(), value)
+    }
+
+    /// Takes the current value out of its object or array and moves up to
+    /// that parent. `None`, removing nothing, at the root.
+    pub fn remove(&mut self) -> Option<Type> {
+        let removed = self.root.remove_tokens(self.pointer.tokens())?;
+        self.pointer.pop();
+        Some(removed)
+    }
+
+    /// Ends the walk, giving back the whole edited document.
+    pub fn into_root(self) -> &'a mut Type {
+        self.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use crate::{parse, Type};
+
+    #[test]
+    fn moves_around() {
+        let mut value =
+            parse(r#"{"users": [{"name": "Ada"}, {"name": "Grace"}], "n": 1}"#).unwrap();
+        let mut cursor = value.cursor();
+        assert!(cursor.down_key("users"));
+        assert!(cursor.down_index(0));
+        assert!(cursor.down_key("name"));
+        assert_eq!(cursor.pointer().to_string(), "/users/0/name");
+        assert_eq!(cursor.value(), &Type::String("Ada".into()));
+        assert!(!cursor.down_key("x"));
+        assert!(!cursor.next_sibling());
+        assert!(cursor.up());
+        assert!(cursor.next_sibling());
+        assert!(!cursor.next_sibling());
+        assert!(!cursor.down_index(0));
+        assert_eq!(cursor.pointer().to_string(), "/users/1");
+        cursor.up_to_root();
+        assert!(!cursor.up());
+        assert!(!cursor.next_sibling());
+        let first = match cursor.value() {
+            Type::Object(members) => String::from(&**members.keys().next().unwrap()),
+            other => panic!("{:?}", other),
+        };
+        assert!(cursor.down_key(&first));
+        let mut keys = vec![first];
+        while cursor.next_sibling() {
+            keys.push(cursor.pointer().tokens()[0].clone());
+        }
+        keys.sort();
+        assert_eq!(keys, ["n", "users"]);
+    }
+
+    #[test]
+    fn edits_in_place() {
+        let mut value = parse(r#"{"tags": ["a", "b", "c"], "n": 1}"#).unwrap();
+        let mut cursor = value.cursor();
+        cursor.down_key("tags");
+        cursor.down_index(1);
+        assert_eq!(cursor.remove(), Some(Type::String("b".into())));
+        assert_eq!(cursor.pointer().to_string(), "/tags");
+        cursor.down_index(1);
+        assert_eq!(cursor.replace(Type::Null), Type::String("c".into()));
+        cursor.up_to_root();
+        assert_eq!(cursor.remove(), None);
+        cursor.down_key("n");
+        *cursor.value_mut() = Type::Boolean(true);
+        let root = cursor.into_root();
+        assert_eq!(root, &parse(r#"{"tags": ["a", null], "n": true}"#).unwrap());
+    }
+}
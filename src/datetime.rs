@@ -0,0 +1,72 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat, TimeZone, Utc};
+
+use crate::{into_str, DecodeError, FromJson, ToJson, Type};
+
+impl Type {
+    /// Parses a string value as an RFC 3339 timestamp, such as
+    /// `2024-05-01T12:30:00.5+02:00`, keeping its offset. `None` for other
+    /// values and for strings that are not such timestamps.
+    pub fn as_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Type::String(s) => DateTime::parse_from_rfc3339(s).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Written as RFC 3339 with as many fractional digits as needed, and `Z`
+/// for UTC.
+impl<Tz: TimeZone> ToJson for DateTime<Tz> {
+    fn to_json(&self) -> Type {
+        Type::String(into_str(self.to_rfc3339_opts(SecondsFormat::AutoSi, true)))
+    }
+}
+
+impl FromJson for DateTime<FixedOffset> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        value
+            .as_datetime()
+            .ok_or_else(|| DecodeError::new("expected an RFC 3339 timestamp"))
+    }
+}
+
+impl FromJson for DateTime<Utc> {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        DateTime::<FixedOffset>::from_json(value).map(|datetime| datetime.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+    use crate::{DecodeError, FromJson, ToJson, Type};
+
+    #[test]
+    fn reads_timestamps() {
+        let value = Type::String("2024-05-01T12:30:00.5+02:00".into());
+        let datetime = value.as_datetime().unwrap();
+        assert_eq!(datetime.offset(), &FixedOffset::east_opt(7200).unwrap());
+        assert_eq!(datetime.timestamp_millis(), 1714559400500);
+        assert_eq!(
+            DateTime::<Utc>::from_json(&value).unwrap().to_json(),
+            Type::String("2024-05-01T10:30:00.500Z".into())
+        );
+        assert_eq!(Type::String("2024-05-01".into()).as_datetime(), None);
+        assert_eq!(Type::Number(0.into()).as_datetime(), None);
+        assert_eq!(
+            DateTime::<FixedOffset>::from_json(&Type::Null),
+            Err(DecodeError::new("expected an RFC 3339 timestamp"))
+        );
+    }
+
+    #[test]
+    fn writes_timestamps() {
+        let utc = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(utc.to_json(), Type::String("2024-01-02T03:04:05Z".into()));
+        let local = utc.with_timezone(&FixedOffset::west_opt(5 * 3600).unwrap());
+        let value = local.to_json();
+        assert_eq!(value, Type::String("2024-01-01T22:04:05-05:00".into()));
+        assert_eq!(value.as_datetime(), Some(local));
+    }
+}
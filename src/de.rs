@@ -0,0 +1,472 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::lexer::Lexer;
+use crate::parser::{Event, Parser};
+use crate::{JsonError, JsonPointer, Number};
+
+/// An error from [`Deserializer`]: malformed JSON, or JSON that does not
+/// match the shape the target type expects.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeserializeError {
+    Json(JsonError),
+    /// A message from the type being deserialized, such as a missing field,
+    /// about the value at `pointer`.
+    Custom {
+        pointer: JsonPointer,
+        message: String,
+    },
+}
+
+impl DeserializeError {
+    /// Moves a custom error one level down, as for [`DecodeError::within`](crate::DecodeError::within).
+    fn within(self, token: impl Into<String>) -> Self {
+        match self {
+            DeserializeError::Custom { pointer, message } => {
+                let mut within = JsonPointer::root();
+                within.push(token);
+                for token in pointer.tokens() {
+                    within.push(token.as_str());
+                }
+                DeserializeError::Custom {
+                    pointer: within,
+                    message,
+                }
+            }
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeserializeError::Json(err) => err.fmt(f),
+            DeserializeError::Custom { pointer, message } if pointer.is_root() => {
+                f.write_str(message)
+            }
+            DeserializeError::Custom { pointer, message } => {
+                write!(f, "'{}': {}", pointer, message)
+            }
+        }
+    }
+}
+
+impl core::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        DeserializeError::Custom {
+            pointer: JsonPointer::root(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<JsonError> for DeserializeError {
+    fn from(err: JsonError) -> Self {
+        DeserializeError::Json(err)
+    }
+}
+
+/// A serde `Deserializer` that feeds parser events straight to the visitor,
+/// without building a [`Type`](crate::Type) first. Passed to
+/// `serde_transcode`, it converts JSON into any serde format in one pass.
+///
+/// Integral numbers are visited as `u64` or `i64` when they fit, and all
+/// other numbers as `f64`. Strings are decoded into owned `String`s, so
+/// types that borrow `&str` from the input cannot be deserialized.
+///
+/// Errors from the target type carry the JSON Pointer of the value they are
+/// about.
+pub struct Deserializer<'a> {
+    lexer: Lexer<'a>,
+    parser: Parser,
+    peeked: Option<Event>,
+    /// The index of the next element of each array being read.
+    indices: Vec<usize>,
+    /// The key or variant name whose value is read next.
+    key: Option<String>,
+}
+
+impl<'a> Deserializer<'a> {
+    pub fn new(json: &'a str) -> Self {
+        Deserializer::from_slice(json.as_bytes())
+    }
+
+    /// Reads UTF-8 bytes; invalid UTF-8 is an error inside string literals.
+    pub fn from_slice(json: &'a [u8]) -> Self {
+        Deserializer {
+            lexer: Lexer::new(json).skip_bom(),
+            parser: Parser::new(),
+            peeked: None,
+            indices: Vec::new(),
+            key: None,
+        }
+    }
+
+    /// Checks that nothing but whitespace follows the value.
+    pub fn end(&mut self) -> Result<(), DeserializeError> {
+        if self.peeked.is_none() && self.parser.next_event(&mut self.lexer)?.is_none() {
+            Ok(())
+        } else {
+            Err(de::Error::custom("the value has not been read to its end"))
+        }
+    }
+
+    fn next(&mut self) -> Result<Event, DeserializeError> {
+        if let Some(event) = self.peeked.take() {
+            return Ok(event);
+        }
+        self.parser.next_event(&mut self.lexer)?.ok_or_else(|| {
+            DeserializeError::Json(JsonError::UnexpectEof {
+                offset: self.lexer.input().len(),
+            })
+        })
+    }
+
+    fn peek(&mut self) -> Result<&Event, DeserializeError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next()?);
+        }
+        Ok(self.peeked.as_ref().unwrap())
+    }
+
+    /// Consumes the end of the container that a visitor stopped reading.
+    fn close(&mut self, end: Event, message: &'static str) -> Result<(), DeserializeError> {
+        if self.next()? == end {
+            Ok(())
+        } else {
+            Err(de::Error::custom(message))
+        }
+    }
+}
+
+/// Deserializes a `T` from one JSON document, rejecting trailing input.
+pub fn from_str<T: DeserializeOwned>(json: &str) -> Result<T, DeserializeError> {
+    let mut deserializer = Deserializer::new(json);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+fn visit_number<'de, V: Visitor<'de>>(n: Number, visitor: V) -> Result<V::Value, DeserializeError> {
+    if let Some(n) = n.as_u64() {
+        visitor.visit_u64(n)
+    } else if let Some(n) = n.as_i64() {
+        visitor.visit_i64(n)
+    } else {
+        visitor.visit_f64(n.to_f64())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'_> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.next()? {
+            Event::Null => visitor.visit_unit(),
+            Event::Boolean(b) => visitor.visit_bool(b),
+            Event::Number(n) => visit_number(n, visitor),
+            Event::String(s) => visitor.visit_string(s),
+            Event::StartArray => {
+                self.indices.push(0);
+                let value = visitor.visit_seq(&mut *self)?;
+                self.close(Event::EndArray, "the array has more elements than expected")?;
+                self.indices.pop();
+                Ok(value)
+            }
+            Event::StartObject => {
+                let value = visitor.visit_map(&mut *self)?;
+                self.close(
+                    Event::EndObject,
+                    "the object has more members than expected",
+                )?;
+                Ok(value)
+            }
+            Event::Key(_) | Event::EndArray | Event::EndObject => {
+                Err(de::Error::custom("expected a value"))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if *self.peek()? == Event::Null {
+            self.next()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit variants are strings, and other variants objects with a single
+    /// member named after the variant.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.next()? {
+            Event::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Event::StartObject => {
+                let value = visitor.visit_enum(&mut *self)?;
+                self.close(Event::EndObject, "expected a single variant")?;
+                Ok(value)
+            }
+            _ => Err(de::Error::custom(
+                "expected a string or an object for an enum",
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Deserializer<'_> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if *self.peek()? == Event::EndArray {
+            return Ok(None);
+        }
+        let index = self.indices.last().copied().unwrap_or_default();
+        let value = seed
+            .deserialize(&mut *self)
+            .map_err(|err| err.within(index.to_string()))?;
+        if let Some(next) = self.indices.last_mut() {
+            *next += 1;
+        }
+        Ok(Some(value))
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Deserializer<'_> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.peek()? {
+            Event::EndObject => Ok(None),
+            Event::Key(_) => match self.next()? {
+                Event::Key(key) => {
+                    let key = self.key.insert(key);
+                    seed.deserialize(key.as_str().into_deserializer()).map(Some)
+                }
+                _ => unreachable!(),
+            },
+            _ => Err(de::Error::custom("expected a key")),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let key = self.key.take();
+        within_key(seed.deserialize(self), key)
+    }
+}
+
+fn within_key<T>(
+    result: Result<T, DeserializeError>,
+    key: Option<String>,
+) -> Result<T, DeserializeError> {
+    match key {
+        Some(key) => result.map_err(|err| err.within(key)),
+        None => result,
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'_> {
+    type Error = DeserializeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        match self.next()? {
+            Event::Key(variant) => {
+                let variant = self.key.insert(variant);
+                let variant = seed.deserialize(
+                    IntoDeserializer::<DeserializeError>::into_deserializer(variant.as_str()),
+                )?;
+                Ok((variant, self))
+            }
+            _ => Err(de::Error::custom("expected a single variant")),
+        }
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'_> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        let key = self.key.take();
+        within_key(de::Deserialize::deserialize(self), key)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        let key = self.key.take();
+        within_key(seed.deserialize(self), key)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let key = self.key.take();
+        within_key(de::Deserializer::deserialize_seq(self, visitor), key)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let key = self.key.take();
+        within_key(de::Deserializer::deserialize_map(self, visitor), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use super::{from_str, DeserializeError, Deserializer};
+    use crate::JsonError;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Point,
+        Circle(f64),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        port: u16,
+        offset: i64,
+        ratio: f32,
+        tags: Vec<String>,
+        parent: Option<Box<Config>>,
+        limits: BTreeMap<String, u8>,
+        shapes: Vec<Shape>,
+    }
+
+    #[test]
+    fn deserializes_types() {
+        let config: Config = from_str(
+            r#"{
+                "name": "svc", "port": 8080, "offset": -3, "ratio": 0.5,
+                "tags": ["a", "b"], "limits": {"x": 1}, "extra": [{"ignored": true}],
+                "shapes": ["Point", {"Circle": 2.5}, {"Rect": {"w": 1, "h": 2}}],
+                "parent": {"name": "p", "port": 1, "offset": 0, "ratio": 1, "tags": [],
+                           "parent": null, "limits": {}, "shapes": []}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.name, "svc");
+        assert_eq!((config.port, config.offset, config.ratio), (8080, -3, 0.5));
+        assert_eq!(config.tags, ["a", "b"]);
+        assert_eq!(config.limits["x"], 1);
+        assert_eq!(
+            config.shapes,
+            [Shape::Point, Shape::Circle(2.5), Shape::Rect { w: 1, h: 2 }]
+        );
+        assert_eq!(config.parent.unwrap().name, "p");
+    }
+
+    #[test]
+    fn transcodes_without_a_tree() {
+        let json = r#"{"a": [1, -2, 2.5, 1e300, true, null], "b": {"c": "é\n"}}"#;
+        let mut out = Vec::new();
+        serde_transcode::transcode(
+            &mut Deserializer::new(json),
+            &mut serde_json::Serializer::new(&mut out),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"{"a":[1,-2,2.5,1e+300,true,null],"b":{"c":"é\n"}}"#
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            from_str::<Vec<u8>>("[1, 2"),
+            Err(DeserializeError::Json(JsonError::UnexpectEof { offset: 5 }))
+        );
+        assert!(matches!(
+            from_str::<Vec<u8>>("[1] 2"),
+            Err(DeserializeError::Json(JsonError::UnexpectToken {
+                offset: 4,
+                ..
+            }))
+        ));
+        assert_eq!(
+            from_str::<u8>("300").unwrap_err().to_string(),
+            "invalid value: integer `300`, expected u8"
+        );
+        assert_eq!(
+            from_str::<(u8, u8)>("[1, 2, 3]").unwrap_err().to_string(),
+            "the array has more elements than expected"
+        );
+        assert_eq!(
+            from_str::<Shape>(r#"{"Circle": 1, "Point": null}"#)
+                .unwrap_err()
+                .to_string(),
+            "expected a single variant"
+        );
+        assert_eq!(
+            from_str::<Config>(
+                r#"{"name": "svc", "port": 8080, "offset": 1, "ratio": 1, "tags": ["a", 2]}"#
+            )
+            .unwrap_err()
+            .to_string(),
+            "'/tags/1': invalid type: integer `2`, expected a string"
+        );
+        assert_eq!(
+            from_str::<Vec<Shape>>(r#"["Point", {"Rect": {"w": 1, "h": -2}}]"#)
+                .unwrap_err()
+                .to_string(),
+            "'/1/Rect/h': invalid value: integer `-2`, expected u32"
+        );
+        assert_eq!(
+            from_str::<BTreeMap<String, Config>>(r#"{"a": {}}"#)
+                .unwrap_err()
+                .to_string(),
+            "'/a': missing field `name`"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{to_string_with, JsonPointer, SerializeOptions, Type};
+
+/// One difference between two values, as found by [`diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A member or element only the new value has.
+    Added { pointer: JsonPointer, value: Type },
+    /// A member or element only the old value has.
+    Removed { pointer: JsonPointer, value: Type },
+    /// A value that differs, where the two are not both objects or both
+    /// arrays to look into.
+    Replaced {
+        pointer: JsonPointer,
+        old: Type,
+        new: Type,
+    },
+}
+
+impl Change {
+    pub fn pointer(&self) -> &JsonPointer {
+        match self {
+            Change::Added { pointer, .. }
+            | Change::Removed { pointer, .. }
+            | Change::Replaced { pointer, .. } => pointer,
+        }
+    }
+}
+
+/// The structural differences from `old` to `new`. Objects are compared
+/// member by member in key order, and arrays element by element by index,
+/// with the elements past the end of the shorter one added or removed;
+/// removals at the end of an array come last element first.
+pub fn diff(old: &Type, new: &Type) -> Vec<Change> {
+    let mut changes = Vec::new();
+    walk(old, new, &mut JsonPointer::root(), &mut changes);
+    changes
+}
+
+fn walk(old: &Type, new: &Type, pointer: &mut JsonPointer, out: &mut Vec<Change>) {
+    match (old, new) {
+        (Type::Object(a), Type::Object(b)) => {
+            let mut keys: Vec<&str> = a.keys().chain(b.keys()).map(|key| &**key).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            for key in keys {
+                pointer.push(key);
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => walk(a, b, pointer, out),
+                    (Some(a), None) => out.push(Change::Removed {
+                        pointer: pointer.clone(),
+                        value: a.clone(),
+                    }),
+                    (None, Some(b)) => out.push(Change::Added {
+                        pointer: pointer.clone(),
+                        value: b.clone(),
+                    }),
+                    (None, None) => unreachable!("the key came from one of the maps"),
+                }
+                pointer.pop();
+            }
+        }
+        (Type::Array(a), Type::Array(b)) => {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                pointer.push(i.to_string());
+                walk(a, b, pointer, out);
+                pointer.pop();
+            }
+            for (i, b) in b.iter().enumerate().skip(a.len()) {
+                pointer.push(i.to_string());
+                out.push(Change::Added {
+                    pointer: pointer.clone(),
+                    value: b.clone(),
+                });
+                pointer.pop();
+            }
+            for (i, a) in a.iter().enumerate().skip(b.len()).rev() {
+                pointer.push(i.to_string());
+                out.push(Change::Removed {
+                    pointer: pointer.clone(),
+                    value: a.clone(),
+                });
+                pointer.pop();
+            }
+        }
+        (a, b) if a != b => out.push(Change::Replaced {
+            pointer: pointer.clone(),
+            old: a.clone(),
+            new: b.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Formats `changes` like a unified diff, for people: a `@ 'pointer'` line
+/// for each change, then the old value pretty-printed with sorted keys on
+/// `-` lines and the new one on `+` lines.
+///
+/// ```text
+/// @ '/name'
+/// - "Ada"
+/// + "Grace"
+/// @ '/tags/1'
+/// + "admin"
+/// ```
+///
+/// With `color`, removed lines are red and added lines green, using ANSI
+/// escapes. No changes render as the empty string.
+pub fn render_diff(changes: &[Change], color: bool) -> String {
+    let mut out = String::new();
+    for change in changes {
+        let _ = writeln!(out, "@ '{}'", change.pointer());
+        match change {
+            Change::Added { value, .. } => lines(&mut out, '+', value, color),
+            Change::Removed { value, .. } => lines(&mut out, '-', value, color),
+            Change::Replaced { old, new, .. } => {
+                lines(&mut out, '-', old, color);
+                lines(&mut out, '+', new, color);
+            }
+        }
+    }
+    out
+}
+
+fn lines(out: &mut String, sign: char, value: &Type, color: bool) {
+    let (start, end) = match (color, sign) {
+        (false, _) => ("", ""),
+        (true, '-') => ("\x1b[31m", "\x1b[0m"),
+        (true, _) => ("\x1b[32m", "\x1b[0m"),
+    };
+    let options = SerializeOptions {
+        sort_keys: true,
+        ..SerializeOptions::pretty()
+    };
+    for line in to_string_with(value, &options).lines() {
+        let _ = writeln!(out, "{}{} {}{}", start, sign, line, end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, render_diff, Change};
+    use crate::{parse, Type};
+
+    #[test]
+    fn finds_changes() {
+        let old = parse(r#"{"name": "Ada", "tags": ["a", "b", "c"], "gone": 1, "n": {"x": 1}}"#);
+        let new = parse(r#"{"name": "Grace", "tags": ["a"], "n": {"x": 1, "y": [2]}}"#);
+        let changes = diff(&old.unwrap(), &new.unwrap());
+        let summary: Vec<(String, &str)> = changes
+            .iter()
+            .map(|change| {
+                let kind = match change {
+                    Change::Added { .. } => "added",
+                    Change::Removed { .. } => "removed",
+                    Change::Replaced { .. } => "replaced",
+                };
+                (change.pointer().to_string(), kind)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("/gone".to_string(), "removed"),
+                ("/n/y".to_string(), "added"),
+                ("/name".to_string(), "replaced"),
+                ("/tags/2".to_string(), "removed"),
+                ("/tags/1".to_string(), "removed"),
+            ]
+        );
+        assert_eq!(diff(&Type::Null, &Type::Null), []);
+        assert_eq!(
+            diff(&parse("[1]").unwrap(), &parse("[1, 2]").unwrap()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn renders_changes() {
+        let old = parse(r#"{"name": "Ada", "n": 1}"#).unwrap();
+        let new = parse(r#"{"name": "Grace", "n": {"y": [1], "x": 2}}"#).unwrap();
+        assert_eq!(
+            render_diff(&diff(&old, &new), false),
+            concat!(
+                "@ '/n'\n- 1\n+ {\n+   \"x\": 2,\n+   \"y\": [\n+     1\n+   ]\n+ }\n",
+                "@ '/name'\n- \"Ada\"\n+ \"Grace\"\n"
+            )
+        );
+        assert_eq!(
+            render_diff(&diff(&Type::Null, &Type::Boolean(true)), true),
+            "@ ''\n\x1b[31m- null\x1b[0m\n\x1b[32m+ true\x1b[0m\n"
+        );
+        assert_eq!(render_diff(&[], true), "");
+    }
+}
@@ -0,0 +1,219 @@
+use alloc::string::String;
+use core::char::REPLACEMENT_CHARACTER;
+
+use crate::{parse_slice, Expected, JsonError, Type};
+
+/// The Unicode encodings JSON text may come in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Encoding {
+    /// Guesses the encoding of `bytes` from a byte-order mark or, failing
+    /// that, from the pattern of zero bytes around the first character, as
+    /// RFC 4627 describes: JSON text starts with an ASCII character, so in
+    /// UTF-16 and UTF-32 all but one of its bytes are zero.
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        match bytes {
+            [0, 0, 0xfe, 0xff, ..] => Encoding::Utf32Be,
+            [0xff, 0xfe, 0, 0, ..] => Encoding::Utf32Le,
+            [0xfe, 0xff, ..] => Encoding::Utf16Be,
+            [0xff, 0xfe, ..] => Encoding::Utf16Le,
+            [0, 0, 0, _, ..] => Encoding::Utf32Be,
+            [_, 0, 0, 0, ..] => Encoding::Utf32Le,
+            [0, _, ..] => Encoding::Utf16Be,
+            [_, 0, ..] => Encoding::Utf16Le,
+            _ => Encoding::Utf8,
+        }
+    }
+}
+
+/// Parses JSON text in any of the [`Encoding`]s, detected with
+/// [`Encoding::detect`], so that payloads from systems writing UTF-16 or
+/// UTF-32 need no transcoding first. A byte-order mark is skipped.
+///
+/// Offsets in syntax errors count bytes of the text as UTF-8. A code unit
+/// that is not valid in the encoding, or a truncated one at the end, is an
+/// error at its byte offset in `bytes`.
+pub fn parse_bytes(bytes: &[u8]) -> Result<Type, JsonError> {
+    match Encoding::detect(bytes) {
+        Encoding::Utf8 => parse_slice(bytes),
+        encoding => parse_slice(transcode(bytes, encoding, false)?.as_bytes()),
+    }
+}
+
+/// Like [`parse_bytes`], but replaces each invalid or truncated sequence,
+/// in whichever encoding, with U+FFFD instead of failing, for dirty input
+/// such as logs where one bad byte should not cost the whole record.
+///
+/// Offsets in syntax errors count bytes of the repaired text as UTF-8.
+pub fn parse_bytes_lossy(bytes: &[u8]) -> Result<Type, JsonError> {
+    match Encoding::detect(bytes) {
+        Encoding::Utf8 => parse_slice(String::from_utf8_lossy(bytes).as_bytes()),
+        encoding => parse_slice(transcode(bytes, encoding, true)?.as_bytes()),
+    }
+}
+
+/// Decodes UTF-16 or UTF-32 `bytes` into UTF-8, replacing what is invalid
+/// with U+FFFD if `lossy`.
+fn transcode(bytes: &[u8], encoding: Encoding, lossy: bool) -> Result<String, JsonError> {
+    let invalid = |offset: usize| {
+        if lossy {
+            Ok(REPLACEMENT_CHARACTER)
+        } else {
+            Err(JsonError::UnexpectToken {
+                offset,
+                expected: Expected::NONE,
+            })
+        }
+    };
+    let mut out = String::with_capacity(bytes.len());
+    match encoding {
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let units = bytes.chunks_exact(2).map(|unit| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([unit[0], unit[1]]),
+                _ => u16::from_be_bytes([unit[0], unit[1]]),
+            });
+            let mut offset = 0;
+            for ch in char::decode_utf16(units) {
+                let (ch, len) = match ch {
+                    Ok(ch) => (ch, ch.len_utf16()),
+                    Err(_) => (invalid(offset)?, 1),
+                };
+                offset += len * 2;
+                out.push(ch);
+            }
+            if !bytes.len().is_multiple_of(2) {
+                out.push(invalid(bytes.len() - 1)?);
+            }
+        }
+        Encoding::Utf32Le | Encoding::Utf32Be => {
+            for (i, unit) in bytes.chunks(4).enumerate() {
+                let ch = match <[u8; 4]>::try_from(unit) {
+                    Ok(unit) => char::from_u32(match encoding {
+                        Encoding::Utf32Le => u32::from_le_bytes(unit),
+                        _ => u32::from_be_bytes(unit),
+                    }),
+                    Err(_) => None,
+                };
+                out.push(match ch {
+                    Some(ch) => ch,
+                    None => invalid(i * 4)?,
+                });
+            }
+        }
+        Encoding::Utf8 => unreachable!("UTF-8 is parsed as it is"),
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_bytes, parse_bytes_lossy, Encoding};
+    use crate::{parse, JsonError};
+
+    fn utf16(text: &str, little: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| {
+                if little {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                }
+            })
+            .collect()
+    }
+
+    fn utf32(text: &str, little: bool) -> Vec<u8> {
+        text.chars()
+            .flat_map(|ch| {
+                if little {
+                    (ch as u32).to_le_bytes()
+                } else {
+                    (ch as u32).to_be_bytes()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detects_and_decodes() {
+        let text = r#"{"name": "é😀", "n": [1, 2]}"#;
+        let expected = parse(text).unwrap();
+        for (bytes, encoding) in [
+            (text.as_bytes().to_vec(), Encoding::Utf8),
+            (utf16(text, true), Encoding::Utf16Le),
+            (utf16(text, false), Encoding::Utf16Be),
+            (utf32(text, true), Encoding::Utf32Le),
+            (utf32(text, false), Encoding::Utf32Be),
+        ] {
+            assert_eq!(Encoding::detect(&bytes), encoding);
+            assert_eq!(parse_bytes(&bytes).unwrap(), expected, "{:?}", encoding);
+        }
+        for (bytes, encoding) in [
+            (utf16("\u{feff}1", true), Encoding::Utf16Le),
+            (utf16("\u{feff}1", false), Encoding::Utf16Be),
+            (utf32("\u{feff}1", true), Encoding::Utf32Le),
+            (utf32("\u{feff}1", false), Encoding::Utf32Be),
+            ("\u{feff}1".as_bytes().to_vec(), Encoding::Utf8),
+        ] {
+            assert_eq!(Encoding::detect(&bytes), encoding);
+            assert_eq!(
+                parse_bytes(&bytes).unwrap(),
+                parse("1").unwrap(),
+                "{:?}",
+                encoding
+            );
+        }
+        assert_eq!(Encoding::detect(b"1"), Encoding::Utf8);
+        assert_eq!(Encoding::detect(b""), Encoding::Utf8);
+    }
+
+    #[test]
+    fn rejects_bad_code_units() {
+        let mut bytes = utf16("[1]", true);
+        bytes.extend([0x00, 0xd8, b']', 0]);
+        assert_eq!(
+            parse_bytes(&bytes),
+            Err(JsonError::UnexpectToken {
+                offset: 6,
+                expected: crate::Expected::NONE
+            })
+        );
+        let mut bytes = utf16("[1]", false);
+        bytes.push(0);
+        assert_eq!(parse_bytes(&bytes).unwrap_err().offset(), Some(6));
+        let mut bytes = utf32("1", true);
+        bytes.extend([0, 0, 0x11, 0]);
+        assert_eq!(parse_bytes(&bytes).unwrap_err().offset(), Some(4));
+    }
+
+    #[test]
+    fn replaces_bad_code_units() {
+        let replaced = parse(r#"["a\ufffdb", "\ufffd"]"#).unwrap();
+        let mut utf8 = b"[\"a\xffb\", \"".to_vec();
+        utf8.extend(b"\xe2\x82\"]");
+        assert_eq!(parse_bytes_lossy(&utf8).unwrap(), replaced);
+        assert!(parse_bytes(&utf8).is_err());
+
+        let mut bytes = utf16(r#"["a"#, true);
+        bytes.extend([0x00, 0xdc]);
+        bytes.extend(utf16(r#"b", ""#, true));
+        bytes.extend([0x3d, 0xd8]);
+        bytes.extend(utf16(r#""]"#, true));
+        assert_eq!(parse_bytes_lossy(&bytes).unwrap(), replaced);
+
+        let mut bytes = utf32(r#"["a"#, false);
+        bytes.extend([0, 0x11, 0, 0]);
+        bytes.extend(utf32(r#"b", ""#, false));
+        bytes.extend([0, 0, 0xd8, 0]);
+        bytes.extend(utf32(r#""]"#, false));
+        assert_eq!(parse_bytes_lossy(&bytes).unwrap(), replaced);
+        assert!(parse_bytes_lossy(b"[1,]").is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use alloc::boxed::Box;
+
+use crate::{JsonError, JsonPointer, Type};
+
+/// The place a JSON Pointer addresses, which may not exist yet; made by
+/// [`Type::pointer_entry`]. Nothing is created until a value is inserted.
+#[derive(Debug)]
+pub struct PointerEntry<'a> {
+    root: &'a mut Type,
+    pointer: JsonPointer,
+}
+
+impl<'a> PointerEntry<'a> {
+    pub fn pointer(&self) -> &JsonPointer {
+        &self.pointer
+    }
+
+    /// The value there, if there is one.
+    pub fn get(&self) -> Option<&Type> {
+        self.root.at_tokens(self.pointer.tokens())
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut Type> {
+        self.root.tokens_mut(self.pointer.tokens())
+    }
+
+    /// Stores `value` there, creating the objects and arrays on the way as
+    /// [`Type::set_at`] does, and returns it.
+    ///
+    /// `JsonError::InvalidPath` in the cases [`Type::set_at`] rejects.
+    pub fn insert(self, value: Type) -> Result<&'a mut Type, JsonError> {
+        let target = self.root.vivify_tokens(self.pointer.tokens())?;
+        *target = value;
+        Ok(target)
+    }
+
+    /// The value there, inserting `value` first if there is none.
+    pub fn or_insert(self, value: Type) -> Result<&'a mut Type, JsonError> {
+        self.or_insert_with(|| value)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> Type>(self, f: F) -> Result<&'a mut Type, JsonError> {
+        if self.get().is_some() {
+            return Ok(self
+                .root
+                .tokens_mut(self.pointer.tokens())
+                .expect("the value was just found"));
+        }
+        self.insert(f())
+    }
+
+    /// Takes the value out, as [`Type::remove_pointer`] does.
+    pub fn remove(self) -> Option<Type> {
+        self.root.remove_tokens(self.pointer.tokens())
+    }
+}
+
+impl Type {
+    /// The entry at the JSON Pointer `pointer`, for reading, updating or
+    /// creating the value there; `JsonError::InvalidPath` if the pointer is
+    /// malformed.
+    pub fn pointer_entry(&mut self, pointer: &str) -> Result<PointerEntry<'_>, JsonError> {
+        Ok(PointerEntry {
+            root: self,
+            pointer: pointer.parse()?,
+        })
+    }
+
+    /// The member `key` of an object, inserting `f()` first if it is
+    /// missing, for accumulating into values under keys. A `null` value
+    /// becomes an empty object first, as with [`Type::set_at`]; `None` if
+    /// the value is anything else.
+    pub fn get_or_insert_with<F: FnOnce() -> Type>(
+        &mut self,
+        key: &str,
+        f: F,
+    ) -> Option<&mut Type> {
+        if *self == Type::Null {
+            *self = Type::Object(Box::default());
+        }
+        match self {
+            Type::Object(members) => Some(members.entry(key.into()).or_insert_with(f)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, JsonError, Type};
+
+    #[test]
+    fn groups_under_keys() {
+        let records = parse(r#"[["a", 1], ["b", 2], ["a", 3]]"#).unwrap();
+        let mut groups = Type::Null;
+        for record in records.get_array("").unwrap() {
+            let key = record.get_str("/0").unwrap();
+            let group = groups
+                .get_or_insert_with(key, || Type::Array(Vec::new()))
+                .unwrap();
+            if let Type::Array(items) = group {
+                items.push(record.pointer("/1").unwrap().clone());
+            }
+        }
+        assert_eq!(groups, parse(r#"{"a": [1, 3], "b": [2]}"#).unwrap());
+        assert_eq!(
+            Type::Boolean(true).get_or_insert_with("a", || Type::Null),
+            None
+        );
+    }
+
+    #[test]
+    fn creates_on_insertion() {
+        let mut value = parse(r#"{"a": {"n": 1}}"#).unwrap();
+        let entry = value.pointer_entry("/a/b/c").unwrap();
+        assert_eq!(entry.get(), None);
+        assert_eq!(entry.pointer().to_string(), "/a/b/c");
+        drop(entry);
+        assert_eq!(value, parse(r#"{"a": {"n": 1}}"#).unwrap());
+
+        *value
+            .pointer_entry("/a/b/c")
+            .unwrap()
+            .or_insert(Type::Null)
+            .unwrap() = Type::Boolean(true);
+        let n = value
+            .pointer_entry("/a/n")
+            .unwrap()
+            .or_insert_with(|| unreachable!())
+            .unwrap();
+        *n = Type::Number(2.into());
+        value
+            .pointer_entry("/list/1")
+            .unwrap()
+            .insert(Type::Number(3.into()))
+            .unwrap();
+        assert_eq!(
+            value,
+            parse(r#"{"a": {"n": 2, "b": {"c": true}}, "list": [null, 3]}"#).unwrap()
+        );
+
+        let mut entry = value.pointer_entry("/list/1").unwrap();
+        *entry.get_mut().unwrap() = Type::Null;
+        assert_eq!(entry.remove(), Some(Type::Null));
+        assert_eq!(
+            value.pointer_entry("/a/n/x").unwrap().insert(Type::Null),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(
+            value.pointer_entry("a").unwrap_err(),
+            JsonError::InvalidPath
+        );
+        for pointer in [
+            "/list/18446744073709551615",
+            "/list/4000000000",
+            &"/a".repeat(200_000),
+        ] {
+            assert_eq!(
+                value.pointer_entry(pointer).unwrap().or_insert(Type::Null),
+                Err(JsonError::InvalidPath)
+            );
+        }
+    }
+}
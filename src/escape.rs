@@ -0,0 +1,80 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::lexer::{decode_string, Lexer, TokenKind};
+use crate::ser::write_string;
+use crate::{Expected, JsonError};
+
+/// `s` escaped as the contents of a JSON string, without the surrounding
+/// quotes, for splicing into hand-built JSON. Escapes are those the
+/// serializer writes.
+pub fn escape_json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    write_string(&mut out, s, false, false).expect("writing to a String never fails");
+    out.pop();
+    out.remove(0);
+    out
+}
+
+/// Decodes the contents of a JSON string, given without the surrounding
+/// quotes, as the parser would. Error offsets are byte offsets in `s`.
+pub fn unescape_json_string(s: &str) -> Result<String, JsonError> {
+    let mut quoted = Vec::with_capacity(s.len() + 2);
+    quoted.push(b'"');
+    quoted.extend_from_slice(s.as_bytes());
+    quoted.push(b'"');
+    let unquoted = |err: JsonError| match err {
+        JsonError::UnexpectToken { offset, expected } => JsonError::UnexpectToken {
+            offset: offset.saturating_sub(1).min(s.len()),
+            expected,
+        },
+        JsonError::UnexpectEof { offset } => JsonError::UnexpectEof {
+            offset: offset.saturating_sub(1).min(s.len()),
+        },
+        err => err,
+    };
+    let token = Lexer::new(&quoted).next_token().map_err(unquoted)?;
+    match token {
+        Some(token) if token.kind == TokenKind::String && token.end == quoted.len() => {
+            decode_string(s.as_bytes(), 0)
+        }
+        // An unescaped quote ended the string early.
+        Some(token) => Err(JsonError::UnexpectToken {
+            offset: token.end - 2,
+            expected: Expected::NONE,
+        }),
+        None => unreachable!("the input is not empty"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_json_string, unescape_json_string};
+    use crate::{Expected, JsonError};
+
+    #[test]
+    fn round_trips() {
+        let text = "quote \" slash \\ tab \t nul \u{0} é 😀";
+        let escaped = escape_json_string(text);
+        assert_eq!(escaped, r#"quote \" slash \\ tab \t nul \u0000 é 😀"#);
+        assert_eq!(unescape_json_string(&escaped).unwrap(), text);
+        assert_eq!(unescape_json_string(r"é😀\/").unwrap(), "é😀/");
+        assert_eq!(escape_json_string(""), "");
+        assert_eq!(unescape_json_string("").unwrap(), "");
+    }
+
+    #[test]
+    fn rejects_bad_escapes() {
+        let error = |offset| {
+            Err(JsonError::UnexpectToken {
+                offset,
+                expected: Expected::NONE,
+            })
+        };
+        assert_eq!(unescape_json_string(r"ab\x"), error(3));
+        assert_eq!(unescape_json_string("a\"b"), error(1));
+        assert_eq!(unescape_json_string("a\nb"), error(1));
+        assert!(unescape_json_string(r"\ud800").is_err());
+        assert!(unescape_json_string("a\\").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::io::Read;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{decode_string, Lexer, TokenKind, BOM};
+use crate::parser::{to_event, Grammar};
+use crate::pointer::{parse_index, JsonPointer};
+use crate::{JsonError, Type};
+
+/// One level of the path to the token being read.
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+impl Step {
+    fn matches(&self, token: &str) -> bool {
+        match self {
+            Step::Key(key) => key == token,
+            Step::Index(index) => parse_index(token) == Some(*index),
+        }
+    }
+}
+
+/// Reads JSON from `reader` and returns the value addressed by the JSON
+/// Pointer `pointer`, without building anything else.
+///
+/// Memory use is bounded by the size of the extracted value plus the largest
+/// single token, and reading stops as soon as the value is complete. Returns
+/// `Ok(None)` if the document has no such value. A UTF-8 byte-order mark at
+/// the start of the input is skipped.
+pub fn extract_pointer<R: Read>(reader: R, pointer: &str) -> Result<Option<Type>, JsonError> {
+    let mut found = extract_pointers(reader, &[pointer])?;
+    Ok(found.pop().flatten())
+}
+
+/// Like [`extract_pointer`] for several JSON Pointers at once, reading the
+/// input once for all of them: the values they address, in the order of
+/// `pointers`, each `None` if the document has no such value.
+///
+/// Reading stops as soon as every value is complete, and the values being
+/// extracted are all that is built, pointers into one another included.
+pub fn extract_pointers<R: Read>(
+    mut reader: R,
+    pointers: &[&str],
+) -> Result<Vec<Option<Type>>, JsonError> {
+    let targets = pointers
+        .iter()
+        .map(|pointer| pointer.parse())
+        .collect::<Result<Vec<JsonPointer>, _>>()?;
+    let max_depth = targets.iter().map(|target| target.tokens().len()).max();
+    let mut found: Vec<Option<Type>> = vec![None; targets.len()];
+    let mut remaining = targets.len();
+    if remaining == 0 {
+        return Ok(found);
+    }
+    // The targets being built, by index in `targets`. A target is captured
+    // once, at its first occurrence.
+    let mut captures: Vec<(usize, TreeBuilder)> = Vec::new();
+    let mut started = vec![false; targets.len()];
+    let mut grammar = Grammar::new();
+    let mut path: Vec<Step> = Vec::new();
+
+    let mut buf = Vec::new();
+    // Offset of `buf[0]` in the whole input.
+    let mut base = 0;
+    let mut chunk = vec![0; 8 * 1024];
+    loop {
+        let n = reader.read(&mut chunk)?;
+        buf.extend_from_slice(&chunk[..n]);
+        // Wait for a whole byte-order mark before deciding to skip one.
+        if n != 0 && base == 0 && buf.len() < BOM.len() && BOM.starts_with(&buf) {
+            continue;
+        }
+        let lexer = if n == 0 {
+            Lexer::new(&buf)
+        } else {
+            Lexer::partial(&buf)
+        };
+        let mut lexer = if base == 0 { lexer.skip_bom() } else { lexer };
+
+        while let Some(token) = lexer.next_token().map_err(|err| err.shifted(base))? {
+            let is_key = grammar.expects_key();
+            let starts_value = !is_key
+                && !matches!(
+                    token.kind,
+                    TokenKind::Colon
+                        | TokenKind::Comma
+                        | TokenKind::EndArray
+                        | TokenKind::EndObject
+                );
+            grammar.accept(token).map_err(|err| err.shifted(base))?;
+
+            if starts_value {
+                for (i, target) in targets.iter().enumerate() {
+                    if !started[i]
+                        && path.len() == target.tokens().len()
+                        && path
+                            .iter()
+                            .zip(target.tokens())
+                            .all(|(step, token)| step.matches(token))
+                    {
+                        started[i] = true;
+                        captures.push((i, TreeBuilder::new()));
+                    }
+                }
+            }
+            if !captures.is_empty() {
+                let event =
+                    to_event(lexer.input(), token, is_key).map_err(|err| err.shifted(base))?;
+                if let Some(event) = event {
+                    let mut j = 0;
+                    while j < captures.len() {
+                        let (i, builder) = &mut captures[j];
+                        match builder.push(event.clone()) {
+                            Some(value) => {
+                                found[*i] = Some(value);
+                                captures.swap_remove(j);
+                                remaining -= 1;
+                            }
+                            None => j += 1,
+                        }
+                    }
+                    if remaining == 0 {
+                        return Ok(found);
+                    }
+                }
+            }
+
+            match token.kind {
+                TokenKind::BeginObject => path.push(Step::Key(String::new())),
+                TokenKind::BeginArray => path.push(Step::Index(0)),
+                TokenKind::EndObject | TokenKind::EndArray => {
+                    path.pop();
+                }
+                TokenKind::Comma => {
+                    if let Some(Step::Index(index)) = path.last_mut() {
+                        *index += 1;
+                    }
+                }
+                // Keys deeper than every target can never lead to one.
+                TokenKind::String if is_key && max_depth.is_some_and(|max| path.len() <= max) => {
+                    let raw = &lexer.input()[token.start + 1..token.end - 1];
+                    let key =
+                        decode_string(raw, token.start + 1).map_err(|err| err.shifted(base))?;
+                    if let Some(step) = path.last_mut() {
+                        *step = Step::Key(key);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if n == 0 {
+            if !grammar.is_done() {
+                return Err(JsonError::UnexpectEof {
+                    offset: base + buf.len(),
+                });
+            }
+            return Ok(found);
+        }
+        let consumed = lexer.pos();
+        buf.drain(..consumed);
+        base += consumed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_pointer, extract_pointers};
+    use crate::{parse, Expected, JsonError, TokenKind, Type};
+    use std::io::Read;
+
+    /// Hands out input a few bytes at a time to exercise chunk boundaries.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    const DOC: &str =
+        r#"{"meta": {"items": "decoy"}, "items": [10, {"id": "a/b", "tags": ["x"]}, 30], "z": 1}"#;
+
+    #[test]
+    fn extracts() {
+        let get = |pointer| extract_pointer(Trickle(DOC.as_bytes()), pointer);
+        assert_eq!(get("/items/0").unwrap(), Some(Type::Number(10.into())));
+        assert_eq!(
+            get("/items/1").unwrap(),
+            Some(parse(r#"{"id": "a/b", "tags": ["x"]}"#).unwrap())
+        );
+        assert_eq!(
+            get("/items/1/tags/0").unwrap(),
+            Some(Type::String("x".into()))
+        );
+        assert_eq!(
+            get("/meta/items").unwrap(),
+            Some(Type::String("decoy".into()))
+        );
+        assert_eq!(get("").unwrap(), Some(parse(DOC).unwrap()));
+        assert_eq!(get("/items/3").unwrap(), None);
+        assert_eq!(get("/zz").unwrap(), None);
+        assert_eq!(get("bad").unwrap_err(), JsonError::InvalidPath);
+    }
+
+    #[test]
+    fn stops_early() {
+        // The garbage after the target is never read.
+        let input = br#"[{"a": 1}, 2] ]]] garbage"#;
+        assert_eq!(
+            extract_pointer(&input[..], "/0/a").unwrap(),
+            Some(Type::Number(1.into()))
+        );
+        assert_eq!(
+            extract_pointer(&b"[1, 2"[..], "/5").unwrap_err(),
+            JsonError::UnexpectEof { offset: 5 }
+        );
+        assert_eq!(
+            extract_pointer(Trickle(b"[1, 2, 3 4]"), "/5").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 9,
+                expected: Expected::of(&[TokenKind::Comma, TokenKind::EndArray])
+            }
+        );
+    }
+
+    #[test]
+    fn extracts_several() {
+        let found = extract_pointers(
+            Trickle(DOC.as_bytes()),
+            &[
+                "/z",
+                "/items/1",
+                "/items/1/tags",
+                "/missing",
+                "/meta/items",
+                "/z",
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            found,
+            [
+                Some(Type::Number(1.into())),
+                Some(parse(r#"{"id": "a/b", "tags": ["x"]}"#).unwrap()),
+                Some(parse(r#"["x"]"#).unwrap()),
+                None,
+                Some(Type::String("decoy".into())),
+                Some(Type::Number(1.into())),
+            ]
+        );
+        assert_eq!(extract_pointers(&b"[1, ]]"[..], &[]).unwrap(), []);
+        assert_eq!(
+            extract_pointers(&br#"[{"a": 1}, 2] ]]"#[..], &["/1", "/0/a"]).unwrap(),
+            [Some(Type::Number(2.into())), Some(Type::Number(1.into()))]
+        );
+        assert_eq!(
+            extract_pointers(&b"[1]"[..], &["/0", "x"]).unwrap_err(),
+            JsonError::InvalidPath
+        );
+    }
+
+    #[test]
+    fn skips_bom() {
+        let split = (&b"\xef"[..]).chain(&b"\xbb\xbf[1, [2]]"[..]);
+        assert_eq!(
+            extract_pointer(split, "/1/0").unwrap(),
+            Some(Type::Number(2.into()))
+        );
+        let found = extract_pointers(Trickle("\u{feff}{\"a\": 1}".as_bytes()), &["", "/a"]);
+        assert_eq!(
+            found.unwrap(),
+            vec![
+                Some(parse(r#"{"a": 1}"#).unwrap()),
+                Some(Type::Number(1.into()))
+            ]
+        );
+        assert!(matches!(
+            extract_pointer(&b"\xef\xbb"[..], ""),
+            Err(JsonError::UnexpectToken { offset: 0, .. })
+        ));
+    }
+}
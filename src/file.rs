@@ -0,0 +1,182 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{parse, Expected, JsonError, Type};
+
+/// An error from [`parse_file`] or [`JsonFile`], naming the file it came
+/// from. Syntax errors keep their byte offset in `error`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: JsonError,
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+enum Contents {
+    Read(String),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+/// The text of a JSON file, checked to be UTF-8.
+///
+/// With the `mmap` feature the file is mapped into memory rather than read,
+/// so `as_str` hands out the page cache itself, and with `parse_in` a whole
+/// tree can borrow its strings from the file without copying them.
+pub struct JsonFile {
+    path: PathBuf,
+    contents: Contents,
+}
+
+impl JsonFile {
+    pub fn open(path: impl AsRef<Path>) -> Result<JsonFile, FileError> {
+        let path = path.as_ref().to_path_buf();
+        let fail = |error| FileError {
+            path: path.clone(),
+            error,
+        };
+        let contents = Self::load(&path).map_err(fail)?;
+        Ok(JsonFile { path, contents })
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    fn load(path: &Path) -> Result<Contents, JsonError> {
+        let bytes = fs::read(path)?;
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(Contents::Read(text)),
+            Err(err) => Err(JsonError::UnexpectToken {
+                offset: err.utf8_error().valid_up_to(),
+                expected: Expected::NONE,
+            }),
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    fn load(path: &Path) -> Result<Contents, JsonError> {
+        let file = fs::File::open(path)?;
+        // Mapping an empty file fails on some platforms.
+        if file.metadata()?.len() == 0 {
+            return Ok(Contents::Read(String::new()));
+        }
+        // SAFETY: the mapping is read-only. Another process truncating or
+        // rewriting the file while it is mapped is undefined behaviour, as
+        // with any mmap; callers that cannot rule that out should build
+        // without the `mmap` feature.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        if let Err(err) = std::str::from_utf8(&map) {
+            return Err(JsonError::UnexpectToken {
+                offset: err.valid_up_to(),
+                expected: Expected::NONE,
+            });
+        }
+        Ok(Contents::Mapped(map))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.contents {
+            Contents::Read(text) => text,
+            // SAFETY: checked to be UTF-8 in `load`.
+            #[cfg(feature = "mmap")]
+            Contents::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+
+    /// Parses the file into a [`Type`].
+    pub fn parse(&self) -> Result<Type, FileError> {
+        parse(self.as_str()).map_err(|error| FileError {
+            path: self.path.clone(),
+            error,
+        })
+    }
+}
+
+/// Reads and parses the JSON file at `path`. Errors, whether from reading the
+/// file or from its contents, carry the path.
+pub fn parse_file(path: impl AsRef<Path>) -> Result<Type, FileError> {
+    JsonFile::open(path)?.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_file, JsonFile};
+    use crate::{parse, Expected, JsonError};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("json-rs-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn parses_files() {
+        let path = temp_file("ok.json", br#"{"a": [1, "two"]}"#);
+        assert_eq!(
+            parse_file(&path).unwrap(),
+            parse(r#"{"a": [1, "two"]}"#).unwrap()
+        );
+        let file = JsonFile::open(&path).unwrap();
+        assert_eq!(file.as_str(), r#"{"a": [1, "two"]}"#);
+        assert_eq!(file.path(), path);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn errors_name_the_file() {
+        let path = temp_file("bad.json", b"[1,\n 2,]");
+        let err = parse_file(&path).unwrap_err();
+        assert_eq!(err.path, path);
+        assert_eq!(
+            err.error,
+            JsonError::UnexpectToken {
+                offset: 7,
+                expected: Expected::VALUE
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("{}: unexpected token at byte 7", path.display())
+        );
+        fs::remove_file(&path).unwrap();
+
+        let path = temp_file("latin1.json", b"[\"caf\xe9\"]");
+        assert_eq!(
+            parse_file(&path).unwrap_err().error,
+            JsonError::UnexpectToken {
+                offset: 5,
+                expected: Expected::NONE
+            }
+        );
+        fs::remove_file(&path).unwrap();
+
+        let path = temp_file("empty.json", b"");
+        assert_eq!(
+            parse_file(&path).unwrap_err().error,
+            JsonError::UnexpectEof { offset: 0 }
+        );
+        fs::remove_file(&path).unwrap();
+
+        let missing = parse_file(std::env::temp_dir().join("json-rs-missing.json"));
+        assert_eq!(
+            missing.unwrap_err().error,
+            JsonError::Io(std::io::ErrorKind::NotFound)
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{JsonPointer, Type};
+
+/// The iterator returned by [`Type::find_all`].
+pub struct FindAll<'a> {
+    key: &'a str,
+    /// Values still to search, with their pointers and whether they are the
+    /// value of a member named `key`.
+    stack: Vec<(JsonPointer, &'a Type, bool)>,
+}
+
+impl<'a> Iterator for FindAll<'a> {
+    type Item = (JsonPointer, &'a Type);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((pointer, value, found)) = self.stack.pop() {
+            match value {
+                Type::Array(items) => {
+                    for (i, item) in items.iter().enumerate().rev() {
+                        let mut child = pointer.clone();
+                        child.push(i.to_string());
+                        self.stack.push((child, item, false));
+                    }
+                }
+                Type::Object(members) => {
+                    for (name, member) in members.iter() {
+                        let mut child = pointer.clone();
+                        child.push(&**name);
+                        self.stack.push((child, member, &**name == self.key));
+                    }
+                }
+                _ => {}
+            }
+            if found {
+                return Some((pointer, value));
+            }
+        }
+        None
+    }
+}
+
+impl Type {
+    /// Finds the value of every member named `key`, at any depth, with the
+    /// pointer to it. A value is yielded before the matches nested inside
+    /// it; the order of an object's members is the map's.
+    pub fn find_all<'a>(&'a self, key: &'a str) -> FindAll<'a> {
+        FindAll {
+            key,
+            stack: vec![(JsonPointer::root(), self, false)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Type};
+
+    #[test]
+    fn finds_nested_keys() {
+        let value =
+            parse(r#"{"id": 1, "items": [{"id": 2, "sub": {"id": {"id": 3}}}, {"name": "id"}]}"#)
+                .unwrap();
+        let mut found: Vec<_> = value
+            .find_all("id")
+            .map(|(pointer, value)| (pointer.to_string(), value.clone()))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            found,
+            [
+                ("/id".to_string(), Type::Number(1.into())),
+                ("/items/0/id".to_string(), Type::Number(2.into())),
+                (
+                    "/items/0/sub/id".to_string(),
+                    parse(r#"{"id": 3}"#).unwrap()
+                ),
+                ("/items/0/sub/id/id".to_string(), Type::Number(3.into())),
+            ]
+        );
+        for (pointer, found) in value.find_all("id") {
+            assert_eq!(value.pointer(&pointer.to_string()), Some(found));
+        }
+        assert_eq!(value.find_all("missing").count(), 0);
+        assert_eq!(Type::Null.find_all("id").count(), 0);
+    }
+}
@@ -0,0 +1,176 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::pointer::parse_index;
+use crate::scan::split_path;
+use crate::{JsonError, Key, Map, Type, MAX_DEPTH};
+
+impl Type {
+    /// Flattens the value into a single-level object keyed by dotted paths
+    /// in the syntax of [`get_path`](crate::get_path), e.g.
+    /// `{"a": {"b": [1]}}` becomes `{"a.b.0": 1}`. Dots and backslashes in
+    /// keys are escaped with a backslash.
+    ///
+    /// Scalars and empty arrays and objects are the leaves; a scalar root is
+    /// stored under the empty key.
+    pub fn flatten(&self) -> Type {
+        let mut out = Map::default();
+        flatten_into(self, &mut String::new(), &mut out);
+        Type::Object(Box::new(out))
+    }
+
+    /// Rebuilds a nested value from an object made by [`Type::flatten`].
+    ///
+    /// Objects whose keys are exactly `0` to `n - 1` become arrays, so an
+    /// object that had such keys before flattening comes back as an array.
+    /// `JsonError::InvalidPath` if the value is not an object, one key is a
+    /// prefix of another, as in `{"a": 1, "a.b": 2}`, or a key has more than
+    /// [`MAX_DEPTH`] segments.
+    pub fn unflatten(&self) -> Result<Type, JsonError> {
+        let Type::Object(members) = self else {
+            return Err(JsonError::InvalidPath);
+        };
+        let mut root = None;
+        for (key, value) in members.iter() {
+            let segments = split_path(key);
+            if segments.len() > MAX_DEPTH {
+                return Err(JsonError::InvalidPath);
+            }
+            insert(&mut root, &segments, value.clone())?;
+        }
+        Ok(root.map_or_else(|| Type::Object(Box::default()), Node::into_type))
+    }
+}
+
+fn flatten_into(value: &Type, path: &mut String, out: &mut Map<Key, Type>) {
+    let len = path.len();
+    let mut child = |path: &mut String, segment: &str, value: &Type| {
+        if len > 0 {
+            path.push('.');
+        }
+        for ch in segment.chars() {
+            if ch == '.' || ch == '\\' {
+                path.push('\\');
+            }
+            path.push(ch);
+        }
+        flatten_into(value, path, out);
+        path.truncate(len);
+    };
+    match value {
+        Type::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                child(path, &i.to_string(), item);
+            }
+        }
+        Type::Object(members) if !members.is_empty() => {
+            for (key, member) in members.iter() {
+                child(path, key, member);
+            }
+        }
+        leaf => {
+            out.insert(path.as_str().into(), leaf.clone());
+        }
+    }
+}
+
+enum Node {
+    Leaf(Type),
+    Branch(BTreeMap<String, Option<Node>>),
+}
+
+impl Node {
+    fn into_type(self) -> Type {
+        match self {
+            Node::Leaf(value) => value,
+            Node::Branch(children) => {
+                let is_array = children
+                    .keys()
+                    .map(|key| parse_index(key))
+                    .collect::<Option<Vec<_>>>()
+                    .is_some_and(|mut indices| {
+                        indices.sort_unstable();
+                        indices.into_iter().eq(0..children.len())
+                    });
+                let children = children
+                    .into_iter()
+                    .map(|(key, node)| (key, node.map_or(Type::Null, Node::into_type)));
+                if is_array {
+                    let mut items: Vec<_> = children.collect();
+                    items.sort_by_key(|(key, _)| parse_index(key));
+                    Type::Array(items.into_iter().map(|(_, value)| value).collect())
+                } else {
+                    Type::Object(Box::new(
+                        children
+                            .map(|(key, value)| (key.as_str().into(), value))
+                            .collect(),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+fn insert(slot: &mut Option<Node>, segments: &[String], value: Type) -> Result<(), JsonError> {
+    let Some((first, rest)) = segments.split_first() else {
+        if slot.is_some() {
+            return Err(JsonError::InvalidPath);
+        }
+        *slot = Some(Node::Leaf(value));
+        return Ok(());
+    };
+    match slot.get_or_insert_with(|| Node::Branch(BTreeMap::new())) {
+        Node::Leaf(_) => Err(JsonError::InvalidPath),
+        Node::Branch(children) => {
+            insert(children.entry(first.clone()).or_insert(None), rest, value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, JsonError, Key, Map, Type, MAX_DEPTH};
+
+    #[test]
+    fn round_trips() {
+        let value =
+            parse(r#"{"a": {"b": [1, {"c": true}], "d.e": null, "f\\g": "x"}, "h": [], "i": {}}"#)
+                .unwrap();
+        let flat = value.flatten();
+        assert_eq!(
+            flat,
+            parse(
+                r#"{"a.b.0": 1, "a.b.1.c": true, "a.d\\.e": null, "a.f\\\\g": "x", "h": [], "i": {}}"#
+            )
+            .unwrap()
+        );
+        assert_eq!(flat.unflatten().unwrap(), value);
+        let twelve: Vec<Type> = (0..12).map(|i| Type::Number(i.into())).collect();
+        let array = Type::Array(twelve);
+        assert_eq!(array.flatten().unflatten().unwrap(), array);
+        assert_eq!(
+            Type::Number(1.into()).flatten().unflatten().unwrap(),
+            Type::Number(1.into())
+        );
+    }
+
+    #[test]
+    fn unflatten_errors() {
+        assert_eq!(
+            parse(r#"{"a": 1, "a.b": 2}"#).unwrap().unflatten(),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(Type::Null.unflatten(), Err(JsonError::InvalidPath));
+        for (segments, nested) in [(200_000, false), (MAX_DEPTH + 1, false), (MAX_DEPTH, true)] {
+            let mut flat = Map::default();
+            flat.insert(Key::from(vec!["a"; segments].join(".")), Type::Null);
+            assert_eq!(Type::Object(Box::new(flat)).unflatten().is_ok(), nested);
+        }
+        assert_eq!(
+            parse(r#"{"0": 1, "2": 2}"#).unwrap().unflatten().unwrap(),
+            parse(r#"{"0": 1, "2": 2}"#).unwrap()
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::{Key, Map, ToJson, Type};
+
+/// Builds an object one member at a time; made by [`Type::object`].
+///
+/// ```text
+/// Type::object()
+///     .field("name", "json-rs")
+///     .field("tags", Type::array().push(1).push(true))
+///     .build()
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectBuilder {
+    members: Map<Key, Type>,
+}
+
+/// Builds an array one element at a time; made by [`Type::array`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ArrayBuilder {
+    items: Vec<Type>,
+}
+
+impl Type {
+    pub fn object() -> ObjectBuilder {
+        ObjectBuilder::default()
+    }
+
+    pub fn array() -> ArrayBuilder {
+        ArrayBuilder::default()
+    }
+}
+
+impl ObjectBuilder {
+    /// Sets the member `key`, replacing an earlier one of the same name.
+    pub fn field(mut self, key: &str, value: impl ToJson) -> Self {
+        self.members.insert(key.into(), value.to_json());
+        self
+    }
+
+    /// Sets the member `key` if `value` is `Some`, and leaves it out if not.
+    pub fn field_opt(self, key: &str, value: Option<impl ToJson>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    pub fn build(self) -> Type {
+        Type::Object(Box::new(self.members))
+    }
+}
+
+impl ArrayBuilder {
+    pub fn push(mut self, value: impl ToJson) -> Self {
+        self.items.push(value.to_json());
+        self
+    }
+
+    pub fn extend<T: ToJson>(mut self, values: impl IntoIterator<Item = T>) -> Self {
+        self.items
+            .extend(values.into_iter().map(|value| value.to_json()));
+        self
+    }
+
+    pub fn build(self) -> Type {
+        Type::Array(self.items)
+    }
+}
+
+/// So that a builder can be nested without calling `build`.
+impl ToJson for ObjectBuilder {
+    fn to_json(&self) -> Type {
+        self.clone().build()
+    }
+}
+
+impl ToJson for ArrayBuilder {
+    fn to_json(&self) -> Type {
+        self.clone().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Type};
+
+    #[test]
+    fn builds_values() {
+        let value = Type::object()
+            .field("name", "json-rs")
+            .field("tags", Type::array().push(1).push(true).extend(["a", "b"]))
+            .field("nested", Type::object().field("n", Type::Null))
+            .field_opt("missing", None::<u8>)
+            .field_opt("port", Some(8080))
+            .field("name", "json")
+            .build();
+        assert_eq!(
+            value,
+            parse(r#"{"name": "json", "tags": [1, true, "a", "b"], "nested": {"n": null}, "port": 8080}"#)
+                .unwrap()
+        );
+        assert_eq!(Type::array().build(), parse("[]").unwrap());
+        assert_eq!(Type::object().build(), parse("{}").unwrap());
+    }
+}
@@ -0,0 +1,176 @@
+use alloc::string::String;
+
+use crate::lexer::{check_string, Lexer, TokenKind};
+use crate::parser::Grammar;
+use crate::JsonError;
+
+/// Removes insignificant whitespace from a JSON document without building a
+/// tree. Strings and numbers are copied exactly as written; a leading UTF-8
+/// byte-order mark is dropped along with the whitespace.
+pub fn minify(json: &str) -> Result<String, JsonError> {
+    minify_tokens(json, Lexer::new(json.as_bytes()).skip_bom())
+}
+
+/// Like [`minify`], but also accepts and strips `//` and `/* */` comments.
+pub fn minify_relaxed(json: &str) -> Result<String, JsonError> {
+    minify_tokens(json, Lexer::new(json.as_bytes()).with_comments().skip_bom())
+}
+
+/// Reformats a JSON document with one member or element per line, each
+/// nesting level indented by `indent`, without building a tree. Strings and
+/// numbers are copied exactly as written, and a leading UTF-8 byte-order
+/// mark is dropped.
+pub fn prettify(json: &str, indent: &str) -> Result<String, JsonError> {
+    let mut out = String::with_capacity(json.len() * 2);
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    let mut grammar = Grammar::new();
+    let mut depth = 0;
+    while let Some(token) = lexer.next_token()? {
+        grammar.accept(token)?;
+        match token.kind {
+            TokenKind::BeginObject | TokenKind::BeginArray => {
+                out.push_str(&json[token.start..token.end]);
+                let mut peek = lexer.clone();
+                match peek.next_token()? {
+                    Some(next)
+                        if matches!(next.kind, TokenKind::EndObject | TokenKind::EndArray) =>
+                    {
+                        grammar.accept(next)?;
+                        out.push_str(&json[next.start..next.end]);
+                        lexer = peek;
+                    }
+                    _ => {
+                        depth += 1;
+                        newline(&mut out, indent, depth);
+                    }
+                }
+            }
+            TokenKind::EndObject | TokenKind::EndArray => {
+                depth -= 1;
+                newline(&mut out, indent, depth);
+                out.push_str(&json[token.start..token.end]);
+            }
+            TokenKind::Comma => {
+                out.push(',');
+                newline(&mut out, indent, depth);
+            }
+            TokenKind::Colon => out.push_str(": "),
+            TokenKind::String => {
+                check_string(
+                    &json.as_bytes()[token.start + 1..token.end - 1],
+                    token.start + 1,
+                )?;
+                out.push_str(&json[token.start..token.end]);
+            }
+            _ => out.push_str(&json[token.start..token.end]),
+        }
+    }
+    if !grammar.is_done() {
+        return Err(JsonError::UnexpectEof { offset: json.len() });
+    }
+    Ok(out)
+}
+
+fn newline(out: &mut String, indent: &str, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str(indent);
+    }
+}
+
+fn minify_tokens(json: &str, mut lexer: Lexer) -> Result<String, JsonError> {
+    let mut out = String::with_capacity(json.len());
+    let mut grammar = Grammar::new();
+    while let Some(token) = lexer.next_token()? {
+        grammar.accept(token)?;
+        if token.kind == TokenKind::String {
+            check_string(
+                &json.as_bytes()[token.start + 1..token.end - 1],
+                token.start + 1,
+            )?;
+        }
+        out.push_str(&json[token.start..token.end]);
+    }
+    if !grammar.is_done() {
+        return Err(JsonError::UnexpectEof { offset: json.len() });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{minify, minify_relaxed, prettify};
+    use crate::{Expected, JsonError, TokenKind};
+
+    #[test]
+    fn strips_whitespace() {
+        assert_eq!(
+            minify(" {\n  \"a b\": [1.50, 2e3, true],\n  \"c\": \"x\\n y\"\n} ").unwrap(),
+            r#"{"a b":[1.50,2e3,true],"c":"x\n y"}"#
+        );
+        assert_eq!(
+            minify("[1,]").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::VALUE
+            }
+        );
+        assert_eq!(
+            minify("[1").unwrap_err(),
+            JsonError::UnexpectEof { offset: 2 }
+        );
+        assert_eq!(
+            minify("[1] // c").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 4,
+                expected: Expected::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn strips_comments() {
+        let json = "// config\n{\n  \"a\": 1, /* the \"b\" key */ \"b\": \"//\"\n}\n";
+        assert_eq!(minify_relaxed(json).unwrap(), r#"{"a":1,"b":"//"}"#);
+        assert_eq!(
+            minify_relaxed("[1] /*").unwrap_err(),
+            JsonError::UnexpectEof { offset: 6 }
+        );
+    }
+
+    #[test]
+    fn reindents() {
+        let json = r#"{"a":[1.50,{},[]],"b":{"c":"\u00e9"}}"#;
+        assert_eq!(
+            prettify(json, "  ").unwrap(),
+            "{\n  \"a\": [\n    1.50,\n    {},\n    []\n  ],\n  \"b\": {\n    \"c\": \"\\u00e9\"\n  }\n}"
+        );
+        assert_eq!(prettify(" [ ] ", "\t").unwrap(), "[]");
+        assert_eq!(minify(&prettify(json, "\t").unwrap()).unwrap(), json);
+        assert_eq!(
+            prettify("{\"a\" 1}", "  ").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 5,
+                expected: Expected::of(&[TokenKind::Colon])
+            }
+        );
+        assert_eq!(
+            prettify("[[]", "  ").unwrap_err(),
+            JsonError::UnexpectEof { offset: 3 }
+        );
+    }
+
+    #[test]
+    fn drops_bom() {
+        assert_eq!(minify("\u{feff} [1, 2]").unwrap(), "[1,2]");
+        assert_eq!(minify_relaxed("\u{feff}// c\n[1]").unwrap(), "[1]");
+        assert_eq!(prettify("\u{feff}[1]", "  ").unwrap(), "[\n  1\n]");
+        assert_eq!(
+            minify("[1]\u{feff}").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::NONE
+            }
+        );
+    }
+}
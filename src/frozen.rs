@@ -0,0 +1,336 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::hash::subtree_hash;
+use crate::pointer::parse_index;
+use crate::{into_str, JsonError, JsonPointer, Key, Map, Number, Type};
+
+/// A JSON value whose strings and containers sit behind `Arc`s, so that
+/// cloning it, or any part of it, is cheap and identical subtrees can be
+/// stored once; see [`SubtreeCache`]. It is `Send` and `Sync`, so one parsed
+/// configuration can be handed to many worker threads by cloning the handle
+/// rather than the tree.
+///
+/// Edits are copy-on-write: [`pointer_mut`](FrozenValue::pointer_mut) copies
+/// only the shared nodes on the path to the target, so an edited clone is a
+/// new version that shares everything else with the original.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrozenValue {
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(Arc<str>),
+    Array(Arc<Vec<FrozenValue>>),
+    Object(Arc<Map<Key, FrozenValue>>),
+}
+
+impl FrozenValue {
+    /// A mutable copy of the whole tree.
+    pub fn thaw(&self) -> Type {
+        self.into()
+    }
+
+    /// Looks up a value by JSON Pointer, as [`Type::pointer`] does.
+    pub fn pointer(&self, pointer: &str) -> Option<&FrozenValue> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        pointer
+            .tokens()
+            .iter()
+            .try_fold(self, |target, token| match target {
+                FrozenValue::Object(members) => members.get(token.as_str()),
+                FrozenValue::Array(items) => items.get(parse_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Whether the two values are the same allocation, not just equal;
+    /// scalars other than strings never are.
+    pub fn ptr_eq(&self, other: &FrozenValue) -> bool {
+        match (self, other) {
+            (FrozenValue::String(a), FrozenValue::String(b)) => Arc::ptr_eq(a, b),
+            (FrozenValue::Array(a), FrozenValue::Array(b)) => Arc::ptr_eq(a, b),
+            (FrozenValue::Object(a), FrozenValue::Object(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl FrozenValue {
+    /// Looks up a value by JSON Pointer for editing, first copying each
+    /// node on the way that is shared with another value.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut FrozenValue> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        self.tokens_mut(pointer.tokens())
+    }
+
+    /// Stores `value` at the JSON Pointer `pointer`, which must name an
+    /// existing value, a member of an existing object, or `-` to append to
+    /// an existing array; `JsonError::InvalidPath` otherwise.
+    pub fn set_at(&mut self, pointer: &str, value: FrozenValue) -> Result<(), JsonError> {
+        let mut pointer: JsonPointer = pointer.parse()?;
+        let Some(last) = pointer.pop() else {
+            *self = value;
+            return Ok(());
+        };
+        match self.tokens_mut(pointer.tokens()) {
+            Some(FrozenValue::Object(members)) => {
+                Arc::make_mut(members).insert(last.as_str().into(), value);
+            }
+            Some(FrozenValue::Array(items)) => {
+                let items = Arc::make_mut(items);
+                match parse_index(&last) {
+                    Some(index) if index < items.len() => items[index] = value,
+                    _ if last == "-" => items.push(value),
+                    _ => return Err(JsonError::InvalidPath),
+                }
+            }
+            _ => return Err(JsonError::InvalidPath),
+        }
+        Ok(())
+    }
+
+    fn tokens_mut(&mut self, tokens: &[String]) -> Option<&mut FrozenValue> {
+        tokens.iter().try_fold(self, |target, token| match target {
+            FrozenValue::Object(members) => Arc::make_mut(members).get_mut(token.as_str()),
+            FrozenValue::Array(items) => Arc::make_mut(items).get_mut(parse_index(token)?),
+            _ => None,
+        })
+    }
+}
+
+impl From<&Type> for FrozenValue {
+    fn from(value: &Type) -> FrozenValue {
+        match value {
+            Type::Null => FrozenValue::Null,
+            Type::Boolean(b) => FrozenValue::Boolean(*b),
+            Type::Number(n) => FrozenValue::Number(*n),
+            Type::String(s) => FrozenValue::String(Arc::from(&**s)),
+            Type::Array(items) => {
+                FrozenValue::Array(Arc::new(items.iter().map(Into::into).collect()))
+            }
+            Type::Object(members) => FrozenValue::Object(Arc::new(
+                members
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.into()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl From<&FrozenValue> for Type {
+    fn from(value: &FrozenValue) -> Type {
+        match value {
+            FrozenValue::Null => Type::Null,
+            FrozenValue::Boolean(b) => Type::Boolean(*b),
+            FrozenValue::Number(n) => Type::Number(*n),
+            FrozenValue::String(s) => Type::String(into_str(s.as_ref().into())),
+            FrozenValue::Array(items) => Type::Array(items.iter().map(Into::into).collect()),
+            FrozenValue::Object(members) => Type::Object(alloc::boxed::Box::new(
+                members
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.into()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Freezes values so that every string, array and object equal to one it
+/// has seen before shares that one's memory, e.g. across thousands of
+/// near-identical configuration snapshots. Keep one cache for all the
+/// documents that should share.
+#[derive(Debug, Default)]
+pub struct SubtreeCache {
+    /// Each distinct subtree seen, by a hash of its contents.
+    seen: BTreeMap<u64, Vec<FrozenValue>>,
+}
+
+impl SubtreeCache {
+    pub fn new() -> Self {
+        SubtreeCache::default()
+    }
+
+    /// `value` frozen, with its subtrees shared with those already in the
+    /// cache wherever they are equal.
+    pub fn freeze(&mut self, value: &Type) -> FrozenValue {
+        self.intern(value).0
+    }
+
+    /// The number of distinct strings, arrays and objects held.
+    pub fn len(&self) -> usize {
+        self.seen.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Interns the children first, so two candidates for sharing are equal
+    /// exactly when their children are the same allocations; returns the
+    /// hash along with the value.
+    fn intern(&mut self, value: &Type) -> (FrozenValue, u64) {
+        let mut children = Vec::new();
+        let hash = subtree_hash(value, |_, _, child| {
+            let (child, hash) = self.intern(child);
+            children.push(child);
+            hash
+        });
+        let node = match value {
+            Type::String(s) => FrozenValue::String(Arc::from(&**s)),
+            Type::Array(_) => FrozenValue::Array(Arc::new(children)),
+            Type::Object(members) => {
+                FrozenValue::Object(Arc::new(members.keys().cloned().zip(children).collect()))
+            }
+            scalar => return (scalar.into(), hash),
+        };
+        let bucket = self.seen.entry(hash).or_default();
+        if let Some(seen) = bucket.iter().find(|seen| shallow_eq(seen, &node)) {
+            return (seen.clone(), hash);
+        }
+        bucket.push(node.clone());
+        (node, hash)
+    }
+}
+
+/// Equality for interned nodes, whose children are equal only if they are
+/// the same allocation.
+fn shallow_eq(a: &FrozenValue, b: &FrozenValue) -> bool {
+    let same = |a: &FrozenValue, b: &FrozenValue| match (a, b) {
+        (FrozenValue::String(_) | FrozenValue::Array(_) | FrozenValue::Object(_), _) => a.ptr_eq(b),
+        _ => a == b,
+    };
+    match (a, b) {
+        (FrozenValue::String(a), FrozenValue::String(b)) => a == b,
+        (FrozenValue::Array(a), FrozenValue::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same(a, b))
+        }
+        (FrozenValue::Object(a), FrozenValue::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| same(a, b)))
+        }
+        _ => false,
+    }
+}
+
+impl Type {
+    /// A shareable copy of the value, to be cloned cheaply from then on.
+    pub fn freeze(&self) -> FrozenValue {
+        self.into()
+    }
+
+    /// The value frozen with identical subtrees stored once; use a
+    /// [`SubtreeCache`] to share them across several documents.
+    pub fn dedup_subtrees(&self) -> FrozenValue {
+        SubtreeCache::new().freeze(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrozenValue, SubtreeCache};
+    use crate::{parse, JsonError, Type};
+
+    fn get<'a>(value: &'a FrozenValue, key: &str) -> &'a FrozenValue {
+        match value {
+            FrozenValue::Object(members) => members.get(key).unwrap(),
+            FrozenValue::Array(items) => &items[key.parse::<usize>().unwrap()],
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn shares_identical_subtrees() {
+        let value = parse(
+            r#"{"a": {"x": [1, "s"], "y": null}, "b": {"y": null, "x": [1, "s"]}, "c": [[1, "s"], "s"]}"#,
+        )
+        .unwrap();
+        let frozen = value.dedup_subtrees();
+        assert!(get(&frozen, "a").ptr_eq(get(&frozen, "b")));
+        let c = get(&frozen, "c");
+        assert!(get(c, "0").ptr_eq(get(get(&frozen, "a"), "x")));
+        assert!(get(c, "1").ptr_eq(get(get(c, "0"), "1")));
+        assert_eq!(Type::from(&frozen), value);
+        assert_eq!(frozen, FrozenValue::from(&value));
+    }
+
+    #[test]
+    fn shares_across_documents() {
+        let mut cache = SubtreeCache::new();
+        let first = cache.freeze(&parse(r#"{"v": 1, "deps": {"a": "1.0", "b": "2.0"}}"#).unwrap());
+        let distinct = cache.len();
+        let second = cache.freeze(&parse(r#"{"v": 2, "deps": {"b": "2.0", "a": "1.0"}}"#).unwrap());
+        assert_eq!(cache.len(), distinct + 1);
+        assert!(get(&first, "deps").ptr_eq(get(&second, "deps")));
+        assert!(!first.ptr_eq(&second));
+        assert!(!FrozenValue::Null.ptr_eq(&FrozenValue::Null));
+        assert!(SubtreeCache::new().is_empty());
+    }
+
+    #[test]
+    fn freezes_and_thaws() {
+        fn shareable<T: Send + Sync + Clone>(_: &T) {}
+        let value = parse(r#"{"workers": [{"port": 80}, {"port": 81}]}"#).unwrap();
+        let frozen = value.freeze();
+        shareable(&frozen);
+        let ports: Vec<_> = (0..2)
+            .map(|i| {
+                let config = frozen.clone();
+                std::thread::spawn(move || {
+                    config
+                        .pointer(&format!("/workers/{}/port", i))
+                        .unwrap()
+                        .thaw()
+                })
+                .join()
+                .unwrap()
+            })
+            .collect();
+        assert_eq!(ports, [Type::Number(80.into()), Type::Number(81.into())]);
+        assert!(frozen
+            .pointer("/workers")
+            .unwrap()
+            .ptr_eq(frozen.clone().pointer("/workers").unwrap()));
+        assert_eq!(frozen.pointer("/workers/2"), None);
+        assert_eq!(frozen.pointer(""), Some(&frozen));
+        assert_eq!(frozen.thaw(), value);
+    }
+
+    #[test]
+    fn copies_on_write() {
+        let original = parse(r#"{"a": {"b": [1, 2]}, "big": {"x": [3, 4, 5]}}"#)
+            .unwrap()
+            .freeze();
+        let mut edited = original.clone();
+        *edited.pointer_mut("/a/b/0").unwrap() = FrozenValue::Boolean(true);
+        edited
+            .set_at("/a/c", FrozenValue::String("new".into()))
+            .unwrap();
+        edited.set_at("/a/b/-", FrozenValue::Null).unwrap();
+        assert_eq!(
+            edited.thaw(),
+            parse(r#"{"a": {"b": [true, 2, null], "c": "new"}, "big": {"x": [3, 4, 5]}}"#).unwrap()
+        );
+        assert_eq!(
+            original.thaw(),
+            parse(r#"{"a": {"b": [1, 2]}, "big": {"x": [3, 4, 5]}}"#).unwrap()
+        );
+        assert!(get(&edited, "big").ptr_eq(get(&original, "big")));
+        assert!(!get(&edited, "a").ptr_eq(get(&original, "a")));
+
+        assert_eq!(
+            edited.set_at("/a/b/7", FrozenValue::Null),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(
+            edited.set_at("/missing/x", FrozenValue::Null),
+            Err(JsonError::InvalidPath)
+        );
+        assert!(edited.pointer_mut("/a/x").is_none());
+        edited.set_at("", FrozenValue::Null).unwrap();
+        assert_eq!(edited, FrozenValue::Null);
+    }
+}
@@ -0,0 +1,216 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{into_str, Key, Map, Number, Type};
+
+/// Bounds on the values [`Type::arbitrary_with`] generates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitraryLimits {
+    /// Containers are not generated below this depth.
+    pub max_depth: usize,
+    /// The most characters in a string or key, and the most elements or
+    /// members in an array or object.
+    pub max_len: usize,
+}
+
+/// A depth of 16, so the generator itself stays shallow while still
+/// producing nesting the parser has to track, and no bound on lengths.
+impl Default for ArbitraryLimits {
+    fn default() -> Self {
+        ArbitraryLimits {
+            max_depth: 16,
+            max_len: usize::MAX,
+        }
+    }
+}
+
+/// Generates random trees within the default [`ArbitraryLimits`]. Numbers
+/// are always finite, since NaN and the infinities have no JSON form, so
+/// every generated value survives a serialize/parse round trip unchanged.
+impl<'a> Arbitrary<'a> for Type {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Type::arbitrary_with(u, ArbitraryLimits::default())
+    }
+}
+
+impl Type {
+    /// A random tree within `limits`, for property tests: `proptest` and
+    /// `quickcheck` strategies can be built on it by feeding it their
+    /// random bytes, with [`shrink`](Type::shrink) to minimize failures.
+    pub fn arbitrary_with(u: &mut Unstructured, limits: ArbitraryLimits) -> Result<Type> {
+        arbitrary_value(u, &limits, 0)
+    }
+
+    /// Values simpler than this one, the simplest first, for narrowing a
+    /// failing property test down to a small counterexample: scalars move
+    /// towards `null`, `false`, 0 and the empty string, and containers are
+    /// replaced by one of their children, lose an element or member, or
+    /// have one shrunk in turn. Empty for `null`.
+    pub fn shrink(&self) -> Vec<Type> {
+        let mut out = Vec::new();
+        if !self.is_null() {
+            out.push(Type::Null);
+        }
+        match self {
+            Type::Null | Type::Boolean(false) => {}
+            Type::Boolean(true) => out.push(Type::Boolean(false)),
+            Type::Number(n) => {
+                let f = n.to_f64();
+                if f != 0.0 {
+                    out.push(Type::Number(0.into()));
+                }
+                if f.trunc() != f {
+                    out.push(Type::Number(Number::from(f.trunc())));
+                }
+                if f.abs() >= 2.0 {
+                    out.push(Type::Number(Number::from((f / 2.0).trunc())));
+                }
+            }
+            Type::String(s) => {
+                let len = s.chars().count();
+                if len > 0 {
+                    out.push(Type::String(into_str(String::new())));
+                }
+                if len > 1 {
+                    let half: String = s.chars().take(len / 2).collect();
+                    out.push(Type::String(into_str(half)));
+                }
+            }
+            Type::Array(items) => {
+                out.extend(items.iter().cloned());
+                for i in 0..items.len() {
+                    let mut fewer = items.clone();
+                    fewer.remove(i);
+                    out.push(Type::Array(fewer));
+                }
+                for (i, item) in items.iter().enumerate() {
+                    for smaller in item.shrink() {
+                        let mut items = items.clone();
+                        items[i] = smaller;
+                        out.push(Type::Array(items));
+                    }
+                }
+            }
+            Type::Object(members) => {
+                out.extend(members.values().cloned());
+                for key in members.keys() {
+                    let fewer = members
+                        .iter()
+                        .filter(|(other, _)| *other != key)
+                        .map(|(key, member)| (key.clone(), member.clone()))
+                        .collect();
+                    out.push(Type::Object(Box::new(fewer)));
+                }
+                for (key, member) in members.iter() {
+                    for smaller in member.shrink() {
+                        let mut members = members.clone();
+                        members.insert(key.clone(), smaller);
+                        out.push(Type::Object(members));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn arbitrary_value(u: &mut Unstructured, limits: &ArbitraryLimits, depth: usize) -> Result<Type> {
+    let kinds = if depth < limits.max_depth { 6 } else { 4 };
+    let value = match u.choose_index(kinds)? {
+        0 => Type::Null,
+        1 => Type::Boolean(u.arbitrary()?),
+        2 => {
+            let n: f64 = u.arbitrary()?;
+            Type::Number(if n.is_finite() { n } else { 0.0 }.into())
+        }
+        3 => Type::String(into_str(arbitrary_string(u, limits)?)),
+        4 => {
+            let mut items = Vec::new();
+            while items.len() < limits.max_len && u.arbitrary()? {
+                items.push(arbitrary_value(u, limits, depth + 1)?);
+            }
+            Type::Array(items)
+        }
+        _ => {
+            let mut members = Map::default();
+            while members.len() < limits.max_len && u.arbitrary()? {
+                let key = arbitrary_string(u, limits)?;
+                members.insert(Key::from(key), arbitrary_value(u, limits, depth + 1)?);
+            }
+            Type::Object(Box::new(members))
+        }
+    };
+    Ok(value)
+}
+
+fn arbitrary_string(u: &mut Unstructured, limits: &ArbitraryLimits) -> Result<String> {
+    let s: String = u.arbitrary()?;
+    Ok(match s.char_indices().nth(limits.max_len) {
+        Some((end, _)) => s[..end].into(),
+        None => s,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArbitraryLimits;
+    use crate::{parse, to_string, Type};
+    use arbitrary::{Arbitrary, Unstructured};
+
+    fn data() -> Vec<u8> {
+        (0..4096u32).map(|i| (i * 7919 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn generated_values_round_trip() {
+        let data = data();
+        let mut u = Unstructured::new(&data);
+        while !u.is_empty() {
+            let value = Type::arbitrary(&mut u).unwrap();
+            assert_eq!(parse(&to_string(&value)).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn respects_limits() {
+        let limits = ArbitraryLimits {
+            max_depth: 2,
+            max_len: 3,
+        };
+        let data = data();
+        let mut u = Unstructured::new(&data);
+        while !u.is_empty() {
+            let value = Type::arbitrary_with(&mut u, limits).unwrap();
+            let stats = value.stats();
+            assert!(stats.max_depth <= 2);
+            assert!(stats.largest_array <= 3 && stats.largest_object <= 3);
+        }
+    }
+
+    #[test]
+    fn shrinks() {
+        assert!(Type::Null.shrink().is_empty());
+        assert_eq!(
+            parse("12.5").unwrap().shrink(),
+            [
+                Type::Null,
+                parse("0").unwrap(),
+                parse("12").unwrap(),
+                parse("6").unwrap()
+            ]
+        );
+        let value = parse(r#"[true, "ab"]"#).unwrap();
+        let shrunk = value.shrink();
+        assert_eq!(shrunk[0], Type::Null);
+        for simpler in [r#"true"#, r#"["ab"]"#, r#"[false, "ab"]"#, r#"[true, ""]"#] {
+            assert!(shrunk.contains(&parse(simpler).unwrap()), "{}", simpler);
+        }
+        assert!(!shrunk.contains(&value));
+
+        // Repeated shrinking ends.
+        let mut value = parse(r#"{"a": [1, {"b": "xyz"}], "c": -3.5}"#).unwrap();
+        while let Some(simpler) = value.shrink().pop() {
+            value = simpler;
+        }
+        assert_eq!(value, Type::Null);
+    }
+}
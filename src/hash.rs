@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+use crate::Type;
+
+/// 64-bit FNV-1a, which is fixed by its specification and so gives the same
+/// digest on every platform, version and run.
+pub(crate) struct Fnv(u64);
+
+impl Fnv {
+    pub(crate) fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        // FNV mixes its last bytes poorly; finish with the SplitMix64 mixer.
+        let mut h = self.0;
+        h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        h ^ (h >> 31)
+    }
+
+    pub(crate) fn value(&mut self, value: &Type) {
+        match value {
+            Type::Null => self.write(&[0]),
+            Type::Boolean(false) => self.write(&[1]),
+            Type::Boolean(true) => self.write(&[2]),
+            Type::Number(n) => {
+                self.write(&[3]);
+                // `0.0 == -0.0`, so they must hash alike.
+                let n = n.to_f64();
+                let n = if n == 0.0 { 0.0 } else { n };
+                self.write(&n.to_bits().to_le_bytes());
+            }
+            Type::String(s) => {
+                self.write(&[4]);
+                self.write_len(s.len());
+                self.write(s.as_bytes());
+            }
+            Type::Array(items) => {
+                self.write(&[5]);
+                self.write_len(items.len());
+                for item in items {
+                    self.value(item);
+                }
+            }
+            Type::Object(members) => {
+                self.write(&[6]);
+                self.write_len(members.len());
+                let mut members: Vec<_> = members.iter().collect();
+                members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                for (key, value) in members {
+                    self.write_len(key.len());
+                    self.write(key.as_bytes());
+                    self.value(value);
+                }
+            }
+        }
+    }
+}
+
+/// Hashes `value` from the hashes of its children, which `child` gives for
+/// each child with its index and, in an object, its key, so that a walk
+/// over a tree can hash every node once. Values equal under `==` hash alike,
+/// whatever the order of their object members.
+pub(crate) fn subtree_hash<'a>(
+    value: &'a Type,
+    mut child: impl FnMut(usize, Option<&'a str>, &'a Type) -> u64,
+) -> u64 {
+    let mut hasher = Fnv::new();
+    match value {
+        Type::Array(items) => {
+            hasher.write(&[5]);
+            hasher.write_len(items.len());
+            for (i, item) in items.iter().enumerate() {
+                hasher.write(&child(i, None, item).to_le_bytes());
+            }
+        }
+        Type::Object(members) => {
+            hasher.write(&[6]);
+            hasher.write_len(members.len());
+            // Combine the members in an order-independent way.
+            let mut sum = 0u64;
+            for (i, (key, member)) in members.iter().enumerate() {
+                let mut entry = Fnv::new();
+                entry.write_len(key.len());
+                entry.write(key.as_bytes());
+                entry.write(&child(i, Some(&**key), member).to_le_bytes());
+                sum = sum.wrapping_add(entry.finish());
+            }
+            hasher.write(&sum.to_le_bytes());
+        }
+        scalar => hasher.value(scalar),
+    }
+    hasher.finish()
+}
+
+impl Type {
+    /// A 64-bit digest of the value that is stable across runs, platforms
+    /// and crate versions, for cache keys and change detection. Values equal
+    /// under `==` hash alike whatever the order of their object members.
+    ///
+    /// Not a cryptographic hash: do not use it where an attacker could
+    /// profit from a collision.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv::new();
+        hasher.value(self);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, Type};
+
+    #[test]
+    fn stable_hashes() {
+        let a = parse(r#"{"x": [1, "two", null], "y": {"p": true, "q": -0.0}}"#).unwrap();
+        let b = parse(r#"{"y": {"q": 0, "p": true}, "x": [1, "two", null]}"#).unwrap();
+        assert_eq!(a.content_hash(), b.content_hash());
+        // Pinned so that an accidental change to the digest is caught.
+        assert_eq!(Type::Null.content_hash(), 0x25fc_6dd3_6ce0_4b20);
+        let hashes: Vec<u64> = [
+            "null",
+            "false",
+            "true",
+            "0",
+            "1",
+            "\"\"",
+            "\"0\"",
+            "[]",
+            "{}",
+            "[[]]",
+            "[null]",
+            r#"{"": null}"#,
+            r#"["a", "b"]"#,
+            r#"["ab"]"#,
+            r#"{"a": "b"}"#,
+        ]
+        .iter()
+        .map(|json| parse(json).unwrap().content_hash())
+        .collect();
+        for (i, a) in hashes.iter().enumerate() {
+            assert!(!hashes[i + 1..].contains(a), "collision at {}", i);
+        }
+    }
+}
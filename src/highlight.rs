@@ -0,0 +1,141 @@
+use alloc::vec::Vec;
+
+use crate::lexer::{is_whitespace, Lexer, TokenKind};
+
+/// What a [`Highlight`] covers, for choosing its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    /// An object key, quotes included.
+    Key,
+    /// A string value, quotes included.
+    String,
+    Number,
+    /// `true`, `false` or `null`.
+    Literal,
+    /// A bracket, brace, colon or comma.
+    Punctuation,
+    /// A `//` or `/* */` comment, delimiters included.
+    Comment,
+}
+
+/// A classified run of bytes from [`highlight`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    pub kind: HighlightKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Splits `json` into classified spans, in order, for syntax highlighting;
+/// the whitespace between them is left out. Comments are accepted.
+///
+/// Only the tokens are checked, not the grammar, so a document being typed
+/// still highlights. At a malformed token the spans stop, leaving the rest
+/// of the input uncovered. A string is a key when a colon follows it.
+pub fn highlight(json: &str) -> Vec<Highlight> {
+    let mut lexer = Lexer::new(json.as_bytes()).with_comments();
+    let mut out: Vec<Highlight> = Vec::new();
+    let mut prev = 0;
+    while let Ok(token) = lexer.next_token() {
+        let end = token.map_or(json.len(), |token| token.start);
+        comments(json, prev, end, &mut out);
+        let Some(token) = token else {
+            break;
+        };
+        let kind = match token.kind {
+            TokenKind::String => HighlightKind::String,
+            TokenKind::Number => HighlightKind::Number,
+            TokenKind::True | TokenKind::False | TokenKind::Null => HighlightKind::Literal,
+            TokenKind::Colon => {
+                let before = out
+                    .iter_mut()
+                    .rev()
+                    .find(|span| span.kind != HighlightKind::Comment);
+                if let Some(key) = before.filter(|span| span.kind == HighlightKind::String) {
+                    key.kind = HighlightKind::Key;
+                }
+                HighlightKind::Punctuation
+            }
+            _ => HighlightKind::Punctuation,
+        };
+        out.push(Highlight {
+            kind,
+            start: token.start,
+            end: token.end,
+        });
+        prev = token.end;
+    }
+    out
+}
+
+/// Adds a span for each comment in `json[start..end]`, a run of whitespace
+/// and comments the lexer has already checked.
+fn comments(json: &str, mut start: usize, end: usize, out: &mut Vec<Highlight>) {
+    let bytes = json.as_bytes();
+    loop {
+        while start < end && is_whitespace(bytes[start]) {
+            start += 1;
+        }
+        if start >= end {
+            return;
+        }
+        let rest = &json[start..end];
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else {
+            rest.find("*/").map_or(rest.len(), |len| len + 2)
+        };
+        out.push(Highlight {
+            kind: HighlightKind::Comment,
+            start,
+            end: start + len,
+        });
+        start += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{highlight, HighlightKind};
+
+    fn spans(json: &str) -> Vec<(HighlightKind, &str)> {
+        highlight(json)
+            .into_iter()
+            .map(|span| (span.kind, &json[span.start..span.end]))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_tokens() {
+        use HighlightKind::*;
+        assert_eq!(
+            spans("// hi\n{\"a\": [1, \"b\", true] /* c */, \"d\" : null}"),
+            [
+                (Comment, "// hi"),
+                (Punctuation, "{"),
+                (Key, "\"a\""),
+                (Punctuation, ":"),
+                (Punctuation, "["),
+                (Number, "1"),
+                (Punctuation, ","),
+                (String, "\"b\""),
+                (Punctuation, ","),
+                (Literal, "true"),
+                (Punctuation, "]"),
+                (Comment, "/* c */"),
+                (Punctuation, ","),
+                (Key, "\"d\""),
+                (Punctuation, ":"),
+                (Literal, "null"),
+                (Punctuation, "}"),
+            ]
+        );
+        assert_eq!(
+            spans("[1, @ 2]"),
+            [(Punctuation, "["), (Number, "1"), (Punctuation, ",")]
+        );
+        assert_eq!(spans("1 // end"), [(Number, "1"), (Comment, "// end")]);
+        assert_eq!(spans("\"k\" /**/ :")[0], (Key, "\"k\""));
+        assert_eq!(spans("{\"a\""), [(Punctuation, "{"), (String, "\"a\"")]);
+    }
+}
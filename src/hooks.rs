@@ -0,0 +1,155 @@
+use alloc::string::String;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{Lexer, TokenKind};
+use crate::parser::{to_event, Event, Grammar};
+use crate::{JsonError, Type};
+
+/// Decides what each number in a document becomes, for
+/// [`parse_with_numbers`]. Closures taking the lexeme and its offset are
+/// handlers too.
+///
+/// The handler sees the number exactly as written, so it can keep values a
+/// [`Number`](crate::Number) cannot, such as decimals or `u128`s, in a type
+/// of its own, and put a stand-in of its choosing in the tree.
+pub trait NumberHandler {
+    /// Turns the number `lexeme`, which starts at byte `offset`, into the
+    /// value stored in its place. An error ends the parse.
+    fn number(&mut self, lexeme: &str, offset: usize) -> Result<Type, JsonError>;
+}
+
+impl<F: FnMut(&str, usize) -> Result<Type, JsonError>> NumberHandler for F {
+    fn number(&mut self, lexeme: &str, offset: usize) -> Result<Type, JsonError> {
+        self(lexeme, offset)
+    }
+}
+
+/// Decides what each string in a document becomes, for
+/// [`parse_with_strings`]: it can intern, cap, normalize or reject strings
+/// as they are read. Closures taking the string, its offset and whether it
+/// is a key are handlers too.
+pub trait StringHandler {
+    /// Turns the decoded string `s`, whose opening quote is at byte
+    /// `offset`, into the one stored in its place; `is_key` tells object
+    /// keys from values. An error ends the parse.
+    fn string(&mut self, s: String, offset: usize, is_key: bool) -> Result<String, JsonError>;
+}
+
+impl<F: FnMut(String, usize, bool) -> Result<String, JsonError>> StringHandler for F {
+    fn string(&mut self, s: String, offset: usize, is_key: bool) -> Result<String, JsonError> {
+        self(s, offset, is_key)
+    }
+}
+
+/// Parses `json` like [`parse`](crate::parse), but hands every number to
+/// `handler` instead of decoding it.
+pub fn parse_with_numbers(json: &str, handler: &mut impl NumberHandler) -> Result<Type, JsonError> {
+    parse_hooked(json, Some(handler), None)
+}
+
+/// Parses `json` like [`parse`](crate::parse), but passes every key and
+/// string value through `handler` once it is decoded.
+pub fn parse_with_strings(json: &str, handler: &mut impl StringHandler) -> Result<Type, JsonError> {
+    parse_hooked(json, None, Some(handler))
+}
+
+fn parse_hooked(
+    json: &str,
+    mut numbers: Option<&mut dyn NumberHandler>,
+    mut strings: Option<&mut dyn StringHandler>,
+) -> Result<Type, JsonError> {
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    let mut grammar = Grammar::new();
+    let mut builder = TreeBuilder::new();
+    let mut root = None;
+    while let Some(token) = lexer.next_token()? {
+        let is_key = grammar.expects_key();
+        grammar.accept(token)?;
+        let done = match (token.kind, numbers.as_mut()) {
+            (TokenKind::Number, Some(handler)) => {
+                let value = handler.number(&json[token.start..token.end], token.start)?;
+                builder.push_value(value)
+            }
+            _ => match (to_event(lexer.input(), token, is_key)?, strings.as_mut()) {
+                (Some(Event::Key(s)), Some(handler)) => {
+                    builder.push(Event::Key(handler.string(s, token.start, true)?))
+                }
+                (Some(Event::String(s)), Some(handler)) => {
+                    builder.push(Event::String(handler.string(s, token.start, false)?))
+                }
+                (Some(event), _) => builder.push(event),
+                (None, _) => None,
+            },
+        };
+        if done.is_some() {
+            root = done;
+        }
+    }
+    root.ok_or(JsonError::UnexpectEof { offset: json.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_with_numbers, parse_with_strings};
+    use crate::{parse, Expected, JsonError, Type};
+
+    #[test]
+    fn hands_numbers_to_the_handler() {
+        let mut big = Vec::new();
+        let mut keep = |lexeme: &str, _| {
+            big.push(lexeme.parse::<u128>().unwrap());
+            Ok(Type::String(lexeme.into()))
+        };
+        let value = parse_with_numbers(
+            r#"{"id": 340282366920938463463374607431768211455, "n": [7]}"#,
+            &mut keep,
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            parse(r#"{"id": "340282366920938463463374607431768211455", "n": ["7"]}"#).unwrap()
+        );
+        assert!(parse_with_numbers("[1, ]", &mut keep).is_err());
+        assert_eq!(parse_with_numbers("null", &mut keep), Ok(Type::Null));
+        assert_eq!(big, [u128::MAX, 7, 1]);
+
+        let mut reject = |_: &str, offset| {
+            Err(JsonError::UnexpectToken {
+                offset,
+                expected: Expected::NONE,
+            })
+        };
+        assert_eq!(
+            parse_with_numbers("[true, 1.5]", &mut reject)
+                .unwrap_err()
+                .offset(),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn passes_strings_through_the_handler() {
+        let mut seen = Vec::new();
+        let mut upper = |s: String, offset, is_key| {
+            seen.push((offset, is_key));
+            Ok(if is_key { s } else { s.to_uppercase() })
+        };
+        assert_eq!(
+            parse_with_strings(r#"{"a": ["b", 1], "c": "d"}"#, &mut upper).unwrap(),
+            parse(r#"{"a": ["B", 1], "c": "D"}"#).unwrap()
+        );
+        assert_eq!(seen, [(1, true), (7, false), (16, true), (21, false)]);
+
+        let mut cap = |s: String, offset, _| {
+            if s.len() > 3 {
+                Err(JsonError::LimitExceeded { offset })
+            } else {
+                Ok(s)
+            }
+        };
+        assert_eq!(
+            parse_with_strings(r#"["ok", "too long"]"#, &mut cap),
+            Err(JsonError::LimitExceeded { offset: 7 })
+        );
+    }
+}
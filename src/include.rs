@@ -0,0 +1,240 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::{Type, MAX_DEPTH};
+
+/// An include [`Type::resolve_includes`] could not resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludeError {
+    /// The names being included, outermost first, ending with the one that
+    /// failed.
+    pub chain: Vec<String>,
+    pub message: String,
+}
+
+impl fmt::Display for IncludeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.chain.join(" -> "), self.message)
+    }
+}
+
+impl core::error::Error for IncludeError {}
+
+/// How many nodes includes may bring in all, so that a few small files
+/// that each include the next several times cannot expand to billions of
+/// nodes.
+const MAX_INCLUDED_NODES: usize = 1 << 20;
+
+/// Loads an include given the chain of includes it appears in, returning
+/// the name that identifies it in cycles and its value, or a message.
+type Load<'a> = dyn FnMut(&[String], &str) -> Result<(String, Type), String> + 'a;
+
+impl Type {
+    /// Replaces each object of the form `{"$include": "name"}`, and nothing
+    /// else, with `loader(name)`, for composing a large configuration from
+    /// several documents. Included values are resolved in turn; a name
+    /// that includes itself, directly or not, is an error, as is an error
+    /// from `loader`. So are includes nested, or values nested by them,
+    /// deeper than [`MAX_DEPTH`], and includes that bring in more than 2^20
+    /// nodes in all.
+    pub fn resolve_includes<F: FnMut(&str) -> Result<Type, String>>(
+        &mut self,
+        mut loader: F,
+    ) -> Result<(), IncludeError> {
+        let mut load = |_: &[String], name: &str| loader(name).map(|value| (name.into(), value));
+        resolve(self, &mut Vec::new(), &mut load, &mut 0, 0)
+    }
+
+    /// Like [`resolve_includes`](Type::resolve_includes), with names that
+    /// are paths of JSON files: relative to `dir` in the value itself, and
+    /// to the including file's directory in included files.
+    #[cfg(feature = "std")]
+    pub fn resolve_file_includes(&mut self, dir: impl AsRef<Path>) -> Result<(), IncludeError> {
+        let dir = dir.as_ref();
+        let mut load = |chain: &[String], name: &str| {
+            let base = chain
+                .last()
+                .and_then(|parent| Path::new(parent).parent())
+                .unwrap_or(dir);
+            // Canonical paths, so that a cycle is found however its paths
+            // are spelled.
+            let path = std::fs::canonicalize(base.join(name)).map_err(|err| err.to_string())?;
+            let value = crate::parse_file(&path).map_err(|err| err.error.to_string())?;
+            Ok((path.display().to_string(), value))
+        };
+        resolve(self, &mut Vec::new(), &mut load, &mut 0, 0)
+    }
+}
+
+/// Resolves the includes in `value`, which is inside `depth` arrays and
+/// objects and was brought in by the includes on `chain`; `included` counts
+/// the nodes brought in so far.
+fn resolve(
+    value: &mut Type,
+    chain: &mut Vec<String>,
+    load: &mut Load,
+    included: &mut usize,
+    depth: usize,
+) -> Result<(), IncludeError> {
+    let error = |chain: &[String], name: &str, message: &str| IncludeError {
+        chain: chain.iter().cloned().chain([name.to_string()]).collect(),
+        message: message.into(),
+    };
+    let limit = |chain: &[String], message: &str| IncludeError {
+        chain: chain.to_vec(),
+        message: message.into(),
+    };
+    if !chain.is_empty() {
+        *included += 1;
+        if *included > MAX_INCLUDED_NODES {
+            return Err(limit(chain, "includes expand to too many nodes"));
+        }
+    }
+    let is_container = matches!(value, Type::Array(_) | Type::Object(_));
+    if is_container && depth == MAX_DEPTH {
+        return Err(limit(chain, "includes nested too deep"));
+    }
+    match value {
+        Type::Object(members) if members.len() == 1 && members.get("$include").is_some() => {
+            let name = match members.get("$include") {
+                Some(Type::String(name)) => name.to_string(),
+                _ => return Err(error(chain, "$include", "the name must be a string")),
+            };
+            let (id, mut loaded) =
+                load(chain, &name).map_err(|message| error(chain, &name, &message))?;
+            if chain.contains(&id) {
+                return Err(error(chain, &id, "include cycle"));
+            }
+            if chain.len() == MAX_DEPTH {
+                return Err(error(chain, &id, "includes nested too deep"));
+            }
+            chain.push(id);
+            resolve(&mut loaded, chain, load, included, depth)?;
+            chain.pop();
+            *value = loaded;
+        }
+        Type::Object(members) => {
+            for (_, member) in members.iter_mut() {
+                resolve(member, chain, load, included, depth + 1)?;
+            }
+        }
+        Type::Array(items) => {
+            for item in items {
+                resolve(item, chain, load, included, depth + 1)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use crate::{parse, Type, MAX_DEPTH};
+
+    fn loader<'a>(files: &'a HashMap<&str, &str>) -> impl FnMut(&str) -> Result<Type, String> + 'a {
+        |name| match files.get(name) {
+            Some(text) => parse(text).map_err(|err| err.to_string()),
+            None => Err("not found".into()),
+        }
+    }
+
+    #[test]
+    fn resolves_includes() {
+        let files = HashMap::from([
+            ("db", r#"{"host": "x", "auth": {"$include": "auth"}}"#),
+            ("auth", r#"{"user": "u"}"#),
+            ("loop", r#"[{"$include": "back"}]"#),
+            ("back", r#"{"$include": "loop"}"#),
+        ]);
+        let mut config =
+            parse(r#"{"db": {"$include": "db"}, "list": [{"$include": "auth"}], "other": {"$include": "x", "y": 1}}"#)
+                .unwrap();
+        config.resolve_includes(loader(&files)).unwrap();
+        assert_eq!(
+            config,
+            parse(r#"{"db": {"host": "x", "auth": {"user": "u"}}, "list": [{"user": "u"}], "other": {"$include": "x", "y": 1}}"#)
+                .unwrap()
+        );
+
+        let mut config = parse(r#"{"a": {"$include": "loop"}}"#).unwrap();
+        assert_eq!(
+            config
+                .resolve_includes(loader(&files))
+                .unwrap_err()
+                .to_string(),
+            "loop -> back -> loop: include cycle"
+        );
+        let mut config = parse(r#"[{"$include": "db"}, {"$include": "missing"}]"#).unwrap();
+        let err = config.resolve_includes(loader(&files)).unwrap_err();
+        assert_eq!(err.chain, ["missing"]);
+        assert_eq!(err.message, "not found");
+        let mut config = parse(r#"{"$include": 1}"#).unwrap();
+        assert!(config.resolve_includes(loader(&files)).is_err());
+    }
+
+    /// A loader of files `0` to `{n - 1}`, each `make(i)`, with `{n}` null.
+    fn chain(n: usize, make: impl Fn(usize) -> String) -> impl FnMut(&str) -> Result<Type, String> {
+        move |name| {
+            let i: usize = name.parse().unwrap();
+            Ok(if i == n {
+                Type::Null
+            } else {
+                parse(&make(i)).unwrap()
+            })
+        }
+    }
+
+    #[test]
+    fn limits_expansion() {
+        let twice = |i| format!(r#"[{{"$include": "{0}"}}, {{"$include": "{0}"}}]"#, i + 1);
+        let mut config = parse(r#"{"$include": "0"}"#).unwrap();
+        let err = config.resolve_includes(chain(30, twice)).unwrap_err();
+        assert_eq!(err.message, "includes expand to too many nodes");
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let once = |i| format!(r#"{{"$include": "{}"}}"#, i + 1);
+        let mut config = parse(r#"{"$include": "0"}"#).unwrap();
+        let err = config.resolve_includes(chain(200, once)).unwrap_err();
+        assert_eq!(err.message, "includes nested too deep");
+        assert_eq!(err.chain.len(), MAX_DEPTH + 1);
+        let mut config = parse(r#"{"$include": "0"}"#).unwrap();
+        config.resolve_includes(chain(MAX_DEPTH - 1, once)).unwrap();
+
+        let nest = |i| format!(r#"[{{"$include": "{}"}}]"#, i + 1);
+        let mut config = parse(r#"{"$include": "0"}"#).unwrap();
+        let err = config.resolve_includes(chain(MAX_DEPTH, nest)).unwrap_err();
+        assert_eq!(err.message, "includes nested too deep");
+    }
+
+    #[test]
+    fn resolves_file_includes() {
+        let dir = std::env::temp_dir().join(format!("json-rs-include-{}", std::process::id()));
+        fs::create_dir_all(dir.join("parts")).unwrap();
+        fs::write(dir.join("parts/a.json"), r#"{"b": {"$include": "b.json"}}"#).unwrap();
+        fs::write(dir.join("parts/b.json"), "[1, 2]").unwrap();
+        fs::write(
+            dir.join("parts/self.json"),
+            r#"{"$include": "../parts/./self.json"}"#,
+        )
+        .unwrap();
+
+        let mut config = parse(r#"{"a": {"$include": "parts/a.json"}}"#).unwrap();
+        config.resolve_file_includes(&dir).unwrap();
+        assert_eq!(config, parse(r#"{"a": {"b": [1, 2]}}"#).unwrap());
+
+        let mut config = parse(r#"{"$include": "parts/self.json"}"#).unwrap();
+        let err = config.resolve_file_includes(&dir).unwrap_err();
+        assert_eq!(err.message, "include cycle");
+        assert_eq!(err.chain.len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Map, Type};
+
+/// What the samples seen at one place in the documents looked like.
+#[derive(Default)]
+pub(crate) struct Shape {
+    pub(crate) null: bool,
+    pub(crate) boolean: bool,
+    /// The range of the numbers, and whether they were all integers.
+    pub(crate) number: Option<(f64, f64, bool)>,
+    /// Whether an integer above `i64::MAX` was seen.
+    pub(crate) unsigned: bool,
+    pub(crate) string: bool,
+    pub(crate) array: Option<Box<Shape>>,
+    pub(crate) object: Option<Fields>,
+}
+
+#[derive(Default)]
+pub(crate) struct Fields {
+    /// How many objects were seen.
+    pub(crate) objects: usize,
+    /// Each key, with how many of the objects had it.
+    pub(crate) fields: BTreeMap<String, (usize, Shape)>,
+}
+
+impl Shape {
+    pub(crate) fn of(samples: &[Type]) -> Shape {
+        let mut shape = Shape::default();
+        for sample in samples {
+            shape.add(sample);
+        }
+        shape
+    }
+
+    fn add(&mut self, value: &Type) {
+        match value {
+            Type::Null => self.null = true,
+            Type::Boolean(_) => self.boolean = true,
+            Type::Number(n) => {
+                self.unsigned |= n.as_u64().is_some() && n.as_i64().is_none();
+                let n = n.to_f64();
+                let integer = n % 1.0 == 0.0;
+                self.number = Some(match self.number {
+                    None => (n, n, integer),
+                    Some((min, max, all)) => (min.min(n), max.max(n), all && integer),
+                });
+            }
+            Type::String(_) => self.string = true,
+            Type::Array(items) => {
+                let shape = self.array.get_or_insert_with(Box::default);
+                for item in items {
+                    shape.add(item);
+                }
+            }
+            Type::Object(members) => {
+                let object = self.object.get_or_insert_with(Fields::default);
+                object.objects += 1;
+                for (key, member) in members.iter() {
+                    let (seen, shape) = object.fields.entry(String::from(&**key)).or_default();
+                    *seen += 1;
+                    shape.add(member);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.null
+            && !self.boolean
+            && self.number.is_none()
+            && !self.string
+            && self.array.is_none()
+            && self.object.is_none()
+    }
+
+    fn to_schema(&self) -> Type {
+        if self.is_empty() {
+            return Type::Boolean(true);
+        }
+        let mut schema = Map::default();
+        let mut types = Vec::new();
+        let mut put = |key: &str, value: Type| {
+            schema.insert(key.into(), value);
+        };
+        if self.null {
+            types.push("null");
+        }
+        if self.boolean {
+            types.push("boolean");
+        }
+        if let Some((min, max, integer)) = self.number {
+            types.push(if integer { "integer" } else { "number" });
+            put("minimum", Type::Number(min.into()));
+            put("maximum", Type::Number(max.into()));
+        }
+        if self.string {
+            types.push("string");
+        }
+        if let Some(items) = &self.array {
+            types.push("array");
+            if !items.is_empty() {
+                put("items", items.to_schema());
+            }
+        }
+        if let Some(object) = &self.object {
+            types.push("object");
+            let properties = object
+                .fields
+                .iter()
+                .map(|(key, (_, shape))| (key.as_str().into(), shape.to_schema()))
+                .collect();
+            let required = object
+                .fields
+                .iter()
+                .filter(|(_, (seen, _))| *seen == object.objects)
+                .map(|(key, _)| Type::String(key.as_str().into()))
+                .collect();
+            put("properties", Type::Object(Box::new(properties)));
+            put("required", Type::Array(required));
+        }
+        let types = match types[..] {
+            [kind] => Type::String(kind.into()),
+            _ => Type::Array(
+                types
+                    .into_iter()
+                    .map(|kind| Type::String(kind.into()))
+                    .collect(),
+            ),
+        };
+        put("type", types);
+        Type::Object(Box::new(schema))
+    }
+}
+
+/// Infers a JSON Schema that all of `samples` satisfy, by merging their
+/// shapes: a value seen with several kinds gets a union `type`, numbers get
+/// the `minimum` and `maximum` seen, and an object key is `required` only if
+/// every sample object at that place had it.
+///
+/// The schema uses only keywords [`Schema`](crate::Schema) understands. With
+/// no samples it is `true`, which accepts anything.
+pub fn infer_schema(samples: &[Type]) -> Type {
+    Shape::of(samples).to_schema()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::infer_schema;
+    use crate::{parse, Type};
+
+    #[test]
+    fn merges_samples() {
+        let samples = [
+            parse(r#"{"id": 1, "name": "a", "tags": ["x"], "score": 1.5}"#).unwrap(),
+            parse(r#"{"id": 7, "name": null, "tags": [], "extra": {"k": true}}"#).unwrap(),
+        ];
+        let schema = infer_schema(&samples);
+        let expected = parse(
+            r#"{
+                "type": "object",
+                "required": ["id", "name", "tags"],
+                "properties": {
+                    "id": {"type": "integer", "minimum": 1, "maximum": 7},
+                    "name": {"type": ["null", "string"]},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "score": {"type": "number", "minimum": 1.5, "maximum": 1.5},
+                    "extra": {
+                        "type": "object",
+                        "required": ["k"],
+                        "properties": {"k": {"type": "boolean"}}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(schema, expected);
+        assert_eq!(infer_schema(&[]), Type::Boolean(true));
+        assert_eq!(
+            infer_schema(&[parse("[]").unwrap()]),
+            parse(r#"{"type": "array"}"#).unwrap()
+        );
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn samples_satisfy_the_schema() {
+        let samples = [
+            parse(r#"[{"a": 1}, {"a": "x", "b": [1, 2.5]}]"#).unwrap(),
+            parse(r#"[{"a": null}, 3]"#).unwrap(),
+        ];
+        let schema = crate::Schema::compile(&infer_schema(&samples)).unwrap();
+        for sample in &samples {
+            assert_eq!(schema.validate(sample), Ok(()));
+        }
+        assert!(!schema.is_valid(&parse(r#"[{"b": []}]"#).unwrap()));
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{into_str, to_string, JsonPointer, Map, Type};
+
+/// Settings for [`Type::interpolate_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterpolateOptions {
+    /// Whether a string that is a single placeholder and nothing else, like
+    /// `"${port}"`, is replaced by the variable's value itself, so that
+    /// numbers, objects and the rest keep their type.
+    pub whole_nodes: bool,
+}
+
+/// A placeholder [`Type::interpolate`] could not fill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpolateError {
+    /// The string that holds the placeholder.
+    pub pointer: JsonPointer,
+    pub message: String,
+}
+
+impl fmt::Display for InterpolateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}': {}", self.pointer, self.message)
+    }
+}
+
+impl std::error::Error for InterpolateError {}
+
+impl Type {
+    /// A copy with each `${name}` placeholder in the string values replaced
+    /// by the variable `name`: a string as its text, and anything else as
+    /// its JSON. `$${` stands for a literal `${`. Keys are left alone.
+    ///
+    /// An undefined variable or a `${` without its `}` is an error.
+    pub fn interpolate(&self, vars: &HashMap<String, Type>) -> Result<Type, InterpolateError> {
+        self.interpolate_with(vars, &InterpolateOptions::default())
+    }
+
+    /// Like [`interpolate`](Type::interpolate), with `options`.
+    pub fn interpolate_with(
+        &self,
+        vars: &HashMap<String, Type>,
+        options: &InterpolateOptions,
+    ) -> Result<Type, InterpolateError> {
+        walk(self, vars, options, &mut JsonPointer::root())
+    }
+
+    /// Replaces each `${VAR}` in the string values, in place, with the
+    /// environment variable `VAR`, and each `${VAR:-fallback}` with the
+    /// variable or, if it is unset or empty, with `fallback`, as shells do.
+    /// `$${` stands for a literal `${`. Keys are left alone.
+    ///
+    /// An unset variable without a fallback, or a `${` without its `}`, is
+    /// an error, and leaves the strings before it already expanded.
+    pub fn expand_env(&mut self) -> Result<(), InterpolateError> {
+        expand_env(self, &mut JsonPointer::root())
+    }
+}
+
+fn expand_env(value: &mut Type, pointer: &mut JsonPointer) -> Result<(), InterpolateError> {
+    match value {
+        Type::String(s) => {
+            let expanded = substitute(s, |name| {
+                let (name, fallback) = match name.split_once(":-") {
+                    Some((name, fallback)) => (name, Some(fallback)),
+                    None => (name, None),
+                };
+                match (std::env::var(name), fallback) {
+                    (Ok(value), Some(fallback)) if value.is_empty() => Ok(fallback.into()),
+                    (Ok(value), _) => Ok(value),
+                    (Err(_), Some(fallback)) => Ok(fallback.into()),
+                    (Err(_), None) => Err(format!("undefined variable '{}'", name)),
+                }
+            })
+            .map_err(|message| InterpolateError {
+                pointer: pointer.clone(),
+                message,
+            })?;
+            *s = into_str(expanded);
+        }
+        Type::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                pointer.push(i.to_string());
+                expand_env(item, pointer)?;
+                pointer.pop();
+            }
+        }
+        Type::Object(members) => {
+            for (key, member) in members.iter_mut() {
+                pointer.push(&**key);
+                expand_env(member, pointer)?;
+                pointer.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// `s` with each `${name}` replaced by `lookup(name)` and each `$${` by
+/// `${`; the error is a message.
+fn substitute(
+    s: &str,
+    mut lookup: impl FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(at) = rest.find('$') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        if let Some(after) = rest.strip_prefix("$${") {
+            out.push_str("${");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let Some(len) = after.find('}') else {
+                return Err("unterminated placeholder".into());
+            };
+            out.push_str(&lookup(&after[..len])?);
+            rest = &after[len + 1..];
+        } else {
+            out.push('$');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn walk(
+    value: &Type,
+    vars: &HashMap<String, Type>,
+    options: &InterpolateOptions,
+    pointer: &mut JsonPointer,
+) -> Result<Type, InterpolateError> {
+    let error = |pointer: &JsonPointer, message: String| InterpolateError {
+        pointer: pointer.clone(),
+        message,
+    };
+    match value {
+        Type::String(s) => {
+            if options.whole_nodes {
+                let name = s.strip_prefix("${").and_then(|rest| rest.strip_suffix('}'));
+                if let Some(name) = name.filter(|name| !name.contains('}')) {
+                    return vars
+                        .get(name)
+                        .cloned()
+                        .ok_or_else(|| error(pointer, format!("undefined variable '{}'", name)));
+                }
+            }
+            let out = substitute(s, |name| match vars.get(name) {
+                Some(Type::String(text)) => Ok(text.to_string()),
+                Some(value) => Ok(to_string(value)),
+                None => Err(format!("undefined variable '{}'", name)),
+            })
+            .map_err(|message| error(pointer, message))?;
+            Ok(Type::String(into_str(out)))
+        }
+        Type::Array(items) => {
+            let mut out = Vec::with_capacity(items.len());
+            for (i, item) in items.iter().enumerate() {
+                pointer.push(i.to_string());
+                out.push(walk(item, vars, options, pointer)?);
+                pointer.pop();
+            }
+            Ok(Type::Array(out))
+        }
+        Type::Object(members) => {
+            let mut out = Map::default();
+            for (key, member) in members.iter() {
+                pointer.push(&**key);
+                out.insert(key.clone(), walk(member, vars, options, pointer)?);
+                pointer.pop();
+            }
+            Ok(Type::Object(Box::new(out)))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::InterpolateOptions;
+    use crate::{parse, Type};
+
+    fn vars() -> HashMap<String, Type> {
+        HashMap::from([
+            ("host".to_string(), Type::String("example.com".into())),
+            ("port".to_string(), Type::Number(8080.into())),
+            ("tags".to_string(), parse(r#"["a", "b"]"#).unwrap()),
+        ])
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let template = parse(
+            r#"{"url": "https://${host}:${port}/", "tags": "${tags}", "cost": "$5 $${x}", "n": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            template.interpolate(&vars()).unwrap(),
+            parse(r#"{"url": "https://example.com:8080/", "tags": "[\"a\",\"b\"]", "cost": "$5 ${x}", "n": 1}"#)
+                .unwrap()
+        );
+        let options = InterpolateOptions { whole_nodes: true };
+        assert_eq!(
+            template.interpolate_with(&vars(), &options).unwrap(),
+            parse(r#"{"url": "https://example.com:8080/", "tags": ["a", "b"], "cost": "$5 ${x}", "n": 1}"#)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn reports_bad_placeholders() {
+        let template = parse(r#"{"a": [1, "${missing}"]}"#).unwrap();
+        assert_eq!(
+            template.interpolate(&vars()).unwrap_err().to_string(),
+            "'/a/1': undefined variable 'missing'"
+        );
+        let template = Type::String("${host".into());
+        assert_eq!(
+            template.interpolate(&vars()).unwrap_err().to_string(),
+            "'': unterminated placeholder"
+        );
+    }
+
+    #[test]
+    fn expands_env() {
+        std::env::set_var("JSON_RS_TEST_HOST", "db.local");
+        std::env::set_var("JSON_RS_TEST_EMPTY", "");
+        let mut config = parse(
+            r#"{"url": "pg://${JSON_RS_TEST_HOST}:${JSON_RS_TEST_PORT:-5432}/", "user": "${JSON_RS_TEST_EMPTY:-admin}", "raw": ["$${JSON_RS_TEST_HOST}", "${JSON_RS_TEST_EMPTY}"]}"#,
+        )
+        .unwrap();
+        config.expand_env().unwrap();
+        assert_eq!(
+            config,
+            parse(r#"{"url": "pg://db.local:5432/", "user": "admin", "raw": ["${JSON_RS_TEST_HOST}", ""]}"#)
+                .unwrap()
+        );
+
+        let mut config = parse(r#"{"a": ["${JSON_RS_TEST_UNSET}"]}"#).unwrap();
+        assert_eq!(
+            config.expand_env().unwrap_err().to_string(),
+            "'/a/0': undefined variable 'JSON_RS_TEST_UNSET'"
+        );
+    }
+}
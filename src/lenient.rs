@@ -0,0 +1,315 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{decode_number, decode_string, Lexer, Token, TokenKind};
+use crate::parser::{Event, Expect, Frame, MAX_DEPTH};
+use crate::{JsonError, Type};
+
+/// The parser state of [`parse_lenient`]: the same states as the strict
+/// grammar, plus a repair for every token the grammar would reject.
+struct Recovery {
+    builder: TreeBuilder,
+    stack: Vec<Frame>,
+    expect: Expect,
+    root: Option<Type>,
+    errors: Vec<JsonError>,
+    /// Set after an error until a token is accepted again, so that one
+    /// mistake is reported once rather than for every token it derails.
+    quiet: bool,
+}
+
+impl Recovery {
+    fn error(&mut self, err: JsonError) {
+        if !self.quiet {
+            self.errors.push(err);
+            self.quiet = true;
+        }
+    }
+
+    fn emit(&mut self, event: Event) {
+        if let Some(root) = self.builder.push(event) {
+            self.root = Some(root);
+        }
+    }
+
+    fn end_value(&mut self) {
+        self.quiet = false;
+        self.expect = if self.stack.is_empty() {
+            Expect::Done
+        } else {
+            Expect::CommaOrEnd
+        };
+    }
+
+    fn after_comma(&self) -> Expect {
+        match self.stack.last() {
+            Some(Frame::Object) => Expect::Key,
+            _ => Expect::Value,
+        }
+    }
+
+    /// Closes the innermost container, giving a member that is still
+    /// waiting for its value a `null`.
+    fn close(&mut self) {
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        if matches!(self.expect, Expect::Colon | Expect::Value) && frame == Frame::Object {
+            self.emit(Event::Null);
+        }
+        self.emit(match frame {
+            Frame::Object => Event::EndObject,
+            Frame::Array => Event::EndArray,
+        });
+        self.end_value();
+    }
+
+    fn scalar(&mut self, input: &[u8], token: Token) -> Event {
+        let decoded = match token.kind {
+            TokenKind::String => {
+                decode_string(&input[token.start + 1..token.end - 1], token.start + 1)
+                    .map(Event::String)
+            }
+            TokenKind::Number => {
+                decode_number(&input[token.start..token.end], token.start).map(Event::Number)
+            }
+            TokenKind::True => Ok(Event::Boolean(true)),
+            TokenKind::False => Ok(Event::Boolean(false)),
+            _ => Ok(Event::Null),
+        };
+        decoded.unwrap_or_else(|err| {
+            self.error(err);
+            Event::Null
+        })
+    }
+
+    fn token(&mut self, input: &[u8], token: Token) {
+        let starts_value = matches!(
+            token.kind,
+            TokenKind::BeginObject
+                | TokenKind::BeginArray
+                | TokenKind::String
+                | TokenKind::Number
+                | TokenKind::True
+                | TokenKind::False
+                | TokenKind::Null
+        );
+        loop {
+            let unexpected = JsonError::UnexpectToken {
+                offset: token.start,
+                expected: self.expect.tokens(self.stack.last()),
+            };
+            match (self.expect, token.kind) {
+                // Trailing content after the root value is ignored.
+                (Expect::Done, _) => self.error(unexpected),
+                // A container nested too deep to build safely becomes `null`.
+                (
+                    Expect::Value | Expect::ValueOrEnd,
+                    TokenKind::BeginObject | TokenKind::BeginArray,
+                ) if self.stack.len() == MAX_DEPTH => {
+                    self.error(JsonError::LimitExceeded {
+                        offset: token.start,
+                    });
+                    self.emit(Event::Null);
+                    self.end_value();
+                }
+                (Expect::Value | Expect::ValueOrEnd, TokenKind::BeginObject) => {
+                    self.emit(Event::StartObject);
+                    self.stack.push(Frame::Object);
+                    self.expect = Expect::KeyOrEnd;
+                    self.quiet = false;
+                }
+                (Expect::Value | Expect::ValueOrEnd, TokenKind::BeginArray) => {
+                    self.emit(Event::StartArray);
+                    self.stack.push(Frame::Array);
+                    self.expect = Expect::ValueOrEnd;
+                    self.quiet = false;
+                }
+                (Expect::Value | Expect::ValueOrEnd, _) if starts_value => {
+                    let event = self.scalar(input, token);
+                    self.emit(event);
+                    self.end_value();
+                }
+                (Expect::ValueOrEnd, TokenKind::EndArray)
+                    if self.stack.last() == Some(&Frame::Array) =>
+                {
+                    self.close()
+                }
+                (Expect::KeyOrEnd, TokenKind::EndObject)
+                | (Expect::CommaOrEnd, TokenKind::EndObject)
+                    if self.stack.last() == Some(&Frame::Object) =>
+                {
+                    self.close()
+                }
+                (Expect::CommaOrEnd, TokenKind::EndArray)
+                    if self.stack.last() == Some(&Frame::Array) =>
+                {
+                    self.close()
+                }
+                // A missing value, trailing comma or mismatched bracket: close
+                // the innermost container anyway.
+                (_, TokenKind::EndArray | TokenKind::EndObject) => {
+                    self.error(unexpected);
+                    self.close();
+                }
+                // `[1,,2]` or `{"a":,}`: the missing value becomes `null`.
+                (Expect::Value | Expect::ValueOrEnd, TokenKind::Comma)
+                    if !self.stack.is_empty() =>
+                {
+                    self.error(unexpected);
+                    self.emit(Event::Null);
+                    self.expect = self.after_comma();
+                }
+                (Expect::KeyOrEnd | Expect::Key, TokenKind::String) => {
+                    let raw = &input[token.start + 1..token.end - 1];
+                    let key = decode_string(raw, token.start + 1).unwrap_or_else(|err| {
+                        self.error(err);
+                        String::from_utf8_lossy(raw).into_owned()
+                    });
+                    self.emit(Event::Key(key));
+                    self.expect = Expect::Colon;
+                    self.quiet = false;
+                }
+                (Expect::Colon, TokenKind::Colon) => {
+                    self.expect = Expect::Value;
+                    self.quiet = false;
+                }
+                // `{"a" 1}`: the colon is missing.
+                (Expect::Colon, _) if starts_value => {
+                    self.error(unexpected);
+                    self.expect = Expect::Value;
+                    continue;
+                }
+                // `{"a", ...}`: the member has no value.
+                (Expect::Colon, TokenKind::Comma) => {
+                    self.error(unexpected);
+                    self.emit(Event::Null);
+                    self.expect = Expect::Key;
+                }
+                (Expect::CommaOrEnd, TokenKind::Comma) => {
+                    self.expect = self.after_comma();
+                    self.quiet = false;
+                }
+                // `[1 2]`: the comma is missing.
+                (Expect::CommaOrEnd, _) if starts_value => {
+                    self.error(unexpected);
+                    self.expect = self.after_comma();
+                    continue;
+                }
+                // Anything else is skipped.
+                _ => self.error(unexpected),
+            }
+            return;
+        }
+    }
+}
+
+/// Parses `json` as far as possible, repairing each syntax error instead of
+/// stopping at it: malformed tokens and missing values become `null`, missing
+/// commas and colons are assumed, stray tokens are skipped, and unclosed
+/// containers are closed at the end of the input.
+///
+/// Returns the repaired value and every error found, in document order. The
+/// first error, if any, is the one [`parse`](crate::parse) reports. Meant for
+/// editors and linters that want all the problems in a document at once.
+pub fn parse_lenient(json: &str) -> (Type, Vec<JsonError>) {
+    let input = json.as_bytes();
+    let mut lexer = Lexer::new(input).skip_bom();
+    let mut state = Recovery {
+        builder: TreeBuilder::new(),
+        stack: Vec::new(),
+        expect: Expect::Value,
+        root: None,
+        errors: Vec::new(),
+        quiet: false,
+    };
+    loop {
+        match lexer.next_token() {
+            Ok(Some(token)) => state.token(input, token),
+            Ok(None) => break,
+            Err(err) => {
+                state.error(err);
+                // Stand a `null` in for the malformed token.
+                let (start, end) = lexer.skip_invalid();
+                let token = Token {
+                    kind: TokenKind::Null,
+                    start,
+                    end,
+                };
+                state.token(input, token);
+            }
+        }
+    }
+    if state.expect != Expect::Done {
+        state.quiet = false;
+        state.error(JsonError::UnexpectEof { offset: json.len() });
+        while !state.stack.is_empty() {
+            state.close();
+        }
+    }
+    (state.root.unwrap_or(Type::Null), state.errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_lenient;
+    use crate::{parse, Expected, JsonError, TokenKind, Type};
+
+    #[test]
+    fn repairs_documents() {
+        for (input, repaired) in [
+            ("[1 2]", "[1, 2]"),
+            ("[1,,2]", "[1, null, 2]"),
+            ("[1, 2,]", "[1, 2]"),
+            ("[1, tru, 3]", "[1, null, 3]"),
+            (r#"{"a" 1, "b": }"#, r#"{"a": 1, "b": null}"#),
+            (r#"{"a", "b": 2}"#, r#"{"a": null, "b": 2}"#),
+            (r#"{"a": [1, {"b": "\x"}"#, r#"{"a": [1, {"b": null}]}"#),
+            (r#"{1: 2, "c": 3}"#, r#"{"c": 3}"#),
+            ("[1} 2", "[1]"),
+            ("", "null"),
+        ] {
+            let (value, errors) = parse_lenient(input);
+            assert_eq!(value, parse(repaired).unwrap(), "{}", input);
+            assert_eq!(errors.first(), parse(input).err().as_ref(), "{}", input);
+        }
+        assert_eq!(
+            parse_lenient(r#"{"a": 1}"#),
+            (parse(r#"{"a": 1}"#).unwrap(), vec![])
+        );
+    }
+
+    #[test]
+    fn reports_every_error() {
+        let (value, errors) = parse_lenient("[1 2, @, {\"k\" 3}, \"\\ud800\"");
+        assert_eq!(
+            errors,
+            vec![
+                JsonError::UnexpectToken {
+                    offset: 3,
+                    expected: Expected::of(&[TokenKind::Comma, TokenKind::EndArray])
+                },
+                JsonError::UnexpectToken {
+                    offset: 6,
+                    expected: Expected::NONE
+                },
+                JsonError::UnexpectToken {
+                    offset: 14,
+                    expected: Expected::of(&[TokenKind::Colon])
+                },
+                JsonError::UnexpectToken {
+                    offset: 19,
+                    expected: Expected::NONE
+                },
+                JsonError::UnexpectEof { offset: 26 },
+            ]
+        );
+        let Type::Array(items) = value else {
+            panic!("expected an array");
+        };
+        assert_eq!(items.len(), 5);
+        assert_eq!(items[2], Type::Null);
+        assert_eq!(items[4], Type::Null);
+    }
+}
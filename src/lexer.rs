@@ -0,0 +1,775 @@
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::simd::{skip_string_body, skip_whitespace};
+use crate::{Expected, JsonError, Number};
+
+/// The kinds of token JSON text is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    BeginObject,
+    EndObject,
+    BeginArray,
+    EndArray,
+    Colon,
+    Comma,
+    String,
+    Number,
+    True,
+    False,
+    Null,
+}
+
+/// A token and the byte range it occupies in the lexer input. For strings the
+/// range includes both quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A place in a [`Lexer`]'s input, from [`Lexer::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LexerCheckpoint {
+    pos: usize,
+}
+
+impl LexerCheckpoint {
+    /// The byte offset of the place.
+    pub fn offset(self) -> usize {
+        self.pos
+    }
+}
+
+/// The UTF-8 byte-order mark, U+FEFF.
+pub(crate) const BOM: &[u8] = b"\xef\xbb\xbf";
+
+pub(crate) fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Splits JSON text into tokens without decoding string or number contents.
+///
+/// A lexer created with [`Lexer::partial`] treats running out of input in the
+/// middle of a token as "need more data" rather than an error, which is what
+/// lets the push parser resume at arbitrary chunk boundaries.
+///
+/// With [`Lexer::with_comments`], `//` line comments and `/* */` block
+/// comments are skipped like whitespace.
+///
+/// [`Lexer::checkpoint`] and [`Lexer::restore`] let a tool built on the
+/// lexer look ahead, or try a reading and back out of it, without lexing
+/// again from the start.
+#[derive(Clone)]
+pub struct Lexer<'a> {
+    input: &'a [u8],
+    pos: usize,
+    eof: bool,
+    comments: bool,
+    /// Where scanning the string at `pos` resumes, or 0 to scan it from the
+    /// start. After a partial lexer stops inside a string, how far the
+    /// string is known to run without ending.
+    resume: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Lexer {
+            input,
+            pos: 0,
+            eof: true,
+            comments: false,
+            resume: 0,
+        }
+    }
+
+    pub fn partial(input: &'a [u8]) -> Self {
+        Lexer {
+            input,
+            pos: 0,
+            eof: false,
+            comments: false,
+            resume: 0,
+        }
+    }
+
+    pub fn with_comments(mut self) -> Self {
+        self.comments = true;
+        self
+    }
+
+    /// Starts after a UTF-8 byte-order mark at the start of the input, which
+    /// Windows editors often write. Offsets still count it.
+    pub fn skip_bom(mut self) -> Self {
+        if self.pos == 0 && self.input.starts_with(BOM) {
+            self.pos = BOM.len();
+        }
+        self
+    }
+
+    /// Resumes a string that an earlier partial lexer stopped inside, which
+    /// starts at the lexer's position, at `pos` of this input rather than
+    /// scanning it from the start again. `pos` is from
+    /// [`string_progress`](Lexer::string_progress), moved to this input.
+    pub(crate) fn resume_string(mut self, pos: usize) -> Self {
+        self.resume = pos;
+        self
+    }
+
+    /// After `next_token` stops inside a string needing more input, how far
+    /// the string was scanned; 0 otherwise.
+    pub(crate) fn string_progress(&self) -> usize {
+        self.resume
+    }
+
+    pub fn input(&self) -> &'a [u8] {
+        self.input
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.eof
+    }
+
+    /// The lexer's place in its input, to [`restore`](Lexer::restore) later.
+    pub fn checkpoint(&self) -> LexerCheckpoint {
+        LexerCheckpoint { pos: self.pos }
+    }
+
+    /// Goes back, or forward, to a place from [`checkpoint`](Lexer::checkpoint)
+    /// on this lexer or a clone of it; the tokens after it are lexed again.
+    pub fn restore(&mut self, checkpoint: LexerCheckpoint) {
+        debug_assert!(checkpoint.pos <= self.input.len());
+        self.pos = checkpoint.pos.min(self.input.len());
+        self.resume = 0;
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.pos = skip_whitespace(self.input, self.pos);
+    }
+
+    /// Skips whitespace and, if enabled, comments. Returns `false` when a
+    /// partial lexer stops at a comment that may continue in the next chunk;
+    /// `pos` is then left at its start.
+    fn skip_trivia(&mut self) -> Result<bool, JsonError> {
+        loop {
+            self.skip_whitespace();
+            if !self.comments || self.input.get(self.pos) != Some(&b'/') {
+                return Ok(true);
+            }
+            let body = self.pos + 2;
+            let end = match self.input.get(self.pos + 1) {
+                Some(b'/') => match self.input[body..].iter().position(|&b| b == b'\n') {
+                    Some(len) => Some(body + len + 1),
+                    None if self.eof => Some(self.input.len()),
+                    None => None,
+                },
+                Some(b'*') => match self.input[body..].windows(2).position(|w| w == b"*/") {
+                    Some(len) => Some(body + len + 2),
+                    None if self.eof => {
+                        return Err(JsonError::UnexpectEof {
+                            offset: self.input.len(),
+                        })
+                    }
+                    None => None,
+                },
+                None if !self.eof => None,
+                _ => {
+                    return Err(JsonError::UnexpectToken {
+                        offset: self.pos,
+                        expected: Expected::NONE,
+                    })
+                }
+            };
+            match end {
+                Some(end) => self.pos = end,
+                None => return Ok(false),
+            }
+        }
+    }
+
+    /// After `next_token` fails on a malformed token, moves past it and
+    /// returns the span skipped: a string runs to its closing quote or the
+    /// end of the line, anything else to the next whitespace or punctuation.
+    pub fn skip_invalid(&mut self) -> (usize, usize) {
+        let start = self.pos;
+        let mut pos = start + 1;
+        if self.input.get(start) == Some(&b'"') {
+            while let Some(&byte) = self.input.get(pos) {
+                match byte {
+                    b'"' => {
+                        pos += 1;
+                        break;
+                    }
+                    b'\n' => break,
+                    b'\\' => pos += 2,
+                    _ => pos += 1,
+                }
+            }
+        } else {
+            while let Some(&byte) = self.input.get(pos) {
+                if is_whitespace(byte) || b",:[]{}\"".contains(&byte) {
+                    break;
+                }
+                pos += 1;
+            }
+        }
+        self.pos = pos.min(self.input.len());
+        (start, self.pos)
+    }
+
+    /// Like [`Lexer::next_token`], treating running out of tokens as an error.
+    pub fn expect_token(&mut self) -> Result<Token, JsonError> {
+        self.next_token()?.ok_or(JsonError::UnexpectEof {
+            offset: self.input.len(),
+        })
+    }
+
+    /// Returns the next complete token, or `None` when the input is exhausted.
+    /// For a partial lexer `None` may also mean the remaining bytes are the
+    /// start of a token that needs more input; `pos` is left at its start.
+    pub fn next_token(&mut self) -> Result<Option<Token>, JsonError> {
+        if !self.skip_trivia()? {
+            return Ok(None);
+        }
+        let start = self.pos;
+        let Some(&byte) = self.input.get(start) else {
+            return Ok(None);
+        };
+        let kind = match byte {
+            b'{' => TokenKind::BeginObject,
+            b'}' => TokenKind::EndObject,
+            b'[' => TokenKind::BeginArray,
+            b']' => TokenKind::EndArray,
+            b':' => TokenKind::Colon,
+            b',' => TokenKind::Comma,
+            b'"' => {
+                let mut pos = if self.resume > start {
+                    self.resume
+                } else {
+                    start + 1
+                };
+                let scan = scan_string(self.input, start, &mut pos);
+                self.resume = if matches!(scan, Scan::Truncated(_)) {
+                    pos
+                } else {
+                    0
+                };
+                return self.finish(start, TokenKind::String, scan);
+            }
+            b'-' | b'0'..=b'9' => {
+                return self.finish(start, TokenKind::Number, scan_number(self.input, start))
+            }
+            b't' => {
+                return self.finish(
+                    start,
+                    TokenKind::True,
+                    scan_literal(self.input, start, b"true"),
+                )
+            }
+            b'f' => {
+                return self.finish(
+                    start,
+                    TokenKind::False,
+                    scan_literal(self.input, start, b"false"),
+                )
+            }
+            b'n' => {
+                return self.finish(
+                    start,
+                    TokenKind::Null,
+                    scan_literal(self.input, start, b"null"),
+                )
+            }
+            _ => {
+                return Err(JsonError::UnexpectToken {
+                    offset: start,
+                    expected: Expected::NONE,
+                })
+            }
+        };
+        self.pos += 1;
+        Ok(Some(Token {
+            kind,
+            start,
+            end: self.pos,
+        }))
+    }
+
+    fn finish(
+        &mut self,
+        start: usize,
+        kind: TokenKind,
+        scan: Scan,
+    ) -> Result<Option<Token>, JsonError> {
+        match scan {
+            Scan::Complete(end) => {
+                self.pos = end;
+                Ok(Some(Token { kind, start, end }))
+            }
+            // A number running into the end of complete input is simply done.
+            Scan::Truncated(end) if self.eof && kind == TokenKind::Number => {
+                if number_is_complete(&self.input[start..end]) {
+                    self.pos = end;
+                    Ok(Some(Token { kind, start, end }))
+                } else {
+                    Err(JsonError::UnexpectEof { offset: end })
+                }
+            }
+            Scan::Truncated(end) if self.eof => Err(JsonError::UnexpectEof { offset: end }),
+            Scan::Truncated(_) => Ok(None),
+            Scan::Invalid(offset) => Err(JsonError::UnexpectToken {
+                offset,
+                expected: Expected::NONE,
+            }),
+        }
+    }
+}
+
+enum Scan {
+    /// The token ends just before this offset.
+    Complete(usize),
+    /// The input ran out at this offset before the token could be terminated.
+    Truncated(usize),
+    /// The byte at this offset cannot continue the token.
+    Invalid(usize),
+}
+
+/// Scans the string starting at `start` from `pos`, a place in its body
+/// outside any escape. If the input runs out, `pos` is left where scanning
+/// can resume once more of it arrives.
+fn scan_string(input: &[u8], start: usize, pos: &mut usize) -> Scan {
+    debug_assert!(*pos > start);
+    while let Some(&byte) = input.get(*pos) {
+        match byte {
+            b'"' => return Scan::Complete(*pos + 1),
+            b'\\' => {
+                let Some(&escape) = input.get(*pos + 1) else {
+                    return Scan::Truncated(input.len());
+                };
+                match escape {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => *pos += 2,
+                    b'u' => {
+                        for i in 0..4 {
+                            match input.get(*pos + 2 + i) {
+                                Some(digit) if digit.is_ascii_hexdigit() => {}
+                                Some(_) => return Scan::Invalid(*pos + 2 + i),
+                                None => return Scan::Truncated(input.len()),
+                            }
+                        }
+                        *pos += 6;
+                    }
+                    _ => return Scan::Invalid(*pos + 1),
+                }
+            }
+            0x00..=0x1f => return Scan::Invalid(*pos),
+            _ => *pos = skip_string_body(input, *pos + 1),
+        }
+    }
+    Scan::Truncated(input.len())
+}
+
+fn scan_number(input: &[u8], start: usize) -> Scan {
+    let mut pos = start;
+    if input.get(pos) == Some(&b'-') {
+        pos += 1;
+    }
+    match input.get(pos) {
+        Some(b'0') => pos += 1,
+        Some(b'1'..=b'9') => pos = skip_digits(input, pos),
+        Some(_) => return Scan::Invalid(pos),
+        None => return Scan::Truncated(pos),
+    }
+    if input.get(pos) == Some(&b'.') {
+        pos += 1;
+        match input.get(pos) {
+            Some(b'0'..=b'9') => pos = skip_digits(input, pos),
+            Some(_) => return Scan::Invalid(pos),
+            None => return Scan::Truncated(pos),
+        }
+    }
+    if let Some(b'e' | b'E') = input.get(pos) {
+        pos += 1;
+        if let Some(b'+' | b'-') = input.get(pos) {
+            pos += 1;
+        }
+        match input.get(pos) {
+            Some(b'0'..=b'9') => pos = skip_digits(input, pos),
+            Some(_) => return Scan::Invalid(pos),
+            None => return Scan::Truncated(pos),
+        }
+    }
+    if pos == input.len() {
+        // More digits may still arrive.
+        Scan::Truncated(pos)
+    } else {
+        Scan::Complete(pos)
+    }
+}
+
+fn skip_digits(input: &[u8], mut pos: usize) -> usize {
+    while let Some(b'0'..=b'9') = input.get(pos) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Whether a lexeme cut off by the end of input is nonetheless a valid number.
+fn number_is_complete(lexeme: &[u8]) -> bool {
+    lexeme.last().is_some_and(u8::is_ascii_digit)
+}
+
+fn scan_literal(input: &[u8], start: usize, literal: &[u8]) -> Scan {
+    let available = &input[start..];
+    if available.len() < literal.len() {
+        match mismatch(available, literal) {
+            None => Scan::Truncated(input.len()),
+            Some(i) => Scan::Invalid(start + i),
+        }
+    } else {
+        match mismatch(available, literal) {
+            None => Scan::Complete(start + literal.len()),
+            Some(i) => Scan::Invalid(start + i),
+        }
+    }
+}
+
+/// Index of the first byte where `available` stops matching `literal`.
+fn mismatch(available: &[u8], literal: &[u8]) -> Option<usize> {
+    available.iter().zip(literal).position(|(a, b)| a != b)
+}
+
+/// Decodes the contents of a string token, `raw` being the bytes between the
+/// quotes and `offset` the position of `raw` in the input (for errors). The
+/// lexer has already checked the escape syntax.
+pub(crate) fn decode_string(raw: &[u8], offset: usize) -> Result<String, JsonError> {
+    if !raw.contains(&b'\\') {
+        return match core::str::from_utf8(raw) {
+            Ok(s) => Ok(s.to_owned()),
+            Err(err) => Err(JsonError::UnexpectToken {
+                offset: offset + err.valid_up_to(),
+                expected: Expected::NONE,
+            }),
+        };
+    }
+
+    let mut out = Vec::with_capacity(raw.len());
+    let mut pos = 0;
+    while pos < raw.len() {
+        let byte = raw[pos];
+        if byte != b'\\' {
+            out.push(byte);
+            pos += 1;
+            continue;
+        }
+        let unescaped = match raw.get(pos + 1) {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let (ch, len) = decode_unicode_escape(&raw[pos..], offset + pos)?;
+                let mut buf = [0; 4];
+                out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                pos += len;
+                continue;
+            }
+            _ => {
+                return Err(JsonError::UnexpectToken {
+                    offset: offset + pos,
+                    expected: Expected::NONE,
+                })
+            }
+        };
+        out.push(unescaped as u8);
+        pos += 2;
+    }
+    String::from_utf8(out).map_err(|_| {
+        let valid = core::str::from_utf8(raw).map_or_else(|err| err.valid_up_to(), |_| 0);
+        JsonError::UnexpectToken {
+            offset: offset + valid,
+            expected: Expected::NONE,
+        }
+    })
+}
+
+/// Performs the checks of [`decode_string`] without building the string.
+pub(crate) fn check_string(raw: &[u8], offset: usize) -> Result<(), JsonError> {
+    if let Err(err) = core::str::from_utf8(raw) {
+        return Err(JsonError::UnexpectToken {
+            offset: offset + err.valid_up_to(),
+            expected: Expected::NONE,
+        });
+    }
+    let mut pos = 0;
+    while let Some(skip) = raw[pos..].iter().position(|&b| b == b'\\') {
+        pos += skip;
+        if raw.get(pos + 1) == Some(&b'u') {
+            pos += decode_unicode_escape(&raw[pos..], offset + pos)?.1;
+        } else {
+            pos += 2;
+        }
+    }
+    Ok(())
+}
+
+/// Decodes a `\uXXXX` escape (or a surrogate pair of them) at the start of
+/// `raw`, returning the character and the number of bytes consumed.
+fn decode_unicode_escape(raw: &[u8], offset: usize) -> Result<(char, usize), JsonError> {
+    let invalid = JsonError::UnexpectToken {
+        offset,
+        expected: Expected::NONE,
+    };
+    let Some(high) = hex4(raw.get(2..6)) else {
+        return Err(invalid);
+    };
+    let code = match high {
+        0xd800..=0xdbff => {
+            if raw.get(6) != Some(&b'\\') || raw.get(7) != Some(&b'u') {
+                return Err(invalid);
+            }
+            match hex4(raw.get(8..12)) {
+                Some(low @ 0xdc00..=0xdfff) => {
+                    let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                    return char::from_u32(code).map(|ch| (ch, 12)).ok_or(invalid);
+                }
+                _ => return Err(invalid),
+            }
+        }
+        0xdc00..=0xdfff => return Err(invalid),
+        code => code,
+    };
+    char::from_u32(code).map(|ch| (ch, 6)).ok_or(invalid)
+}
+
+fn hex4(digits: Option<&[u8]>) -> Option<u32> {
+    let mut code = 0;
+    for &digit in digits? {
+        code = code * 16 + (digit as char).to_digit(16)?;
+    }
+    Some(code)
+}
+
+/// Decodes a number lexeme, exactly when it is an integer that fits in
+/// `u64` or `i64`.
+pub(crate) fn decode_number(lexeme: &[u8], offset: usize) -> Result<Number, JsonError> {
+    let text = core::str::from_utf8(lexeme).map_err(|_| JsonError::UnexpectToken {
+        offset,
+        expected: Expected::NONE,
+    })?;
+    if !text.contains(['.', 'e', 'E']) {
+        if let Ok(n) = text.parse::<u64>() {
+            return Ok(Number::from(n));
+        }
+        // `-0` goes through `f64` to keep its sign.
+        if let Ok(n @ (..=-1)) = text.parse::<i64>() {
+            return Ok(Number::from(n));
+        }
+    }
+    text.parse::<f64>()
+        .map(Number::from)
+        .map_err(|_| JsonError::UnexpectToken {
+            offset,
+            expected: Expected::NONE,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_string, Lexer, TokenKind};
+    use crate::{Expected, JsonError};
+
+    fn kinds(input: &str) -> Result<Vec<TokenKind>, JsonError> {
+        let mut lexer = Lexer::new(input.as_bytes());
+        let mut kinds = vec![];
+        while let Some(token) = lexer.next_token()? {
+            kinds.push(token.kind);
+        }
+        Ok(kinds)
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            kinds(" {\"a\" : [1, -2.5e3, true, false, null]} ").unwrap(),
+            vec![
+                TokenKind::BeginObject,
+                TokenKind::String,
+                TokenKind::Colon,
+                TokenKind::BeginArray,
+                TokenKind::Number,
+                TokenKind::Comma,
+                TokenKind::Number,
+                TokenKind::Comma,
+                TokenKind::True,
+                TokenKind::Comma,
+                TokenKind::False,
+                TokenKind::Comma,
+                TokenKind::Null,
+                TokenKind::EndArray,
+                TokenKind::EndObject,
+            ]
+        );
+        assert_eq!(
+            kinds("1.").unwrap_err(),
+            JsonError::UnexpectEof { offset: 2 }
+        );
+        assert_eq!(
+            kinds("\"abc").unwrap_err(),
+            JsonError::UnexpectEof { offset: 4 }
+        );
+        assert_eq!(
+            kinds(" nul").unwrap_err(),
+            JsonError::UnexpectEof { offset: 4 }
+        );
+        assert_eq!(
+            kinds("nil").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            kinds("[\"\\x\"").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            kinds("\"\t\"").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            kinds("[1, @]").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 4,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            kinds("-x").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn partial_tokens() {
+        let mut lexer = Lexer::partial(b"[12");
+        assert_eq!(
+            lexer.next_token().unwrap().unwrap().kind,
+            TokenKind::BeginArray
+        );
+        assert_eq!(lexer.next_token().unwrap(), None);
+        assert_eq!(lexer.pos(), 1);
+
+        let mut lexer = Lexer::partial(b"\"ab\\");
+        assert_eq!(lexer.next_token().unwrap(), None);
+        assert_eq!(lexer.pos(), 0);
+    }
+
+    #[test]
+    fn checkpoints() {
+        let mut lexer = Lexer::new(b"[1, tru, 2]");
+        lexer.next_token().unwrap();
+        let checkpoint = lexer.checkpoint();
+        assert_eq!(checkpoint.offset(), 1);
+        assert_eq!(lexer.next_token().unwrap().unwrap().kind, TokenKind::Number);
+        lexer.next_token().unwrap();
+        let before_bad = lexer.checkpoint();
+        assert!(lexer.next_token().is_err());
+        lexer.restore(before_bad);
+        lexer.skip_whitespace();
+        assert_eq!(lexer.skip_invalid(), (4, 7));
+        assert_eq!(lexer.next_token().unwrap().unwrap().kind, TokenKind::Comma);
+        lexer.restore(checkpoint);
+        let token = lexer.next_token().unwrap().unwrap();
+        assert_eq!(
+            (token.kind, token.start, token.end),
+            (TokenKind::Number, 1, 2)
+        );
+        assert!(before_bad > checkpoint);
+    }
+
+    #[test]
+    fn comments() {
+        let mut lexer = Lexer::new(b"// a\n[1, /* b */ 2] // c").with_comments();
+        let mut count = 0;
+        while lexer.next_token().unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, 5);
+        assert_eq!(
+            kinds("/* a */").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 0,
+                expected: Expected::NONE
+            }
+        );
+        for (input, err) in [
+            (&b"1 /* a"[..], JsonError::UnexpectEof { offset: 6 }),
+            (
+                b"1 / 2",
+                JsonError::UnexpectToken {
+                    offset: 2,
+                    expected: Expected::NONE,
+                },
+            ),
+        ] {
+            let mut lexer = Lexer::new(input).with_comments();
+            assert!(lexer.next_token().unwrap().is_some());
+            assert_eq!(lexer.next_token().unwrap_err(), err);
+        }
+
+        let mut lexer = Lexer::partial(b"1 /* a *").with_comments();
+        assert!(lexer.next_token().unwrap().is_some());
+        assert_eq!(lexer.next_token().unwrap(), None);
+        assert_eq!(lexer.pos(), 2);
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(decode_string(b"plain", 0).unwrap(), "plain");
+        assert_eq!(
+            decode_string(br#"\"\\\/\b\f\n\r\t"#, 0).unwrap(),
+            "\"\\/\u{8}\u{c}\n\r\t"
+        );
+        assert_eq!(decode_string(br"\u00e9\ud83d\ude00", 0).unwrap(), "é😀");
+        assert_eq!(
+            decode_string(br"ab\ud83d", 1).unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            decode_string(br"\ude00", 1).unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            decode_string(b"a\xff", 1).unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 2,
+                expected: Expected::NONE
+            }
+        );
+    }
+}
@@ -1,278 +1,555 @@
-use std::collections::HashMap;
+#![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+mod approx;
+#[cfg(feature = "arena")]
+mod arena;
+#[cfg(feature = "std")]
+mod array_reader;
+#[cfg(feature = "tokio")]
+mod async_parse;
+mod base64;
+mod builder;
+mod case;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod codegen;
+mod coerce;
+mod compare;
+mod convert;
+mod cst;
+#[cfg(feature = "csv")]
+mod csv;
+mod cursor;
+#[cfg(feature = "chrono")]
+mod datetime;
+#[cfg(feature = "serde")]
+mod de;
+mod diff;
+mod encoding;
+mod entry;
+mod escape;
+#[cfg(feature = "std")]
+mod extract;
+#[cfg(feature = "std")]
+mod file;
+mod find;
+mod flatten;
+mod fluent;
+mod format;
+mod frozen;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
+mod hash;
+mod highlight;
+mod hooks;
+mod include;
+mod infer;
+#[cfg(feature = "std")]
+mod interpolate;
+mod lenient;
+mod lexer;
+mod lines;
+mod lint;
+mod many;
+mod merge;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "std")]
+mod ndjson;
+mod number;
+mod options;
+mod order;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod parser;
+mod patch;
+mod pointer;
+mod push;
+mod query;
+mod query_string;
+mod raw;
+mod redact;
+#[cfg(feature = "std")]
+mod reformat;
+mod refs;
+mod render;
+mod scan;
+#[cfg(feature = "schema")]
+mod schema;
+#[cfg(feature = "std")]
+mod seq;
+mod ser;
+#[cfg(feature = "bytes")]
+mod shared;
+mod simd;
+#[cfg(feature = "small-map")]
+mod small_map;
+mod spanned;
+mod stats;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "toml")]
+mod toml;
+mod transform;
+mod truncate;
+mod trusted;
+mod typed;
+#[cfg(feature = "unicode")]
+mod unicode;
+#[cfg(feature = "uuid")]
+mod uuid;
+mod visit;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "xml")]
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+#[cfg(feature = "arena")]
+pub use arena::{parse_in, BorrowedType};
+#[cfg(feature = "std")]
+pub use array_reader::ArrayReader;
+#[cfg(feature = "tokio")]
+pub use async_parse::parse_async;
+pub use builder::{Events, TreeBuilder};
+pub use case::Case;
+#[cfg(feature = "cbor")]
+pub use cbor::{from_cbor, to_cbor, CborError};
+pub use codegen::rust_types;
+pub use compare::CompareOptions;
+pub use convert::{DecodeError, FromJson, ToJson};
+pub use cst::{parse_cst, Cst, CstElement, CstKind, CstMember, CstNode};
+#[cfg(feature = "csv")]
+pub use csv::{from_csv, to_csv, CsvError};
+pub use cursor::Cursor;
+#[cfg(feature = "serde")]
+pub use de::{from_str, DeserializeError, Deserializer};
+pub use diff::{diff, render_diff, Change};
+pub use encoding::{parse_bytes, parse_bytes_lossy, Encoding};
+pub use entry::PointerEntry;
+pub use escape::{escape_json_string, unescape_json_string};
+#[cfg(feature = "std")]
+pub use extract::{extract_pointer, extract_pointers};
+#[cfg(feature = "std")]
+pub use file::{parse_file, FileError, JsonFile};
+pub use find::FindAll;
+pub use fluent::{ArrayBuilder, ObjectBuilder};
+pub use format::{minify, minify_relaxed, prettify};
+pub use frozen::{FrozenValue, SubtreeCache};
+#[cfg(feature = "arbitrary")]
+pub use fuzzing::ArbitraryLimits;
+pub use highlight::{highlight, Highlight, HighlightKind};
+pub use hooks::{parse_with_numbers, parse_with_strings, NumberHandler, StringHandler};
+pub use include::IncludeError;
+pub use infer::infer_schema;
+#[cfg(feature = "std")]
+pub use interpolate::{InterpolateError, InterpolateOptions};
+#[cfg(feature = "derive")]
+pub use json_rs_derive::{FromJson, ToJson};
+pub use lenient::parse_lenient;
+pub use lexer::{Lexer, LexerCheckpoint, Token, TokenKind};
+pub use lines::LineIndex;
+pub use lint::{lint, Diagnostic, LintConfig, Severity};
+pub use many::{parse_all, parse_many, ParseAllError, ParseMany};
+pub use merge::{ArrayMerge, Collision, MergeIntoError, MergeOptions};
+#[cfg(feature = "msgpack")]
+pub use msgpack::{from_msgpack, to_msgpack, MsgpackError};
+#[cfg(feature = "std")]
+pub use ndjson::{NdjsonReader, NdjsonWriter};
+pub use number::Number;
+pub use options::{parse_with, ParseOptions};
+#[cfg(feature = "rayon")]
+pub use parallel::{par_lines, par_lines_ordered, parse_parallel};
+pub use parser::{Event, Expected, MAX_DEPTH};
+pub use patch::{diff_as_patch, diff_as_patch_with_moves, Patch, PatchError, PatchOp};
+pub use pointer::{JsonPointer, RelativePointer};
+pub use push::PushParser;
+pub use query::{query, Filter, QueryError};
+pub use query_string::{from_query_string, to_query_string, QueryStringError, QueryStringStyle};
+pub use raw::RawValue;
+pub use redact::{redact, Redaction};
+#[cfg(feature = "std")]
+pub use reformat::{minify_reader, prettify_reader, reformat};
+pub use refs::RefError;
+pub use scan::{get_path, set_path};
+#[cfg(feature = "schema")]
+pub use schema::{Schema, SchemaError, Violation};
+#[cfg(feature = "std")]
+pub use seq::{JsonSeqReader, JsonSeqWriter};
+pub use ser::{
+    to_canonical_string, to_string, to_string_pretty, to_string_with, to_string_with_formatter,
+    CompactFormatter, FloatFormat, Formatter, KeyCompare, LineEnding, PrettyFormatter,
+    SerializeOptions,
+};
+#[cfg(feature = "bytes")]
+pub use shared::{parse_shared, SharedStr, SharedType};
+#[cfg(feature = "small-map")]
+pub use small_map::SmallMap;
+pub use spanned::{parse_spanned, parse_spanned_relaxed, Spanned, SpannedType};
+pub use stats::{Repeated, Repetitions, Stats};
+#[cfg(feature = "std")]
+pub use stream::ArraySerializer;
+#[cfg(feature = "toml")]
+pub use toml::{from_toml, to_toml, TomlError};
+pub use transform::Segment;
+pub use truncate::to_string_truncated;
+pub use trusted::parse_trusted;
+pub use typed::{GetError, ValueKind};
+pub use visit::{
+    walk_array, walk_array_mut, walk_object, walk_object_mut, walk_value, walk_value_mut, Visitor,
+    VisitorMut,
+};
+#[cfg(feature = "xml")]
+pub use xml::{from_xml, to_xml, XmlError, XmlOptions};
+#[cfg(feature = "yaml")]
+pub use yaml::{from_yaml, to_yaml, YamlError};
+
+use parser::{skip_document, Parser};
+
+/// The map behind [`Type::Object`]: a `HashMap` with the `std` feature and a
+/// `BTreeMap` without it. The `btree-map` feature picks the `BTreeMap`
+/// regardless, `small-map` a [`SmallMap`], and `indexmap` an `IndexMap` that
+/// keeps insertion order, in increasing precedence. The hashing maps use
+/// [`MapHasher`].
+#[cfg(feature = "indexmap")]
+pub type Map<K, V> = indexmap::IndexMap<K, V, MapHasher>;
+#[cfg(all(feature = "small-map", not(feature = "indexmap")))]
+pub type Map<K, V> = SmallMap<K, V>;
+#[cfg(all(
+    feature = "std",
+    not(any(feature = "btree-map", feature = "small-map", feature = "indexmap"))
+))]
+pub type Map<K, V> = std::collections::HashMap<K, V, MapHasher>;
+#[cfg(all(
+    any(not(feature = "std"), feature = "btree-map"),
+    not(any(feature = "small-map", feature = "indexmap"))
+))]
+pub type Map<K, V> = alloc::collections::BTreeMap<K, V>;
+
+/// The hasher of the hashing [`Map`]s: the standard library's SipHash, or
+/// with the `foldhash` feature the faster foldhash. `Map::new()` exists
+/// only for SipHash's `RandomState`, so a map with another hasher, and
+/// code that works with both, must create one with `Map::default()`.
+#[cfg(all(feature = "std", not(feature = "foldhash")))]
+pub type MapHasher = std::collections::hash_map::RandomState;
+#[cfg(feature = "foldhash")]
+pub type MapHasher = foldhash::fast::RandomState;
+
+/// The key type of [`Type::Object`]: `String`, or with the `arc-keys`
+/// feature a shared `Arc<str>`, so that a key repeated across many objects
+/// is allocated once per parse and cloning a value only bumps reference
+/// counts for its keys. Without `arc-keys`, the `compact-str`
+/// feature makes it a [`Str`].
+#[cfg(not(any(feature = "arc-keys", feature = "compact-str")))]
+pub type Key = String;
+#[cfg(all(feature = "compact-str", not(feature = "arc-keys")))]
+pub type Key = Str;
+#[cfg(feature = "arc-keys")]
+pub type Key = alloc::sync::Arc<str>;
+
+/// The string type of [`Type::String`]: `String`, or with the `compact-str`
+/// feature a `CompactString`, which keeps short strings inline.
+#[cfg(not(feature = "compact-str"))]
+pub type Str = String;
+#[cfg(feature = "compact-str")]
+pub type Str = compact_str::CompactString;
+
+#[cfg(not(feature = "compact-str"))]
+pub(crate) fn into_str(s: String) -> Str {
+    s
+}
+
+#[cfg(feature = "compact-str")]
+pub(crate) fn into_str(s: String) -> Str {
+    Str::from(s)
+}
+
+/// A JSON value. The object map is boxed so that containers, which dominate
+/// large documents, keep `Type` itself to 32 bytes on 64-bit targets.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     Null,
     Boolean(bool),
-    Number(f64),
-    String(String),
+    Number(Number),
+    String(Str),
     Array(Vec<Type>),
-    Object(HashMap<String, Type>),
+    Object(Box<Map<Key, Type>>),
 }
 
-#[derive(Debug, PartialEq)]
+/// Syntax errors carry the byte offset in the input where they were detected.
+#[derive(Debug, Clone, PartialEq)]
 pub enum JsonError {
-    UnexpectToken,
-}
-
-fn skip_whitespace(chars: &Vec<char>, pos: &mut usize) {
-    while *pos < chars.len() && chars[*pos].is_ascii_whitespace() {
-        *pos += 1;
-    }
+    UnexpectToken {
+        offset: usize,
+        /// The tokens that would have been accepted here; empty when the
+        /// token itself is malformed or the value was already complete.
+        expected: Expected,
+    },
+    UnexpectEof {
+        offset: usize,
+    },
+    /// The input broke one of the [`ParseOptions`] limits at `offset`.
+    LimitExceeded {
+        offset: usize,
+    },
+    InvalidPath,
+    #[cfg(feature = "std")]
+    Io(io::ErrorKind),
 }
 
-fn find_str(chars: &Vec<char>, pos: &mut usize, str: &str) -> bool {
-    skip_whitespace(chars, pos);
-    if *pos + str.len() <= chars.len() {
-        for (i, ch) in str.chars().enumerate() {
-            if chars[*pos + i] != ch {
-                return false;
-            }
+impl JsonError {
+    /// The byte offset of a syntax error.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            JsonError::UnexpectToken { offset, .. }
+            | JsonError::UnexpectEof { offset }
+            | JsonError::LimitExceeded { offset } => Some(*offset),
+            JsonError::InvalidPath => None,
+            #[cfg(feature = "std")]
+            JsonError::Io(_) => None,
         }
-        true
-    } else {
-        false
     }
-}
-
-fn parse_object(chars: &Vec<char>, pos: &mut usize) -> Result<Type, JsonError> {
-    let mut hash: HashMap<String, Type> = HashMap::new();
-    while *pos < chars.len() {
-        skip_whitespace(chars, pos);
-
-        if chars[*pos] == '}' {
-            *pos += 1;
-            return Ok(Type::Object(hash));
-        }
 
-        if let Type::String(key) = _parse(chars, pos).unwrap() {
-            if find_str(chars, pos, ":") {
-                *pos += 1;
-                let value = _parse(chars, pos).unwrap();
-                hash.insert(key, value);
-                skip_whitespace(chars, pos);
-                match chars[*pos] {
-                    ',' => *pos += 1,
-                    '}' => continue,
-                    _ => break,
-                }
-            } else {
-                break;
-            }
-        } else {
-            break;
+    /// Moves the reported offset by `base`, for errors found in a window of
+    /// a larger input.
+    pub(crate) fn shifted(self, base: usize) -> JsonError {
+        match self {
+            JsonError::UnexpectToken { offset, expected } => JsonError::UnexpectToken {
+                offset: base + offset,
+                expected,
+            },
+            JsonError::UnexpectEof { offset } => JsonError::UnexpectEof {
+                offset: base + offset,
+            },
+            JsonError::LimitExceeded { offset } => JsonError::LimitExceeded {
+                offset: base + offset,
+            },
+            err => err,
         }
     }
-    Err(JsonError::UnexpectToken)
 }
 
-fn parse_string(chars: &Vec<char>, pos: &mut usize) -> Result<Type, JsonError> {
-    let mut result = String::new();
-
-    while *pos < chars.len() {
-        match chars[*pos] {
-            '"' => {
-                *pos += 1;
-                return Ok(Type::String(result));
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::UnexpectToken { offset, .. } => {
+                write!(f, "unexpected token at byte {}", offset)
             }
-            '\\' => {
-                match chars[*pos + 1] {
-                    'n' => result.push('\n'),
-                    _ => result.push(chars[*pos + 1]),
-                };
-                *pos += 2;
+            JsonError::UnexpectEof { offset } => {
+                write!(f, "unexpected end of input at byte {}", offset)
             }
-            ch @ _ => {
-                result.push(ch);
-                *pos += 1;
+            JsonError::LimitExceeded { offset } => {
+                write!(f, "parse limit exceeded at byte {}", offset)
             }
+            JsonError::InvalidPath => f.write_str("path does not address a settable value"),
+            #[cfg(feature = "std")]
+            JsonError::Io(kind) => write!(f, "I/O error: {}", kind),
         }
     }
-    Err(JsonError::UnexpectToken)
 }
 
-fn parse_array(chars: &Vec<char>, pos: &mut usize) -> Result<Type, JsonError> {
-    let mut result = Vec::<Type>::new();
-    while *pos < chars.len() {
-        skip_whitespace(chars, pos);
-        match chars[*pos] {
-            ',' if result.len() > 0 => {
-                *pos += 1;
-            }
-            ',' => break,
-            ']' => {
-                *pos += 1;
-                return Ok(Type::Array(result));
-            }
-            _ => {
-                let value = _parse(chars, pos).unwrap();
-                result.push(value);
-            }
-        }
-    }
-    Err(JsonError::UnexpectToken)
-}
+impl core::error::Error for JsonError {}
 
-fn parse_number(chars: &Vec<char>, pos: &mut usize) -> Result<Type, JsonError> {
-    let mut number_string = String::new();
-    let mut found_decimal = false;
-    let mut found_exponent = false;
-
-    if chars[*pos] == '-' {
-        number_string.push('-');
-        *pos += 1;
+#[cfg(feature = "std")]
+impl From<io::Error> for JsonError {
+    fn from(err: io::Error) -> Self {
+        JsonError::Io(err.kind())
     }
+}
 
-    if chars[*pos] == '0' {
-        *pos += 1;
-        if find_str(chars, pos, ".") {
-            number_string.push_str("0.");
-            found_decimal = true;
-            *pos += 1;
-        } else {
-            return Err(JsonError::UnexpectToken);
-        }
-    }
+/// Parses one JSON document. A leading UTF-8 byte-order mark is skipped,
+/// though offsets in errors still count it.
+pub fn parse(json: &str) -> Result<Type, JsonError> {
+    parse_slice(json.as_bytes())
+}
 
-    while *pos < chars.len() {
-        match chars[*pos] {
-            ch @ '0'..='9' => {
-                number_string.push(ch);
-                *pos += 1;
-            }
-            ch @ '.' => {
-                if found_decimal || found_exponent {
-                    return Err(JsonError::UnexpectToken);
-                }
-                found_decimal = true;
-                number_string.push(ch);
-                *pos += 1;
-            }
-            ch @ ('e' | 'E') => {
-                if found_exponent {
-                    return Err(JsonError::UnexpectToken);
-                }
-                found_exponent = true;
-                number_string.push(ch);
-                *pos += 1;
-
-                match chars[*pos] {
-                    ch @ ('-' | '+') => {
-                        number_string.push(ch);
-                        *pos += 1;
-                    }
-                    _ => {}
-                }
-            }
-            _ => {
-                break;
-            }
-        }
-    }
+/// Checks that `json` is a single well-formed JSON value without building a
+/// tree; no memory is allocated. Errors are the same `parse` would report.
+pub fn validate(json: &str) -> Result<(), JsonError> {
+    skip_document(&mut Lexer::new(json.as_bytes()).skip_bom()).map(|_| ())
+}
 
-    Ok(Type::Number(number_string.parse().unwrap()))
+/// Parses the value at the start of `json` and returns it together with the
+/// number of bytes it occupied (including leading whitespace), leaving the
+/// rest of the input to the caller.
+pub fn parse_prefix(json: &str) -> Result<(Type, usize), JsonError> {
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    let value = TreeBuilder::new().build(&mut Parser::new(), &mut lexer)?;
+    let value = value.ok_or(JsonError::UnexpectEof { offset: json.len() })?;
+    Ok((value, lexer.pos()))
 }
 
-fn _parse(chars: &Vec<char>, pos: &mut usize) -> Result<Type, JsonError> {
-    skip_whitespace(chars, pos);
-    match chars[*pos] {
-        '{' => {
-            *pos += 1;
-            parse_object(&chars, pos)
-        }
-        '[' => {
-            *pos += 1;
-            parse_array(chars, pos)
-        }
-        '"' => {
-            *pos += 1;
-            parse_string(&chars, pos)
-        }
-        't' => {
-            if find_str(chars, pos, "true") {
-                *pos += 4;
-                Ok(Type::Boolean(true))
-            } else {
-                Err(JsonError::UnexpectToken)
-            }
-        }
-        'f' => {
-            if find_str(chars, pos, "false") {
-                *pos += 5;
-                Ok(Type::Boolean(false))
-            } else {
-                Err(JsonError::UnexpectToken)
-            }
-        }
-        'n' => {
-            if find_str(chars, pos, "null") {
-                *pos += 4;
-                Ok(Type::Null)
-            } else {
-                Err(JsonError::UnexpectToken)
-            }
-        }
-        '0'..='9' | '-' => parse_number(chars, pos),
-        _ => Err(JsonError::UnexpectToken),
-    }
+/// Parses one JSON document from UTF-8 bytes, such as a network buffer,
+/// without a `str::from_utf8` pass first: only string contents are checked,
+/// since any other non-ASCII byte is a syntax error anyway. Otherwise as
+/// [`parse`]; malformed UTF-8 is an error at its offset.
+pub fn parse_slice(json: &[u8]) -> Result<Type, JsonError> {
+    parse_lexer(Lexer::new(json).skip_bom())
 }
 
-pub fn parse(json: &str) -> Result<Type, JsonError> {
-    let chars: Vec<char> = json.chars().into_iter().collect();
-    let mut pos: usize = 0;
-    let result = _parse(&chars, &mut pos);
-    skip_whitespace(&chars, &mut pos);
-    if pos == chars.len() {
-        result
-    } else {
-        Err(JsonError::UnexpectToken)
-    }
+/// Parses the single value that makes up all of `lexer`'s input.
+pub(crate) fn parse_lexer(mut lexer: Lexer) -> Result<Type, JsonError> {
+    let mut parser = Parser::new();
+    let value = TreeBuilder::new().build(&mut parser, &mut lexer)?;
+    // Anything but whitespace after the value is an error.
+    parser.next_event(&mut lexer)?;
+    value.ok_or(JsonError::UnexpectEof {
+        offset: lexer.input().len(),
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse, JsonError, Type};
-    use std::collections::HashMap;
+    use crate::{parse, parse_prefix, parse_slice, validate, Expected, JsonError, Map, Type};
 
     #[test]
     fn it_works() {
         assert_eq!(parse("null").unwrap(), Type::Null);
         assert_eq!(parse("true").unwrap(), Type::Boolean(true));
         assert_eq!(parse("false").unwrap(), Type::Boolean(false));
-        assert_eq!(parse("1").unwrap(), Type::Number(1.0));
-        assert_eq!(parse("-1").unwrap(), Type::Number(-1.0));
-        assert_eq!(parse("-1.1").unwrap(), Type::Number(-1.1));
-        assert_eq!(parse("1e3").unwrap(), Type::Number(1000.0));
-        assert_eq!(parse("1e+3").unwrap(), Type::Number(1000.0));
-        assert_eq!(parse("1e-3").unwrap(), Type::Number(0.001));
-        assert_eq!(parse("-1e-3").unwrap(), Type::Number(-0.001));
-        assert_eq!(parse("01").unwrap_err(), JsonError::UnexpectToken);
-        assert_eq!(parse("1.1.1").unwrap_err(), JsonError::UnexpectToken);
+        assert_eq!(parse("1").unwrap(), Type::Number(1.into()));
+        assert_eq!(parse("-1").unwrap(), Type::Number((-1).into()));
+        assert_eq!(parse("-1.1").unwrap(), Type::Number((-1.1).into()));
+        assert_eq!(parse("1e3").unwrap(), Type::Number(1000.into()));
+        assert_eq!(parse("1e+3").unwrap(), Type::Number(1000.into()));
+        assert_eq!(parse("1e-3").unwrap(), Type::Number(0.001.into()));
+        assert_eq!(parse("-1e-3").unwrap(), Type::Number((-0.001).into()));
+        assert_eq!(
+            parse("01").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            parse("1.1.1").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::NONE
+            }
+        );
         assert_eq!(
             parse("\"hello world\"").unwrap(),
-            Type::String("hello world".to_string())
+            Type::String("hello world".into())
         );
         assert_eq!(parse(" [ ] ").unwrap(), Type::Array(vec![]));
         assert_eq!(
             parse(" [ 1,-1 , null , true    , false, \"hello\", [ ] ] ").unwrap(),
             Type::Array(vec![
-                Type::Number(1.0),
-                Type::Number(-1.0),
+                Type::Number(1.into()),
+                Type::Number((-1).into()),
                 Type::Null,
                 Type::Boolean(true),
                 Type::Boolean(false),
-                Type::String("hello".to_string()),
+                Type::String("hello".into()),
                 Type::Array(vec![])
             ])
         );
 
-        assert_eq!(parse("{ }").unwrap(), Type::Object(HashMap::new()));
+        assert_eq!(parse("{ }").unwrap(), Type::Object(Box::default()));
         assert_eq!(
             parse("{ \"name\": \"json-rs\" }").unwrap(),
-            Type::Object(HashMap::from_iter(vec![(
-                "name".to_string(),
-                Type::String("json-rs".to_string())
-            )]))
+            Type::Object(Box::new(Map::from_iter(vec![(
+                "name".into(),
+                Type::String("json-rs".into())
+            )])))
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(validate(r#" {"a": [1, "\u00e9", null]} "#), Ok(()));
+        for input in ["[1,]", "{\"a\" 1}", "\"\\ud800\"", "[1] 2", "tru", ""] {
+            assert_eq!(validate(input), parse(input).map(|_| ()), "{}", input);
+        }
+        assert_eq!(
+            validate("{\n  \"a\": 1,\n}").unwrap_err().offset(),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn parses_byte_slices() {
+        let json = r#"{"name": "é", "n": [1]}"#;
+        assert_eq!(parse_slice(json.as_bytes()), parse(json));
+        assert_eq!(
+            parse_slice(b"[\"a\xff\"]").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::NONE
+            }
+        );
+        assert!(parse_slice(b"[1, \xc3\xa9]").is_err());
+    }
+
+    #[test]
+    fn skips_a_byte_order_mark() {
+        assert_eq!(parse("\u{feff}[1]").unwrap(), parse("[1]").unwrap());
+        assert_eq!(validate("\u{feff} {}"), Ok(()));
+        assert_eq!(
+            parse("\u{feff}[1,]").unwrap_err().offset(),
+            Some(6),
+            "offsets count the mark"
+        );
+        assert!(parse("[\u{feff}1]").is_err());
+        assert!(parse("\u{feff}\u{feff}1").is_err());
+    }
+
+    #[test]
+    fn compact_representation() {
+        assert!(std::mem::size_of::<Type>() <= 4 * std::mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn prefix() {
+        let input = r#" {"len": 2} trailing text"#;
+        let (value, consumed) = parse_prefix(input).unwrap();
+        assert_eq!(value, parse(r#"{"len": 2}"#).unwrap());
+        assert_eq!(&input[consumed..], " trailing text");
+        assert_eq!(parse_prefix("12").unwrap(), (Type::Number(12.into()), 2));
+        assert_eq!(
+            parse_prefix("[1, 2").unwrap_err(),
+            JsonError::UnexpectEof { offset: 5 }
+        );
+    }
+
+    #[cfg(any(feature = "indexmap", feature = "btree-map", feature = "small-map"))]
+    #[test]
+    fn ordered_objects() {
+        let Type::Object(members) = parse(r#"{"b": 1, "c": 2, "a": 3}"#).unwrap() else {
+            unreachable!()
+        };
+        let keys: Vec<&str> = members.keys().map(|key| &**key).collect();
+        #[cfg(any(feature = "indexmap", feature = "small-map"))]
+        assert_eq!(keys, ["b", "c", "a"]);
+        #[cfg(not(any(feature = "indexmap", feature = "small-map")))]
+        assert_eq!(keys, ["a", "b", "c"]);
+    }
+
+    #[cfg(feature = "compact-str")]
+    #[test]
+    fn inline_strings() {
+        let Type::Array(items) = parse(r#"["id", "a string too long to be kept inline"]"#).unwrap()
+        else {
+            unreachable!()
+        };
+        let Type::String(short) = &items[0] else {
+            unreachable!()
+        };
+        assert!(!short.is_heap_allocated());
+        assert_eq!(
+            items[1],
+            Type::String("a string too long to be kept inline".into())
         );
     }
 }
@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+
+/// Converts the byte offsets in errors and spans into line and column
+/// numbers. Built once per source, after which each lookup is a binary
+/// search.
+///
+/// Lines end at `\n`; the `\r` of a `\r\n` belongs to the line ending, not
+/// the text. Lines and columns count from 1, and columns count characters
+/// rather than bytes.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    /// The byte offset each line starts at.
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let starts = core::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { source, starts }
+    }
+
+    /// The line and column of `offset`. Offsets past the end are clamped to
+    /// it, and offsets inside a multi-byte character give that character's
+    /// column.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = floor_char_boundary(self.source, offset.min(self.source.len()));
+        let line = self.starts.partition_point(|&start| start <= offset);
+        let start = self.starts[line - 1];
+        (line, self.source[start..offset].chars().count() + 1)
+    }
+
+    /// The text of the 1-based `line`, without its line ending.
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        let start = *self.starts.get(line.checked_sub(1)?)?;
+        let end = self.starts.get(line).map_or(self.source.len(), |&i| i - 1);
+        Some(self.source[start..end].trim_end_matches('\r'))
+    }
+
+    /// The byte offset the 1-based `line` starts at.
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.starts.get(line.checked_sub(1)?).copied()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+}
+
+pub(crate) fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineIndex;
+
+    #[test]
+    fn lines_and_columns() {
+        let source = "{\r\n  \"é\": 1,\n\t\"b\": [\r\n]}";
+        let index = LineIndex::new(source);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(0), (1, 1));
+        assert_eq!(index.line_col(1), (1, 2));
+        assert_eq!(index.line_col(2), (1, 3));
+        assert_eq!(index.line_col(3), (2, 1));
+        // After the two-byte 'é', and inside it.
+        assert_eq!(index.line_col(source.find("\":").unwrap()), (2, 5));
+        assert_eq!(index.line_col(7), (2, 4));
+        assert_eq!(index.line_col(source.find("[").unwrap()), (3, 7));
+        assert_eq!(index.line_col(source.len()), (4, 3));
+        assert_eq!(index.line_col(source.len() + 10), (4, 3));
+        assert_eq!(index.line(1), Some("{"));
+        assert_eq!(index.line(2), Some("  \"é\": 1,"));
+        assert_eq!(index.line(3), Some("\t\"b\": ["));
+        assert_eq!(index.line(4), Some("]}"));
+        assert_eq!(index.line(0), None);
+        assert_eq!(index.line(5), None);
+        assert_eq!(index.line_start(2), Some(3));
+
+        let empty = LineIndex::new("");
+        assert_eq!(empty.line_col(0), (1, 1));
+        assert_eq!(empty.line(1), Some(""));
+        assert_eq!(LineIndex::new("1\n").line(2), Some(""));
+    }
+}
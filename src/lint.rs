@@ -0,0 +1,353 @@
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::lines::LineIndex;
+use crate::{parse_spanned, Spanned, SpannedType};
+
+/// How much a [`Diagnostic`] matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The document is not JSON.
+    Error,
+    /// Valid JSON that is likely to be read differently than meant.
+    Warning,
+    /// Valid JSON that some tools may handle poorly.
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        })
+    }
+}
+
+/// A problem [`lint`] found, at the bytes `start..end` of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The name of the check, e.g. `duplicate-key`.
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub start: usize,
+    pub end: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.severity, self.message, self.rule)
+    }
+}
+
+/// Which checks [`lint`] runs. The defaults run them all, flagging nesting
+/// deeper than 32 and lines longer than 120 characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintConfig {
+    /// How deeply arrays and objects may nest before `max-depth` fires.
+    pub max_depth: Option<usize>,
+    /// The longest line, in characters, before `line-length` fires.
+    pub max_line_len: Option<usize>,
+    /// `duplicate-key`: keys repeated in one object, of which only the last
+    /// counts.
+    pub duplicate_keys: bool,
+    /// `precision`: numbers an `f64` cannot hold, which many parsers would
+    /// silently round, overflow or flush to zero.
+    pub precision: bool,
+    /// `portability`: NUL characters, raw U+2028 and U+2029, which older
+    /// JavaScript rejects in strings, and scalars at the root and a leading
+    /// byte-order mark, which RFC 4627 and RFC 8259 parsers may reject.
+    pub portability: bool,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            max_depth: Some(32),
+            max_line_len: Some(120),
+            duplicate_keys: true,
+            precision: true,
+            portability: true,
+        }
+    }
+}
+
+/// Checks `source` for the problems `config` turns on, returning them in
+/// source order. A document that does not parse gives a single `syntax`
+/// error, plus any long lines.
+pub fn lint(source: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    if let Some(max) = config.max_line_len {
+        long_lines(source, max, &mut out);
+    }
+    if config.portability && source.starts_with('\u{feff}') {
+        out.push(Diagnostic {
+            rule: "portability",
+            severity: Severity::Info,
+            start: 0,
+            end: 3,
+            message: "the document starts with a byte-order mark".into(),
+        });
+    }
+    match parse_spanned(source) {
+        Ok(root) => {
+            if config.portability
+                && !matches!(root.value, SpannedType::Array(_) | SpannedType::Object(_))
+            {
+                out.push(Diagnostic {
+                    rule: "portability",
+                    severity: Severity::Info,
+                    start: root.start,
+                    end: root.end,
+                    message: "the root is not an array or object".into(),
+                });
+            }
+            walk(source, &root, 0, config, &mut out);
+        }
+        Err(err) => {
+            let offset = err.offset().unwrap_or(source.len()).min(source.len());
+            out.push(Diagnostic {
+                rule: "syntax",
+                severity: Severity::Error,
+                start: offset,
+                end: offset,
+                message: err.to_string(),
+            });
+        }
+    }
+    out.sort_by_key(|diagnostic| diagnostic.start);
+    out
+}
+
+fn long_lines(source: &str, max: usize, out: &mut Vec<Diagnostic>) {
+    let lines = LineIndex::new(source);
+    for number in 1..=lines.line_count() {
+        let (Some(start), Some(text)) = (lines.line_start(number), lines.line(number)) else {
+            continue;
+        };
+        let len = text.chars().count();
+        if len > max {
+            out.push(Diagnostic {
+                rule: "line-length",
+                severity: Severity::Info,
+                start,
+                end: start + text.len(),
+                message: format!("line {} is {} characters long, over {}", number, len, max),
+            });
+        }
+    }
+}
+
+fn walk(
+    source: &str,
+    node: &Spanned<SpannedType>,
+    depth: usize,
+    config: &LintConfig,
+    out: &mut Vec<Diagnostic>,
+) {
+    let text = &source[node.start..node.end];
+    let container = matches!(node.value, SpannedType::Array(_) | SpannedType::Object(_));
+    let depth = depth + usize::from(container);
+    // Only the outermost container past the limit, not everything in it.
+    if let Some(max) = config
+        .max_depth
+        .filter(|&max| container && depth == max + 1)
+    {
+        out.push(Diagnostic {
+            rule: "max-depth",
+            severity: Severity::Warning,
+            start: node.start,
+            end: node.end,
+            message: format!("nested {} levels deep, over {}", depth, max),
+        });
+    }
+    match &node.value {
+        SpannedType::Number(n) if config.precision => {
+            let problem = if n.is_integer() && !text.contains(['.', 'e', 'E']) {
+                n.as_f64()
+                    .is_none()
+                    .then_some("is an integer an f64 cannot hold exactly")
+            } else {
+                lost_float(text, n.to_f64())
+            };
+            if let Some(problem) = problem {
+                out.push(Diagnostic {
+                    rule: "precision",
+                    severity: Severity::Warning,
+                    start: node.start,
+                    end: node.end,
+                    message: format!("{} {}", text, problem),
+                });
+            }
+        }
+        SpannedType::String(s) if config.portability => {
+            portability(s, node.start, node.end, out);
+        }
+        SpannedType::Array(items) => {
+            for item in items {
+                walk(source, item, depth, config, out);
+            }
+        }
+        SpannedType::Object(members) => {
+            let mut seen = BTreeSet::new();
+            for (key, value) in members.iter() {
+                if config.duplicate_keys && !seen.insert(&*key.value) {
+                    out.push(Diagnostic {
+                        rule: "duplicate-key",
+                        severity: Severity::Warning,
+                        start: key.start,
+                        end: key.end,
+                        message: format!(
+                            "duplicate key {}; only the last is kept",
+                            &source[key.start..key.end]
+                        ),
+                    });
+                }
+                if config.portability {
+                    portability(&key.value, key.start, key.end, out);
+                }
+                walk(source, value, depth, config, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// What is lost reading the non-integral number `text` as `value`.
+fn lost_float(text: &str, value: f64) -> Option<&'static str> {
+    if value.is_infinite() {
+        return Some("is too large for an f64");
+    }
+    let mantissa = text.split(['e', 'E']).next().unwrap_or(text);
+    let digits = mantissa
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .skip_while(|&digit| digit == b'0')
+        .collect::<Vec<u8>>();
+    let significant = digits.len()
+        - digits
+            .iter()
+            .rev()
+            .take_while(|&&digit| digit == b'0')
+            .count();
+    if value == 0.0 && significant > 0 {
+        Some("is too small for an f64 and reads as zero")
+    } else if significant > 17 {
+        Some("has more digits than an f64 holds")
+    } else {
+        None
+    }
+}
+
+fn portability(s: &str, start: usize, end: usize, out: &mut Vec<Diagnostic>) {
+    let message = if s.contains('\0') {
+        "contains a NUL character"
+    } else if s.contains(['\u{2028}', '\u{2029}']) {
+        "contains a line or paragraph separator older JavaScript rejects"
+    } else {
+        return;
+    };
+    out.push(Diagnostic {
+        rule: "portability",
+        severity: Severity::Info,
+        start,
+        end,
+        message: message.into(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint, LintConfig, Severity};
+
+    fn rules(source: &str, config: &LintConfig) -> Vec<(&'static str, String)> {
+        lint(source, config)
+            .into_iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.rule,
+                    source[diagnostic.start..diagnostic.end].to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flags_problems() {
+        let source = concat!(
+            r#"{"a": 1, "b": [9007199254740993, 0.1, 1e400, 1e-400, 0.123456789012345678],"#,
+            r#" "a": "x\u0000", "c": "#,
+            "\"\u{2028}\", ",
+            r#""d": [[[1]]]}"#
+        );
+        let config = LintConfig {
+            max_depth: Some(3),
+            max_line_len: None,
+            ..LintConfig::default()
+        };
+        assert_eq!(
+            rules(source, &config),
+            [
+                ("precision", "9007199254740993".to_string()),
+                ("precision", "1e400".to_string()),
+                ("precision", "1e-400".to_string()),
+                ("precision", "0.123456789012345678".to_string()),
+                ("duplicate-key", r#""a""#.to_string()),
+                ("portability", r#""x\u0000""#.to_string()),
+                ("portability", "\"\u{2028}\"".to_string()),
+                ("max-depth", "[1]".to_string()),
+            ]
+        );
+        let quiet = LintConfig {
+            max_depth: None,
+            max_line_len: None,
+            duplicate_keys: false,
+            precision: false,
+            portability: false,
+        };
+        assert_eq!(lint(source, &quiet), []);
+        assert_eq!(
+            rules("\u{feff}[]", &LintConfig::default()),
+            [("portability", "\u{feff}".to_string())]
+        );
+        assert_eq!(
+            rules("1", &LintConfig::default()),
+            [("portability", "1".to_string())]
+        );
+        let keys: Vec<String> = (0..50_000).map(|i| format!("\"k{}\": 0", i)).collect();
+        let wide = format!("{{{}, \"k\\u0030\": 1}}", keys.join(", "));
+        assert_eq!(
+            rules(&wide, &config),
+            [("duplicate-key", "\"k\\u0030\"".to_string())]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_and_long_lines() {
+        let source = "[\n  \"aaaaaaaaaa\",\n]";
+        let config = LintConfig {
+            max_line_len: Some(10),
+            ..LintConfig::default()
+        };
+        let diagnostics = lint(source, &config);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].rule, "line-length");
+        assert_eq!(
+            &source[diagnostics[0].start..diagnostics[0].end],
+            "  \"aaaaaaaaaa\","
+        );
+        assert_eq!(diagnostics[1].rule, "syntax");
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+        assert_eq!(diagnostics[1].start, source.len() - 1);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "info: line 2 is 15 characters long, over 10 [line-length]"
+        );
+        assert_eq!(lint("[1, 2]", &LintConfig::default()), []);
+    }
+}
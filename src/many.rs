@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{is_whitespace, Lexer};
+use crate::parser::Parser;
+use crate::{JsonError, Type};
+
+/// Iterates over the top-level values of a stream of concatenated JSON texts.
+///
+/// Values may be back to back (`{"a":1}{"a":2}`) or separated by whitespace.
+/// Iteration stops after the first error.
+pub struct ParseMany<'a> {
+    lexer: Lexer<'a>,
+    failed: bool,
+}
+
+/// Parses successive top-level values from `json` instead of rejecting
+/// everything after the first one. A UTF-8 byte-order mark at the start of
+/// `json` is skipped.
+pub fn parse_many(json: &str) -> ParseMany<'_> {
+    ParseMany {
+        lexer: Lexer::new(json.as_bytes()).skip_bom(),
+        failed: false,
+    }
+}
+
+impl ParseMany<'_> {
+    /// Byte offset just past the last value returned.
+    pub fn byte_offset(&self) -> usize {
+        self.lexer.pos()
+    }
+}
+
+impl Iterator for ParseMany<'_> {
+    type Item = Result<Type, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        self.lexer.skip_whitespace();
+        if self.lexer.pos() == self.lexer.input().len() {
+            return None;
+        }
+        let result = TreeBuilder::new()
+            .build(&mut Parser::new(), &mut self.lexer)
+            .and_then(|value| {
+                value.ok_or(JsonError::UnexpectEof {
+                    offset: self.lexer.input().len(),
+                })
+            });
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+/// The document [`parse_all`] failed on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseAllError {
+    /// Which document, counting from 0.
+    pub index: usize,
+    /// The byte offset the document starts at.
+    pub start: usize,
+    /// The error, with its offset in the whole input.
+    pub error: JsonError,
+}
+
+impl fmt::Display for ParseAllError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "document {} (at byte {}): {}",
+            self.index, self.start, self.error
+        )
+    }
+}
+
+impl core::error::Error for ParseAllError {}
+
+/// Parses every top-level value in `json`, as [`parse_many`] yields them,
+/// for files that accumulate snapshots one after another. An error names
+/// the document it is in.
+pub fn parse_all(json: &str) -> Result<Vec<Type>, ParseAllError> {
+    let mut values = Vec::new();
+    let mut many = parse_many(json);
+    loop {
+        let start = json.as_bytes()[many.byte_offset()..]
+            .iter()
+            .position(|&b| !is_whitespace(b))
+            .map_or(json.len(), |skipped| many.byte_offset() + skipped);
+        match many.next() {
+            Some(Ok(value)) => values.push(value),
+            Some(Err(error)) => {
+                return Err(ParseAllError {
+                    index: values.len(),
+                    start,
+                    error,
+                })
+            }
+            None => return Ok(values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_all, parse_many, ParseAllError};
+    use crate::{parse, Expected, JsonError, Type};
+
+    #[test]
+    fn concatenated_values() {
+        let values: Vec<_> = parse_many(r#"{"a":1}{"a":2} [3]"x"4 null "#).collect();
+        assert_eq!(
+            values,
+            vec![
+                parse(r#"{"a":1}"#),
+                parse(r#"{"a":2}"#),
+                parse("[3]"),
+                Ok(Type::String("x".into())),
+                Ok(Type::Number(4.into())),
+                Ok(Type::Null),
+            ]
+        );
+        assert_eq!(parse_many("  ").count(), 0);
+    }
+
+    #[test]
+    fn stops_at_error() {
+        let mut values = parse_many("[1] [2 ]]");
+        assert_eq!(values.next(), Some(parse("[1]")));
+        assert_eq!(values.byte_offset(), 3);
+        assert_eq!(values.next(), Some(parse("[2]")));
+        assert_eq!(
+            values.next(),
+            Some(Err(JsonError::UnexpectToken {
+                offset: 8,
+                expected: Expected::VALUE
+            }))
+        );
+        assert_eq!(values.next(), None);
+    }
+
+    #[test]
+    fn collects_all_values() {
+        assert_eq!(
+            parse_all("{\"v\": 1}\n{\"v\": 2}\n"),
+            Ok(vec![
+                parse(r#"{"v": 1}"#).unwrap(),
+                parse(r#"{"v": 2}"#).unwrap()
+            ])
+        );
+        assert_eq!(parse_all(" "), Ok(vec![]));
+        let err = parse_all("1\n[2]\n  [3,]").unwrap_err();
+        assert_eq!(
+            err,
+            ParseAllError {
+                index: 2,
+                start: 8,
+                error: JsonError::UnexpectToken {
+                    offset: 11,
+                    expected: Expected::VALUE
+                }
+            }
+        );
+        assert!(err.to_string().starts_with("document 2 (at byte 8): "));
+    }
+
+    #[test]
+    fn skips_bom() {
+        let values: Vec<_> = parse_many("\u{feff}1 2").collect();
+        assert_eq!(
+            values,
+            vec![Ok(Type::Number(1.into())), Ok(Type::Number(2.into()))]
+        );
+        assert_eq!(
+            parse_all("\u{feff}[1]\n[2]"),
+            Ok(vec![parse("[1]").unwrap(), parse("[2]").unwrap()])
+        );
+        assert_eq!(parse_all("\u{feff}[1]\u{feff}").unwrap_err().start, 6);
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+
+use crate::{Type, ValueKind};
+
+/// How [`Type::merge_with`] combines an array with the one layered over it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The overlay's array replaces the base's.
+    #[default]
+    Replace,
+    /// The overlay's elements are appended to the base's.
+    Concat,
+    /// Elements at the same index are merged; the overlay's extra elements
+    /// are appended.
+    ByIndex,
+    /// Objects with equal values of the named member are merged, as
+    /// Kubernetes' strategic merge does with `name`; any other overlay
+    /// element is appended.
+    ByKey(String),
+}
+
+/// Settings for [`Type::merge_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    pub arrays: ArrayMerge,
+}
+
+/// What [`Type::merge_into`] does with a member both objects have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    /// The moved member replaces the target's.
+    Overwrite,
+    /// The target's member stays and the moved one is dropped.
+    KeepTarget,
+    /// The two are combined as [`Type::merge`] does, the moved member
+    /// layered over the target's.
+    Merge,
+    /// Nothing is moved and the key is reported.
+    Error,
+}
+
+/// Why [`Type::merge_into`] moved nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeIntoError {
+    /// One of the two values is not an object.
+    NotObject(ValueKind),
+    /// Both objects have this member, under [`Collision::Error`].
+    Collision(String),
+}
+
+impl fmt::Display for MergeIntoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeIntoError::NotObject(kind) => write!(f, "expected an object, found {}", kind),
+            MergeIntoError::Collision(key) => write!(f, "member '{}' is in both objects", key),
+        }
+    }
+}
+
+impl core::error::Error for MergeIntoError {}
+
+impl Type {
+    /// Moves every member of this object into `target`, as serde's
+    /// `flatten` does, for assembling one object from several sources. A
+    /// null target becomes an object first; a key both have is settled by
+    /// `on_collision`.
+    ///
+    /// On an error `target` is left as it was.
+    pub fn merge_into(
+        self,
+        target: &mut Type,
+        on_collision: Collision,
+    ) -> Result<(), MergeIntoError> {
+        let members = match self {
+            Type::Object(members) => members,
+            other => return Err(MergeIntoError::NotObject(other.kind())),
+        };
+        if target.is_null() {
+            *target = Type::Object(Box::default());
+        }
+        let Type::Object(base) = target else {
+            return Err(MergeIntoError::NotObject(target.kind()));
+        };
+        if on_collision == Collision::Error {
+            if let Some((key, _)) = members.iter().find(|(key, _)| base.get(*key).is_some()) {
+                return Err(MergeIntoError::Collision(String::from(&**key)));
+            }
+        }
+        for (key, value) in members.into_iter() {
+            match base.get_mut(&key) {
+                Some(slot) => match on_collision {
+                    Collision::Overwrite | Collision::Error => *slot = value,
+                    Collision::KeepTarget => {}
+                    Collision::Merge => slot.merge(value),
+                },
+                None => {
+                    base.insert(key, value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Layers `other` over the value, for configuration overrides: objects
+    /// are merged member by member at any depth, and anything else is
+    /// replaced by `other`'s value, arrays included.
+    pub fn merge(&mut self, other: Type) {
+        self.merge_with(other, &MergeOptions::default());
+    }
+
+    /// Like [`merge`](Type::merge), combining arrays as `options` says.
+    pub fn merge_with(&mut self, other: Type, options: &MergeOptions) {
+        match (self, other) {
+            (Type::Object(base), Type::Object(overlay)) => {
+                for (key, value) in overlay.into_iter() {
+                    match base.get_mut(&key) {
+                        Some(slot) => slot.merge_with(value, options),
+                        None => {
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (Type::Array(base), Type::Array(overlay)) => match &options.arrays {
+                ArrayMerge::Replace => *base = overlay,
+                ArrayMerge::Concat => base.extend(overlay),
+                ArrayMerge::ByIndex => {
+                    let mut overlay = overlay.into_iter();
+                    for (slot, value) in base.iter_mut().zip(overlay.by_ref()) {
+                        slot.merge_with(value, options);
+                    }
+                    base.extend(overlay);
+                }
+                ArrayMerge::ByKey(field) => {
+                    for value in overlay {
+                        let id = match &value {
+                            Type::Object(members) => members.get(field.as_str()),
+                            _ => None,
+                        };
+                        let slot = id.and_then(|id| {
+                            base.iter().position(|item| match item {
+                                Type::Object(members) => members.get(field.as_str()) == Some(id),
+                                _ => false,
+                            })
+                        });
+                        match slot {
+                            Some(i) => base[i].merge_with(value, options),
+                            None => base.push(value),
+                        }
+                    }
+                }
+            },
+            (slot, value) => *slot = value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArrayMerge, Collision, MergeIntoError, MergeOptions};
+    use crate::{parse, Type, ValueKind};
+
+    const BASE: &str = r#"{"name": "app", "tags": ["a"], "db": {"host": "x", "port": 1},
+        "containers": [{"name": "web", "image": "v1", "env": [1]}, {"name": "db"}]}"#;
+    const OVERLAY: &str = r#"{"tags": ["b"], "db": {"port": 2}, "debug": true,
+        "containers": [{"name": "web", "image": "v2", "env": [2]}, {"name": "cache"}, 3]}"#;
+
+    fn merged(arrays: ArrayMerge) -> crate::Type {
+        let mut value = parse(BASE).unwrap();
+        value.merge_with(parse(OVERLAY).unwrap(), &MergeOptions { arrays });
+        value
+    }
+
+    #[test]
+    fn merges_objects_deeply() {
+        let mut value = parse(BASE).unwrap();
+        value.merge(parse(OVERLAY).unwrap());
+        assert_eq!(value, merged(ArrayMerge::Replace));
+        assert_eq!(
+            value,
+            parse(
+                r#"{"name": "app", "tags": ["b"], "db": {"host": "x", "port": 2}, "debug": true,
+                "containers": [{"name": "web", "image": "v2", "env": [2]}, {"name": "cache"}, 3]}"#
+            )
+            .unwrap()
+        );
+        let mut scalar = parse("[1]").unwrap();
+        scalar.merge(parse(r#"{"a": 1}"#).unwrap());
+        assert_eq!(scalar, parse(r#"{"a": 1}"#).unwrap());
+    }
+
+    #[test]
+    fn merges_arrays() {
+        let value = merged(ArrayMerge::Concat);
+        assert_eq!(
+            value.pointer("/tags"),
+            Some(&parse(r#"["a", "b"]"#).unwrap())
+        );
+        assert_eq!(value.pointer("/containers").and_then(|c| c.len()), Some(5));
+
+        let value = merged(ArrayMerge::ByIndex);
+        assert_eq!(
+            value.pointer("/containers"),
+            Some(
+                &parse(r#"[{"name": "web", "image": "v2", "env": [2]}, {"name": "cache"}, 3]"#)
+                    .unwrap()
+            )
+        );
+
+        let value = merged(ArrayMerge::ByKey("name".into()));
+        assert_eq!(
+            value.pointer("/containers"),
+            Some(
+                &parse(r#"[{"name": "web", "image": "v2", "env": [1, 2]}, {"name": "db"}, {"name": "cache"}, 3]"#)
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            value.pointer("/tags"),
+            Some(&parse(r#"["a", "b"]"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn moves_members_into_target() {
+        let source = || parse(r#"{"id": 2, "meta": {"b": 2}, "extra": true}"#).unwrap();
+        let target = || parse(r#"{"id": 1, "meta": {"a": 1}}"#).unwrap();
+        let merged = |on_collision| {
+            let mut value = target();
+            source()
+                .merge_into(&mut value, on_collision)
+                .map(|()| value)
+        };
+        assert_eq!(
+            merged(Collision::Overwrite),
+            Ok(parse(r#"{"id": 2, "meta": {"b": 2}, "extra": true}"#).unwrap())
+        );
+        assert_eq!(
+            merged(Collision::KeepTarget),
+            Ok(parse(r#"{"id": 1, "meta": {"a": 1}, "extra": true}"#).unwrap())
+        );
+        assert_eq!(
+            merged(Collision::Merge),
+            Ok(parse(r#"{"id": 2, "meta": {"a": 1, "b": 2}, "extra": true}"#).unwrap())
+        );
+
+        let mut value = target();
+        let err = parse(r#"{"id": 3}"#)
+            .unwrap()
+            .merge_into(&mut value, Collision::Error)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "member 'id' is in both objects");
+        assert_eq!(value, target());
+        parse(r#"{"x": 1}"#)
+            .unwrap()
+            .merge_into(&mut value, Collision::Error)
+            .unwrap();
+        assert_eq!(value.pointer("/x"), Some(&parse("1").unwrap()));
+
+        let mut value = Type::Null;
+        source().merge_into(&mut value, Collision::Error).unwrap();
+        assert_eq!(value, source());
+        assert_eq!(
+            source().merge_into(&mut parse("[]").unwrap(), Collision::Overwrite),
+            Err(MergeIntoError::NotObject(ValueKind::Array))
+        );
+        assert_eq!(
+            parse("1")
+                .unwrap()
+                .merge_into(&mut target(), Collision::Overwrite),
+            Err(MergeIntoError::NotObject(ValueKind::Number))
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::base64::base64url;
+use crate::{into_str, to_string, Map, Type, MAX_DEPTH};
+
+/// An error from [`from_msgpack`]: the input is not well-formed MessagePack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsgpackError {
+    /// The byte offset of the offending object.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for MsgpackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid MessagePack at byte {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl core::error::Error for MsgpackError {}
+
+/// Encodes `value` as MessagePack, in the smallest form for each value.
+///
+/// Numbers that are integers in the `i64` or `u64` range are written as
+/// integers, other numbers as the shorter float that holds them exactly.
+/// Object members are written in key order, so equal values encode alike.
+pub fn to_msgpack(value: &Type) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode(value, &mut out);
+    out
+}
+
+/// Writes a length with the fixed-size marker `fix` if it fits in `bits`
+/// bits, or else after the first of the 8-bit, 16-bit and 32-bit `markers`
+/// it fits. There is no 8-bit form for arrays and maps, whose `markers[0]`
+/// is `None`.
+fn header(len: usize, fix: u8, bits: u32, markers: [Option<u8>; 3], out: &mut Vec<u8>) {
+    if len < 1 << bits {
+        out.push(fix | len as u8);
+    } else if let (Some(marker), Ok(len)) = (markers[0], u8::try_from(len)) {
+        out.extend_from_slice(&[marker, len]);
+    } else if let Ok(len) = u16::try_from(len) {
+        out.push(markers[1].unwrap());
+        out.extend_from_slice(&len.to_be_bytes());
+    } else {
+        out.push(markers[2].unwrap());
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+fn encode(value: &Type, out: &mut Vec<u8>) {
+    match value {
+        Type::Null => out.push(0xc0),
+        Type::Boolean(false) => out.push(0xc2),
+        Type::Boolean(true) => out.push(0xc3),
+        Type::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                match n {
+                    n @ 0..=0x7f => out.push(n as u8),
+                    n @ 0..=0xff => out.extend_from_slice(&[0xcc, n as u8]),
+                    n @ 0..=0xffff => {
+                        out.push(0xcd);
+                        out.extend_from_slice(&(n as u16).to_be_bytes());
+                    }
+                    n @ 0..=0xffff_ffff => {
+                        out.push(0xce);
+                        out.extend_from_slice(&(n as u32).to_be_bytes());
+                    }
+                    n => {
+                        out.push(0xcf);
+                        out.extend_from_slice(&n.to_be_bytes());
+                    }
+                }
+            } else if let Some(n) = n.as_i64() {
+                match n {
+                    n @ -32..=-1 => out.push(n as u8),
+                    n @ -0x80..=-1 => out.extend_from_slice(&[0xd0, n as u8]),
+                    n @ -0x8000..=-1 => {
+                        out.push(0xd1);
+                        out.extend_from_slice(&(n as i16).to_be_bytes());
+                    }
+                    n @ -0x8000_0000..=-1 => {
+                        out.push(0xd2);
+                        out.extend_from_slice(&(n as i32).to_be_bytes());
+                    }
+                    n => {
+                        out.push(0xd3);
+                        out.extend_from_slice(&n.to_be_bytes());
+                    }
+                }
+            } else {
+                let n = n.to_f64();
+                if (n as f32) as f64 == n {
+                    out.push(0xca);
+                    out.extend_from_slice(&(n as f32).to_be_bytes());
+                } else {
+                    out.push(0xcb);
+                    out.extend_from_slice(&n.to_be_bytes());
+                }
+            }
+        }
+        Type::String(s) => string(s, out),
+        Type::Array(items) => {
+            header(items.len(), 0x90, 4, [None, Some(0xdc), Some(0xdd)], out);
+            for item in items {
+                encode(item, out);
+            }
+        }
+        Type::Object(members) => {
+            header(members.len(), 0x80, 4, [None, Some(0xde), Some(0xdf)], out);
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            for (key, member) in members {
+                string(key, out);
+                encode(member, out);
+            }
+        }
+    }
+}
+
+fn string(s: &str, out: &mut Vec<u8>) {
+    header(s.len(), 0xa0, 5, [Some(0xd9), Some(0xda), Some(0xdb)], out);
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Decodes one MessagePack object spanning all of `bytes`.
+///
+/// Binary data becomes an unpadded base64url string, as does the payload of
+/// an extension type other than the timestamp (-1), which becomes a number
+/// of seconds. Non-finite floats become `null`, and map keys that are not
+/// strings become their compact JSON serialization.
+pub fn from_msgpack(bytes: &[u8]) -> Result<Type, MsgpackError> {
+    let mut decoder = Decoder {
+        bytes,
+        pos: 0,
+        depth: 0,
+    };
+    let value = decoder.object()?;
+    if decoder.pos < bytes.len() {
+        return Err(decoder.error(decoder.pos, "trailing bytes after the object"));
+    }
+    Ok(value)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// How many arrays and maps enclose the current object.
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, offset: usize, message: &'static str) -> MsgpackError {
+        MsgpackError { offset, message }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], MsgpackError> {
+        let Some(bytes) = self.bytes.get(self.pos..).and_then(|rest| rest.get(..n)) else {
+            return Err(self.error(self.bytes.len(), "unexpected end of input"));
+        };
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], MsgpackError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Reads a big-endian length of `width` bytes.
+    fn len(&mut self, width: usize) -> Result<usize, MsgpackError> {
+        Ok(match width {
+            1 => self.array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.array()?) as usize,
+            _ => u32::from_be_bytes(self.array()?) as usize,
+        })
+    }
+
+    /// Bounds the count of a container by the bytes left, each element
+    /// taking at least one, so a forged count cannot make us allocate.
+    fn count(&self, start: usize, count: usize) -> Result<usize, MsgpackError> {
+        if count <= self.bytes.len() - self.pos {
+            Ok(count)
+        } else {
+            Err(self.error(start, "length exceeds the input"))
+        }
+    }
+
+    fn object(&mut self) -> Result<Type, MsgpackError> {
+        let start = self.pos;
+        let marker = self.array::<1>()?[0];
+        Ok(match marker {
+            0x00..=0x7f => Type::Number(marker.into()),
+            0xe0..=0xff => Type::Number((marker as i8).into()),
+            0x80..=0x8f => self.map(start, (marker & 0x0f) as usize)?,
+            0x90..=0x9f => self.items(start, (marker & 0x0f) as usize)?,
+            0xa0..=0xbf => self.str(start, (marker & 0x1f) as usize)?,
+            0xc0 => Type::Null,
+            0xc2 => Type::Boolean(false),
+            0xc3 => Type::Boolean(true),
+            0xc4..=0xc6 => {
+                let len = self.len(1 << (marker - 0xc4))?;
+                Type::String(into_str(base64url(self.take(len)?)))
+            }
+            0xc7..=0xc9 => {
+                let len = self.len(1 << (marker - 0xc7))?;
+                self.ext(start, len)?
+            }
+            0xca => number(f32::from_be_bytes(self.array()?) as f64),
+            0xcb => number(f64::from_be_bytes(self.array()?)),
+            0xcc => Type::Number(self.array::<1>()?[0].into()),
+            0xcd => Type::Number(u16::from_be_bytes(self.array()?).into()),
+            0xce => Type::Number(u32::from_be_bytes(self.array()?).into()),
+            0xcf => Type::Number(u64::from_be_bytes(self.array()?).into()),
+            0xd0 => Type::Number((self.array::<1>()?[0] as i8).into()),
+            0xd1 => Type::Number(i16::from_be_bytes(self.array()?).into()),
+            0xd2 => Type::Number(i32::from_be_bytes(self.array()?).into()),
+            0xd3 => Type::Number(i64::from_be_bytes(self.array()?).into()),
+            0xd4..=0xd8 => self.ext(start, 1 << (marker - 0xd4))?,
+            0xd9..=0xdb => {
+                let len = self.len(1 << (marker - 0xd9))?;
+                self.str(start, len)?
+            }
+            0xdc | 0xdd => {
+                let count = self.len(2 << (marker - 0xdc))?;
+                self.items(start, count)?
+            }
+            0xde | 0xdf => {
+                let count = self.len(2 << (marker - 0xde))?;
+                self.map(start, count)?
+            }
+            0xc1 => return Err(self.error(start, "reserved marker byte")),
+        })
+    }
+
+    fn str(&mut self, start: usize, len: usize) -> Result<Type, MsgpackError> {
+        let bytes = self.take(len)?;
+        match core::str::from_utf8(bytes) {
+            Ok(s) => Ok(Type::String(s.into())),
+            Err(_) => Err(self.error(start, "string is not valid UTF-8")),
+        }
+    }
+
+    /// Enters an array or map, which may nest no deeper than JSON does.
+    fn enter(&mut self, start: usize) -> Result<(), MsgpackError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(start, "nesting too deep"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn items(&mut self, start: usize, count: usize) -> Result<Type, MsgpackError> {
+        self.enter(start)?;
+        let mut items = Vec::with_capacity(self.count(start, count)?);
+        for _ in 0..count {
+            items.push(self.object()?);
+        }
+        self.depth -= 1;
+        Ok(Type::Array(items))
+    }
+
+    fn map(&mut self, start: usize, count: usize) -> Result<Type, MsgpackError> {
+        self.enter(start)?;
+        let mut members = Map::default();
+        for _ in 0..self.count(start, count)? {
+            let key = match self.object()? {
+                Type::String(key) => key,
+                key => into_str(to_string(&key)),
+            };
+            let value = self.object()?;
+            members.insert(key.as_str().into(), value);
+        }
+        self.depth -= 1;
+        Ok(Type::Object(Box::new(members)))
+    }
+
+    /// Reads an extension type whose payload is `len` bytes.
+    fn ext(&mut self, start: usize, len: usize) -> Result<Type, MsgpackError> {
+        let kind = self.array::<1>()?[0] as i8;
+        let data = self.take(len)?;
+        if kind != -1 {
+            return Ok(Type::String(into_str(base64url(data))));
+        }
+        let (seconds, nanos) = match *data {
+            [a, b, c, d] => (u32::from_be_bytes([a, b, c, d]) as f64, 0),
+            [a, b, c, d, e, f, g, h] => {
+                let n = u64::from_be_bytes([a, b, c, d, e, f, g, h]);
+                ((n & 0x3_ffff_ffff) as f64, (n >> 34) as u32)
+            }
+            [a, b, c, d, ref rest @ ..] if rest.len() == 8 => {
+                let seconds = i64::from_be_bytes(rest.try_into().unwrap());
+                (seconds as f64, u32::from_be_bytes([a, b, c, d]))
+            }
+            _ => return Err(self.error(start, "malformed timestamp")),
+        };
+        Ok(Type::Number((seconds + nanos as f64 / 1e9).into()))
+    }
+}
+
+fn number(n: f64) -> Type {
+    if n.is_finite() {
+        Type::Number(n.into())
+    } else {
+        Type::Null
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_msgpack, to_msgpack, MsgpackError};
+    use crate::{parse, Type, MAX_DEPTH};
+
+    #[test]
+    fn encodes() {
+        let cases = [
+            ("0", vec![0x00]),
+            ("127", vec![0x7f]),
+            ("128", vec![0xcc, 0x80]),
+            ("65536", vec![0xce, 0x00, 0x01, 0x00, 0x00]),
+            ("-1", vec![0xff]),
+            ("-33", vec![0xd0, 0xdf]),
+            ("-129", vec![0xd1, 0xff, 0x7f]),
+            ("1.5", vec![0xca, 0x3f, 0xc0, 0x00, 0x00]),
+            ("-0.0", vec![0xca, 0x80, 0x00, 0x00, 0x00]),
+            (
+                "1.1",
+                vec![0xcb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+            ),
+            ("null", vec![0xc0]),
+            ("false", vec![0xc2]),
+            (r#""ab""#, vec![0xa2, 0x61, 0x62]),
+            ("[1, []]", vec![0x92, 0x01, 0x90]),
+            (
+                r#"{"b": 1, "a": true}"#,
+                vec![0x82, 0xa1, 0x61, 0xc3, 0xa1, 0x62, 0x01],
+            ),
+        ];
+        for (json, msgpack) in cases {
+            let value = parse(json).unwrap();
+            assert_eq!(to_msgpack(&value), msgpack, "{}", json);
+            assert_eq!(from_msgpack(&msgpack), Ok(value), "{}", json);
+        }
+
+        let value = Type::String("x".repeat(40).as_str().into());
+        let bytes = to_msgpack(&value);
+        assert_eq!(bytes[..2], [0xd9, 40]);
+        assert_eq!(from_msgpack(&bytes), Ok(value));
+        let value = Type::Array(vec![Type::Null; 16]);
+        let bytes = to_msgpack(&value);
+        assert_eq!(bytes[..3], [0xdc, 0x00, 0x10]);
+        assert_eq!(from_msgpack(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn decodes_msgpack_only_values() {
+        let decode = |bytes: &[u8]| from_msgpack(bytes).unwrap();
+        assert_eq!(
+            decode(&[0xc4, 0x03, 0xfb, 0xff, 0x01]),
+            Type::String("-_8B".into())
+        );
+        assert_eq!(decode(&[0xd4, 0x05, 0xfb]), Type::String("-w".into()));
+        assert_eq!(
+            decode(&[0xd6, 0xff, 0x00, 0x00, 0x00, 0x10]),
+            Type::Number(16.into())
+        );
+        // 500ms in the 30-bit nanoseconds, then 2 seconds.
+        assert_eq!(
+            decode(&[0xd7, 0xff, 0x77, 0x35, 0x94, 0x00, 0x00, 0x00, 0x00, 0x02]),
+            Type::Number(2.5.into())
+        );
+        assert_eq!(
+            decode(&[0x81, 0x01, 0xc0]),
+            parse(r#"{"1": null}"#).unwrap()
+        );
+        assert_eq!(decode(&[0xcb, 0x7f, 0xf0, 0, 0, 0, 0, 0, 0]), Type::Null);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let error = |offset, message| Err(MsgpackError { offset, message });
+        assert_eq!(from_msgpack(&[]), error(0, "unexpected end of input"));
+        assert_eq!(from_msgpack(&[0xc1]), error(0, "reserved marker byte"));
+        assert_eq!(
+            from_msgpack(&[0x92, 0x01]),
+            error(0, "length exceeds the input")
+        );
+        assert_eq!(
+            from_msgpack(&[0x81, 0x01]),
+            error(2, "unexpected end of input")
+        );
+        assert_eq!(
+            from_msgpack(&[0xa2, 0x61]),
+            error(2, "unexpected end of input")
+        );
+        assert_eq!(
+            from_msgpack(&[0xa1, 0xff]),
+            error(0, "string is not valid UTF-8")
+        );
+        assert_eq!(
+            from_msgpack(&[0xc0, 0xc0]),
+            error(1, "trailing bytes after the object")
+        );
+        assert_eq!(
+            from_msgpack(&[0xd5, 0xff, 0x00, 0x00]),
+            error(0, "malformed timestamp")
+        );
+        assert_eq!(
+            from_msgpack(&[0xdd, 0xff, 0xff, 0xff, 0xff]),
+            error(0, "length exceeds the input")
+        );
+        assert_eq!(
+            from_msgpack(&[0x91; 200_000]),
+            error(MAX_DEPTH, "nesting too deep")
+        );
+        let mut nested = vec![0x91; MAX_DEPTH - 1];
+        nested.push(0x90);
+        assert!(from_msgpack(&nested).is_ok());
+    }
+}
@@ -0,0 +1,116 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{parse_slice, JsonError, Type};
+
+/// Reads newline-delimited JSON (JSON Lines), yielding one value per line.
+///
+/// Blank lines are skipped. A line that fails to parse yields an error and
+/// reading continues with the next line; an I/O error ends the iteration.
+pub struct NdjsonReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> NdjsonReader<R> {
+    pub fn new(reader: R) -> Self {
+        NdjsonReader {
+            reader,
+            line: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for NdjsonReader<R> {
+    type Item = Result<Type, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => self.done = true,
+                Ok(_) if self.line.iter().all(u8::is_ascii_whitespace) => {}
+                Ok(_) => return Some(parse_slice(&self.line)),
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Writes values as newline-delimited JSON, one compact value per line.
+///
+/// Compact output never contains a raw newline (newlines inside strings are
+/// escaped), so every record is terminated by exactly one `\n`.
+pub struct NdjsonWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        NdjsonWriter { writer }
+    }
+
+    pub fn write(&mut self, value: &Type) -> io::Result<()> {
+        value.write_to(&mut self.writer)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn write_all<'a, I>(&mut self, values: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Type>,
+    {
+        for value in values {
+            self.write(value)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NdjsonReader, NdjsonWriter};
+    use crate::{JsonError, Type};
+
+    #[test]
+    fn reads_lines() {
+        let input = "{\"a\":1}\n\n  [true]\r\n{\"a\":\nnull";
+        let values: Vec<_> = NdjsonReader::new(input.as_bytes()).collect();
+        assert_eq!(values.len(), 4);
+        assert!(matches!(values[0], Ok(Type::Object(_))));
+        assert_eq!(values[1], Ok(Type::Array(vec![Type::Boolean(true)])));
+        assert_eq!(values[2], Err(JsonError::UnexpectEof { offset: 6 }));
+        assert_eq!(values[3], Ok(Type::Null));
+    }
+
+    #[test]
+    fn writes_lines() {
+        let values = vec![
+            Type::String("multi\nline".into()),
+            Type::Array(vec![Type::Null, Type::Number(2.into())]),
+        ];
+        let mut writer = NdjsonWriter::new(Vec::new());
+        writer.write_all(&values).unwrap();
+        let out = writer.into_inner();
+        assert_eq!(out, b"\"multi\\nline\"\n[null,2]\n");
+
+        let read: Result<Vec<_>, _> = NdjsonReader::new(&out[..]).collect();
+        assert_eq!(read.unwrap(), values);
+    }
+}
@@ -0,0 +1,356 @@
+use core::cmp::Ordering;
+use core::fmt;
+
+/// A JSON number. Integral values that fit in `u64` or `i64` are held
+/// exactly as integers, so large IDs survive a round trip; everything else
+/// is an `f64`.
+///
+/// Each value has one representation: `1`, `1.0` and `1e0` all parse to the
+/// integer 1, so two numbers are equal exactly when their values are.
+#[derive(Clone, Copy)]
+pub struct Number(N);
+
+#[derive(Clone, Copy)]
+enum N {
+    PosInt(u64),
+    /// Always negative.
+    NegInt(i64),
+    /// Never integral within the `i64` to `u64` range, except `-0.0`.
+    Float(f64),
+}
+
+impl Number {
+    /// The value as a `u64`, if it is an integer in range.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.0 {
+            N::PosInt(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The value as an `i64`, if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.0 {
+            N::PosInt(n) => i64::try_from(n).ok(),
+            N::NegInt(n) => Some(n),
+            N::Float(_) => None,
+        }
+    }
+
+    /// The value as an `f64`, if it has one exactly; integers beyond 2^53
+    /// may not.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.0 {
+            N::PosInt(n) => {
+                let f = n as f64;
+                (f < 18446744073709551616.0 && f as u64 == n).then_some(f)
+            }
+            N::NegInt(n) => Some(n as f64).filter(|&f| f as i64 == n),
+            N::Float(f) => Some(f),
+        }
+    }
+
+    /// The nearest `f64` to the value.
+    pub fn to_f64(&self) -> f64 {
+        match self.0 {
+            N::PosInt(n) => n as f64,
+            N::NegInt(n) => n as f64,
+            N::Float(f) => f,
+        }
+    }
+
+    /// Whether the value is held as an integer, which every integral value
+    /// between `i64::MIN` and `u64::MAX` is.
+    pub fn is_integer(&self) -> bool {
+        !matches!(self.0, N::Float(_))
+    }
+
+    /// A total order consistent with `==`: integers compare exactly with
+    /// integers and floats alike, and floats with each other as
+    /// [`f64::total_cmp`] does.
+    pub fn total_cmp(&self, other: &Number) -> Ordering {
+        match (self.0, other.0) {
+            (N::PosInt(a), N::PosInt(b)) => a.cmp(&b),
+            (N::NegInt(a), N::NegInt(b)) => a.cmp(&b),
+            (N::NegInt(_), N::PosInt(_)) => Ordering::Less,
+            (N::PosInt(_), N::NegInt(_)) => Ordering::Greater,
+            (N::PosInt(a), N::Float(b)) => cmp_int_float(a.into(), b),
+            (N::NegInt(a), N::Float(b)) => cmp_int_float(a.into(), b),
+            (N::Float(a), N::PosInt(b)) => cmp_int_float(b.into(), a).reverse(),
+            (N::Float(a), N::NegInt(b)) => cmp_int_float(b.into(), a).reverse(),
+            (N::Float(a), N::Float(b)) => a.total_cmp(&b),
+        }
+    }
+}
+
+/// Compares the integer `int`, which is within the `i64` to `u64` range,
+/// with `f` exactly.
+fn cmp_int_float(int: i128, f: f64) -> Ordering {
+    if f.is_nan() {
+        // As in `f64::total_cmp`, NaN lies beyond everything on its side.
+        return if f.is_sign_negative() {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+    }
+    if f >= 18446744073709551616.0 {
+        return Ordering::Less;
+    }
+    if f < -9223372036854775808.0 {
+        return Ordering::Greater;
+    }
+    // Within the range the integral part converts exactly, both ways.
+    let whole = f as i128;
+    int.cmp(&whole).then_with(|| {
+        (whole as f64)
+            .partial_cmp(&f)
+            .expect("NaN was handled above")
+    })
+}
+
+/// Integral values in range become integers; `-0.0` and non-finite values
+/// stay floats.
+impl From<f64> for Number {
+    fn from(f: f64) -> Self {
+        if f % 1.0 == 0.0 && !(f == 0.0 && f.is_sign_negative()) {
+            if (0.0..18446744073709551616.0).contains(&f) {
+                return Number(N::PosInt(f as u64));
+            }
+            if (-9223372036854775808.0..0.0).contains(&f) {
+                return Number(N::NegInt(f as i64));
+            }
+        }
+        Number(N::Float(f))
+    }
+}
+
+impl From<f32> for Number {
+    fn from(f: f32) -> Self {
+        Number::from(f64::from(f))
+    }
+}
+
+macro_rules! unsigned {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Number {
+            fn from(n: $t) -> Self {
+                Number(N::PosInt(n as u64))
+            }
+        }
+    )*};
+}
+
+macro_rules! signed {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Number {
+            fn from(n: $t) -> Self {
+                if n < 0 {
+                    Number(N::NegInt(n as i64))
+                } else {
+                    Number(N::PosInt(n as u64))
+                }
+            }
+        }
+    )*};
+}
+
+unsigned!(u8 u16 u32 u64 usize);
+signed!(i8 i16 i32 i64 isize);
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool {
+        match (self.0, other.0) {
+            (N::PosInt(a), N::PosInt(b)) => a == b,
+            (N::NegInt(a), N::NegInt(b)) => a == b,
+            (N::Float(a), N::Float(b)) => a == b,
+            // Only `-0.0` is a float equal to an integer.
+            (N::Float(f), N::PosInt(0)) | (N::PosInt(0), N::Float(f)) => f == 0.0,
+            _ => false,
+        }
+    }
+}
+
+/// The JSON text of the number: integers in full, and floats in the fewest
+/// digits that read back as the same `f64`, switching to an exponent for
+/// very large and very small magnitudes. Non-finite values, which JSON
+/// cannot hold, are written as `null`.
+///
+/// Together with the parser, which rounds correctly, every finite float
+/// survives a round trip bit for bit: `-0.0` keeps its sign, subnormals
+/// keep their last bit, and `0.1` stays `0.1`.
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            N::PosInt(n) => f.write_str(itoa::Buffer::new().format(n)),
+            N::NegInt(n) => f.write_str(itoa::Buffer::new().format(n)),
+            N::Float(n) if n.is_finite() => f.write_str(ryu::Buffer::new().format_finite(n)),
+            N::Float(_) => f.write_str("null"),
+        }
+    }
+}
+
+impl fmt::Debug for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            N::PosInt(n) => n.fmt(f),
+            N::NegInt(n) => n.fmt(f),
+            N::Float(n) => n.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cmp::Ordering;
+
+    use super::Number;
+    use crate::{parse, to_string, Type};
+
+    fn number(json: &str) -> Number {
+        match parse(json).unwrap() {
+            Type::Number(n) => n,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn holds_integers_exactly() {
+        let max = number("18446744073709551615");
+        assert_eq!(max.as_u64(), Some(u64::MAX));
+        assert_eq!(max.as_i64(), None);
+        assert_eq!(max.as_f64(), None);
+        assert_eq!(to_string(&Type::Number(max)), "18446744073709551615");
+        assert_eq!(to_string(&Type::Number(number("-12"))), "-12");
+        let min = number("-9223372036854775808");
+        assert_eq!(min.as_i64(), Some(i64::MIN));
+        assert_eq!(min.as_f64(), Some(-9223372036854775808.0));
+        assert_eq!(number("9007199254740993").as_f64(), None);
+        assert_eq!(
+            number("9007199254740992").as_f64(),
+            Some(9007199254740992.0)
+        );
+        for json in ["1", "1.0", "1e0", "0.1e1"] {
+            assert!(number(json).is_integer(), "{}", json);
+            assert_eq!(number(json), Number::from(1u8), "{}", json);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_floats() {
+        let half = number("-0.5");
+        assert!(!half.is_integer());
+        assert_eq!(
+            (half.as_i64(), half.as_u64(), half.as_f64()),
+            (None, None, Some(-0.5))
+        );
+        assert_eq!(number("1e20").as_u64(), None);
+        assert_eq!(
+            number("18446744073709551616").to_f64(),
+            18446744073709551616.0
+        );
+        let zero = number("-0.0");
+        assert!(!zero.is_integer());
+        assert_eq!(zero, Number::from(0));
+        assert_eq!(zero.total_cmp(&Number::from(0)), Ordering::Equal);
+        assert_ne!(Number::from(f64::NAN), Number::from(f64::NAN));
+        assert_eq!(Number::from(-3i8).as_i64(), Some(-3));
+        assert_eq!(Number::from(2.5f32).to_f64(), 2.5);
+    }
+
+    #[test]
+    fn writes_shortest_floats() {
+        for (n, json) in [
+            (0.1, "0.1"),
+            (-2.5, "-2.5"),
+            (-0.0, "-0.0"),
+            (1e300, "1e300"),
+            (1.5e-7, "1.5e-7"),
+            (f64::MAX, "1.7976931348623157e308"),
+            (f64::NAN, "null"),
+        ] {
+            assert_eq!(Number::from(n).to_string(), json);
+        }
+    }
+
+    #[test]
+    fn round_trips_floats_exactly() {
+        let round_trip = |f: f64| {
+            let json = Number::from(f).to_string();
+            (number(&json).to_f64().to_bits(), json)
+        };
+        for (f, json) in [
+            (-0.0, "-0.0"),
+            (5e-324, "5e-324"),
+            (-5e-324, "-5e-324"),
+            (2.225073858507201e-308, "2.225073858507201e-308"),
+            (f64::MIN_POSITIVE, "2.2250738585072014e-308"),
+            (f64::MAX, "1.7976931348623157e308"),
+            (f64::MIN, "-1.7976931348623157e308"),
+            (f64::EPSILON, "2.220446049250313e-16"),
+            (0.1, "0.1"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (1e23, "1e23"),
+            (1e21, "1e21"),
+            (18446744073709551616.0, "1.8446744073709552e19"),
+            (-9223372036854777856.0, "-9.223372036854778e18"),
+        ] {
+            assert_eq!(round_trip(f), (f.to_bits(), json.into()), "{}", json);
+        }
+        assert!(number("-0").to_f64().is_sign_negative());
+        assert!(number("-0e10").to_f64().is_sign_negative());
+        assert!(number("-1e-400").to_f64().is_sign_negative());
+
+        // A sweep over arbitrary bit patterns, xorshift-generated.
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..20_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let f = f64::from_bits(state);
+            if f.is_finite() && !Number::from(f).is_integer() {
+                assert_eq!(round_trip(f).0, state, "{:e}", f);
+            }
+        }
+    }
+
+    #[test]
+    fn orders_exactly() {
+        let order = |a: &str, b: &str| number(a).total_cmp(&number(b));
+        assert_eq!(
+            order("18446744073709551615", "18446744073709551614"),
+            Ordering::Greater
+        );
+        assert_eq!(order("-1", "0.5"), Ordering::Less);
+        assert_eq!(order("2.5", "2"), Ordering::Greater);
+        assert_eq!(
+            order("-9223372036854775808", "-9223372036854775807"),
+            Ordering::Less
+        );
+        // 2^64 just past `u64::MAX`, which its nearest `f64` equals.
+        assert_eq!(
+            order("18446744073709551615", "1.8446744073709552e19"),
+            Ordering::Less
+        );
+        assert_eq!(
+            order("1.8446744073709552e19", "18446744073709551615"),
+            Ordering::Greater
+        );
+        assert_eq!(order("-1e19", "-9223372036854775808"), Ordering::Less);
+        assert_eq!(order("-1", "-1.5"), Ordering::Greater);
+        assert_eq!(order("-2", "-1.5"), Ordering::Less);
+        assert_eq!(order("0", "-0.0"), Ordering::Equal);
+        assert_eq!(order("-1", "-0.0"), Ordering::Less);
+        let float = |f: f64, n: Number| Number::from(f).total_cmp(&n);
+        assert_eq!(
+            float(f64::INFINITY, Number::from(u64::MAX)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            float(f64::NEG_INFINITY, Number::from(i64::MIN)),
+            Ordering::Less
+        );
+        assert_eq!(float(f64::NAN, Number::from(u64::MAX)), Ordering::Greater);
+        assert_eq!(float(-f64::NAN, Number::from(i64::MIN)), Ordering::Less);
+    }
+}
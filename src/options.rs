@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::Lexer;
+use crate::parser::{to_event, Event, Grammar};
+#[cfg(feature = "unicode")]
+use crate::unicode::nfc;
+use crate::{JsonError, Type};
+
+/// Settings for [`parse_with`].
+///
+/// The limits bound the memory and time an attacker-controlled payload can
+/// cost; `None`, the default, leaves a dimension unbounded. A document that
+/// breaks a limit is rejected with `JsonError::LimitExceeded` at the offset
+/// of the offending token.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// How many arrays and objects may be nested inside one another; never
+    /// more than [`MAX_DEPTH`](crate::MAX_DEPTH), whatever the setting.
+    pub max_depth: Option<usize>,
+    /// The longest string or key, in bytes after unescaping.
+    pub max_string_len: Option<usize>,
+    /// The most elements a single array may have.
+    pub max_array_len: Option<usize>,
+    /// The most members a single object may have, counting duplicates.
+    pub max_object_len: Option<usize>,
+    /// The most values in the whole document, containers included.
+    pub max_nodes: Option<usize>,
+    /// Whether to NFC-normalize strings and keys, so that keys which differ
+    /// only in how their characters are composed, like a precomposed `é` and
+    /// an `e` followed by a combining accent, name the same member. Lengths
+    /// are checked after normalizing.
+    #[cfg(feature = "unicode")]
+    pub normalize_unicode: bool,
+}
+
+/// Parses `json` like [`parse`](crate::parse), enforcing `options`.
+pub fn parse_with(json: &str, options: &ParseOptions) -> Result<Type, JsonError> {
+    let over = |n: usize, limit: Option<usize>| limit.is_some_and(|limit| n > limit);
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    let mut grammar = Grammar::new();
+    let mut builder = TreeBuilder::new();
+    // For each open container, its elements so far and the limit on them.
+    let mut open: Vec<(usize, Option<usize>)> = Vec::new();
+    let mut nodes = 0;
+    let mut root = None;
+    while let Some(token) = lexer.next_token()? {
+        let is_key = grammar.expects_key();
+        grammar.accept(token)?;
+        let Some(event) = to_event(lexer.input(), token, is_key)? else {
+            continue;
+        };
+        #[cfg(feature = "unicode")]
+        let event = match event {
+            Event::Key(s) if options.normalize_unicode => Event::Key(nfc(s)),
+            Event::String(s) if options.normalize_unicode => Event::String(nfc(s)),
+            event => event,
+        };
+        let exceeded = Err(JsonError::LimitExceeded {
+            offset: token.start,
+        });
+        match &event {
+            Event::Key(s) | Event::String(s) if over(s.len(), options.max_string_len) => {
+                return exceeded
+            }
+            Event::Key(_) => {}
+            Event::EndArray | Event::EndObject => {
+                open.pop();
+            }
+            _ => {
+                nodes += 1;
+                if over(nodes, options.max_nodes) {
+                    return exceeded;
+                }
+                if let Some((count, limit)) = open.last_mut() {
+                    *count += 1;
+                    if over(*count, *limit) {
+                        return exceeded;
+                    }
+                }
+                match event {
+                    Event::StartArray => open.push((0, options.max_array_len)),
+                    Event::StartObject => open.push((0, options.max_object_len)),
+                    _ => {}
+                }
+                if over(open.len(), options.max_depth) {
+                    return exceeded;
+                }
+            }
+        }
+        if let Some(value) = builder.push(event) {
+            root = Some(value);
+        }
+    }
+    root.ok_or(JsonError::UnexpectEof { offset: json.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_with, ParseOptions};
+    use crate::{parse, Expected, JsonError, TokenKind};
+
+    fn check(json: &str, options: ParseOptions, offset: Option<usize>) {
+        match offset {
+            None => assert_eq!(parse_with(json, &options), parse(json), "{}", json),
+            Some(offset) => assert_eq!(
+                parse_with(json, &options),
+                Err(JsonError::LimitExceeded { offset }),
+                "{}",
+                json
+            ),
+        }
+    }
+
+    #[test]
+    fn enforces_limits() {
+        let depth = |n| ParseOptions {
+            max_depth: Some(n),
+            ..ParseOptions::default()
+        };
+        check("[[{\"a\": []}]]", depth(4), None);
+        check("[[{\"a\": []}]]", depth(3), Some(8));
+        check("1", depth(0), None);
+
+        let string = ParseOptions {
+            max_string_len: Some(3),
+            ..ParseOptions::default()
+        };
+        check(r#"{"abc": "\u00e9"}"#, string.clone(), None);
+        check(r#"{"abcd": 1}"#, string.clone(), Some(1));
+        check(r#"["ab", "a\nbc"]"#, string, Some(7));
+
+        let lengths = ParseOptions {
+            max_array_len: Some(2),
+            max_object_len: Some(1),
+            ..ParseOptions::default()
+        };
+        check(r#"[1, {"a": [2, 3]}]"#, lengths.clone(), None);
+        check("[1, 2, 3]", lengths.clone(), Some(7));
+        check(r#"{"a": 1, "a": 2}"#, lengths, Some(14));
+
+        let nodes = ParseOptions {
+            max_nodes: Some(4),
+            ..ParseOptions::default()
+        };
+        check(r#"{"a": [1, null]}"#, nodes.clone(), None);
+        check(r#"{"a": [1, null, []]}"#, nodes, Some(16));
+    }
+
+    #[test]
+    fn syntax_errors_are_unchanged() {
+        for json in ["[1,]", "[1 2]", "[", "", "[1] 2", "\"\\x\""] {
+            check(json, ParseOptions::default(), None);
+        }
+        // The limit is only checked once the token is known to be valid.
+        let depth = ParseOptions {
+            max_depth: Some(1),
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_with("[}", &depth),
+            Err(JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::VALUE.union(Expected::of(&[TokenKind::EndArray]))
+            })
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{Key, Map, Type};
+
+impl Type {
+    /// A total order over values: first by kind, `null` < booleans <
+    /// numbers < strings < arrays < objects, then by content. Numbers
+    /// compare as `f64::total_cmp`, strings bytewise, arrays element by
+    /// element, and objects as their members sorted by key.
+    pub fn total_cmp(&self, other: &Type) -> Ordering {
+        fn rank(value: &Type) -> u8 {
+            match value {
+                Type::Null => 0,
+                Type::Boolean(_) => 1,
+                Type::Number(_) => 2,
+                Type::String(_) => 3,
+                Type::Array(_) => 4,
+                Type::Object(_) => 5,
+            }
+        }
+        match (self, other) {
+            (Type::Boolean(a), Type::Boolean(b)) => a.cmp(b),
+            (Type::Number(a), Type::Number(b)) => a.total_cmp(b),
+            (Type::String(a), Type::String(b)) => a.cmp(b),
+            (Type::Array(a), Type::Array(b)) => a
+                .iter()
+                .zip(b.iter())
+                .map(|(a, b)| a.total_cmp(b))
+                .find(|order| order.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Type::Object(a), Type::Object(b)) => {
+                fn sorted(members: &Map<Key, Type>) -> Vec<(&Key, &Type)> {
+                    let mut members: Vec<_> = members.iter().collect();
+                    members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                    members
+                }
+                let (a, b) = (sorted(a), sorted(b));
+                a.iter()
+                    .zip(b.iter())
+                    .map(|((ka, va), (kb, vb))| ka.cmp(kb).then_with(|| va.total_cmp(vb)))
+                    .find(|order| order.is_ne())
+                    .unwrap_or_else(|| a.len().cmp(&b.len()))
+            }
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+
+    /// Sorts an array in place with `compare`, keeping equal elements in
+    /// order. Other values are left alone.
+    pub fn sort_by<F: FnMut(&Type, &Type) -> Ordering>(&mut self, compare: F) {
+        if let Type::Array(items) = self {
+            items.sort_by(compare);
+        }
+    }
+
+    /// Sorts an array in place by the value each element has at the JSON
+    /// Pointer `key`, e.g. `/age`, in [`Type::total_cmp`] order. Elements
+    /// without that value sort first.
+    pub fn sort_by_pointer(&mut self, key: &str) {
+        self.sort_by(|a, b| match (a.pointer(key), b.pointer(key)) {
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        });
+    }
+
+    /// Removes consecutive repeated elements from an array, like
+    /// `Vec::dedup`. Elements are equal when [`Type::total_cmp`] says so,
+    /// which unlike `==` makes a `NaN` equal to itself.
+    pub fn dedup(&mut self) {
+        if let Type::Array(items) = self {
+            items.dedup_by(|a, b| a.total_cmp(b).is_eq());
+        }
+    }
+
+    /// Like [`Type::dedup`], comparing the values the elements have at the
+    /// JSON Pointer `key`. Elements without that value are equal to each
+    /// other.
+    pub fn dedup_by_key(&mut self, key: &str) {
+        if let Type::Array(items) = self {
+            items.dedup_by(|a, b| match (a.pointer(key), b.pointer(key)) {
+                (Some(a), Some(b)) => a.total_cmp(b).is_eq(),
+                (a, b) => a.is_none() && b.is_none(),
+            });
+        }
+    }
+
+    /// Removes every element of an array equal to an earlier one, wherever
+    /// it is, keeping the order of the rest. Takes `O(n log n)` comparisons.
+    pub fn dedup_all(&mut self) {
+        let Type::Array(items) = self else {
+            return;
+        };
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by(|&a, &b| items[a].total_cmp(&items[b]));
+        let mut keep = vec![true; items.len()];
+        // The sort is stable, so the first of each run of equal elements is
+        // the earliest one.
+        for pair in order.windows(2) {
+            if items[pair[0]].total_cmp(&items[pair[1]]).is_eq() {
+                keep[pair[1]] = false;
+            }
+        }
+        let mut keep = keep.into_iter();
+        items.retain(|_| keep.next() == Some(true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cmp::Ordering;
+
+    use crate::{parse, Type};
+
+    #[test]
+    fn orders_values() {
+        let sorted = parse(
+            r#"[null, false, true, -1, 2.5, "", "a", "b", [], [1], [1, 0], [2], {}, {"a": 1}, {"a": 1, "b": 0}, {"b": 0}]"#,
+        )
+        .unwrap();
+        let Type::Array(items) = &sorted else {
+            panic!("expected an array");
+        };
+        for (i, a) in items.iter().enumerate() {
+            for (j, b) in items.iter().enumerate() {
+                assert_eq!(a.total_cmp(b), i.cmp(&j), "{:?} {:?}", a, b);
+            }
+        }
+        let mut reversed = Type::Array(items.iter().rev().cloned().collect());
+        reversed.sort_by(Type::total_cmp);
+        assert_eq!(reversed, sorted);
+        assert_eq!(
+            parse(r#"{"b": 1, "a": 2}"#)
+                .unwrap()
+                .total_cmp(&parse(r#"{"a": 2, "b": 1}"#).unwrap()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn sorts_by_pointer() {
+        let mut people = parse(
+            r#"[{"name": "a", "age": 40}, {"name": "b"}, {"name": "c", "age": 30}, {"name": "d", "age": 30}]"#,
+        )
+        .unwrap();
+        people.sort_by_pointer("/age");
+        assert_eq!(
+            people,
+            parse(
+                r#"[{"name": "b"}, {"name": "c", "age": 30}, {"name": "d", "age": 30}, {"name": "a", "age": 40}]"#
+            )
+            .unwrap()
+        );
+        people.sort_by(|a, b| {
+            b.pointer("/name")
+                .unwrap()
+                .total_cmp(a.pointer("/name").unwrap())
+        });
+        assert_eq!(people.pointer("/0/name"), Some(&Type::String("d".into())));
+    }
+
+    #[test]
+    fn dedups() {
+        let mut value = parse(r#"[1, 1, {"a": [2]}, {"a": [2]}, 1, "x", "x", 1]"#).unwrap();
+        let mut all = value.clone();
+        value.dedup();
+        assert_eq!(value, parse(r#"[1, {"a": [2]}, 1, "x", 1]"#).unwrap());
+        all.dedup_all();
+        assert_eq!(all, parse(r#"[1, {"a": [2]}, "x"]"#).unwrap());
+
+        let mut nan = Type::Array(vec![
+            Type::Number(f64::NAN.into()),
+            Type::Number(f64::NAN.into()),
+        ]);
+        nan.dedup();
+        assert!(matches!(&nan, Type::Array(items) if items.len() == 1));
+
+        let mut rows =
+            parse(r#"[{"id": 1, "v": 1}, {"id": 1, "v": 2}, {"v": 3}, {"v": 4}, {"id": 2}]"#)
+                .unwrap();
+        rows.dedup_by_key("/id");
+        assert_eq!(
+            rows,
+            parse(r#"[{"id": 1, "v": 1}, {"v": 3}, {"id": 2}]"#).unwrap()
+        );
+    }
+}
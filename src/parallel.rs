@@ -0,0 +1,212 @@
+use rayon::prelude::*;
+
+use crate::lexer::is_whitespace;
+use crate::{parse, parse_slice, JsonError, Type, MAX_DEPTH};
+
+/// Parses `json` like [`parse`], but if the root is an array its elements
+/// are parsed in parallel on the rayon thread pool. Meant for documents of
+/// many megabytes holding an array of records; anything else is parsed on
+/// the calling thread.
+///
+/// Errors are exactly those `parse` reports.
+pub fn parse_parallel(json: &str) -> Result<Type, JsonError> {
+    let Some(spans) = split_elements(json.as_bytes()) else {
+        return parse(json);
+    };
+    let per_task = spans
+        .len()
+        .div_ceil(rayon::current_num_threads() * 4)
+        .max(1);
+    let chunks: Vec<Result<Vec<Type>, JsonError>> = spans
+        .par_chunks(per_task)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .map(|&(start, end)| parse_slice(&json.as_bytes()[start..end]))
+                .collect()
+        })
+        .collect();
+    let mut items = Vec::with_capacity(spans.len());
+    for chunk in chunks {
+        match chunk {
+            Ok(values) => items.extend(values),
+            // The scan only finds boundaries; let the sequential parser find
+            // the first error and report it with its proper offset.
+            Err(_) => return parse(json),
+        }
+    }
+    Ok(Type::Array(items))
+}
+
+/// Parses newline-delimited JSON (JSON Lines) across the rayon thread
+/// pool, calling `f` with the 1-based line number and parse result of each
+/// line as soon as it is parsed, from whichever thread parsed it, in no
+/// particular order. Blank lines are skipped, as by
+/// [`NdjsonReader`](crate::NdjsonReader).
+pub fn par_lines<F>(input: &str, f: F)
+where
+    F: Fn(usize, Result<Type, JsonError>) + Sync + Send,
+{
+    records(input)
+        .par_iter()
+        .for_each(|&(line, text)| f(line, parse(text)));
+}
+
+/// Like [`par_lines`], but calls `f` on the calling thread in line order.
+/// Lines are parsed a batch at a time, so only one batch of values is held
+/// in memory while `f` catches up.
+pub fn par_lines_ordered<F>(input: &str, mut f: F)
+where
+    F: FnMut(usize, Result<Type, JsonError>),
+{
+    let batch = rayon::current_num_threads() * 256;
+    for records in records(input).chunks(batch) {
+        let results: Vec<_> = records
+            .par_iter()
+            .map(|&(line, text)| (line, parse(text)))
+            .collect();
+        for (line, result) in results {
+            f(line, result);
+        }
+    }
+}
+
+/// The non-blank lines of `input`, with their 1-based numbers.
+fn records(input: &str) -> Vec<(usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.bytes().all(is_whitespace))
+        .map(|(i, text)| (i + 1, text))
+        .collect()
+}
+
+/// Finds the byte range of each element of a top-level array by tracking
+/// strings and bracket depth, without validating anything. Returns `None` if
+/// the root is not an array, the structure does not add up or nests deeper
+/// than [`MAX_DEPTH`], leaving those cases to the sequential parser.
+fn split_elements(input: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let open = input.iter().position(|&b| !is_whitespace(b))?;
+    if input[open] != b'[' {
+        return None;
+    }
+    let mut spans = Vec::new();
+    let mut start = open + 1;
+    let mut depth = 1;
+    let mut pos = start;
+    while pos < input.len() {
+        match input[pos] {
+            b'"' => {
+                pos += 1;
+                while pos < input.len() && input[pos] != b'"' {
+                    pos += if input[pos] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'[' | b'{' => {
+                depth += 1;
+                // Each element would otherwise get the whole depth budget.
+                if depth > MAX_DEPTH {
+                    return None;
+                }
+            }
+            b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    if input[pos] != b']' || !input[pos + 1..].iter().all(|&b| is_whitespace(b)) {
+                        return None;
+                    }
+                    if !spans.is_empty() || !input[start..pos].iter().all(|&b| is_whitespace(b)) {
+                        spans.push((start, pos));
+                    }
+                    return Some(spans);
+                }
+            }
+            b',' if depth == 1 => {
+                spans.push((start, pos));
+                start = pos + 1;
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::{par_lines, par_lines_ordered, parse_parallel};
+    use crate::{parse, JsonError, MAX_DEPTH};
+
+    #[test]
+    fn matches_sequential_parse() {
+        let records: Vec<String> = (0..2000)
+            .map(|i| {
+                format!(
+                    r#"{{"id": {}, "tags": ["a,b", "]"], "nested": {{"x": [{}]}}}}"#,
+                    i, i
+                )
+            })
+            .collect();
+        let json = format!(" [ {} ] ", records.join(",\n"));
+        assert_eq!(parse_parallel(&json).unwrap(), parse(&json).unwrap());
+
+        for json in [
+            "[]",
+            " [ ] ",
+            "[1]",
+            "{\"a\": [1, 2]}",
+            "\"[1, 2]\"",
+            "[\"\\\"]\", 2]",
+            &format!(
+                "[1, {}{}]",
+                "[".repeat(MAX_DEPTH - 1),
+                "]".repeat(MAX_DEPTH - 1)
+            ),
+        ] {
+            assert_eq!(parse_parallel(json), parse(json), "{}", json);
+        }
+    }
+
+    #[test]
+    fn reports_the_first_error() {
+        for json in [
+            "[1, 2,]",
+            "[1, [2}, 3]",
+            "[1, 2] 3",
+            "[1, 2",
+            "[1 2]",
+            "[,]",
+            "[1}",
+            "[\"a]",
+            &format!("[1, {}{}]", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH)),
+        ] {
+            assert_eq!(parse_parallel(json), parse(json), "{}", json);
+            assert!(parse_parallel(json).is_err());
+        }
+    }
+
+    #[test]
+    fn parses_lines_in_parallel() {
+        let mut input: String = (0..1000).map(|i| format!("{{\"n\": {}}}\n", i)).collect();
+        input.push_str("\n[1,\r\n  \n");
+        let mut ordered = Vec::new();
+        par_lines_ordered(&input, |line, result| ordered.push((line, result)));
+        assert_eq!(ordered.len(), 1001);
+        for (i, (line, result)) in ordered.iter().take(1000).enumerate() {
+            assert_eq!(*line, i + 1);
+            assert_eq!(result, &parse(&format!("{{\"n\": {}}}", i)));
+        }
+        assert_eq!(ordered[1000].0, 1002);
+        assert_eq!(ordered[1000].1, Err(JsonError::UnexpectEof { offset: 3 }));
+
+        let unordered = Mutex::new(Vec::new());
+        par_lines(&input, |line, result| {
+            unordered.lock().unwrap().push((line, result))
+        });
+        let mut unordered = unordered.into_inner().unwrap();
+        unordered.sort_by_key(|(line, _)| *line);
+        assert_eq!(unordered, ordered);
+    }
+}
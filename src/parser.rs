@@ -0,0 +1,465 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::lexer::{check_string, decode_number, decode_string, Lexer, Token, TokenKind};
+use crate::{JsonError, Number};
+
+/// A structural step through a JSON document, in document order, as a
+/// [`TreeBuilder`](crate::TreeBuilder) consumes them and
+/// [`Type::events`](crate::Type::events) produces them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// An object member's key, followed by the events of its value.
+    Key(String),
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Frame {
+    Object,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Expect {
+    /// Any value.
+    Value,
+    /// A value or `]`, right after `[`.
+    ValueOrEnd,
+    /// A key or `}`, right after `{`.
+    KeyOrEnd,
+    /// A key, after `,` in an object.
+    Key,
+    Colon,
+    /// `,` or the end of the enclosing container.
+    CommaOrEnd,
+    /// The top-level value is complete.
+    Done,
+}
+
+impl Expect {
+    /// The tokens acceptable in this state, inside a container of kind `top`.
+    pub fn tokens(self, top: Option<&Frame>) -> Expected {
+        match self {
+            Expect::Value => Expected::VALUE,
+            Expect::ValueOrEnd => Expected::VALUE.union(Expected::of(&[TokenKind::EndArray])),
+            Expect::KeyOrEnd => Expected::of(&[TokenKind::String, TokenKind::EndObject]),
+            Expect::Key => Expected::of(&[TokenKind::String]),
+            Expect::Colon => Expected::of(&[TokenKind::Colon]),
+            Expect::CommaOrEnd => match top {
+                Some(Frame::Object) => Expected::of(&[TokenKind::Comma, TokenKind::EndObject]),
+                _ => Expected::of(&[TokenKind::Comma, TokenKind::EndArray]),
+            },
+            Expect::Done => Expected::NONE,
+        }
+    }
+}
+
+/// A set of [`TokenKind`]s. A syntax error carries the kinds that would have
+/// been accepted in place of the token it found, for completion and repair.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Expected(u16);
+
+const KINDS: [TokenKind; 11] = [
+    TokenKind::Colon,
+    TokenKind::Comma,
+    TokenKind::BeginObject,
+    TokenKind::EndObject,
+    TokenKind::BeginArray,
+    TokenKind::EndArray,
+    TokenKind::String,
+    TokenKind::Number,
+    TokenKind::True,
+    TokenKind::False,
+    TokenKind::Null,
+];
+
+impl Expected {
+    pub const NONE: Expected = Expected(0);
+
+    /// The tokens that can start a value.
+    pub const VALUE: Expected = Expected::of(&[
+        TokenKind::BeginObject,
+        TokenKind::BeginArray,
+        TokenKind::String,
+        TokenKind::Number,
+        TokenKind::True,
+        TokenKind::False,
+        TokenKind::Null,
+    ]);
+
+    pub const fn of(kinds: &[TokenKind]) -> Expected {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < kinds.len() {
+            bits |= 1 << kinds[i] as u16;
+            i += 1;
+        }
+        Expected(bits)
+    }
+
+    pub const fn union(self, other: Expected) -> Expected {
+        Expected(self.0 | other.0)
+    }
+
+    pub fn contains(self, kind: TokenKind) -> bool {
+        self.0 & (1 << kind as u16) != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = TokenKind> {
+        KINDS.into_iter().filter(move |&kind| self.contains(kind))
+    }
+}
+
+impl fmt::Debug for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Lists the kinds as in "a value or ']'", folding the value tokens into
+/// "a value" when all of them are present.
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = Vec::new();
+        let mut rest = *self;
+        if rest.0 & Expected::VALUE.0 == Expected::VALUE.0 {
+            names.push("a value");
+            rest.0 &= !Expected::VALUE.0;
+        }
+        names.extend(rest.iter().map(|kind| match kind {
+            TokenKind::BeginObject => "'{'",
+            TokenKind::EndObject => "'}'",
+            TokenKind::BeginArray => "'['",
+            TokenKind::EndArray => "']'",
+            TokenKind::Colon => "':'",
+            TokenKind::Comma => "','",
+            TokenKind::String => "a string",
+            TokenKind::Number => "a number",
+            TokenKind::True => "'true'",
+            TokenKind::False => "'false'",
+            TokenKind::Null => "'null'",
+        }));
+        match names.split_last() {
+            None => f.write_str("nothing"),
+            Some((last, [])) => f.write_str(last),
+            Some((last, init)) => write!(f, "{} or {}", init.join(", "), last),
+        }
+    }
+}
+
+/// How many arrays and objects a document may nest inside one another.
+/// Parsing itself keeps nesting on an explicit stack, but dropping, cloning,
+/// comparing and serializing a [`Type`](crate::Type) all recurse, so a
+/// deeper tree could overflow the call stack later; the JSON parsers refuse one
+/// with `JsonError::LimitExceeded`.
+pub const MAX_DEPTH: usize = 128;
+
+/// Checks that a token sequence forms a single JSON value. Nesting is tracked
+/// on an explicit stack, so deep documents cannot overflow the call stack,
+/// and is limited to [`MAX_DEPTH`].
+#[derive(Debug, Clone)]
+pub(crate) struct Grammar {
+    stack: Vec<Frame>,
+    expect: Expect,
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        Grammar {
+            stack: Vec::new(),
+            expect: Expect::Value,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.expect == Expect::Done
+    }
+
+    /// Whether the next string token is an object key rather than a value.
+    pub fn expects_key(&self) -> bool {
+        matches!(self.expect, Expect::KeyOrEnd | Expect::Key)
+    }
+
+    pub fn accept(&mut self, token: Token) -> Result<(), JsonError> {
+        if matches!(token.kind, TokenKind::BeginObject | TokenKind::BeginArray)
+            && self.stack.len() == MAX_DEPTH
+        {
+            return Err(JsonError::LimitExceeded {
+                offset: token.start,
+            });
+        }
+        match (self.expect, token.kind) {
+            (Expect::Value | Expect::ValueOrEnd, TokenKind::BeginObject) => {
+                self.stack.push(Frame::Object);
+                self.expect = Expect::KeyOrEnd;
+            }
+            (Expect::Value | Expect::ValueOrEnd, TokenKind::BeginArray) => {
+                self.stack.push(Frame::Array);
+                self.expect = Expect::ValueOrEnd;
+            }
+            (
+                Expect::Value | Expect::ValueOrEnd,
+                TokenKind::String
+                | TokenKind::Number
+                | TokenKind::True
+                | TokenKind::False
+                | TokenKind::Null,
+            ) => self.end_value(),
+            (Expect::KeyOrEnd | Expect::Key, TokenKind::String) => self.expect = Expect::Colon,
+            (Expect::Colon, TokenKind::Colon) => self.expect = Expect::Value,
+            (Expect::CommaOrEnd, TokenKind::Comma) => {
+                self.expect = match self.stack.last() {
+                    Some(Frame::Object) => Expect::Key,
+                    _ => Expect::Value,
+                }
+            }
+            (Expect::ValueOrEnd | Expect::CommaOrEnd, TokenKind::EndArray)
+                if self.stack.last() == Some(&Frame::Array) =>
+            {
+                self.stack.pop();
+                self.end_value();
+            }
+            (Expect::KeyOrEnd | Expect::CommaOrEnd, TokenKind::EndObject)
+                if self.stack.last() == Some(&Frame::Object) =>
+            {
+                self.stack.pop();
+                self.end_value();
+            }
+            _ => {
+                return Err(JsonError::UnexpectToken {
+                    offset: token.start,
+                    expected: self.expect.tokens(self.stack.last()),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn end_value(&mut self) {
+        self.expect = if self.stack.is_empty() {
+            Expect::Done
+        } else {
+            Expect::CommaOrEnd
+        };
+    }
+}
+
+/// Turns tokens from a [`Lexer`] into [`Event`]s for one top-level value.
+///
+/// The parser holds no reference to the input, so the same parser can be
+/// driven by successive lexers over a growing buffer.
+#[derive(Debug, Clone)]
+pub(crate) struct Parser {
+    grammar: Grammar,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Parser {
+            grammar: Grammar::new(),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.grammar.is_done()
+    }
+
+    /// Returns the next event, or `None` once the lexer has no more complete
+    /// tokens. Running out of complete input before the value is finished is
+    /// `JsonError::UnexpectEof`.
+    pub fn next_event(&mut self, lexer: &mut Lexer) -> Result<Option<Event>, JsonError> {
+        loop {
+            let Some(token) = lexer.next_token()? else {
+                if lexer.is_eof() && !self.is_done() {
+                    return Err(JsonError::UnexpectEof {
+                        offset: lexer.input().len(),
+                    });
+                }
+                return Ok(None);
+            };
+            let is_key = self.grammar.expects_key();
+            self.grammar.accept(token)?;
+            if let Some(event) = to_event(lexer.input(), token, is_key)? {
+                return Ok(Some(event));
+            }
+        }
+    }
+}
+
+/// Checks the next complete value in `lexer` without decoding it, returning
+/// the byte range it spans.
+pub(crate) fn skip_value(lexer: &mut Lexer) -> Result<(usize, usize), JsonError> {
+    let mut grammar = Grammar::new();
+    let mut start = None;
+    loop {
+        let token = lexer.expect_token()?;
+        grammar.accept(token)?;
+        let start = *start.get_or_insert(token.start);
+        if token.kind == TokenKind::String {
+            check_string(
+                &lexer.input()[token.start + 1..token.end - 1],
+                token.start + 1,
+            )?;
+        }
+        if grammar.is_done() {
+            return Ok((start, token.end));
+        }
+    }
+}
+
+/// Like [`skip_value`], but also rejects anything after the value.
+pub(crate) fn skip_document(lexer: &mut Lexer) -> Result<(usize, usize), JsonError> {
+    let span = skip_value(lexer)?;
+    match lexer.next_token()? {
+        Some(token) => Err(JsonError::UnexpectToken {
+            offset: token.start,
+            expected: Expected::NONE,
+        }),
+        None => Ok(span),
+    }
+}
+
+pub(crate) fn to_event(
+    input: &[u8],
+    token: Token,
+    is_key: bool,
+) -> Result<Option<Event>, JsonError> {
+    let event = match token.kind {
+        TokenKind::BeginObject => Event::StartObject,
+        TokenKind::EndObject => Event::EndObject,
+        TokenKind::BeginArray => Event::StartArray,
+        TokenKind::EndArray => Event::EndArray,
+        TokenKind::Colon | TokenKind::Comma => return Ok(None),
+        TokenKind::String => {
+            let value = decode_string(&input[token.start + 1..token.end - 1], token.start + 1)?;
+            if is_key {
+                Event::Key(value)
+            } else {
+                Event::String(value)
+            }
+        }
+        TokenKind::Number => {
+            Event::Number(decode_number(&input[token.start..token.end], token.start)?)
+        }
+        TokenKind::True => Event::Boolean(true),
+        TokenKind::False => Event::Boolean(false),
+        TokenKind::Null => Event::Null,
+    };
+    Ok(Some(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, Expected, Parser};
+    use crate::lexer::{Lexer, TokenKind};
+    use crate::JsonError;
+
+    fn events(input: &str) -> Result<Vec<Event>, JsonError> {
+        let mut lexer = Lexer::new(input.as_bytes());
+        let mut parser = Parser::new();
+        let mut events = vec![];
+        while let Some(event) = parser.next_event(&mut lexer)? {
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    #[test]
+    fn event_order() {
+        assert_eq!(
+            events(r#"{"a": [1, "x"], "b": {}}"#).unwrap(),
+            vec![
+                Event::StartObject,
+                Event::Key("a".to_string()),
+                Event::StartArray,
+                Event::Number(1.into()),
+                Event::String("x".to_string()),
+                Event::EndArray,
+                Event::Key("b".to_string()),
+                Event::StartObject,
+                Event::EndObject,
+                Event::EndObject,
+            ]
+        );
+    }
+
+    #[test]
+    fn grammar_errors() {
+        let comma_or = |end| Expected::of(&[TokenKind::Comma, end]);
+        for input in [
+            ("[1,]", 3, Expected::VALUE),
+            ("{\"a\":1,}", 7, Expected::of(&[TokenKind::String])),
+            ("[1 2]", 3, comma_or(TokenKind::EndArray)),
+            (
+                "{1:2}",
+                1,
+                Expected::of(&[TokenKind::String, TokenKind::EndObject]),
+            ),
+            ("{\"a\" 1}", 5, Expected::of(&[TokenKind::Colon])),
+            ("]", 0, Expected::VALUE),
+            (
+                "[}",
+                1,
+                Expected::VALUE.union(Expected::of(&[TokenKind::EndArray])),
+            ),
+            ("{\"a\":1]", 6, comma_or(TokenKind::EndObject)),
+            ("1 2", 2, Expected::NONE),
+        ] {
+            assert_eq!(
+                events(input.0).unwrap_err(),
+                JsonError::UnexpectToken {
+                    offset: input.1,
+                    expected: input.2
+                },
+                "{}",
+                input.0
+            );
+        }
+        for input in ["", "[", "{\"a\":", "[1,"] {
+            assert_eq!(
+                events(input).unwrap_err(),
+                JsonError::UnexpectEof {
+                    offset: input.len()
+                },
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn expected_sets() {
+        let set = Expected::of(&[TokenKind::EndArray, TokenKind::Comma]);
+        assert!(set.contains(TokenKind::Comma) && !set.contains(TokenKind::Colon));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [TokenKind::Comma, TokenKind::EndArray]
+        );
+        assert_eq!(set.to_string(), "',' or ']'");
+        assert_eq!(
+            Expected::VALUE
+                .union(Expected::of(&[TokenKind::EndArray]))
+                .to_string(),
+            "a value or ']'"
+        );
+        assert_eq!(
+            Expected::of(&[TokenKind::String, TokenKind::EndObject, TokenKind::Null]).to_string(),
+            "'}', a string or 'null'"
+        );
+        assert_eq!(Expected::NONE.to_string(), "nothing");
+        assert!(Expected::NONE.is_empty());
+    }
+}
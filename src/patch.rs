@@ -0,0 +1,440 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{diff, into_str, Change, DecodeError, FromJson, JsonPointer, Map, ToJson, Type};
+
+/// One operation of a JSON Patch (RFC 6902).
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+    Add {
+        path: JsonPointer,
+        value: Type,
+    },
+    Remove {
+        path: JsonPointer,
+    },
+    Replace {
+        path: JsonPointer,
+        value: Type,
+    },
+    Move {
+        from: JsonPointer,
+        path: JsonPointer,
+    },
+    Copy {
+        from: JsonPointer,
+        path: JsonPointer,
+    },
+    Test {
+        path: JsonPointer,
+        value: Type,
+    },
+}
+
+/// A JSON Patch (RFC 6902): operations applied in order by
+/// [`Type::apply_patch`]. Its JSON form is an array of operation objects,
+/// through [`ToJson`] and [`FromJson`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Patch {
+    pub operations: Vec<PatchOp>,
+}
+
+/// An error from [`Type::apply_patch`]: the operation at `index` could not
+/// be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    pub index: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "operation {}: {}", self.index, self.message)
+    }
+}
+
+impl core::error::Error for PatchError {}
+
+/// A patch that turns `old` into `new`: `add`, `remove` and `replace`
+/// operations for the changes [`diff`] finds, in the same order.
+pub fn diff_as_patch(old: &Type, new: &Type) -> Patch {
+    let operations = diff(old, new)
+        .into_iter()
+        .map(|change| match change {
+            Change::Added { pointer, value } => PatchOp::Add {
+                path: pointer,
+                value,
+            },
+            Change::Removed { pointer, .. } => PatchOp::Remove { path: pointer },
+            Change::Replaced { pointer, new, .. } => PatchOp::Replace {
+                path: pointer,
+                value: new,
+            },
+        })
+        .collect();
+    Patch { operations }
+}
+
+/// Like [`diff_as_patch`], but an object member that was removed and one
+/// with an equal value that was added elsewhere become a single `move`,
+/// which keeps patches for renamed keys small.
+pub fn diff_as_patch_with_moves(old: &Type, new: &Type) -> Patch {
+    let mut patch = diff_as_patch(old, new);
+    let in_object = |root: &Type, path: &JsonPointer| {
+        let tokens = path.tokens();
+        !tokens.is_empty()
+            && matches!(
+                root.at_tokens(&tokens[..tokens.len() - 1]),
+                Some(Type::Object(_))
+            )
+    };
+    let mut removed: Vec<Option<&JsonPointer>> = patch
+        .operations
+        .iter()
+        .map(|op| match op {
+            PatchOp::Remove { path } if in_object(old, path) => Some(path),
+            _ => None,
+        })
+        .collect();
+    let mut moves = Vec::new();
+    for (i, op) in patch.operations.iter().enumerate() {
+        let PatchOp::Add { path, value } = op else {
+            continue;
+        };
+        if !in_object(new, path) {
+            continue;
+        }
+        let source = removed
+            .iter_mut()
+            .enumerate()
+            .find(|(_, from)| from.is_some_and(|from| old.at_tokens(from.tokens()) == Some(value)));
+        if let Some((j, from)) = source {
+            moves.push((i, j, from.take().expect("matched a removal").clone()));
+        }
+    }
+    for (add, _, from) in &moves {
+        let PatchOp::Add { path, .. } = &patch.operations[*add] else {
+            unreachable!("only additions are matched")
+        };
+        patch.operations[*add] = PatchOp::Move {
+            from: from.clone(),
+            path: path.clone(),
+        };
+    }
+    let mut removals: Vec<usize> = moves.into_iter().map(|(_, remove, _)| remove).collect();
+    removals.sort_unstable();
+    for remove in removals.into_iter().rev() {
+        patch.operations.remove(remove);
+    }
+    patch
+}
+
+impl Patch {
+    /// Whether the patch would apply to `target`: the error
+    /// [`Type::apply_patch`] would return, if any, without changing
+    /// `target`, so that a bad patch can be rejected before anything is
+    /// touched. Later operations are checked against the result of the
+    /// earlier ones, on a copy of `target`.
+    pub fn check(&self, target: &Type) -> Result<(), PatchError> {
+        let mut scratch = target.clone();
+        for (index, op) in self.operations.iter().enumerate() {
+            apply(&mut scratch, op).map_err(|message| PatchError { index, message })?;
+        }
+        Ok(())
+    }
+}
+
+impl Type {
+    /// Applies `patch` as RFC 6902 describes. The patch is atomic: if any
+    /// operation fails, including a failed `test`, the value is left as it
+    /// was.
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<(), PatchError> {
+        let mut patched = self.clone();
+        for (index, op) in patch.operations.iter().enumerate() {
+            apply(&mut patched, op).map_err(|message| PatchError { index, message })?;
+        }
+        *self = patched;
+        Ok(())
+    }
+}
+
+fn apply(root: &mut Type, op: &PatchOp) -> Result<(), &'static str> {
+    match op {
+        PatchOp::Add { path, value } => add(root, path, value.clone()),
+        PatchOp::Remove { path } => remove(root, path).map(drop),
+        PatchOp::Replace { path, value } => {
+            *root
+                .tokens_mut(path.tokens())
+                .ok_or("path does not exist")? = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            if path.tokens().starts_with(from.tokens()) && path != from {
+                return Err("cannot move a value into itself");
+            }
+            let value = remove(root, from)?;
+            add(root, path, value)
+        }
+        PatchOp::Copy { from, path } => {
+            let value = root.at_tokens(from.tokens()).ok_or("from does not exist")?;
+            add(root, path, value.clone())
+        }
+        PatchOp::Test { path, value } => match root.at_tokens(path.tokens()) {
+            Some(found) if found == value => Ok(()),
+            _ => Err("test failed"),
+        },
+    }
+}
+
+fn add(root: &mut Type, path: &JsonPointer, value: Type) -> Result<(), &'static str> {
+    let Some((last, parent)) = path.tokens().split_last() else {
+        *root = value;
+        return Ok(());
+    };
+    match root.tokens_mut(parent) {
+        Some(Type::Object(members)) => {
+            members.insert(last.as_str().into(), value);
+            Ok(())
+        }
+        Some(Type::Array(items)) => {
+            let index = match last.as_str() {
+                "-" => items.len(),
+                token => crate::pointer::parse_index(token)
+                    .filter(|&index| index <= items.len())
+                    .ok_or("index out of bounds")?,
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err("path does not exist"),
+    }
+}
+
+fn remove(root: &mut Type, path: &JsonPointer) -> Result<Type, &'static str> {
+    if path.is_root() {
+        return Err("cannot remove the root");
+    }
+    root.remove_tokens(path.tokens())
+        .ok_or("path does not exist")
+}
+
+impl ToJson for PatchOp {
+    fn to_json(&self) -> Type {
+        let (op, path, from, value) = match self {
+            PatchOp::Add { path, value } => ("add", path, None, Some(value)),
+            PatchOp::Remove { path } => ("remove", path, None, None),
+            PatchOp::Replace { path, value } => ("replace", path, None, Some(value)),
+            PatchOp::Move { from, path } => ("move", path, Some(from), None),
+            PatchOp::Copy { from, path } => ("copy", path, Some(from), None),
+            PatchOp::Test { path, value } => ("test", path, None, Some(value)),
+        };
+        let pointer = |pointer: &JsonPointer| Type::String(into_str(pointer.to_string()));
+        let mut members = Map::default();
+        members.insert("op".into(), Type::String(op.into()));
+        if let Some(from) = from {
+            members.insert("from".into(), pointer(from));
+        }
+        members.insert("path".into(), pointer(path));
+        if let Some(value) = value {
+            members.insert("value".into(), value.clone());
+        }
+        Type::Object(members.into())
+    }
+}
+
+impl FromJson for PatchOp {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        let Type::Object(members) = value else {
+            return Err(DecodeError::new("expected an object"));
+        };
+        let pointer = |name: &'static str| -> Result<JsonPointer, DecodeError> {
+            match members.get(name) {
+                Some(Type::String(text)) => text
+                    .parse()
+                    .map_err(|_| DecodeError::new("expected a JSON Pointer").within(name)),
+                Some(_) => Err(DecodeError::new("expected a string").within(name)),
+                None => Err(DecodeError::new(alloc::format!("missing field `{}`", name))),
+            }
+        };
+        let value = || {
+            members
+                .get("value")
+                .cloned()
+                .ok_or_else(|| DecodeError::new("missing field `value`"))
+        };
+        let path = pointer("path")?;
+        match members.get("op") {
+            Some(Type::String(op)) => match op.as_str() {
+                "add" => Ok(PatchOp::Add {
+                    path,
+                    value: value()?,
+                }),
+                "remove" => Ok(PatchOp::Remove { path }),
+                "replace" => Ok(PatchOp::Replace {
+                    path,
+                    value: value()?,
+                }),
+                "move" => Ok(PatchOp::Move {
+                    from: pointer("from")?,
+                    path,
+                }),
+                "copy" => Ok(PatchOp::Copy {
+                    from: pointer("from")?,
+                    path,
+                }),
+                "test" => Ok(PatchOp::Test {
+                    path,
+                    value: value()?,
+                }),
+                _ => Err(DecodeError::new("unknown operation").within("op")),
+            },
+            Some(_) => Err(DecodeError::new("expected a string").within("op")),
+            None => Err(DecodeError::new("missing field `op`")),
+        }
+    }
+}
+
+impl ToJson for Patch {
+    fn to_json(&self) -> Type {
+        self.operations.to_json()
+    }
+}
+
+impl FromJson for Patch {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        Vec::from_json(value).map(|operations| Patch { operations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_as_patch, diff_as_patch_with_moves, Patch, PatchError};
+    use crate::{parse, to_string_with, FromJson, SerializeOptions, ToJson, Type};
+
+    fn patch(json: &str) -> Patch {
+        Patch::from_json(&parse(json).unwrap()).unwrap()
+    }
+
+    fn sorted(patch: &Patch) -> String {
+        let options = SerializeOptions {
+            sort_keys: true,
+            ..SerializeOptions::default()
+        };
+        to_string_with(&patch.to_json(), &options)
+    }
+
+    #[test]
+    fn applies_operations() {
+        let mut value = parse(r#"{"a": [1, 2], "b": {"c": true}}"#).unwrap();
+        value
+            .apply_patch(&patch(
+                r#"[
+                    {"op": "add", "path": "/a/1", "value": 9},
+                    {"op": "add", "path": "/a/-", "value": 3},
+                    {"op": "remove", "path": "/a/0"},
+                    {"op": "replace", "path": "/b/c", "value": false},
+                    {"op": "copy", "from": "/b", "path": "/d"},
+                    {"op": "move", "from": "/d/c", "path": "/e"},
+                    {"op": "test", "path": "/a", "value": [9, 2, 3]}
+                ]"#,
+            ))
+            .unwrap();
+        assert_eq!(
+            value,
+            parse(r#"{"a": [9, 2, 3], "b": {"c": false}, "d": {}, "e": false}"#).unwrap()
+        );
+        let before = value.clone();
+        let failing = patch(
+            r#"[{"op": "remove", "path": "/a"}, {"op": "test", "path": "/e", "value": true}]"#,
+        );
+        assert_eq!(
+            value.apply_patch(&failing).unwrap_err(),
+            PatchError {
+                index: 1,
+                message: "test failed"
+            }
+        );
+        assert_eq!(value, before);
+        assert_eq!(failing.check(&value), value.clone().apply_patch(&failing));
+        let fixed = patch(
+            r#"[{"op": "remove", "path": "/e"}, {"op": "add", "path": "/e", "value": true}, {"op": "test", "path": "/e", "value": true}]"#,
+        );
+        assert_eq!(fixed.check(&value), Ok(()));
+        assert_eq!(value, before);
+        for (op, message) in [
+            (r#"{"op": "remove", "path": ""}"#, "cannot remove the root"),
+            (
+                r#"{"op": "add", "path": "/a/9", "value": 0}"#,
+                "index out of bounds",
+            ),
+            (
+                r#"{"op": "replace", "path": "/x", "value": 0}"#,
+                "path does not exist",
+            ),
+            (
+                r#"{"op": "move", "from": "/b", "path": "/b/c"}"#,
+                "cannot move a value into itself",
+            ),
+        ] {
+            let op = patch(&format!("[{}]", op));
+            assert_eq!(op.check(&value).unwrap_err().message, message);
+            let err = value.apply_patch(&op).unwrap_err();
+            assert_eq!(err.message, message);
+        }
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let json =
+            r#"[{"op":"add","path":"/a~1b","value":[1]},{"from":"/x","op":"move","path":"/y"}]"#;
+        assert_eq!(sorted(&patch(json)), json);
+        let err = Patch::from_json(&parse(r#"[{"op": "jump", "path": ""}]"#).unwrap());
+        assert_eq!(err.unwrap_err().to_string(), "'/0/op': unknown operation");
+        let err = Patch::from_json(&parse(r#"[{"op": "add", "path": "a"}]"#).unwrap());
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "'/0/path': expected a JSON Pointer"
+        );
+    }
+
+    #[test]
+    fn diffs_into_patches() {
+        let old =
+            parse(r#"{"name": "Ada", "tags": ["a", "b", "c"], "old": {"k": [1, 2]}}"#).unwrap();
+        let new =
+            parse(r#"{"name": "Grace", "tags": ["a"], "new": {"k": [1, 2]}, "n": null}"#).unwrap();
+        let plain = diff_as_patch(&old, &new);
+        assert_eq!(
+            sorted(&plain),
+            concat!(
+                r#"[{"op":"add","path":"/n","value":null},"#,
+                r#"{"op":"replace","path":"/name","value":"Grace"},"#,
+                r#"{"op":"add","path":"/new","value":{"k":[1,2]}},"#,
+                r#"{"op":"remove","path":"/old"},"#,
+                r#"{"op":"remove","path":"/tags/2"},"#,
+                r#"{"op":"remove","path":"/tags/1"}]"#
+            )
+        );
+        let moved = diff_as_patch_with_moves(&old, &new);
+        assert_eq!(
+            sorted(&moved),
+            concat!(
+                r#"[{"op":"add","path":"/n","value":null},"#,
+                r#"{"op":"replace","path":"/name","value":"Grace"},"#,
+                r#"{"from":"/old","op":"move","path":"/new"},"#,
+                r#"{"op":"remove","path":"/tags/2"},"#,
+                r#"{"op":"remove","path":"/tags/1"}]"#
+            )
+        );
+        for patch in [plain, moved] {
+            let mut value = old.clone();
+            value.apply_patch(&patch).unwrap();
+            assert_eq!(value, new);
+        }
+        assert_eq!(diff_as_patch(&new, &new), Patch::default());
+        let mut root = Type::Null;
+        root.apply_patch(&diff_as_patch(&Type::Null, &new)).unwrap();
+        assert_eq!(root, new);
+    }
+}
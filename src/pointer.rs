@@ -0,0 +1,448 @@
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{JsonError, Type, MAX_DEPTH};
+
+/// How many `null`s [`Type::set_at`] pads an array with at most.
+const MAX_PADDING: usize = 1024;
+
+/// A parsed JSON Pointer (RFC 6901), e.g. `/items/0/name`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonPointer {
+    tokens: Vec<String>,
+}
+
+impl JsonPointer {
+    /// The pointer to the whole document.
+    pub fn root() -> Self {
+        JsonPointer::default()
+    }
+
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn push(&mut self, token: impl Into<String>) {
+        self.tokens.push(token.into());
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.tokens.pop()
+    }
+}
+
+impl FromStr for JsonPointer {
+    type Err = JsonError;
+
+    fn from_str(pointer: &str) -> Result<JsonPointer, JsonError> {
+        if pointer.is_empty() {
+            return Ok(JsonPointer::root());
+        }
+        let Some(rest) = pointer.strip_prefix('/') else {
+            return Err(JsonError::InvalidPath);
+        };
+        let tokens = rest.split('/').map(unescape).collect::<Result<_, _>>()?;
+        Ok(JsonPointer { tokens })
+    }
+}
+
+fn unescape(token: &str) -> Result<String, JsonError> {
+    let mut out = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '~' {
+            match chars.next() {
+                Some('0') => out.push('~'),
+                Some('1') => out.push('/'),
+                _ => return Err(JsonError::InvalidPath),
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    Ok(out)
+}
+
+impl fmt::Display for JsonPointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+/// A Relative JSON Pointer, e.g. `1/name` or `0#`: how many levels to go up
+/// from a location, an optional shift of the array index reached, and then
+/// a JSON Pointer below it, or `#` for the key or index it is at.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RelativePointer {
+    up: usize,
+    shift: Option<isize>,
+    /// `None` for `#`.
+    pointer: Option<JsonPointer>,
+}
+
+impl RelativePointer {
+    pub fn up(&self) -> usize {
+        self.up
+    }
+
+    /// The `+1` of `0+1/name`.
+    pub fn index_shift(&self) -> Option<isize> {
+        self.shift
+    }
+
+    /// The JSON Pointer after the prefix; `None` for `#`.
+    pub fn pointer(&self) -> Option<&JsonPointer> {
+        self.pointer.as_ref()
+    }
+
+    /// The absolute pointer this one leads to from `location`, or, for
+    /// `#`, to the value whose key or index it asks for. `None` if it goes
+    /// up past the root or shifts what is not an index, or below zero.
+    pub fn to_absolute(&self, location: &JsonPointer) -> Option<JsonPointer> {
+        let mut tokens = self.base(location)?;
+        if let Some(pointer) = &self.pointer {
+            tokens.extend(pointer.tokens.iter().cloned());
+        }
+        Some(JsonPointer { tokens })
+    }
+
+    /// The tokens of `location` once gone up and shifted.
+    fn base(&self, location: &JsonPointer) -> Option<Vec<String>> {
+        let depth = location.tokens.len().checked_sub(self.up)?;
+        let mut tokens = location.tokens[..depth].to_vec();
+        if let Some(shift) = self.shift {
+            let last = tokens.last_mut()?;
+            let index = parse_index(last)?.checked_add_signed(shift)?;
+            *last = index.to_string();
+        }
+        Some(tokens)
+    }
+}
+
+impl FromStr for RelativePointer {
+    type Err = JsonError;
+
+    fn from_str(pointer: &str) -> Result<RelativePointer, JsonError> {
+        fn integer(s: &str) -> Option<(usize, &str)> {
+            let len = s.bytes().take_while(u8::is_ascii_digit).count();
+            parse_index(&s[..len]).map(|n| (n, &s[len..]))
+        }
+        let (up, mut rest) = integer(pointer).ok_or(JsonError::InvalidPath)?;
+        let mut shift = None;
+        if let Some(sign @ ('+' | '-')) = rest.chars().next() {
+            let (n, after) = integer(&rest[1..]).ok_or(JsonError::InvalidPath)?;
+            let n = isize::try_from(n).map_err(|_| JsonError::InvalidPath)?;
+            shift = Some(if sign == '-' { -n } else { n });
+            rest = after;
+        }
+        let pointer = match rest {
+            "#" => None,
+            rest => Some(rest.parse()?),
+        };
+        Ok(RelativePointer { up, shift, pointer })
+    }
+}
+
+impl fmt::Display for RelativePointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.up)?;
+        if let Some(shift) = self.shift {
+            write!(f, "{:+}", shift)?;
+        }
+        match &self.pointer {
+            Some(pointer) => write!(f, "{}", pointer),
+            None => f.write_str("#"),
+        }
+    }
+}
+
+/// Parses an array index token: digits only, without leading zeros.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    if !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl Type {
+    /// Looks up a value by JSON Pointer; `None` if the pointer is malformed or
+    /// addresses nothing.
+    pub fn pointer(&self, pointer: &str) -> Option<&Type> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        self.at_tokens(&pointer.tokens)
+    }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Type> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        self.tokens_mut(&pointer.tokens)
+    }
+
+    /// Evaluates the Relative JSON Pointer `relative` from the value at the
+    /// JSON Pointer `location`. `#` gives the key, as a string, or the
+    /// index, as a number, of the value it reaches; anything else gives the
+    /// value it addresses.
+    ///
+    /// `None` if either pointer is malformed or addresses nothing, or if
+    /// `#` reaches the root. An index shift must land inside the array.
+    pub fn relative_pointer(&self, location: &str, relative: &str) -> Option<Cow<'_, Type>> {
+        let location: JsonPointer = location.parse().ok()?;
+        let relative: RelativePointer = relative.parse().ok()?;
+        self.at_tokens(&location.tokens)?;
+        let base = relative.base(&location)?;
+        let value = self.at_tokens(&base)?;
+        let (name, parent) = match base.split_last() {
+            Some((name, parent)) => (Some(name), self.at_tokens(parent)),
+            None => (None, None),
+        };
+        if relative.shift.is_some() && !matches!(parent, Some(Type::Array(_))) {
+            return None;
+        }
+        match &relative.pointer {
+            Some(pointer) => value.at_tokens(&pointer.tokens).map(Cow::Borrowed),
+            None => match parent? {
+                Type::Array(_) => Some(Cow::Owned(Type::Number(parse_index(name?)?.into()))),
+                _ => Some(Cow::Owned(Type::String(name?.as_str().into()))),
+            },
+        }
+    }
+
+    pub(crate) fn at_tokens(&self, tokens: &[String]) -> Option<&Type> {
+        tokens.iter().try_fold(self, |value, token| match value {
+            Type::Object(members) => members.get(token.as_str()),
+            Type::Array(items) => items.get(parse_index(token)?),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn tokens_mut(&mut self, tokens: &[String]) -> Option<&mut Type> {
+        tokens.iter().try_fold(self, |value, token| match value {
+            Type::Object(members) => members.get_mut(token.as_str()),
+            Type::Array(items) => items.get_mut(parse_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Removes the value at the JSON Pointer `pointer` and returns it, taking
+    /// the key out of its object or shifting the rest of its array down.
+    /// `None` if the pointer is malformed, addresses nothing, or is the root.
+    pub fn remove_pointer(&mut self, pointer: &str) -> Option<Type> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        self.remove_tokens(&pointer.tokens)
+    }
+
+    pub(crate) fn remove_tokens(&mut self, tokens: &[String]) -> Option<Type> {
+        let (last, path) = tokens.split_last()?;
+        match self.tokens_mut(path)? {
+            #[cfg(feature = "indexmap")]
+            Type::Object(members) => members.shift_remove(last.as_str()),
+            #[cfg(not(feature = "indexmap"))]
+            Type::Object(members) => members.remove(last.as_str()),
+            Type::Array(items) => {
+                let index = parse_index(last).filter(|&index| index < items.len())?;
+                Some(items.remove(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Stores `value` at the JSON Pointer `pointer`, creating what is missing
+    /// on the way: a missing or `null` value becomes an array if the token
+    /// into it is an index or `-`, and an object otherwise, and arrays are
+    /// padded with `null` up to an index past their end. `-` appends.
+    ///
+    /// `JsonError::InvalidPath` if the pointer is malformed, has more than
+    /// [`MAX_DEPTH`] tokens, would descend into a scalar or index an array
+    /// with a key, or would pad an array with more than 1024 `null`s.
+    pub fn set_at(&mut self, pointer: &str, value: Type) -> Result<(), JsonError> {
+        let pointer: JsonPointer = pointer.parse()?;
+        *self.vivify_tokens(&pointer.tokens)? = value;
+        Ok(())
+    }
+
+    /// The value at `tokens`, creating what is missing as [`Type::set_at`]
+    /// does; a created target is `null`.
+    pub(crate) fn vivify_tokens(&mut self, tokens: &[String]) -> Result<&mut Type, JsonError> {
+        if tokens.len() > MAX_DEPTH {
+            return Err(JsonError::InvalidPath);
+        }
+        let mut target = self;
+        for token in tokens {
+            if *target == Type::Null {
+                *target = if token == "-" || parse_index(token).is_some() {
+                    Type::Array(Vec::new())
+                } else {
+                    Type::Object(Box::default())
+                };
+            }
+            target = match target {
+                Type::Object(members) => members.entry(token.as_str().into()).or_insert(Type::Null),
+                Type::Array(items) => {
+                    let index = match token.as_str() {
+                        "-" => items.len(),
+                        token => parse_index(token).ok_or(JsonError::InvalidPath)?,
+                    };
+                    if index >= items.len() {
+                        if index - items.len() > MAX_PADDING {
+                            return Err(JsonError::InvalidPath);
+                        }
+                        items.resize(index + 1, Type::Null);
+                    }
+                    &mut items[index]
+                }
+                _ => return Err(JsonError::InvalidPath),
+            };
+        }
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonPointer, RelativePointer};
+    use crate::{parse, JsonError, Type, MAX_DEPTH};
+
+    #[test]
+    fn parse_and_display() {
+        let pointer: JsonPointer = "/a~1b/~0/0".parse().unwrap();
+        assert_eq!(pointer.tokens(), ["a/b", "~", "0"]);
+        assert_eq!(pointer.to_string(), "/a~1b/~0/0");
+        assert!("".parse::<JsonPointer>().unwrap().is_root());
+        assert_eq!("a".parse::<JsonPointer>(), Err(JsonError::InvalidPath));
+        assert_eq!("/~2".parse::<JsonPointer>(), Err(JsonError::InvalidPath));
+    }
+
+    #[test]
+    fn lookup() {
+        let mut value = parse(r#"{"items": [{"name": "x"}, 2], "": 0}"#).unwrap();
+        assert_eq!(
+            value.pointer("/items/0/name"),
+            Some(&Type::String("x".into()))
+        );
+        assert_eq!(value.pointer("/"), Some(&Type::Number(0.into())));
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/items/01"), None);
+        assert_eq!(value.pointer("/items/2"), None);
+
+        *value.pointer_mut("/items/1").unwrap() = Type::Null;
+        assert_eq!(value.pointer("/items/1"), Some(&Type::Null));
+    }
+
+    #[test]
+    fn remove_pointer() {
+        let mut value = parse(r#"{"users": [1, {"a/b": 2}, 3], "x": null}"#).unwrap();
+        assert_eq!(
+            value.remove_pointer("/users/1/a~1b"),
+            Some(Type::Number(2.into()))
+        );
+        assert_eq!(
+            value.remove_pointer("/users/0"),
+            Some(Type::Number(1.into()))
+        );
+        assert_eq!(value.remove_pointer("/x"), Some(Type::Null));
+        assert_eq!(value, parse(r#"{"users": [{}, 3]}"#).unwrap());
+        assert_eq!(value.remove_pointer("/users/2"), None);
+        assert_eq!(value.remove_pointer("/users/1/a"), None);
+        assert_eq!(value.remove_pointer("/missing/a"), None);
+        assert_eq!(value.remove_pointer(""), None);
+    }
+
+    #[test]
+    fn set_at_creates_the_path() {
+        let mut value = Type::Null;
+        value.set_at("/a/b/2/c", Type::Boolean(true)).unwrap();
+        assert_eq!(
+            value,
+            parse(r#"{"a": {"b": [null, null, {"c": true}]}}"#).unwrap()
+        );
+        value.set_at("/a/b/-", Type::Number(1.into())).unwrap();
+        value.set_at("/a/b/0/x", Type::Number(2.into())).unwrap();
+        value.set_at("/a/d", Type::Null).unwrap();
+        assert_eq!(
+            value,
+            parse(r#"{"a": {"b": [{"x": 2}, null, {"c": true}, 1], "d": null}}"#).unwrap()
+        );
+        assert_eq!(
+            value.set_at("/a/b/3/y", Type::Null),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(
+            value.set_at("/a/b/key", Type::Null),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(value.set_at("a", Type::Null), Err(JsonError::InvalidPath));
+        for pointer in [
+            "/a/b/18446744073709551615",
+            "/a/b/4000000000",
+            "/a/b/1029",
+            &"/a".repeat(200_000),
+            &"/a".repeat(MAX_DEPTH + 1),
+        ] {
+            assert_eq!(
+                value.set_at(pointer, Type::Null),
+                Err(JsonError::InvalidPath)
+            );
+        }
+        value.set_at("/a/b/1028", Type::Null).unwrap();
+        value.set_at(&"/a".repeat(MAX_DEPTH), Type::Null).unwrap();
+        value.set_at("", Type::Number(3.into())).unwrap();
+        assert_eq!(value, Type::Number(3.into()));
+    }
+
+    #[test]
+    fn relative_pointers() {
+        for text in ["0", "1/a~1b", "0#", "2-1/x", "0+3#", "10"] {
+            let pointer: RelativePointer = text.parse().unwrap();
+            assert_eq!(pointer.to_string(), text);
+        }
+        for text in ["", "01", "#", "1a", "-1", "0+", "0+01", "0#/a", "a/b"] {
+            assert!(text.parse::<RelativePointer>().is_err(), "{:?}", text);
+        }
+        let pointer: RelativePointer = "1+1/name".parse().unwrap();
+        assert_eq!((pointer.up(), pointer.index_shift()), (1, Some(1)));
+        let location: JsonPointer = "/items/0/id".parse().unwrap();
+        assert_eq!(
+            pointer.to_absolute(&location).unwrap().to_string(),
+            "/items/1/name"
+        );
+        assert_eq!(
+            "4".parse::<RelativePointer>()
+                .unwrap()
+                .to_absolute(&location),
+            None
+        );
+
+        let value = parse(r#"{"items": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}], "n": 0}"#)
+            .unwrap();
+        let eval = |relative| {
+            value
+                .relative_pointer("/items/1/id", relative)
+                .map(|value| value.into_owned())
+        };
+        assert_eq!(eval("0"), parse("2").ok());
+        assert_eq!(eval("1/name"), parse(r#""b""#).ok());
+        assert_eq!(eval("0#"), parse(r#""id""#).ok());
+        assert_eq!(eval("1#"), parse("1").ok());
+        assert_eq!(eval("1-1/name"), parse(r#""a""#).ok());
+        assert_eq!(eval("1-1#"), parse("0").ok());
+        assert_eq!(eval("3/n"), parse("0").ok());
+        assert_eq!(eval("1+1/name"), None);
+        assert_eq!(eval("0+1/name"), None);
+        assert_eq!(eval("3#"), None);
+        assert_eq!(eval("4"), None);
+        assert_eq!(value.relative_pointer("/missing", "0"), None);
+    }
+}
@@ -0,0 +1,197 @@
+use alloc::vec::Vec;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{Lexer, BOM};
+use crate::parser::Parser;
+use crate::{JsonError, Type};
+
+/// Parses one JSON value from input that arrives in chunks.
+///
+/// Chunk boundaries may fall anywhere, including inside a token or a UTF-8
+/// sequence. Complete tokens are consumed as soon as they arrive; only a
+/// token split by a boundary is kept around until the rest of it is fed.
+/// Syntax errors are reported by the `feed` call that reveals them. A UTF-8
+/// byte-order mark at the start of the input is skipped.
+pub struct PushParser {
+    buf: Vec<u8>,
+    /// Offset of `buf[0]` in the whole input.
+    base: usize,
+    /// How far the string at the start of `buf`, cut off by the end of the
+    /// input so far, has been scanned; 0 if `buf` holds no such string.
+    scanned: usize,
+    parser: Parser,
+    builder: TreeBuilder,
+    value: Option<Type>,
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        PushParser::new()
+    }
+}
+
+impl PushParser {
+    pub fn new() -> Self {
+        PushParser {
+            buf: Vec::new(),
+            base: 0,
+            scanned: 0,
+            parser: Parser::new(),
+            builder: TreeBuilder::new(),
+            value: None,
+        }
+    }
+
+    /// Whether a complete value has been parsed. More input may still follow,
+    /// but only whitespace is accepted after it.
+    pub fn is_complete(&self) -> bool {
+        self.value.is_some()
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<(), JsonError> {
+        self.buf.extend_from_slice(chunk);
+        // Wait for a whole byte-order mark before deciding to skip one.
+        if self.base == 0 && self.buf.len() < BOM.len() && BOM.starts_with(&self.buf) {
+            return Ok(());
+        }
+        let mut lexer = self.lexer(Lexer::partial(&self.buf));
+        let value = drain(&mut self.parser, &mut self.builder, &mut lexer)
+            .map_err(|err| err.shifted(self.base))?;
+        self.value = value.or(self.value.take());
+        let consumed = lexer.pos();
+        // A string cut off at the end starts at `consumed`.
+        self.scanned = lexer.string_progress().saturating_sub(consumed);
+        self.buf.drain(..consumed);
+        self.base += consumed;
+        Ok(())
+    }
+
+    /// Signals the end of input and returns the parsed value.
+    pub fn finish(mut self) -> Result<Type, JsonError> {
+        let mut lexer = self.lexer(Lexer::new(&self.buf));
+        let end = self.base + self.buf.len();
+        drain(&mut self.parser, &mut self.builder, &mut lexer)
+            .map_err(|err| err.shifted(self.base))?
+            .or(self.value)
+            .ok_or(JsonError::UnexpectEof { offset: end })
+    }
+
+    /// Sets `lexer` of `buf` up to continue where the last one stopped.
+    fn lexer<'a>(&self, lexer: Lexer<'a>) -> Lexer<'a> {
+        let lexer = if self.base == 0 {
+            lexer.skip_bom()
+        } else {
+            lexer
+        };
+        lexer.resume_string(self.scanned)
+    }
+}
+
+/// Feeds every event the lexer can produce into the builder, returning the
+/// root value if it was completed along the way.
+fn drain(
+    parser: &mut Parser,
+    builder: &mut TreeBuilder,
+    lexer: &mut Lexer,
+) -> Result<Option<Type>, JsonError> {
+    let value = builder.build(parser, lexer)?;
+    // Anything but whitespace after the root value is an error.
+    parser.next_event(lexer)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PushParser;
+    use crate::{parse, Expected, JsonError};
+
+    #[test]
+    fn every_split_point() {
+        let input = r#" {"name": "json-rs", "tags": ["a\"b", "é"], "n": -12.5e-1, "ok": true} "#;
+        let expected = parse(input).unwrap();
+        for split in 0..=input.len() {
+            let mut parser = PushParser::new();
+            parser.feed(&input.as_bytes()[..split]).unwrap();
+            parser.feed(&input.as_bytes()[split..]).unwrap();
+            assert_eq!(parser.finish().unwrap(), expected, "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let mut parser = PushParser::new();
+        for byte in b"[1, [2, 3], null]" {
+            assert!(!parser.is_complete());
+            parser.feed(&[*byte]).unwrap();
+        }
+        assert!(parser.is_complete());
+        assert_eq!(
+            parser.finish().unwrap(),
+            parse("[1, [2, 3], null]").unwrap()
+        );
+    }
+
+    #[test]
+    fn skips_a_byte_order_mark() {
+        let input = "\u{feff}[\"\u{feff}\"]";
+        for split in 0..=input.len() {
+            let mut parser = PushParser::new();
+            parser.feed(&input.as_bytes()[..split]).unwrap();
+            parser.feed(&input.as_bytes()[split..]).unwrap();
+            assert_eq!(
+                parser.finish().unwrap(),
+                parse("[\"\u{feff}\"]").unwrap(),
+                "split at {}",
+                split
+            );
+        }
+        let mut parser = PushParser::new();
+        parser.feed(b"\xef\xbb").unwrap();
+        assert_eq!(
+            parser.finish().unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 0,
+                expected: Expected::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn long_strings_in_small_chunks() {
+        let text = "ab\\\"\\u00e9".repeat(50_000);
+        let input = format!("[\"{}\", \"{}\"]", text, text);
+        let mut parser = PushParser::new();
+        for chunk in input.as_bytes().chunks(3) {
+            parser.feed(chunk).unwrap();
+        }
+        assert_eq!(parser.finish().unwrap(), parse(&input).unwrap());
+
+        // A root string starts at offset 0.
+        let root = &input[1..text.len() + 3];
+        let mut parser = PushParser::new();
+        for chunk in root.as_bytes().chunks(5) {
+            parser.feed(chunk).unwrap();
+        }
+        assert_eq!(parser.finish().unwrap(), parse(root).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        let mut parser = PushParser::new();
+        parser.feed(b"[1, 2").unwrap();
+        assert_eq!(
+            parser.finish().unwrap_err(),
+            JsonError::UnexpectEof { offset: 5 }
+        );
+
+        let mut parser = PushParser::new();
+        parser.feed(b"[1,").unwrap();
+        assert_eq!(
+            parser.feed(b" 2 x").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 6,
+                expected: Expected::NONE
+            }
+        );
+    }
+}
@@ -0,0 +1,938 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::lexer::{decode_number, decode_string, Lexer, TokenKind};
+use crate::{into_str, Map, Type, MAX_DEPTH};
+
+/// An error from [`Filter::parse`] or from running a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// The expression is malformed at byte `offset`.
+    Syntax {
+        offset: usize,
+        message: &'static str,
+    },
+    /// The filter was applied to a value it cannot handle, such as
+    /// iterating over a number.
+    Runtime(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryError::Syntax { offset, message } => {
+                write!(f, "syntax error at byte {}: {}", offset, message)
+            }
+            QueryError::Runtime(message) => f.write_str(message),
+        }
+    }
+}
+
+impl core::error::Error for QueryError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ast {
+    Identity,
+    /// `..`: the input and everything inside it.
+    Recurse,
+    Literal(Type),
+    Field(Box<Ast>, String),
+    Index(Box<Ast>, Box<Ast>),
+    Iterate(Box<Ast>),
+    Pipe(Box<Ast>, Box<Ast>),
+    Comma(Box<Ast>, Box<Ast>),
+    Binary(Op, Box<Ast>, Box<Ast>),
+    Array(Option<Box<Ast>>),
+    Object(Vec<(String, Ast)>),
+    Call(String, Vec<Ast>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    Field(String),
+    Ident(String),
+    Literal(Type),
+    Punct(&'static str),
+}
+
+const PUNCTUATION: [&str; 21] = [
+    "==", "!=", "<=", ">=", "|", ",", "(", ")", "[", "]", "{", "}", ":", ";", "<", ">", "+", "-",
+    "*", "/", "%",
+];
+
+fn tokenize(expr: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let bytes = expr.as_bytes();
+    let syntax = |offset, message| QueryError::Syntax { offset, message };
+    let ident_len = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+            .count()
+    };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let byte = bytes[i];
+        if byte.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if byte == b'.' {
+            i += 1;
+            match bytes.get(i) {
+                Some(b'.') => {
+                    i += 1;
+                    Token::DotDot
+                }
+                Some(b'"') => {
+                    let (len, name) = json_literal(&expr[i..], i)?;
+                    i += len;
+                    match name {
+                        Type::String(name) => Token::Field(name.as_str().into()),
+                        _ => unreachable!("a quote starts a string"),
+                    }
+                }
+                Some(b) if b.is_ascii_alphabetic() || *b == b'_' => {
+                    let len = ident_len(i);
+                    i += len;
+                    Token::Field(expr[i - len..i].to_string())
+                }
+                _ => Token::Dot,
+            }
+        } else if byte == b'"' || byte.is_ascii_digit() {
+            let (len, value) = json_literal(&expr[i..], i)?;
+            i += len;
+            Token::Literal(value)
+        } else if byte.is_ascii_alphabetic() || byte == b'_' {
+            let len = ident_len(i);
+            i += len;
+            match &expr[start..i] {
+                "true" => Token::Literal(Type::Boolean(true)),
+                "false" => Token::Literal(Type::Boolean(false)),
+                "null" => Token::Literal(Type::Null),
+                name => Token::Ident(name.to_string()),
+            }
+        } else {
+            let punct = PUNCTUATION
+                .into_iter()
+                .find(|p| expr[i..].starts_with(p))
+                .ok_or_else(|| syntax(i, "unexpected character"))?;
+            i += punct.len();
+            Token::Punct(punct)
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Reads the JSON string or number at the start of `text`, which is at byte
+/// `offset` of the expression.
+fn json_literal(text: &str, offset: usize) -> Result<(usize, Type), QueryError> {
+    let invalid = |message| QueryError::Syntax { offset, message };
+    let bytes = text.as_bytes();
+    let token = match Lexer::new(bytes).next_token() {
+        Ok(Some(token)) => token,
+        _ => return Err(invalid("malformed literal")),
+    };
+    let value = match token.kind {
+        TokenKind::String => {
+            decode_string(&bytes[1..token.end - 1], 1).map(|s| Type::String(into_str(s)))
+        }
+        TokenKind::Number => decode_number(&bytes[..token.end], 0).map(Type::Number),
+        _ => return Err(invalid("malformed literal")),
+    };
+    Ok((token.end, value.map_err(|_| invalid("malformed literal"))?))
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    /// How many brackets, parentheses and unary minuses enclose the current
+    /// token.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |(offset, _)| *offset)
+    }
+
+    fn error(&self, message: &'static str) -> QueryError {
+        QueryError::Syntax {
+            offset: self.offset(),
+            message,
+        }
+    }
+
+    /// Runs `parse` one level deeper, after the token just consumed opened
+    /// that level.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Ast, QueryError>,
+    ) -> Result<Ast, QueryError> {
+        if self.depth == MAX_DEPTH {
+            return Err(QueryError::Syntax {
+                offset: self.tokens[self.pos - 1].0,
+                message: "filter nested too deep",
+            });
+        }
+        self.depth += 1;
+        let ast = parse(self)?;
+        self.depth -= 1;
+        Ok(ast)
+    }
+
+    fn eat(&mut self, punct: &'static str) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &'static str, message: &'static str) -> Result<(), QueryError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Ast, QueryError> {
+        let mut stages = vec![self.comma()?];
+        while self.eat("|") {
+            stages.push(self.comma()?);
+        }
+        // `|` associates to the right.
+        let mut ast = stages.pop().expect("at least one stage");
+        while let Some(lhs) = stages.pop() {
+            ast = Ast::Pipe(Box::new(lhs), Box::new(ast));
+        }
+        Ok(ast)
+    }
+
+    fn comma(&mut self) -> Result<Ast, QueryError> {
+        let mut lhs = self.or()?;
+        while self.eat(",") {
+            lhs = Ast::Comma(Box::new(lhs), Box::new(self.or()?));
+        }
+        Ok(lhs)
+    }
+
+    fn or(&mut self) -> Result<Ast, QueryError> {
+        let mut lhs = self.and()?;
+        while self.eat_keyword("or") {
+            lhs = Ast::Binary(Op::Or, Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Ast, QueryError> {
+        let mut lhs = self.comparison()?;
+        while self.eat_keyword("and") {
+            lhs = Ast::Binary(Op::And, Box::new(lhs), Box::new(self.comparison()?));
+        }
+        Ok(lhs)
+    }
+
+    fn comparison(&mut self) -> Result<Ast, QueryError> {
+        let lhs = self.additive()?;
+        let ops = [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ];
+        for (punct, op) in ops {
+            if self.eat(punct) {
+                return Ok(Ast::Binary(op, Box::new(lhs), Box::new(self.additive()?)));
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn additive(&mut self) -> Result<Ast, QueryError> {
+        let mut lhs = self.multiplicative()?;
+        loop {
+            let op = if self.eat("+") {
+                Op::Add
+            } else if self.eat("-") {
+                Op::Sub
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Ast::Binary(op, Box::new(lhs), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Ast, QueryError> {
+        let mut lhs = self.postfix()?;
+        loop {
+            let op = if self.eat("*") {
+                Op::Mul
+            } else if self.eat("/") {
+                Op::Div
+            } else if self.eat("%") {
+                Op::Rem
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Ast::Binary(op, Box::new(lhs), Box::new(self.postfix()?));
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Ast, QueryError> {
+        let mut term = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Field(name)) => {
+                    term = Ast::Field(Box::new(term), name.clone());
+                    self.pos += 1;
+                }
+                Some(Token::Dot)
+                    if self.tokens.get(self.pos + 1).map(|(_, t)| t)
+                        == Some(&Token::Punct("[")) =>
+                {
+                    self.pos += 1;
+                }
+                Some(Token::Punct("[")) => {
+                    self.pos += 1;
+                    if self.eat("]") {
+                        term = Ast::Iterate(Box::new(term));
+                    } else {
+                        let index = self.nested(|p| {
+                            let index = p.pipe()?;
+                            p.expect("]", "expected ']'")?;
+                            Ok(index)
+                        })?;
+                        term = Ast::Index(Box::new(term), Box::new(index));
+                    }
+                }
+                _ => return Ok(term),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Ast, QueryError> {
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.error("expected a filter"));
+        };
+        // `.foo` is a postfix field access on the identity.
+        if let Token::Field(_) = token {
+            return Ok(Ast::Identity);
+        }
+        self.pos += 1;
+        match token {
+            Token::Dot => Ok(Ast::Identity),
+            Token::DotDot => Ok(Ast::Recurse),
+            Token::Literal(value) => Ok(Ast::Literal(value)),
+            Token::Punct("-") => {
+                let operand = self.nested(Self::postfix)?;
+                Ok(Ast::Binary(
+                    Op::Sub,
+                    Box::new(Ast::Literal(Type::Number(0.into()))),
+                    Box::new(operand),
+                ))
+            }
+            Token::Punct("(") => self.nested(|p| {
+                let inner = p.pipe()?;
+                p.expect(")", "expected ')'")?;
+                Ok(inner)
+            }),
+            Token::Punct("[") => {
+                if self.eat("]") {
+                    return Ok(Ast::Array(None));
+                }
+                let inner = self.nested(|p| {
+                    let inner = p.pipe()?;
+                    p.expect("]", "expected ']'")?;
+                    Ok(inner)
+                })?;
+                Ok(Ast::Array(Some(Box::new(inner))))
+            }
+            Token::Punct("{") => self.nested(Self::object),
+            Token::Ident(name) => {
+                let mut args = Vec::new();
+                if self.eat("(") {
+                    loop {
+                        args.push(self.nested(Self::pipe)?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(";", "expected ';' or ')'")?;
+                    }
+                }
+                Ok(Ast::Call(name, args))
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected a filter"))
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Ast, QueryError> {
+        let mut members = Vec::new();
+        if self.eat("}") {
+            return Ok(Ast::Object(members));
+        }
+        loop {
+            let key = match self.peek().cloned() {
+                Some(Token::Ident(name)) => name,
+                Some(Token::Literal(Type::String(name))) => name.as_str().into(),
+                _ => return Err(self.error("expected an object key")),
+            };
+            self.pos += 1;
+            let value = if self.eat(":") {
+                self.or()?
+            } else {
+                Ast::Field(Box::new(Ast::Identity), key.clone())
+            };
+            members.push((key, value));
+            if self.eat("}") {
+                return Ok(Ast::Object(members));
+            }
+            self.expect(",", "expected ',' or '}'")?;
+        }
+    }
+}
+
+/// A compiled filter in a small jq-like language, e.g.
+/// `.users[] | select(.active) | .email`.
+///
+/// A filter turns one input value into a stream of outputs. It supports
+/// `.`, `..`, `.key`, `."key"`, `.[index]`, `.[]`, `|`, `,`, literals,
+/// `[...]` and `{key: ...}` construction, arithmetic (`+ - * / %`),
+/// comparisons, `and`, `or`, and the functions `select(f)`, `map(f)`,
+/// `has(key)`, `length`, `keys`, `type`, `not`, `add`, `sort` and `empty`.
+/// As in jq, `false` and `null` are false and everything else is true, and
+/// orderings follow [`Type::total_cmp`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    ast: Ast,
+}
+
+impl Filter {
+    pub fn parse(expr: &str) -> Result<Filter, QueryError> {
+        let mut parser = Parser {
+            tokens: tokenize(expr)?,
+            pos: 0,
+            end: expr.len(),
+            depth: 0,
+        };
+        let ast = parser.pipe()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(parser.error("unexpected token"));
+        }
+        Ok(Filter { ast })
+    }
+
+    /// Runs the filter on `input`, collecting its outputs.
+    pub fn run(&self, input: &Type) -> Result<Vec<Type>, QueryError> {
+        eval(&self.ast, input)
+    }
+}
+
+/// Parses `expr` and runs it on `input`.
+pub fn query(expr: &str, input: &Type) -> Result<Vec<Type>, QueryError> {
+    Filter::parse(expr)?.run(input)
+}
+
+fn truthy(value: &Type) -> bool {
+    !matches!(value, Type::Null | Type::Boolean(false))
+}
+
+fn runtime(message: String) -> QueryError {
+    QueryError::Runtime(message)
+}
+
+fn eval(ast: &Ast, input: &Type) -> Result<Vec<Type>, QueryError> {
+    Ok(match ast {
+        Ast::Identity => vec![input.clone()],
+        Ast::Recurse => {
+            let mut out = Vec::new();
+            let mut stack = vec![input];
+            while let Some(value) = stack.pop() {
+                out.push(value.clone());
+                match value {
+                    Type::Array(items) => stack.extend(items.iter().rev()),
+                    Type::Object(members) => {
+                        let mut members: Vec<_> = members.iter().collect();
+                        members.sort_unstable_by(|a, b| b.0.cmp(a.0));
+                        stack.extend(members.into_iter().map(|(_, value)| value));
+                    }
+                    _ => {}
+                }
+            }
+            out
+        }
+        Ast::Literal(value) => vec![value.clone()],
+        Ast::Field(target, name) => eval(target, input)?
+            .iter()
+            .map(|value| index(value, &Type::String(name.as_str().into())))
+            .collect::<Result<_, _>>()?,
+        Ast::Index(target, key) => {
+            let keys = eval(key, input)?;
+            let mut out = Vec::new();
+            for value in eval(target, input)? {
+                for key in &keys {
+                    out.push(index(&value, key)?);
+                }
+            }
+            out
+        }
+        Ast::Iterate(target) => {
+            let mut out = Vec::new();
+            for value in eval(target, input)? {
+                match value {
+                    Type::Array(items) => out.extend(items),
+                    Type::Object(members) => {
+                        let mut members: Vec<_> = members.into_iter().collect();
+                        members.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+                        out.extend(members.into_iter().map(|(_, value)| value));
+                    }
+                    value => return Err(runtime(format!("cannot iterate over {}", value.kind()))),
+                }
+            }
+            out
+        }
+        Ast::Pipe(lhs, rhs) => {
+            let mut out = Vec::new();
+            for value in eval(lhs, input)? {
+                out.extend(eval(rhs, &value)?);
+            }
+            out
+        }
+        Ast::Comma(lhs, rhs) => {
+            let mut out = eval(lhs, input)?;
+            out.extend(eval(rhs, input)?);
+            out
+        }
+        Ast::Binary(op @ (Op::And | Op::Or), lhs, rhs) => {
+            let mut out = Vec::new();
+            for value in eval(lhs, input)? {
+                match (op, truthy(&value)) {
+                    (Op::And, false) => out.push(Type::Boolean(false)),
+                    (Op::Or, true) => out.push(Type::Boolean(true)),
+                    _ => out.extend(
+                        eval(rhs, input)?
+                            .iter()
+                            .map(|value| Type::Boolean(truthy(value))),
+                    ),
+                }
+            }
+            out
+        }
+        Ast::Binary(op, lhs, rhs) => {
+            let rhs = eval(rhs, input)?;
+            let mut out = Vec::new();
+            for a in eval(lhs, input)? {
+                for b in &rhs {
+                    out.push(binary(*op, &a, b)?);
+                }
+            }
+            out
+        }
+        Ast::Array(None) => vec![Type::Array(Vec::new())],
+        Ast::Array(Some(inner)) => vec![Type::Array(eval(inner, input)?)],
+        Ast::Object(members) => {
+            let mut objects = vec![Map::default()];
+            for (key, value) in members {
+                let values = eval(value, input)?;
+                let mut next = Vec::new();
+                for object in &objects {
+                    for value in &values {
+                        let mut object = object.clone();
+                        object.insert(key.as_str().into(), value.clone());
+                        next.push(object);
+                    }
+                }
+                objects = next;
+            }
+            objects
+                .into_iter()
+                .map(|object| Type::Object(Box::new(object)))
+                .collect()
+        }
+        Ast::Call(name, args) => call(name, args, input)?,
+    })
+}
+
+fn index(value: &Type, key: &Type) -> Result<Type, QueryError> {
+    match (value, key) {
+        (Type::Null, Type::String(_) | Type::Number(_)) => Ok(Type::Null),
+        (Type::Object(members), Type::String(key)) => {
+            Ok(members.get(key.as_str()).cloned().unwrap_or(Type::Null))
+        }
+        (Type::Array(items), Type::Number(n)) => {
+            let n = n.to_f64() as i64;
+            let i = if n < 0 { n + items.len() as i64 } else { n };
+            Ok(usize::try_from(i)
+                .ok()
+                .and_then(|i| items.get(i))
+                .cloned()
+                .unwrap_or(Type::Null))
+        }
+        _ => Err(runtime(format!(
+            "cannot index {} with {}",
+            value.kind(),
+            key.kind()
+        ))),
+    }
+}
+
+fn binary(op: Op, a: &Type, b: &Type) -> Result<Type, QueryError> {
+    let order = || a.total_cmp(b);
+    Ok(match (op, a, b) {
+        (Op::Eq, ..) => Type::Boolean(a == b),
+        (Op::Ne, ..) => Type::Boolean(a != b),
+        (Op::Lt, ..) => Type::Boolean(order() == Ordering::Less),
+        (Op::Le, ..) => Type::Boolean(order() != Ordering::Greater),
+        (Op::Gt, ..) => Type::Boolean(order() == Ordering::Greater),
+        (Op::Ge, ..) => Type::Boolean(order() != Ordering::Less),
+        (Op::Add, Type::Null, other) | (Op::Add, other, Type::Null) => other.clone(),
+        (Op::Add, Type::Number(a), Type::Number(b)) => {
+            Type::Number((a.to_f64() + b.to_f64()).into())
+        }
+        (Op::Add, Type::String(a), Type::String(b)) => {
+            Type::String(into_str(format!("{}{}", a, b)))
+        }
+        (Op::Add, Type::Array(a), Type::Array(b)) => {
+            Type::Array(a.iter().chain(b.iter()).cloned().collect())
+        }
+        (Op::Add, Type::Object(a), Type::Object(b)) => {
+            let mut merged = a.clone();
+            merged.extend(b.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Type::Object(merged)
+        }
+        (Op::Sub, Type::Number(a), Type::Number(b)) => {
+            Type::Number((a.to_f64() - b.to_f64()).into())
+        }
+        (Op::Sub, Type::Array(a), Type::Array(b)) => {
+            Type::Array(a.iter().filter(|v| !b.contains(v)).cloned().collect())
+        }
+        (Op::Mul, Type::Number(a), Type::Number(b)) => {
+            Type::Number((a.to_f64() * b.to_f64()).into())
+        }
+        (Op::Div, Type::Number(_), Type::Number(b)) if b.to_f64() == 0.0 => {
+            return Err(runtime("division by zero".to_string()))
+        }
+        (Op::Div, Type::Number(a), Type::Number(b)) => {
+            Type::Number((a.to_f64() / b.to_f64()).into())
+        }
+        // As in jq, both operands are truncated to integers first.
+        (Op::Rem, Type::Number(a), Type::Number(b)) => {
+            let divisor = b.to_f64() as i64;
+            if divisor == 0 {
+                return Err(runtime("division by zero".to_string()));
+            }
+            Type::Number((a.to_f64() as i64).wrapping_rem(divisor).into())
+        }
+        _ => {
+            return Err(runtime(format!(
+                "cannot apply {:?} to {} and {}",
+                op,
+                a.kind(),
+                b.kind()
+            )))
+        }
+    })
+}
+
+fn call(name: &str, args: &[Ast], input: &Type) -> Result<Vec<Type>, QueryError> {
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(runtime(format!("{}/{} is not defined", name, args.len())))
+        }
+    };
+    Ok(match name {
+        "empty" => {
+            arity(0)?;
+            Vec::new()
+        }
+        "not" => {
+            arity(0)?;
+            vec![Type::Boolean(!truthy(input))]
+        }
+        "length" => {
+            arity(0)?;
+            let len = match input {
+                Type::Null => 0.0,
+                Type::Boolean(_) => {
+                    return Err(runtime("boolean has no length".to_string()));
+                }
+                Type::Number(n) => n.to_f64().abs(),
+                Type::String(s) => s.chars().count() as f64,
+                Type::Array(items) => items.len() as f64,
+                Type::Object(members) => members.len() as f64,
+            };
+            vec![Type::Number(len.into())]
+        }
+        "keys" => {
+            arity(0)?;
+            let keys = match input {
+                Type::Object(members) => {
+                    let mut keys: Vec<&str> = members.keys().map(|k| &**k).collect();
+                    keys.sort_unstable();
+                    keys.into_iter().map(|k| Type::String(k.into())).collect()
+                }
+                Type::Array(items) => (0..items.len()).map(|i| Type::Number(i.into())).collect(),
+                value => return Err(runtime(format!("{} has no keys", value.kind()))),
+            };
+            vec![Type::Array(keys)]
+        }
+        "type" => {
+            arity(0)?;
+            vec![Type::String(input.kind().as_str().into())]
+        }
+        "add" => {
+            arity(0)?;
+            let mut sum = Type::Null;
+            for item in eval(&Ast::Iterate(Box::new(Ast::Identity)), input)? {
+                sum = binary(Op::Add, &sum, &item)?;
+            }
+            vec![sum]
+        }
+        "sort" => {
+            arity(0)?;
+            let Type::Array(items) = input else {
+                return Err(runtime(format!("cannot sort {}", input.kind())));
+            };
+            let mut items = items.clone();
+            items.sort_by(Type::total_cmp);
+            vec![Type::Array(items)]
+        }
+        "select" => {
+            arity(1)?;
+            if eval(&args[0], input)?.iter().any(truthy) {
+                vec![input.clone()]
+            } else {
+                Vec::new()
+            }
+        }
+        "map" => {
+            arity(1)?;
+            let each = Ast::Pipe(
+                Box::new(Ast::Iterate(Box::new(Ast::Identity))),
+                Box::new(args[0].clone()),
+            );
+            vec![Type::Array(eval(&each, input)?)]
+        }
+        "has" => {
+            arity(1)?;
+            let mut out = Vec::new();
+            for key in eval(&args[0], input)? {
+                let has = match (input, &key) {
+                    (Type::Object(members), Type::String(key)) => {
+                        members.contains_key(key.as_str())
+                    }
+                    (Type::Array(items), Type::Number(n)) => {
+                        n.to_f64() >= 0.0 && (n.to_f64() as usize) < items.len()
+                    }
+                    _ => {
+                        return Err(runtime(format!(
+                            "cannot check whether {} has a {} key",
+                            input.kind(),
+                            key.kind()
+                        )))
+                    }
+                };
+                out.push(Type::Boolean(has));
+            }
+            out
+        }
+        _ => return Err(runtime(format!("{}/{} is not defined", name, args.len()))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{query, Filter, QueryError};
+    use crate::{parse, Type, MAX_DEPTH};
+
+    const DOC: &str = r#"{
+        "users": [
+            {"name": "ann", "email": "a@x", "active": true, "age": 31},
+            {"name": "bob", "email": "b@x", "active": false, "age": 25},
+            {"name": "cy", "email": null, "active": true, "age": 40}
+        ],
+        "meta": {"count": 3, "tags": ["x", "y"]}
+    }"#;
+
+    fn run(expr: &str) -> Vec<Type> {
+        query(expr, &parse(DOC).unwrap()).unwrap_or_else(|err| panic!("{}: {}", expr, err))
+    }
+
+    fn values(json: &str) -> Vec<Type> {
+        match parse(json).unwrap() {
+            Type::Array(items) => items,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn filters() {
+        for (expr, expected) in [
+            (".users[] | select(.active) | .email", r#"["a@x", null]"#),
+            (".meta.count", "[3]"),
+            (".meta.\"tags\"[1]", r#"["y"]"#),
+            (".users[-1].name", r#"["cy"]"#),
+            (".users[5]", "[null]"),
+            (".missing.deeper", "[null]"),
+            (".meta.tags[]", r#"["x", "y"]"#),
+            (".meta | keys", r#"[["count", "tags"]]"#),
+            ("[.users[] | .age] | add", "[96]"),
+            (".users | map(.age * 2 - 1) | sort", "[[49, 61, 79]]"),
+            (".users | length", "[3]"),
+            (
+                "[.users[] | select(.age > 30 and .active) | .name]",
+                r#"[["ann", "cy"]]"#,
+            ),
+            (
+                ".users[0] | {name, years: .age}",
+                r#"[{"name": "ann", "years": 31}]"#,
+            ),
+            (".meta.count, .meta.tags[0]", r#"[3, "x"]"#),
+            ("1, 2 | . + 1", "[2, 3]"),
+            ("[.[] | type]", r#"[["object", "array"]]"#),
+            (".meta | has(\"tags\"), has(\"nope\")", "[true, false]"),
+            ("(1 + 2) * -3", "[-9]"),
+            ("\"a\" + \"b\", [1] + [2], null + 1", r#"["ab", [1, 2], 1]"#),
+            ("[1, 2, 1] - [1]", "[[2]]"),
+            ("7 % 3, 7 / 2", "[1, 3.5]"),
+            ("7 % -3, -7 % 3, 7.9 % 2.5", "[1, -1, 1]"),
+            ("-9223372036854775808 % -1", "[0]"),
+            (
+                "1 < \"a\", [] > {}, 2 >= 2, null == false",
+                "[true, false, true, false]",
+            ),
+            (".users[1].active | not", "[true]"),
+            ("empty, []", "[[]]"),
+        ] {
+            assert_eq!(run(expr), values(expected), "{}", expr);
+        }
+        assert_eq!(
+            run("[.. | select(type == \"number\")] | length"),
+            values("[4]")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let doc = parse(DOC).unwrap();
+        for (expr, offset) in [
+            (".users[", 7),
+            ("| .a", 0),
+            (".a )", 3),
+            ("{a: 1", 5),
+            ("@", 0),
+            ("\"open", 0),
+        ] {
+            match Filter::parse(expr) {
+                Err(QueryError::Syntax { offset: at, .. }) => assert_eq!(at, offset, "{}", expr),
+                other => panic!("{}: {:?}", expr, other),
+            }
+        }
+        assert_eq!(
+            query(".meta.count[]", &doc),
+            Err(QueryError::Runtime(
+                "cannot iterate over number".to_string()
+            ))
+        );
+        assert_eq!(
+            query(".users.name", &doc),
+            Err(QueryError::Runtime(
+                "cannot index array with string".to_string()
+            ))
+        );
+        assert_eq!(
+            query("frobnicate(1)", &doc),
+            Err(QueryError::Runtime(
+                "frobnicate/1 is not defined".to_string()
+            ))
+        );
+        for expr in ["1 / 0", "5 % 0", "5 % 0.5"] {
+            assert_eq!(
+                query(expr, &doc),
+                Err(QueryError::Runtime("division by zero".to_string())),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn limits_nesting() {
+        // The offset is that of the first opener past the limit.
+        for (opener, offset) in [
+            ("(", MAX_DEPTH),
+            ("[", MAX_DEPTH),
+            ("-", MAX_DEPTH),
+            ("{a:", 3 * MAX_DEPTH),
+            (".[", 2 * MAX_DEPTH + 1),
+            ("f(", 2 * MAX_DEPTH + 1),
+        ] {
+            let expr = opener.repeat(200_000);
+            assert_eq!(
+                Filter::parse(&expr),
+                Err(QueryError::Syntax {
+                    offset,
+                    message: "filter nested too deep"
+                }),
+                "{}",
+                opener
+            );
+        }
+        let deepest = format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert_eq!(
+            Filter::parse(&deepest).unwrap().run(&Type::Null),
+            Ok(vec![Type::Number(1.into())])
+        );
+    }
+}
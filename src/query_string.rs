@@ -0,0 +1,472 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+
+use crate::pointer::parse_index;
+use crate::{into_str, to_string, JsonPointer, Key, Map, Type, MAX_DEPTH};
+
+/// How [`to_query_string`] names nested fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStringStyle {
+    /// `a[b][0]=x`, as written by PHP and Rails forms.
+    Brackets,
+    /// `a.b.0=x`.
+    Dots,
+}
+
+/// An error from [`from_query_string`] or [`to_query_string`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryStringError {
+    /// The pair starting at byte `offset` has a malformed name, or one that
+    /// conflicts with an earlier pair, as `a[b]=2` does after `a=1`.
+    Syntax {
+        offset: usize,
+        message: &'static str,
+    },
+    /// The value at `pointer` has no query string form.
+    Unrepresentable {
+        pointer: JsonPointer,
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for QueryStringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryStringError::Syntax { offset, message } => {
+                write!(f, "invalid query string at byte {}: {}", offset, message)
+            }
+            QueryStringError::Unrepresentable { pointer, message } => {
+                write!(f, "'{}': {}", pointer, message)
+            }
+        }
+    }
+}
+
+impl core::error::Error for QueryStringError {}
+
+enum Segment {
+    Key(String),
+    /// `[]`, which appends to an array.
+    Append,
+}
+
+/// Parses a query string or `application/x-www-form-urlencoded` body, such
+/// as `a=1&b[0]=x&b[1]=y&c.d=z`, into an object of strings.
+///
+/// Names are split on both bracket and dot nesting, after percent-decoding,
+/// and `[]` appends to an array. A name repeated at the same path collects
+/// its values into an array, and an object whose keys are exactly `0` to
+/// `n - 1` becomes an array. A pair without `=` is `null`. `+` decodes to a
+/// space; malformed percent escapes are kept as written and invalid UTF-8 is
+/// replaced with U+FFFD. A leading `?` is ignored. A name may have at most
+/// [`MAX_DEPTH`] segments.
+pub fn from_query_string(text: &str) -> Result<Type, QueryStringError> {
+    let base = usize::from(text.starts_with('?'));
+    let mut root = Type::Object(Box::default());
+    let mut offset = base;
+    for pair in text[base..].split('&') {
+        let error = |message| QueryStringError::Syntax { offset, message };
+        if !pair.is_empty() {
+            let (name, value) = match pair.split_once('=') {
+                Some((name, value)) => (name, Type::String(into_str(decode(value)))),
+                None => (pair, Type::Null),
+            };
+            let segments = split_name(&decode(name)).ok_or_else(|| error("malformed name"))?;
+            // Each segment is a level of nesting.
+            if segments.len() > MAX_DEPTH {
+                return Err(error("the name is nested too deep"));
+            }
+            insert(&mut root, &segments, value)
+                .ok_or_else(|| error("the name conflicts with an earlier pair"))?;
+        }
+        offset += pair.len() + 1;
+    }
+    Ok(into_arrays(root))
+}
+
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match (
+                bytes.get(i + 1).and_then(|&b| hex(b)),
+                bytes.get(i + 2).and_then(|&b| hex(b)),
+            ) {
+                (Some(high), Some(low)) => {
+                    out.push(high << 4 | low);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Splits `a[b][]` or `a.b` into its segments; `None` for an empty segment
+/// or an unclosed bracket.
+fn split_name(name: &str) -> Option<Vec<Segment>> {
+    let end = name.find(['[', '.']).unwrap_or(name.len());
+    if end == 0 {
+        return None;
+    }
+    let mut segments = vec![Segment::Key(name[..end].into())];
+    let mut rest = &name[end..];
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let close = inner.find(']')?;
+            segments.push(match &inner[..close] {
+                "" => Segment::Append,
+                key => Segment::Key(key.into()),
+            });
+            rest = &inner[close + 1..];
+        } else {
+            let inner = rest.strip_prefix('.')?;
+            let end = inner.find(['[', '.']).unwrap_or(inner.len());
+            if end == 0 {
+                return None;
+            }
+            segments.push(Segment::Key(inner[..end].into()));
+            rest = &inner[end..];
+        }
+    }
+    Some(segments)
+}
+
+fn container(next: &Segment) -> Type {
+    match next {
+        Segment::Key(_) => Type::Object(Box::default()),
+        Segment::Append => Type::Array(Vec::new()),
+    }
+}
+
+/// Stores `value` at `segments` below `target`; `None` if the path runs
+/// into a value of the wrong kind.
+fn insert(target: &mut Type, segments: &[Segment], value: Type) -> Option<()> {
+    let (first, rest) = segments.split_first()?;
+    match first {
+        Segment::Key(key) => {
+            let Type::Object(members) = target else {
+                return None;
+            };
+            match rest.first() {
+                Some(next) => {
+                    let child = members
+                        .entry(key.as_str().into())
+                        .or_insert_with(|| container(next));
+                    insert(child, rest, value)
+                }
+                None => {
+                    match members.get_mut(key.as_str()) {
+                        None => {
+                            members.insert(key.as_str().into(), value);
+                        }
+                        Some(Type::Object(_)) => return None,
+                        Some(Type::Array(items)) => items.push(value),
+                        Some(existing) => {
+                            let first = mem::replace(existing, Type::Null);
+                            *existing = Type::Array(vec![first, value]);
+                        }
+                    }
+                    Some(())
+                }
+            }
+        }
+        Segment::Append => {
+            let Type::Array(items) = target else {
+                return None;
+            };
+            match rest.first() {
+                Some(next) => {
+                    items.push(container(next));
+                    insert(items.last_mut()?, rest, value)
+                }
+                None => {
+                    items.push(value);
+                    Some(())
+                }
+            }
+        }
+    }
+}
+
+fn into_arrays(value: Type) -> Type {
+    match value {
+        Type::Array(items) => Type::Array(items.into_iter().map(into_arrays).collect()),
+        Type::Object(members) => {
+            let mut indexed: Vec<_> = members
+                .keys()
+                .map(|key| parse_index(key))
+                .collect::<Option<_>>()
+                .unwrap_or_default();
+            indexed.sort_unstable();
+            if !indexed.is_empty() && indexed.into_iter().eq(0..members.len()) {
+                let mut items: Vec<_> = members.into_iter().collect();
+                items.sort_by_key(|(key, _)| parse_index(key));
+                Type::Array(
+                    items
+                        .into_iter()
+                        .map(|(_, item)| into_arrays(item))
+                        .collect(),
+                )
+            } else {
+                let members: Map<_, _> = members
+                    .into_iter()
+                    .map(|(key, member)| (key, into_arrays(member)))
+                    .collect();
+                Type::Object(Box::new(members))
+            }
+        }
+        value => value,
+    }
+}
+
+/// Writes `value`, which must be an object, as a query string with keys in
+/// sorted order and values percent-encoded, spaces as `+`.
+///
+/// Nested fields are named in `style`, with array elements by index.
+/// `null` is written as a name without `=`, and booleans and numbers as
+/// their JSON text, so they read back as strings. Empty arrays and objects,
+/// empty keys, and keys containing the brackets or dots that would split
+/// them are `QueryStringError::Unrepresentable`.
+pub fn to_query_string(value: &Type, style: QueryStringStyle) -> Result<String, QueryStringError> {
+    let mut pointer = JsonPointer::root();
+    let Type::Object(members) = value else {
+        return Err(QueryStringError::Unrepresentable {
+            pointer,
+            message: "a query string must be an object",
+        });
+    };
+    let mut out = String::new();
+    let mut name = String::new();
+    write_members(members, style, &mut name, &mut pointer, &mut out)?;
+    Ok(out)
+}
+
+fn write_members(
+    members: &Map<Key, Type>,
+    style: QueryStringStyle,
+    name: &mut String,
+    pointer: &mut JsonPointer,
+    out: &mut String,
+) -> Result<(), QueryStringError> {
+    let mut members: Vec<_> = members.iter().collect();
+    members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    for (key, member) in members {
+        pointer.push(&**key);
+        let forbidden: &[char] = match style {
+            QueryStringStyle::Brackets if !name.is_empty() => &[']'],
+            _ => &['[', ']', '.'],
+        };
+        if key.is_empty() || key.contains(forbidden) {
+            return Err(QueryStringError::Unrepresentable {
+                pointer: pointer.clone(),
+                message: "the key cannot be written as part of a name",
+            });
+        }
+        write_field(member, key, style, name, pointer, out)?;
+        pointer.pop();
+    }
+    Ok(())
+}
+
+fn write_field(
+    value: &Type,
+    key: &str,
+    style: QueryStringStyle,
+    name: &mut String,
+    pointer: &mut JsonPointer,
+    out: &mut String,
+) -> Result<(), QueryStringError> {
+    let len = name.len();
+    match (style, len) {
+        (_, 0) => name.push_str(key),
+        (QueryStringStyle::Brackets, _) => {
+            name.push('[');
+            name.push_str(key);
+            name.push(']');
+        }
+        (QueryStringStyle::Dots, _) => {
+            name.push('.');
+            name.push_str(key);
+        }
+    }
+    match value {
+        Type::Array(items) if !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                let index = i.to_string();
+                pointer.push(index.as_str());
+                write_field(item, &index, style, name, pointer, out)?;
+                pointer.pop();
+            }
+        }
+        Type::Object(members) if !members.is_empty() => {
+            write_members(members, style, name, pointer, out)?;
+        }
+        Type::Array(_) | Type::Object(_) => {
+            return Err(QueryStringError::Unrepresentable {
+                pointer: pointer.clone(),
+                message: "empty arrays and objects have no query string form",
+            })
+        }
+        scalar => {
+            if !out.is_empty() {
+                out.push('&');
+            }
+            encode(name, out);
+            match scalar {
+                Type::Null => {}
+                Type::String(s) => {
+                    out.push('=');
+                    encode(s, out);
+                }
+                scalar => {
+                    out.push('=');
+                    encode(&to_string(scalar), out);
+                }
+            }
+        }
+    }
+    name.truncate(len);
+    Ok(())
+}
+
+fn encode(text: &str, out: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'[' | b']' => {
+                out.push(b as char)
+            }
+            b' ' => out.push('+'),
+            b => {
+                out.push('%');
+                out.push(HEX[usize::from(b >> 4)] as char);
+                out.push(HEX[usize::from(b & 15)] as char);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_query_string, to_query_string, QueryStringError, QueryStringStyle};
+    use crate::{parse, MAX_DEPTH};
+
+    #[test]
+    fn reads_query_strings() {
+        let value = from_query_string(
+            "?a=1&b[0]=x&b[1]=y&c.d=z&c[e][]=p&c[e][]=q&tag=t1&tag=t2&flag&q=hello+w%C3%B6rld%21&bad=%zz&&u%5Bv%5D=w",
+        )
+        .unwrap();
+        let expected = parse(
+            r#"{
+                "a": "1",
+                "b": ["x", "y"],
+                "c": {"d": "z", "e": ["p", "q"]},
+                "tag": ["t1", "t2"],
+                "flag": null,
+                "q": "hello wörld!",
+                "bad": "%zz",
+                "u": {"v": "w"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(value, expected);
+        assert_eq!(
+            from_query_string("a[2]=x&a[b.c]=y"),
+            Ok(parse(r#"{"a": {"2": "x", "b.c": "y"}}"#).unwrap())
+        );
+        assert_eq!(from_query_string(""), Ok(parse("{}").unwrap()));
+    }
+
+    #[test]
+    fn rejects_malformed_names() {
+        let error = |text: &str| from_query_string(text).unwrap_err();
+        assert_eq!(
+            error("a=1&a[b]=2"),
+            QueryStringError::Syntax {
+                offset: 4,
+                message: "the name conflicts with an earlier pair"
+            }
+        );
+        assert_eq!(
+            error("?x=1&a[b=2").to_string(),
+            "invalid query string at byte 5: malformed name"
+        );
+        for text in ["=1", "[a]=1", "a..b=1", "a[b]c=1", "a[b]=1&a=2"] {
+            assert!(from_query_string(text).is_err(), "{}", text);
+        }
+        assert_eq!(
+            error(&format!("a{}=1", "[x]".repeat(200_000))),
+            QueryStringError::Syntax {
+                offset: 0,
+                message: "the name is nested too deep"
+            }
+        );
+        let deepest = format!("a{}=1", "[x]".repeat(MAX_DEPTH - 1));
+        assert!(from_query_string(&deepest).is_ok());
+    }
+
+    #[test]
+    fn writes_query_strings() {
+        let value = parse(
+            r#"{"q": "a b&c", "page": 2, "on": true, "none": null, "tags": ["x", "y"], "f": {"k": {"l": "é"}}}"#,
+        )
+        .unwrap();
+        let brackets = to_query_string(&value, QueryStringStyle::Brackets).unwrap();
+        assert_eq!(
+            brackets,
+            "f[k][l]=%C3%A9&none&on=true&page=2&q=a+b%26c&tags[0]=x&tags[1]=y"
+        );
+        assert_eq!(
+            to_query_string(&value, QueryStringStyle::Dots).unwrap(),
+            "f.k.l=%C3%A9&none&on=true&page=2&q=a+b%26c&tags.0=x&tags.1=y"
+        );
+        let strings = parse(
+            r#"{"q": "a b&c", "page": "2", "on": "true", "none": null, "tags": ["x", "y"], "f": {"k": {"l": "é"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(from_query_string(&brackets), Ok(strings));
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        let error = |json: &str, style| {
+            to_query_string(&parse(json).unwrap(), style)
+                .unwrap_err()
+                .to_string()
+        };
+        let brackets = QueryStringStyle::Brackets;
+        assert_eq!(
+            error("[1]", brackets),
+            "'': a query string must be an object"
+        );
+        assert_eq!(
+            error(r#"{"a": {"b": []}}"#, brackets),
+            "'/a/b': empty arrays and objects have no query string form"
+        );
+        assert_eq!(
+            error(r#"{"a.b": 1}"#, brackets),
+            "'/a.b': the key cannot be written as part of a name"
+        );
+        assert_eq!(
+            error(r#"{"a": {"b.c": 1}}"#, QueryStringStyle::Dots),
+            "'/a/b.c': the key cannot be written as part of a name"
+        );
+        assert_eq!(
+            to_query_string(&parse(r#"{"a": {"b.c": 1}}"#).unwrap(), brackets),
+            Ok("a[b.c]=1".into())
+        );
+    }
+}
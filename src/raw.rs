@@ -0,0 +1,171 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::lexer::{decode_string, Lexer, TokenKind};
+use crate::parser::{skip_document, skip_value};
+use crate::{parse, JsonError, Type};
+
+/// The unparsed text of a single, syntactically valid JSON value.
+///
+/// Creating a `RawValue` validates the text without building a tree, so a
+/// large document can be split into its members cheaply and only the parts
+/// that are needed parsed with [`RawValue::parse`]. The text is written out
+/// verbatim when the value is displayed.
+///
+/// A `RawValue` stands alone: a [`Type`] cannot hold one, so the parsers
+/// never produce one inside a tree and [`to_string`](crate::to_string)
+/// never passes one through. To put one in a tree, parse it; to pass it
+/// through, write its text into the output directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawValue {
+    json: String,
+}
+
+impl RawValue {
+    pub fn get(&self) -> &str {
+        &self.json
+    }
+
+    pub fn into_string(self) -> String {
+        self.json
+    }
+
+    pub fn parse(&self) -> Result<Type, JsonError> {
+        parse(&self.json)
+    }
+
+    /// Splits an object into its keys and unparsed member values, or returns
+    /// `None` if the value is not an object.
+    pub fn members(&self) -> Option<Vec<(String, RawValue)>> {
+        let mut lexer = Lexer::new(self.json.as_bytes()).skip_bom();
+        if lexer.next_token().ok()??.kind != TokenKind::BeginObject {
+            return None;
+        }
+        let mut members = Vec::new();
+        loop {
+            let token = lexer.next_token().ok()??;
+            match token.kind {
+                TokenKind::EndObject => return Some(members),
+                TokenKind::Comma => continue,
+                _ => {}
+            }
+            let key = &self.json.as_bytes()[token.start + 1..token.end - 1];
+            let key = decode_string(key, 0).ok()?;
+            lexer.next_token().ok()??; // the colon
+            members.push((key, self.slice(&mut lexer)?));
+        }
+    }
+
+    /// Splits an array into its unparsed elements, or returns `None` if the
+    /// value is not an array.
+    pub fn elements(&self) -> Option<Vec<RawValue>> {
+        let mut lexer = Lexer::new(self.json.as_bytes()).skip_bom();
+        if lexer.next_token().ok()??.kind != TokenKind::BeginArray {
+            return None;
+        }
+        let mut elements = Vec::new();
+        let mut peek = lexer.clone();
+        if peek.next_token().ok()??.kind == TokenKind::EndArray {
+            return Some(elements);
+        }
+        loop {
+            elements.push(self.slice(&mut lexer)?);
+            if lexer.next_token().ok()??.kind == TokenKind::EndArray {
+                return Some(elements);
+            }
+        }
+    }
+
+    fn slice(&self, lexer: &mut Lexer) -> Option<RawValue> {
+        let (start, end) = skip_value(lexer).ok()?;
+        Some(RawValue {
+            json: self.json[start..end].to_string(),
+        })
+    }
+}
+
+impl FromStr for RawValue {
+    type Err = JsonError;
+
+    /// Validates `json`, which must hold exactly one value. Surrounding
+    /// whitespace is dropped, and so is a leading UTF-8 byte-order mark.
+    fn from_str(json: &str) -> Result<RawValue, JsonError> {
+        let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+        let (start, end) = skip_document(&mut lexer)?;
+        Ok(RawValue {
+            json: json[start..end].to_string(),
+        })
+    }
+}
+
+impl fmt::Display for RawValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawValue;
+    use crate::{parse, Expected, JsonError, Type};
+    use std::str::FromStr;
+
+    #[test]
+    fn validates_without_parsing() {
+        let raw = RawValue::from_str(" {\"big\": [1, 2, {\"x\": null}]} ").unwrap();
+        assert_eq!(raw.get(), "{\"big\": [1, 2, {\"x\": null}]}");
+        assert_eq!(raw.to_string(), raw.get());
+        assert_eq!(raw.parse().unwrap(), parse(raw.get()).unwrap());
+
+        assert_eq!(
+            RawValue::from_str("[1,]").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::VALUE
+            }
+        );
+        assert_eq!(
+            RawValue::from_str("\"\\ud800\"").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 1,
+                expected: Expected::NONE
+            }
+        );
+        assert_eq!(
+            RawValue::from_str("1 2").unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 2,
+                expected: Expected::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn lazy_members() {
+        let raw = RawValue::from_str(r#"{"id": 7, "payload": {"huge": [1, 2, 3]}}"#).unwrap();
+        let members = raw.members().unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].0, "id");
+        assert_eq!(members[0].1.parse().unwrap(), Type::Number(7.into()));
+        assert_eq!(members[1].0, "payload");
+        assert_eq!(members[1].1.get(), r#"{"huge": [1, 2, 3]}"#);
+
+        let elements = members[1].1.members().unwrap()[0].1.elements().unwrap();
+        let texts: Vec<_> = elements.iter().map(RawValue::get).collect();
+        assert_eq!(texts, vec!["1", "2", "3"]);
+        assert_eq!(RawValue::from_str("[]").unwrap().elements(), Some(vec![]));
+        assert_eq!(RawValue::from_str("{}").unwrap().members(), Some(vec![]));
+        assert_eq!(raw.elements(), None);
+    }
+
+    #[test]
+    fn drops_bom() {
+        let raw = RawValue::from_str("\u{feff} {\"a\": [1]}").unwrap();
+        assert_eq!(raw.get(), "{\"a\": [1]}");
+        let members = raw.members().unwrap();
+        assert_eq!(members[0].1.elements().unwrap()[0].get(), "1");
+        assert_eq!(raw.parse().unwrap(), parse(r#"{"a": [1]}"#).unwrap());
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::pointer::parse_index;
+use crate::{into_str, JsonError, JsonPointer, Type};
+
+/// What [`redact`] puts in place of a matched value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redaction {
+    /// The string `"***"`.
+    Mask,
+    /// The value's [`Type::content_hash`] as a 16-digit hex string, so that
+    /// equal secrets can still be correlated across log lines. The hash is
+    /// not cryptographic; short secrets can be recovered by brute force.
+    Hash,
+    /// `null`.
+    Null,
+}
+
+impl Redaction {
+    fn apply(self, value: &Type) -> Type {
+        match self {
+            Redaction::Mask => Type::String("***".into()),
+            Redaction::Hash => Type::String(into_str(format!("{:016x}", value.content_hash()))),
+            Redaction::Null => Type::Null,
+        }
+    }
+}
+
+/// Replaces every value matched by one of `patterns` according to `policy`,
+/// e.g. to log a request body without its credentials. Returns how many
+/// values were replaced.
+///
+/// A pattern is a JSON Pointer whose tokens may also be `*`, matching any
+/// one key or index, or `**`, matching any number of levels, none included:
+/// `/users/*/password` or `/**/token`. A matched value is replaced whole and
+/// not searched further. Malformed patterns are `JsonError::InvalidPath`,
+/// and nothing is replaced.
+pub fn redact(value: &mut Type, patterns: &[&str], policy: Redaction) -> Result<usize, JsonError> {
+    let patterns = patterns
+        .iter()
+        .map(|pattern| pattern.parse())
+        .collect::<Result<Vec<JsonPointer>, _>>()?;
+    let states = patterns.iter().map(|pattern| pattern.tokens()).collect();
+    Ok(walk(value, states, policy))
+}
+
+/// Redacts `value` given the rest of each pattern that reached it.
+fn walk(value: &mut Type, mut states: Vec<&[String]>, policy: Redaction) -> usize {
+    // `**` may match no levels at all, so it also stands for what follows it.
+    let mut i = 0;
+    while i < states.len() {
+        if let [first, rest @ ..] = states[i] {
+            if first == "**" {
+                states.push(rest);
+            }
+        }
+        i += 1;
+    }
+    if states.iter().any(|state| state.is_empty()) {
+        *value = policy.apply(value);
+        return 1;
+    }
+    let descend = |matches: &dyn Fn(&str) -> bool| -> Vec<&[String]> {
+        states
+            .iter()
+            .filter_map(|state| match state {
+                [first, ..] if first == "**" => Some(*state),
+                [first, rest @ ..] if first == "*" || matches(first) => Some(rest),
+                _ => None,
+            })
+            .collect()
+    };
+    let mut count = 0;
+    match value {
+        Type::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let states = descend(&|token| parse_index(token) == Some(index));
+                if !states.is_empty() {
+                    count += walk(item, states, policy);
+                }
+            }
+        }
+        Type::Object(members) => {
+            for (key, member) in members.iter_mut() {
+                let states = descend(&|token| token == &**key);
+                if !states.is_empty() {
+                    count += walk(member, states, policy);
+                }
+            }
+        }
+        _ => {}
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact, Redaction};
+    use crate::{parse, JsonError, Type};
+
+    #[test]
+    fn redacts_matches() {
+        let mut value = parse(
+            r#"{"token": "t", "users": [{"name": "a", "password": "p"}, {"name": "b", "password": {"x": 1}}],
+                "nested": {"deep": {"token": "u"}}}"#,
+        )
+        .unwrap();
+        let count = redact(
+            &mut value,
+            &["/users/*/password", "/**/token"],
+            Redaction::Mask,
+        )
+        .unwrap();
+        assert_eq!(count, 4);
+        assert_eq!(
+            value,
+            parse(
+                r#"{"token": "***", "users": [{"name": "a", "password": "***"}, {"name": "b", "password": "***"}],
+                    "nested": {"deep": {"token": "***"}}}"#
+            )
+            .unwrap()
+        );
+
+        let mut value = parse(r#"[{"ssn": "1"}, {"ssn": "1"}, {"ssn": "2"}]"#).unwrap();
+        assert_eq!(
+            redact(&mut value, &["/1/ssn", "/*/ssn"], Redaction::Null),
+            Ok(3)
+        );
+        assert_eq!(
+            value,
+            parse(r#"[{"ssn": null}, {"ssn": null}, {"ssn": null}]"#).unwrap()
+        );
+
+        let mut value = parse(r#"[{"ssn": "1"}, {"ssn": "1"}, {"ssn": "2"}]"#).unwrap();
+        redact(&mut value, &["/*/ssn"], Redaction::Hash).unwrap();
+        let hash = |i: usize| value.pointer(&format!("/{}/ssn", i)).cloned().unwrap();
+        assert_eq!(hash(0), hash(1));
+        assert_ne!(hash(0), hash(2));
+        assert_eq!(
+            hash(2),
+            Type::String(
+                format!("{:016x}", Type::String("2".into()).content_hash())
+                    .as_str()
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn root_and_errors() {
+        let mut value = parse("[1, 2]").unwrap();
+        assert_eq!(redact(&mut value, &["/**/missing"], Redaction::Mask), Ok(0));
+        assert_eq!(redact(&mut value, &["/**"], Redaction::Mask), Ok(1));
+        assert_eq!(value, Type::String("***".into()));
+        assert_eq!(
+            redact(&mut value, &["", "nope"], Redaction::Mask),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(value, Type::String("***".into()));
+    }
+}
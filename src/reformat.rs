@@ -0,0 +1,211 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::lexer::{Lexer, BOM};
+use crate::parser::{Event, Parser};
+use crate::ser::IoWriter;
+use crate::{CompactFormatter, Formatter, JsonError, PrettyFormatter};
+
+/// Copies the JSON document in `reader` to `writer`, laid out by
+/// `formatter`, one event at a time: memory use depends on the nesting
+/// depth and the longest token, not the size of the document, so files too
+/// large to load can still be reformatted.
+///
+/// Unlike [`prettify`](crate::prettify), values are decoded and written
+/// back, so numbers come out as [`to_string`](crate::to_string) would write
+/// them. Output is written as the input is read, so a syntax error can leave
+/// part of the document already written. Error offsets count bytes of
+/// `reader`.
+pub fn reformat<R: Read, W: Write, F: Formatter>(
+    mut reader: R,
+    writer: W,
+    formatter: F,
+) -> Result<(), JsonError> {
+    let mut emitter = Emitter {
+        writer: IoWriter {
+            inner: writer,
+            error: None,
+        },
+        formatter,
+        open: Vec::new(),
+    };
+    let mut parser = Parser::new();
+    let mut buf = Vec::new();
+    // Offset of `buf[0]` in the whole input.
+    let mut base = 0;
+    let mut chunk = [0; 8192];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        buf.extend_from_slice(&chunk[..read]);
+        let eof = read == 0;
+        // Wait for a whole byte-order mark before deciding to skip one.
+        if !eof && base == 0 && buf.len() < BOM.len() && BOM.starts_with(&buf) {
+            continue;
+        }
+        let lexer = if eof {
+            Lexer::new(&buf)
+        } else {
+            Lexer::partial(&buf)
+        };
+        let mut lexer = if base == 0 { lexer.skip_bom() } else { lexer };
+        while let Some(event) = parser
+            .next_event(&mut lexer)
+            .map_err(|err| err.shifted(base))?
+        {
+            emitter.emit(event)?;
+        }
+        if eof {
+            break;
+        }
+        let consumed = lexer.pos();
+        buf.drain(..consumed);
+        base += consumed;
+    }
+    emitter.writer.inner.flush()?;
+    Ok(())
+}
+
+/// [`reformat`] with one member or element per line, each nesting level
+/// indented by `indent`.
+pub fn prettify_reader<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    indent: &str,
+) -> Result<(), JsonError> {
+    reformat(reader, writer, PrettyFormatter::with_indent(indent))
+}
+
+/// [`reformat`] with all whitespace removed.
+pub fn minify_reader<R: Read, W: Write>(reader: R, writer: W) -> Result<(), JsonError> {
+    reformat(reader, writer, CompactFormatter)
+}
+
+/// Turns events back into text through a [`Formatter`].
+struct Emitter<W: Write, F> {
+    writer: IoWriter<W>,
+    formatter: F,
+    /// For each open container, whether it is an object, and whether it has
+    /// no members or elements yet.
+    open: Vec<(bool, bool)>,
+}
+
+impl<W: Write, F: Formatter> Emitter<W, F> {
+    fn emit(&mut self, event: Event) -> Result<(), JsonError> {
+        let result = self.write(event);
+        result.map_err(|_| {
+            let err = self.writer.error.take();
+            err.unwrap_or_else(|| io::Error::other("formatter error"))
+                .into()
+        })
+    }
+
+    fn write(&mut self, event: Event) -> fmt::Result {
+        let (formatter, writer) = (&mut self.formatter, &mut self.writer);
+        match event {
+            Event::Key(key) => {
+                let (_, first) = self.open.last_mut().expect("keys are inside objects");
+                formatter.begin_object_key(writer, *first)?;
+                *first = false;
+                formatter.write_string(writer, &key)?;
+                return formatter.begin_object_value(writer);
+            }
+            Event::EndArray => {
+                self.open.pop();
+                formatter.end_array(writer)?;
+            }
+            Event::EndObject => {
+                self.open.pop();
+                formatter.end_object(writer)?;
+            }
+            event => {
+                if let Some((false, first)) = self.open.last_mut() {
+                    formatter.begin_array_value(writer, *first)?;
+                    *first = false;
+                }
+                match event {
+                    Event::StartArray => {
+                        self.open.push((false, true));
+                        return formatter.begin_array(writer);
+                    }
+                    Event::StartObject => {
+                        self.open.push((true, true));
+                        return formatter.begin_object(writer);
+                    }
+                    Event::Null => formatter.write_null(writer)?,
+                    Event::Boolean(b) => formatter.write_bool(writer, b)?,
+                    Event::Number(n) => formatter.write_number(writer, n)?,
+                    Event::String(s) => formatter.write_string(writer, &s)?,
+                    _ => unreachable!("handled above"),
+                }
+            }
+        }
+        // A value has ended; close its slot in the enclosing container.
+        match self.open.last() {
+            Some((true, _)) => formatter.end_object_value(writer),
+            Some((false, _)) => formatter.end_array_value(writer),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::{minify_reader, prettify_reader};
+    use crate::{parse, to_string, to_string_pretty, JsonError};
+
+    /// Hands out its input a few bytes at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(3);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn reformats_streams() {
+        // One member per object, so the expected text does not depend on
+        // map order.
+        let input =
+            "\u{feff} [{\"a\": [1, {\"b\": null}, [], {}]}, \"x\\ny\", {\"d\": {\"e\": true}}] ";
+        let value = parse(input).unwrap();
+        let mut out = Vec::new();
+        prettify_reader(Trickle(input.as_bytes()), &mut out, "  ").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), to_string_pretty(&value));
+        let mut out = Vec::new();
+        minify_reader(input.as_bytes(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), to_string(&value));
+
+        let mut out = Vec::new();
+        minify_reader(&b"\"s\""[..], &mut out).unwrap();
+        assert_eq!(out, b"\"s\"");
+    }
+
+    #[test]
+    fn reports_errors_at_input_offsets() {
+        let mut out = Vec::new();
+        assert_eq!(
+            minify_reader(Trickle(b"[1, 2, 3,"), &mut out),
+            Err(JsonError::UnexpectEof { offset: 9 })
+        );
+        assert_eq!(
+            minify_reader(Trickle(b"[1, 2] x"), &mut Vec::new())
+                .unwrap_err()
+                .offset(),
+            Some(7)
+        );
+        assert_eq!(
+            minify_reader(&b""[..], &mut Vec::new()),
+            Err(JsonError::UnexpectEof { offset: 0 })
+        );
+    }
+}
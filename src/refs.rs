@@ -0,0 +1,396 @@
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{JsonPointer, Type, MAX_DEPTH};
+
+/// A `$ref` [`Type::resolve_refs`] could not resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefError {
+    /// The reference, made absolute against the base URI.
+    pub reference: String,
+    pub message: String,
+}
+
+impl fmt::Display for RefError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}': {}", self.reference, self.message)
+    }
+}
+
+impl core::error::Error for RefError {}
+
+/// How many nodes references may bring in all, so that a small document
+/// of definitions that each reference the next several times cannot expand
+/// to billions of nodes.
+const MAX_EXPANDED_NODES: usize = 1 << 20;
+
+type Fetch<'a> = dyn FnMut(&str) -> Result<Type, String> + 'a;
+
+/// The documents met so far, by URI without fragment.
+struct Documents<'a> {
+    loaded: BTreeMap<String, Rc<Type>>,
+    fetch: Option<&'a mut Fetch<'a>>,
+    /// How many nodes references have brought in so far.
+    expanded: usize,
+}
+
+impl Type {
+    /// Replaces each object with a string `$ref` member by the value it
+    /// references (JSON Reference, as OpenAPI and JSON Schema use it), so
+    /// that the document can be read without following the references.
+    /// `base` is the document's URI, against which references are made
+    /// absolute; here only those into the document itself, like
+    /// `#/definitions/user`, can be resolved.
+    ///
+    /// Referenced values are resolved in turn. The `$ref` object's other
+    /// members are dropped. A reference that leads back to itself, as in a
+    /// recursive schema, has no finite expansion and is an error, as is one
+    /// that addresses nothing. So are references nested, or values nested
+    /// by them, deeper than [`MAX_DEPTH`], and references that bring in more
+    /// than 2^20 nodes in all.
+    pub fn resolve_refs(&mut self, base: &str) -> Result<(), RefError> {
+        let mut documents = Documents {
+            loaded: BTreeMap::new(),
+            fetch: None,
+            expanded: 0,
+        };
+        self.resolve_refs_in(base, &mut documents)
+    }
+
+    /// Like [`resolve_refs`](Type::resolve_refs), also resolving references
+    /// into other documents, which `fetch` is given the absolute URI of,
+    /// without fragment, and loads. Each document is fetched once.
+    pub fn resolve_refs_with<F: FnMut(&str) -> Result<Type, String>>(
+        &mut self,
+        base: &str,
+        mut fetch: F,
+    ) -> Result<(), RefError> {
+        let mut documents = Documents {
+            loaded: BTreeMap::new(),
+            fetch: Some(&mut fetch),
+            expanded: 0,
+        };
+        self.resolve_refs_in(base, &mut documents)
+    }
+
+    fn resolve_refs_in(&mut self, base: &str, documents: &mut Documents) -> Result<(), RefError> {
+        let base = strip_fragment(base);
+        documents
+            .loaded
+            .insert(base.to_string(), Rc::new(self.clone()));
+        resolve(self, base, documents, &mut Vec::new(), 0)
+    }
+}
+
+/// Resolves the references in `value`, which is inside `depth` arrays and
+/// objects and was brought in by the references on `stack`.
+fn resolve(
+    value: &mut Type,
+    uri: &str,
+    documents: &mut Documents,
+    stack: &mut Vec<String>,
+    depth: usize,
+) -> Result<(), RefError> {
+    let limit = |stack: &[String], message: &str| RefError {
+        reference: stack.last().cloned().unwrap_or_else(|| uri.to_string()),
+        message: message.into(),
+    };
+    if !stack.is_empty() {
+        documents.expanded += 1;
+        if documents.expanded > MAX_EXPANDED_NODES {
+            return Err(limit(stack, "references expand to too many nodes"));
+        }
+    }
+    let is_container = matches!(value, Type::Array(_) | Type::Object(_));
+    if is_container && depth == MAX_DEPTH {
+        return Err(limit(stack, "references nested too deep"));
+    }
+    match value {
+        Type::Object(members) => {
+            let Some(Type::String(reference)) = members.get("$ref") else {
+                for (_, member) in members.iter_mut() {
+                    resolve(member, uri, documents, stack, depth + 1)?;
+                }
+                return Ok(());
+            };
+            let reference = join(uri, reference);
+            let error = |message: &str| RefError {
+                reference: reference.clone(),
+                message: message.into(),
+            };
+            if stack.contains(&reference) {
+                return Err(error("circular reference"));
+            }
+            if stack.len() == MAX_DEPTH {
+                return Err(error("references nested too deep"));
+            }
+            let (target_uri, fragment) = reference
+                .split_once('#')
+                .unwrap_or((reference.as_str(), ""));
+            let document = documents
+                .get(target_uri)
+                .map_err(|message| error(&message))?;
+            let pointer: JsonPointer = percent_decode(fragment)
+                .parse()
+                .map_err(|_| error("malformed JSON Pointer"))?;
+            let mut target = document
+                .at_tokens(pointer.tokens())
+                .ok_or_else(|| error("no such value"))?
+                .clone();
+            let target_uri = target_uri.to_string();
+            stack.push(reference);
+            resolve(&mut target, &target_uri, documents, stack, depth)?;
+            stack.pop();
+            *value = target;
+        }
+        Type::Array(items) => {
+            for item in items {
+                resolve(item, uri, documents, stack, depth + 1)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl Documents<'_> {
+    fn get(&mut self, uri: &str) -> Result<Rc<Type>, String> {
+        if let Some(document) = self.loaded.get(uri) {
+            return Ok(document.clone());
+        }
+        let Some(fetch) = self.fetch.as_mut() else {
+            return Err("external references need a fetch callback".into());
+        };
+        let document = Rc::new(fetch(uri)?);
+        self.loaded.insert(uri.into(), document.clone());
+        Ok(document)
+    }
+}
+
+fn strip_fragment(uri: &str) -> &str {
+    uri.split_once('#').map_or(uri, |(uri, _)| uri)
+}
+
+/// Resolves `reference` against `base` as RFC 3986 does, for the forms a
+/// `$ref` takes: a fragment, an absolute URI, or an absolute or relative
+/// path.
+fn join(base: &str, reference: &str) -> String {
+    let base = strip_fragment(base);
+    let scheme = reference.find(':').filter(|&end| {
+        let scheme = &reference[..end];
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+    if reference.starts_with('#') {
+        return [base, reference].concat();
+    }
+    if scheme.is_some() {
+        return reference.to_string();
+    }
+    // The part of `base` before its path: the scheme and the authority.
+    let authority_end = match base.find("://") {
+        Some(start) => base[start + 3..]
+            .find('/')
+            .map_or(base.len(), |end| start + 3 + end),
+        None => 0,
+    };
+    if let Some(rest) = reference.strip_prefix("//") {
+        let scheme_end = base.find(':').map_or(0, |end| end + 1);
+        return [&base[..scheme_end], "//", rest].concat();
+    }
+    let path = if reference.starts_with('/') {
+        reference.to_string()
+    } else {
+        let dir = base[authority_end..]
+            .rfind('/')
+            .map_or("", |end| &base[authority_end..authority_end + end + 1]);
+        [dir, reference].concat()
+    };
+    [&base[..authority_end], &remove_dot_segments(&path)].concat()
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let (path, fragment) = path
+        .split_once('#')
+        .map_or((path, None), |(path, fragment)| (path, Some(fragment)));
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path.split('/').peekable();
+    while let Some(part) = parts.next() {
+        let last = parts.peek().is_none();
+        match part {
+            "." if last => segments.push(""),
+            "." => {}
+            ".." => {
+                if segments.len() > 1 || segments.first().is_some_and(|s| !s.is_empty()) {
+                    segments.pop();
+                }
+                if last {
+                    segments.push("");
+                }
+            }
+            part => segments.push(part),
+        }
+    }
+    let mut out = segments.join("/");
+    if let Some(fragment) = fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = |i: usize| {
+            bytes
+                .get(i)
+                .and_then(|&b| (b as char).to_digit(16))
+                .map(|d| d as u8)
+        };
+        match (bytes[i], hex(i + 1), hex(i + 2)) {
+            (b'%', Some(high), Some(low)) => {
+                out.push(high << 4 | low);
+                i += 3;
+            }
+            (b, _, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::String;
+
+    use super::join;
+    use crate::{parse, MAX_DEPTH};
+
+    #[test]
+    fn joins_uris() {
+        let base = "https://example.com/api/v1/openapi.json#/x";
+        for (reference, joined) in [
+            ("#/a", "https://example.com/api/v1/openapi.json#/a"),
+            ("user.json", "https://example.com/api/v1/user.json"),
+            (
+                "../common/user.json#/u",
+                "https://example.com/api/common/user.json#/u",
+            ),
+            ("./a/./b.json", "https://example.com/api/v1/a/b.json"),
+            ("/root.json", "https://example.com/root.json"),
+            ("//other.org/x.json", "https://other.org/x.json"),
+            ("urn:x:y", "urn:x:y"),
+        ] {
+            assert_eq!(join(base, reference), joined, "{}", reference);
+        }
+        assert_eq!(join("schemas/main.json", "user.json"), "schemas/user.json");
+        assert_eq!(join("", "#/a"), "#/a");
+    }
+
+    #[test]
+    fn resolves_internal_refs() {
+        let mut doc = parse(
+            r##"{"definitions": {"id": {"type": "integer"}, "user": {"properties": {"id": {"$ref": "#/definitions/id"}, "$ref": {"type": "string"}}}, "a b": 1},
+                "items": [{"$ref": "#/definitions/user", "description": "dropped"}, {"$ref": "#/definitions/a%20b"}]}"##,
+        )
+        .unwrap();
+        doc.resolve_refs("").unwrap();
+        assert_eq!(
+            doc.pointer("/items").unwrap(),
+            &parse(
+                r#"[{"properties": {"id": {"type": "integer"}, "$ref": {"type": "string"}}}, 1]"#
+            )
+            .unwrap()
+        );
+
+        let mut doc = parse(r##"{"node": {"next": {"$ref": "#/node"}}}"##).unwrap();
+        assert_eq!(
+            doc.resolve_refs("file.json").unwrap_err().to_string(),
+            "'file.json#/node': circular reference"
+        );
+        let mut doc = parse(r##"[{"$ref": "#/missing"}]"##).unwrap();
+        assert_eq!(doc.resolve_refs("").unwrap_err().message, "no such value");
+        let mut doc = parse(r##"[{"$ref": "other.json"}]"##).unwrap();
+        assert!(doc.resolve_refs("").is_err());
+    }
+
+    /// Definitions `d0` to `d{n - 1}`, each `make(i)`, with `d{n}` null.
+    fn chain(n: usize, make: impl Fn(usize) -> String) -> String {
+        let definitions: String = (0..n)
+            .map(|i| format!(r#""d{}": {}, "#, i, make(i)))
+            .collect();
+        format!(r#"{{"definitions": {{{}"d{}": null}}}}"#, definitions, n)
+    }
+
+    #[test]
+    fn limits_expansion() {
+        let twice = |i| {
+            format!(
+                r##"[{{"$ref": "#/definitions/d{0}"}}, {{"$ref": "#/definitions/d{0}"}}]"##,
+                i + 1
+            )
+        };
+        let mut doc = parse(&chain(30, twice)).unwrap();
+        let err = doc.resolve_refs("").unwrap_err();
+        assert_eq!(err.message, "references expand to too many nodes");
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let once = |i| format!(r##"{{"$ref": "#/definitions/d{}"}}"##, i + 1);
+        let mut doc = parse(&chain(200, once)).unwrap();
+        let err = doc.resolve_refs("").unwrap_err();
+        assert_eq!(err.message, "references nested too deep");
+        let mut doc = parse(&chain(MAX_DEPTH - 1, once)).unwrap();
+        doc.resolve_refs("").unwrap();
+
+        let nest = |i| format!(r##"[{{"$ref": "#/definitions/d{}"}}]"##, i + 1);
+        let mut doc = parse(&chain(MAX_DEPTH, nest)).unwrap();
+        let err = doc.resolve_refs("").unwrap_err();
+        assert_eq!(err.message, "references nested too deep");
+    }
+
+    #[test]
+    fn fetches_external_refs() {
+        let mut doc = parse(
+            r##"{"a": {"$ref": "common.json#/user"}, "b": {"$ref": "sub/x.json"}, "c": {"$ref": "common.json"}}"##,
+        )
+        .unwrap();
+        let mut fetched = Vec::new();
+        doc.resolve_refs_with("https://h/api/main.json", |uri| {
+            fetched.push(uri.to_string());
+            match uri {
+                "https://h/api/common.json" => {
+                    parse(r##"{"user": {"name": {"$ref": "#/name"}}, "name": "string"}"##)
+                        .map_err(|err| err.to_string())
+                }
+                "https://h/api/sub/x.json" => {
+                    parse(r##"{"$ref": "../common.json#/name"}"##).map_err(|err| err.to_string())
+                }
+                _ => Err("not found".into()),
+            }
+        })
+        .unwrap();
+        assert_eq!(
+            doc,
+            parse(r#"{"a": {"name": "string"}, "b": "string", "c": {"user": {"name": "string"}, "name": "string"}}"#)
+                .unwrap()
+        );
+        fetched.sort();
+        assert_eq!(
+            fetched,
+            ["https://h/api/common.json", "https://h/api/sub/x.json"]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::lexer::is_whitespace;
+use crate::lines::{floor_char_boundary, LineIndex};
+use crate::JsonError;
+
+impl JsonError {
+    /// Formats the error for people: the message, the line of `source` it
+    /// points at with a caret under the offending character, and a hint
+    /// when the mistake is a common one.
+    ///
+    /// ```text
+    /// error: unexpected token at byte 12
+    ///  --> 3:1
+    ///   |
+    /// 3 | }
+    ///   | ^ remove the trailing comma
+    /// ```
+    ///
+    /// `source` must be the input the error came from. Errors without an
+    /// offset render as the message alone.
+    pub fn render(&self, source: &str) -> String {
+        let Some(offset) = self.offset() else {
+            return format!("error: {}", self);
+        };
+        let offset = floor_char_boundary(source, offset.min(source.len()));
+        let lines = LineIndex::new(source);
+        let (number, column) = lines.line_col(offset);
+        let line_start = lines.line_start(number).unwrap_or(0);
+        let text = lines.line(number).unwrap_or("");
+
+        // Keep tabs so the caret lines up however they are displayed.
+        let pad: String = source[line_start..offset]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let gutter = " ".repeat(number.to_string().len());
+        let mut out = format!(
+            "error: {}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}^",
+            self, gutter, number, column, gutter, number, text, gutter, pad
+        );
+        if let Some(hint) = hint(self, source.as_bytes(), offset) {
+            out.push(' ');
+            out.push_str(&hint);
+        }
+        out
+    }
+}
+
+/// Guesses the cause of an error from the bytes around it, falling back to
+/// listing the tokens the grammar expected.
+fn hint(err: &JsonError, input: &[u8], offset: usize) -> Option<String> {
+    let expected = match err {
+        JsonError::UnexpectEof { .. } => {
+            return Some("the input ends early; is a bracket, brace or quote left open?".into())
+        }
+        JsonError::LimitExceeded { .. } => {
+            return Some("the document is over a parse limit".into())
+        }
+        JsonError::UnexpectToken { expected, .. } => *expected,
+        _ => return None,
+    };
+    let byte = *input.get(offset)?;
+    let prev = input[..offset].iter().rev().find(|&&b| !is_whitespace(b));
+    let adjacent = offset.checked_sub(1).map(|i| input[i]);
+    let hint = match (byte, prev, adjacent) {
+        (b']' | b'}', Some(b','), _) => "remove the trailing comma",
+        (b'\'', ..) => "strings and keys need double quotes",
+        (b'/', ..) => "JSON does not allow comments",
+        (b'=', ..) => "use ':' between a key and its value",
+        (b'+', ..) => "numbers cannot start with '+'",
+        (b'0'..=b'9', _, Some(b'0')) => "numbers cannot have leading zeros",
+        (_, _, Some(b'\\')) => "invalid escape sequence",
+        (0x00..=0x1f, ..) => "control characters in strings must be escaped",
+        (b'N' | b'I', ..) => "NaN and Infinity are not valid JSON numbers",
+        (b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'$', _, adjacent)
+            if !adjacent.is_some_and(|b| b.is_ascii_alphanumeric()) =>
+        {
+            "strings and keys must be quoted"
+        }
+        _ if !expected.is_empty() => return Some(format!("expected {}", expected)),
+        _ => return None,
+    };
+    Some(hint.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, JsonError};
+
+    fn render(source: &str) -> String {
+        parse(source).unwrap_err().render(source)
+    }
+
+    #[test]
+    fn points_at_the_error() {
+        assert_eq!(
+            render("{\n  \"a\": 1,\n}"),
+            "error: unexpected token at byte 12\n --> 3:1\n  |\n3 | }\n  | ^ remove the trailing comma"
+        );
+        assert_eq!(
+            render("[\"é\", 'x']"),
+            "error: unexpected token at byte 7\n --> 1:7\n  |\n1 | [\"é\", 'x']\n  |       ^ strings and keys need double quotes"
+        );
+        assert_eq!(
+            render("{\r\n\t\"a\": 01\r\n}"),
+            "error: unexpected token at byte 10\n --> 2:8\n  |\n2 | \t\"a\": 01\n  | \t      ^ numbers cannot have leading zeros"
+        );
+        assert_eq!(
+            render("[1, 2"),
+            "error: unexpected end of input at byte 5\n --> 1:6\n  |\n1 | [1, 2\n  |      ^ the input ends early; is a bracket, brace or quote left open?"
+        );
+        assert_eq!(
+            JsonError::InvalidPath.render("{}"),
+            "error: path does not address a settable value"
+        );
+    }
+
+    #[test]
+    fn hints() {
+        for (source, hint) in [
+            ("{a: 1}", Some("strings and keys must be quoted")),
+            ("[NaN]", Some("NaN and Infinity are not valid JSON numbers")),
+            ("[1] // x", Some("JSON does not allow comments")),
+            ("{\"a\" = 1}", Some("use ':' between a key and its value")),
+            ("[1 2]", Some("expected ',' or ']'")),
+            ("{\"a\": 1 \"b\": 2}", Some("expected ',' or '}'")),
+            ("{\"a\" 1}", Some("expected ':'")),
+            ("\"\\x\"", Some("invalid escape sequence")),
+            (
+                "\"a\tb\"",
+                Some("control characters in strings must be escaped"),
+            ),
+            ("[+1]", Some("numbers cannot start with '+'")),
+            ("[tru]", None),
+        ] {
+            let rendered = render(source);
+            let last = rendered.lines().last().unwrap();
+            match hint {
+                Some(hint) => assert!(last.ends_with(&format!("^ {}", hint)), "{}", rendered),
+                None => assert!(last.ends_with('^'), "{}", rendered),
+            }
+        }
+    }
+}
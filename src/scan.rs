@@ -0,0 +1,304 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{decode_string, Lexer, TokenKind};
+use crate::parser::{skip_value, Parser};
+use crate::{to_string, Expected, JsonError, Map, Type, MAX_DEPTH};
+
+/// Splits a dotted path such as `user.addresses.0.city` into its segments.
+/// A literal dot inside a key is written as `\.`.
+pub(crate) fn split_path(path: &str) -> Vec<String> {
+    if path.is_empty() {
+        return Vec::new();
+    }
+    let mut segments = vec![String::new()];
+    let mut chars = path.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    segments.last_mut().unwrap().push(escaped);
+                }
+            }
+            '.' => segments.push(String::new()),
+            ch => segments.last_mut().unwrap().push(ch),
+        }
+    }
+    segments
+}
+
+fn key_matches(raw: &[u8], offset: usize, segment: &str) -> Result<bool, JsonError> {
+    if raw.contains(&b'\\') {
+        Ok(decode_string(raw, offset)? == segment)
+    } else {
+        Ok(raw == segment.as_bytes())
+    }
+}
+
+/// Where [`seek`] stopped.
+enum Seek {
+    /// The lexer stands just before the addressed value.
+    Found,
+    /// Segment `depth` names a key or index missing from the container
+    /// whose closing bracket starts at byte `close`.
+    Missing {
+        depth: usize,
+        close: usize,
+        empty: bool,
+        array_len: Option<usize>,
+    },
+    /// A segment tried to descend into a scalar, or to index an array with
+    /// something other than a number.
+    Blocked,
+}
+
+/// Advances `lexer` through the document towards the value addressed by
+/// `segments`, skipping everything else without building it.
+fn seek(lexer: &mut Lexer, segments: &[String]) -> Result<Seek, JsonError> {
+    for (depth, segment) in segments.iter().enumerate() {
+        let token = lexer.expect_token()?;
+        match token.kind {
+            TokenKind::BeginObject => {
+                let mut empty = true;
+                loop {
+                    let token = lexer.expect_token()?;
+                    match token.kind {
+                        TokenKind::EndObject => {
+                            return Ok(Seek::Missing {
+                                depth,
+                                close: token.start,
+                                empty,
+                                array_len: None,
+                            })
+                        }
+                        TokenKind::Comma => continue,
+                        TokenKind::String => empty = false,
+                        _ => {
+                            return Err(JsonError::UnexpectToken {
+                                offset: token.start,
+                                expected: Expected::of(&[TokenKind::String, TokenKind::EndObject]),
+                            })
+                        }
+                    }
+                    let key = &lexer.input()[token.start + 1..token.end - 1];
+                    let colon = lexer.expect_token()?;
+                    if colon.kind != TokenKind::Colon {
+                        return Err(JsonError::UnexpectToken {
+                            offset: colon.start,
+                            expected: Expected::of(&[TokenKind::Colon]),
+                        });
+                    }
+                    if key_matches(key, token.start + 1, segment)? {
+                        break;
+                    }
+                    skip_value(lexer)?;
+                }
+            }
+            TokenKind::BeginArray => {
+                let Ok(index) = segment.parse::<usize>() else {
+                    return Ok(Seek::Blocked);
+                };
+                let mut len = 0;
+                loop {
+                    let mut peek = lexer.clone();
+                    let token = peek.expect_token()?;
+                    match token.kind {
+                        TokenKind::EndArray => {
+                            return Ok(Seek::Missing {
+                                depth,
+                                close: token.start,
+                                empty: len == 0,
+                                array_len: Some(len),
+                            })
+                        }
+                        TokenKind::Comma => *lexer = peek,
+                        _ if len == index => break,
+                        _ => {
+                            skip_value(lexer)?;
+                            len += 1;
+                        }
+                    }
+                }
+            }
+            _ => return Ok(Seek::Blocked),
+        }
+    }
+    Ok(Seek::Found)
+}
+
+/// Extracts the value at a dotted `path` (gjson style, e.g.
+/// `user.addresses.0.city`) straight from JSON text.
+///
+/// Only the requested value is materialized; everything before it is merely
+/// scanned and everything after it is not looked at, so a malformed document
+/// may still yield a value. An empty path selects the whole document. A
+/// leading UTF-8 byte-order mark is skipped.
+pub fn get_path(input: &str, path: &str) -> Result<Option<Type>, JsonError> {
+    let mut lexer = Lexer::new(input.as_bytes()).skip_bom();
+    if !matches!(seek(&mut lexer, &split_path(path))?, Seek::Found) {
+        return Ok(None);
+    }
+    let value = TreeBuilder::new().build(&mut Parser::new(), &mut lexer)?;
+    value
+        .ok_or(JsonError::UnexpectEof {
+            offset: input.len(),
+        })
+        .map(Some)
+}
+
+/// Replaces the value at a dotted `path` (sjson style) by splicing the
+/// compact serialization of `value` into `input`; every other byte of the
+/// document, a leading UTF-8 byte-order mark included, is kept as is.
+///
+/// A missing object key is appended to its object, creating nested objects
+/// for any remaining segments, and an index one past the end of an array
+/// appends to it. Paths through scalars, further out of range, or with more
+/// than [`MAX_DEPTH`] segments are `JsonError::InvalidPath`.
+pub fn set_path(input: &str, path: &str, value: &Type) -> Result<String, JsonError> {
+    let segments = split_path(path);
+    if segments.len() > MAX_DEPTH {
+        return Err(JsonError::InvalidPath);
+    }
+    let mut lexer = Lexer::new(input.as_bytes()).skip_bom();
+    let (start, end, insert) = match seek(&mut lexer, &segments)? {
+        Seek::Found => {
+            let (start, end) = skip_value(&mut lexer)?;
+            (start, end, to_string(value))
+        }
+        Seek::Missing {
+            depth,
+            close,
+            empty,
+            array_len,
+        } => {
+            let nested = nest(&segments[depth + 1..], value.clone());
+            let separator = if empty { "" } else { "," };
+            let insert = match array_len {
+                Some(len) if segments[depth] == len.to_string() => {
+                    format!("{}{}", separator, to_string(&nested))
+                }
+                Some(_) => return Err(JsonError::InvalidPath),
+                None => format!(
+                    "{}{}:{}",
+                    separator,
+                    to_string(&Type::String(segments[depth].as_str().into())),
+                    to_string(&nested)
+                ),
+            };
+            (close, close, insert)
+        }
+        Seek::Blocked => return Err(JsonError::InvalidPath),
+    };
+    Ok(format!("{}{}{}", &input[..start], insert, &input[end..]))
+}
+
+/// Wraps `value` in one single-member object per segment.
+fn nest(segments: &[String], value: Type) -> Type {
+    segments.iter().rev().fold(value, |value, key| {
+        Type::Object(Box::new(Map::from_iter([(key.as_str().into(), value)])))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_path, set_path, split_path};
+    use crate::{parse, JsonError, Type, MAX_DEPTH};
+
+    const DOC: &str = r#"{
+        "user": {
+            "name": "Ann",
+            "addresses": [{"city": "Oslo"}, {"city": "Rome", "zip": null}],
+            "a.b": true,
+            "q": 1
+        },
+        "tail": [1, 2
+    "#;
+
+    #[test]
+    fn paths() {
+        assert_eq!(split_path("a.b\\.c.0"), vec!["a", "b.c", "0"]);
+        assert_eq!(
+            get_path(DOC, "user.addresses.1.city").unwrap(),
+            Some(Type::String("Rome".into()))
+        );
+        assert_eq!(
+            get_path(DOC, "user.addresses.0").unwrap(),
+            Some(parse(r#"{"city": "Oslo"}"#).unwrap())
+        );
+        assert_eq!(
+            get_path(DOC, "user.a\\.b").unwrap(),
+            Some(Type::Boolean(true))
+        );
+        assert_eq!(
+            get_path(DOC, "user.q").unwrap(),
+            Some(Type::Number(1.into()))
+        );
+        assert_eq!(get_path(DOC, "user.addresses.2").unwrap(), None);
+        assert_eq!(get_path(DOC, "user.name.first").unwrap(), None);
+        assert_eq!(get_path(DOC, "user.missing").unwrap(), None);
+        assert_eq!(
+            get_path(DOC, "tail.5").unwrap_err(),
+            JsonError::UnexpectEof { offset: DOC.len() }
+        );
+        assert_eq!(
+            get_path("[1, 2]", "").unwrap(),
+            Some(parse("[1, 2]").unwrap())
+        );
+    }
+
+    #[test]
+    fn set_paths() {
+        let doc = "{\n  \"settings\": { \"theme\": \"dark\",  \"size\": 12 },\n  \"list\": []\n}";
+        let light = Type::String("light".into());
+        assert_eq!(
+            set_path(doc, "settings.theme", &light).unwrap(),
+            "{\n  \"settings\": { \"theme\": \"light\",  \"size\": 12 },\n  \"list\": []\n}"
+        );
+        assert_eq!(
+            set_path(doc, "settings.font.family", &light).unwrap(),
+            "{\n  \"settings\": { \"theme\": \"dark\",  \"size\": 12 ,\"font\":{\"family\":\"light\"}},\n  \"list\": []\n}"
+        );
+        let appended = set_path(doc, "list.0", &Type::Null).unwrap();
+        assert_eq!(
+            get_path(&appended, "list").unwrap(),
+            Some(parse("[null]").unwrap())
+        );
+        assert_eq!(
+            set_path(doc, "list.3", &Type::Null).unwrap_err(),
+            JsonError::InvalidPath
+        );
+        assert_eq!(
+            set_path(doc, "settings.size.x", &Type::Null).unwrap_err(),
+            JsonError::InvalidPath
+        );
+        assert_eq!(set_path("1", "", &light).unwrap(), "\"light\"");
+        for path in ["a.".repeat(200_000), "a.".repeat(MAX_DEPTH)] {
+            assert_eq!(
+                set_path("{}", &path, &Type::Null).unwrap_err(),
+                JsonError::InvalidPath
+            );
+        }
+        let deepest = set_path("{}", &"a.".repeat(MAX_DEPTH - 1), &Type::Null).unwrap();
+        assert!(parse(&deepest).is_ok());
+    }
+
+    #[test]
+    fn skips_bom() {
+        assert_eq!(
+            get_path("\u{feff}{\"a\": 1}", "a").unwrap(),
+            Some(Type::Number(1.into()))
+        );
+        assert_eq!(
+            set_path("\u{feff}{\"a\": 1}", "a", &Type::Null).unwrap(),
+            "\u{feff}{\"a\": null}"
+        );
+        assert_eq!(
+            set_path("\u{feff}1", "", &Type::Null).unwrap(),
+            "\u{feff}null"
+        );
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use regex::Regex;
+
+use crate::{to_string, JsonPointer, Type};
+
+/// A schema that could not be compiled: where in it, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub pointer: JsonPointer,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid schema at '{}': {}", self.pointer, self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// An instance breaking a rule of a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Where in the instance the offending value is.
+    pub pointer: JsonPointer,
+    /// The schema keyword that failed, e.g. `required`.
+    pub keyword: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}': {}", self.pointer, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl Kind {
+    fn of(value: &Type) -> Kind {
+        match value {
+            Type::Null => Kind::Null,
+            Type::Boolean(_) => Kind::Boolean,
+            Type::Number(n) if n.to_f64().fract() == 0.0 => Kind::Integer,
+            Type::Number(_) => Kind::Number,
+            Type::String(_) => Kind::String,
+            Type::Array(_) => Kind::Array,
+            Type::Object(_) => Kind::Object,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Null => "null",
+            Kind::Boolean => "boolean",
+            Kind::Integer => "integer",
+            Kind::Number => "number",
+            Kind::String => "string",
+            Kind::Array => "array",
+            Kind::Object => "object",
+        }
+    }
+
+    fn accepts(self, value: &Type) -> bool {
+        let kind = Kind::of(value);
+        kind == self || (self == Kind::Number && kind == Kind::Integer)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    /// `true` accepts everything and `false` nothing.
+    Bool(bool),
+    Rules(Box<Rules>),
+}
+
+#[derive(Debug, Default)]
+struct Rules {
+    types: Option<Vec<Kind>>,
+    allowed: Option<Vec<Type>>,
+    constant: Option<Type>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<Regex>,
+    items: Option<Node>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    properties: Vec<(String, Node)>,
+    required: Vec<String>,
+    additional_properties: Option<Node>,
+}
+
+/// A compiled JSON Schema.
+///
+/// The keywords understood are the common core of drafts 7 and 2020-12:
+/// `type`, `enum`, `const`, `minimum`, `maximum`, `exclusiveMinimum`,
+/// `exclusiveMaximum`, `minLength`, `maxLength`, `pattern`, `items`,
+/// `minItems`, `maxItems`, `properties`, `required` and
+/// `additionalProperties`, plus the boolean schemas `true` and `false`.
+/// Other keywords, `$ref` included, are ignored.
+#[derive(Debug)]
+pub struct Schema {
+    root: Node,
+}
+
+impl Schema {
+    pub fn compile(schema: &Type) -> Result<Schema, SchemaError> {
+        let mut pointer = JsonPointer::root();
+        Ok(Schema {
+            root: compile(schema, &mut pointer)?,
+        })
+    }
+
+    /// Checks `instance` against the schema, returning every violation. A
+    /// value's own violations come before those inside it, and object
+    /// members are visited in key order.
+    pub fn validate(&self, instance: &Type) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        let mut pointer = JsonPointer::root();
+        validate(&self.root, instance, &mut pointer, &mut violations);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub fn is_valid(&self, instance: &Type) -> bool {
+        self.validate(instance).is_ok()
+    }
+}
+
+fn compile(schema: &Type, pointer: &mut JsonPointer) -> Result<Node, SchemaError> {
+    let members = match schema {
+        Type::Boolean(accept) => return Ok(Node::Bool(*accept)),
+        Type::Object(members) => members,
+        _ => return Err(invalid(pointer, "a schema must be an object or a boolean")),
+    };
+    let mut rules = Rules::default();
+    for (keyword, value) in members.iter() {
+        pointer.push(&**keyword);
+        match &**keyword {
+            "type" => {
+                let names = match value {
+                    Type::String(name) => vec![name.as_str()],
+                    Type::Array(names) => names
+                        .iter()
+                        .map(|name| match name {
+                            Type::String(name) => Ok(name.as_str()),
+                            _ => Err(invalid(pointer, "type names must be strings")),
+                        })
+                        .collect::<Result<_, _>>()?,
+                    _ => return Err(invalid(pointer, "expected a type name or an array of them")),
+                };
+                let kinds = names
+                    .into_iter()
+                    .map(|name| parse_kind(name).ok_or_else(|| invalid(pointer, "unknown type")))
+                    .collect::<Result<_, _>>()?;
+                rules.types = Some(kinds);
+            }
+            "enum" => match value {
+                Type::Array(values) => rules.allowed = Some(values.clone()),
+                _ => return Err(invalid(pointer, "expected an array")),
+            },
+            "const" => rules.constant = Some(value.clone()),
+            "minimum" => rules.minimum = Some(number(value, pointer)?),
+            "maximum" => rules.maximum = Some(number(value, pointer)?),
+            "exclusiveMinimum" => rules.exclusive_minimum = Some(number(value, pointer)?),
+            "exclusiveMaximum" => rules.exclusive_maximum = Some(number(value, pointer)?),
+            "minLength" => rules.min_length = Some(count(value, pointer)?),
+            "maxLength" => rules.max_length = Some(count(value, pointer)?),
+            "minItems" => rules.min_items = Some(count(value, pointer)?),
+            "maxItems" => rules.max_items = Some(count(value, pointer)?),
+            "pattern" => match value {
+                Type::String(pattern) => {
+                    let regex = Regex::new(pattern)
+                        .map_err(|_| invalid(pointer, "invalid regular expression"))?;
+                    rules.pattern = Some(regex);
+                }
+                _ => return Err(invalid(pointer, "expected a string")),
+            },
+            "items" => rules.items = Some(compile(value, pointer)?),
+            "additionalProperties" => rules.additional_properties = Some(compile(value, pointer)?),
+            "properties" => {
+                let Type::Object(properties) = value else {
+                    return Err(invalid(pointer, "expected an object"));
+                };
+                let mut names: Vec<_> = properties.iter().collect();
+                names.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                for (name, schema) in names {
+                    pointer.push(&**name);
+                    rules
+                        .properties
+                        .push((name.to_string(), compile(schema, pointer)?));
+                    pointer.pop();
+                }
+            }
+            "required" => {
+                let Type::Array(names) = value else {
+                    return Err(invalid(pointer, "expected an array"));
+                };
+                for name in names {
+                    match name {
+                        Type::String(name) => rules.required.push(name.as_str().into()),
+                        _ => return Err(invalid(pointer, "required names must be strings")),
+                    }
+                }
+            }
+            _ => {}
+        }
+        pointer.pop();
+    }
+    Ok(Node::Rules(Box::new(rules)))
+}
+
+fn invalid(pointer: &JsonPointer, message: &str) -> SchemaError {
+    SchemaError {
+        pointer: pointer.clone(),
+        message: message.to_string(),
+    }
+}
+
+fn parse_kind(name: &str) -> Option<Kind> {
+    Some(match name {
+        "null" => Kind::Null,
+        "boolean" => Kind::Boolean,
+        "integer" => Kind::Integer,
+        "number" => Kind::Number,
+        "string" => Kind::String,
+        "array" => Kind::Array,
+        "object" => Kind::Object,
+        _ => return None,
+    })
+}
+
+fn number(value: &Type, pointer: &JsonPointer) -> Result<f64, SchemaError> {
+    match value {
+        Type::Number(n) => Ok(n.to_f64()),
+        _ => Err(invalid(pointer, "expected a number")),
+    }
+}
+
+fn count(value: &Type, pointer: &JsonPointer) -> Result<usize, SchemaError> {
+    match value {
+        Type::Number(n) => n.as_u64().and_then(|n| usize::try_from(n).ok()),
+        _ => None,
+    }
+    .ok_or_else(|| invalid(pointer, "expected a non-negative integer"))
+}
+
+fn validate(node: &Node, value: &Type, pointer: &mut JsonPointer, out: &mut Vec<Violation>) {
+    let rules = match node {
+        Node::Bool(true) => return,
+        Node::Bool(false) => {
+            return fail(
+                out,
+                pointer,
+                "false",
+                "no value is allowed here".to_string(),
+            )
+        }
+        Node::Rules(rules) => rules,
+    };
+    if let Some(types) = &rules.types {
+        if !types.iter().any(|kind| kind.accepts(value)) {
+            let names: Vec<_> = types.iter().map(|kind| kind.name()).collect();
+            let message = format!(
+                "expected {}, found {}",
+                names.join(" or "),
+                Kind::of(value).name()
+            );
+            fail(out, pointer, "type", message);
+        }
+    }
+    if let Some(allowed) = &rules.allowed {
+        if !allowed.contains(value) {
+            let message = format!("{} is not one of the allowed values", to_string(value));
+            fail(out, pointer, "enum", message);
+        }
+    }
+    if let Some(constant) = &rules.constant {
+        if value != constant {
+            fail(
+                out,
+                pointer,
+                "const",
+                format!("expected {}", to_string(constant)),
+            );
+        }
+    }
+    match value {
+        Type::Number(n) => {
+            let n = n.to_f64();
+            let broken = [
+                ("minimum", rules.minimum.filter(|&min| n < min), "at least"),
+                ("maximum", rules.maximum.filter(|&max| n > max), "at most"),
+                (
+                    "exclusiveMinimum",
+                    rules.exclusive_minimum.filter(|&min| n <= min),
+                    "greater than",
+                ),
+                (
+                    "exclusiveMaximum",
+                    rules.exclusive_maximum.filter(|&max| n >= max),
+                    "less than",
+                ),
+            ];
+            for (keyword, bound, relation) in broken {
+                if let Some(bound) = bound {
+                    let message = format!("{} is not {} {}", n, relation, bound);
+                    fail(out, pointer, keyword, message);
+                }
+            }
+        }
+        Type::String(s) => {
+            let len = s.chars().count();
+            if let Some(min) = rules.min_length.filter(|&min| len < min) {
+                let message = format!("shorter than {} characters", min);
+                fail(out, pointer, "minLength", message);
+            }
+            if let Some(max) = rules.max_length.filter(|&max| len > max) {
+                let message = format!("longer than {} characters", max);
+                fail(out, pointer, "maxLength", message);
+            }
+            if let Some(pattern) = &rules.pattern {
+                if !pattern.is_match(s) {
+                    let message = format!("does not match '{}'", pattern.as_str());
+                    fail(out, pointer, "pattern", message);
+                }
+            }
+        }
+        Type::Array(items) => {
+            if let Some(min) = rules.min_items.filter(|&min| items.len() < min) {
+                let message = format!("fewer than {} items", min);
+                fail(out, pointer, "minItems", message);
+            }
+            if let Some(max) = rules.max_items.filter(|&max| items.len() > max) {
+                let message = format!("more than {} items", max);
+                fail(out, pointer, "maxItems", message);
+            }
+            if let Some(schema) = &rules.items {
+                for (i, item) in items.iter().enumerate() {
+                    pointer.push(i.to_string());
+                    validate(schema, item, pointer, out);
+                    pointer.pop();
+                }
+            }
+        }
+        Type::Object(members) => {
+            for name in &rules.required {
+                if !members.contains_key(name.as_str()) {
+                    let message = format!("missing required property '{}'", name);
+                    fail(out, pointer, "required", message);
+                }
+            }
+            let known: BTreeSet<&str> = rules.properties.iter().map(|(n, _)| n.as_str()).collect();
+            for (name, schema) in &rules.properties {
+                if let Some(member) = members.get(name.as_str()) {
+                    pointer.push(name.as_str());
+                    validate(schema, member, pointer, out);
+                    pointer.pop();
+                }
+            }
+            if let Some(schema) = &rules.additional_properties {
+                let mut extra: Vec<_> = members
+                    .iter()
+                    .filter(|&(name, _)| {
+                        let name: &str = name;
+                        !known.contains(name)
+                    })
+                    .collect();
+                extra.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                for (name, member) in extra {
+                    pointer.push(&**name);
+                    validate(schema, member, pointer, out);
+                    pointer.pop();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn fail(out: &mut Vec<Violation>, pointer: &JsonPointer, keyword: &'static str, message: String) {
+    out.push(Violation {
+        pointer: pointer.clone(),
+        keyword,
+        message,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Schema;
+    use crate::{parse, Type};
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "required": ["id", "name"],
+        "properties": {
+            "id": {"type": "integer", "minimum": 1},
+            "name": {"type": "string", "minLength": 1, "pattern": "^[A-Z]"},
+            "role": {"enum": ["admin", "user"]},
+            "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 2},
+            "score": {"type": ["number", "null"], "exclusiveMaximum": 100}
+        },
+        "additionalProperties": false
+    }"#;
+
+    fn violations(instance: &str) -> Vec<(String, &'static str)> {
+        let schema = Schema::compile(&parse(SCHEMA).unwrap()).unwrap();
+        match schema.validate(&parse(instance).unwrap()) {
+            Ok(()) => Vec::new(),
+            Err(violations) => violations
+                .into_iter()
+                .map(|v| (v.pointer.to_string(), v.keyword))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn validates_instances() {
+        assert!(violations(r#"{"id": 3, "name": "Ann", "tags": ["a"], "score": null}"#).is_empty());
+        assert_eq!(
+            violations(
+                r#"{"id": 0.5, "name": "ann", "role": "root", "tags": ["a", 2, "c"], "score": 100, "x": 1}"#
+            ),
+            [
+                ("/id".to_string(), "type"),
+                ("/id".to_string(), "minimum"),
+                ("/name".to_string(), "pattern"),
+                ("/role".to_string(), "enum"),
+                ("/score".to_string(), "exclusiveMaximum"),
+                ("/tags".to_string(), "maxItems"),
+                ("/tags/1".to_string(), "type"),
+                ("/x".to_string(), "false"),
+            ]
+        );
+        assert_eq!(violations(r#"[]"#), [("".to_string(), "type")]);
+        assert_eq!(
+            violations(r#"{"name": ""}"#),
+            [
+                ("".to_string(), "required"),
+                ("/name".to_string(), "minLength"),
+                ("/name".to_string(), "pattern"),
+            ]
+        );
+    }
+
+    #[test]
+    fn messages_and_compile_errors() {
+        let schema = Schema::compile(&parse(SCHEMA).unwrap()).unwrap();
+        let errors = schema
+            .validate(&parse(r#"{"id": "7", "name": "A"}"#).unwrap())
+            .unwrap_err();
+        assert_eq!(
+            errors[0].to_string(),
+            "'/id': expected integer, found string"
+        );
+        assert!(Schema::compile(&Type::Boolean(true))
+            .unwrap()
+            .is_valid(&Type::Null));
+
+        for (schema, pointer) in [
+            (r#"{"type": "text"}"#, "/type"),
+            (
+                r#"{"properties": {"a": {"minLength": -1}}}"#,
+                "/properties/a/minLength",
+            ),
+            (r#"{"items": {"pattern": "("}}"#, "/items/pattern"),
+            ("1", ""),
+        ] {
+            let err = Schema::compile(&parse(schema).unwrap()).unwrap_err();
+            assert_eq!(err.pointer.to_string(), pointer, "{}", schema);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::io::{self, BufRead, Write};
+
+use crate::lexer::is_whitespace;
+use crate::{parse_slice, JsonError, Type};
+
+/// The ASCII record separator that starts every text in a sequence.
+const RS: u8 = 0x1e;
+
+/// Reads a JSON text sequence (RFC 7464), yielding one value per
+/// RS-delimited record.
+///
+/// Empty records are skipped. As the RFC recommends, a top-level number that
+/// is not followed by whitespace is reported as `JsonError::UnexpectEof`,
+/// since it may have been truncated.
+pub struct JsonSeqReader<R> {
+    reader: R,
+    record: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> JsonSeqReader<R> {
+    pub fn new(reader: R) -> Self {
+        JsonSeqReader {
+            reader,
+            record: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for JsonSeqReader<R> {
+    type Item = Result<Type, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.record.clear();
+            match self.reader.read_until(RS, &mut self.record) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    if self.record.last() == Some(&RS) {
+                        self.record.pop();
+                    }
+                    if self.record.iter().all(|&b| is_whitespace(b)) {
+                        continue;
+                    }
+                    return Some(parse_record(&self.record));
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn parse_record(record: &[u8]) -> Result<Type, JsonError> {
+    let value = parse_slice(record)?;
+    let terminated = record.last().is_some_and(|&b| is_whitespace(b));
+    if matches!(value, Type::Number(_)) && !terminated {
+        return Err(JsonError::UnexpectEof {
+            offset: record.len(),
+        });
+    }
+    Ok(value)
+}
+
+/// Writes values as a JSON text sequence: each one is framed as
+/// `RS <compact JSON> LF`.
+pub struct JsonSeqWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> JsonSeqWriter<W> {
+    pub fn new(writer: W) -> Self {
+        JsonSeqWriter { writer }
+    }
+
+    pub fn write(&mut self, value: &Type) -> io::Result<()> {
+        self.writer.write_all(&[RS])?;
+        value.write_to(&mut self.writer)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn write_all<'a, I>(&mut self, values: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Type>,
+    {
+        for value in values {
+            self.write(value)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonSeqReader, JsonSeqWriter};
+    use crate::{JsonError, Type};
+
+    #[test]
+    fn round_trip() {
+        let values = vec![
+            Type::Array(vec![Type::Number(1.into())]),
+            Type::Number(2.into()),
+            Type::String("x".into()),
+        ];
+        let mut writer = JsonSeqWriter::new(Vec::new());
+        writer.write_all(&values).unwrap();
+        let out = writer.into_inner();
+        assert_eq!(out, b"\x1e[1]\n\x1e2\n\x1e\"x\"\n");
+
+        let read: Result<Vec<_>, _> = JsonSeqReader::new(&out[..]).collect();
+        assert_eq!(read.unwrap(), values);
+    }
+
+    #[test]
+    fn malformed_records() {
+        let input = b"\x1e\x1e{\"a\":\n\x1e42\x1etrue\n";
+        let read: Vec<_> = JsonSeqReader::new(&input[..]).collect();
+        assert_eq!(
+            read,
+            vec![
+                Err(JsonError::UnexpectEof { offset: 6 }),
+                Err(JsonError::UnexpectEof { offset: 2 }),
+                Ok(Type::Boolean(true)),
+            ]
+        );
+    }
+}
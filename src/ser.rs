@@ -0,0 +1,1207 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::{Number, Type};
+
+/// Settings for [`to_string_with`] and [`Type::write_to_with`]. The default
+/// is compact output with members in map order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// The string repeated once per nesting level, or `None` for compact
+    /// output on a single line.
+    pub indent: Option<String>,
+    /// Emit object members sorted by key, so repeated runs over equal values
+    /// produce identical, diffable text.
+    pub sort_keys: bool,
+    /// Keys written before all others, in this order, in every object that
+    /// has them, e.g. `id` and `name` for a house style; the rest follow
+    /// as `key_compare` and `sort_keys` say.
+    pub key_priority: Vec<String>,
+    /// Orders the keys not in `key_priority`, in place of `sort_keys`.
+    pub key_compare: Option<KeyCompare>,
+    /// Escape every non-ASCII character as `\uXXXX`, using a surrogate pair
+    /// outside the Basic Multilingual Plane, for consumers that cannot
+    /// handle UTF-8.
+    pub ensure_ascii: bool,
+    /// Escape `<`, `>`, `&`, U+2028 and U+2029 as `\uXXXX`, so the output
+    /// can be inlined in an HTML `<script>` element or a JavaScript string
+    /// literal.
+    pub escape_html: bool,
+    /// What ends each line of indented output.
+    pub line_ending: LineEnding,
+    /// End the output with a line ending, as text files conventionally do.
+    pub trailing_newline: bool,
+    /// Write empty arrays and objects of indented output over two lines,
+    /// like non-empty ones, instead of as `[]` and `{}`.
+    pub expand_empty: bool,
+    /// How numbers that are not integers are written.
+    pub float_format: FloatFormat,
+}
+
+/// How [`SerializeOptions`] writes numbers that are not integers; integers
+/// are always written in full. Rounding is to the nearest, ties to even.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FloatFormat {
+    /// The fewest digits that read back as the same `f64`.
+    #[default]
+    Shortest,
+    /// Rounded to at most this many significant digits, at least one, then
+    /// written as by `Shortest`; `3.14159` to 3 digits is `3.14`.
+    Significant(usize),
+    /// Rounded to exactly this many digits after the decimal point, trailing
+    /// zeros kept; `2.5` to 2 decimals is `2.50`.
+    Fixed(usize),
+}
+
+/// A comparator for object keys, for [`SerializeOptions::key_compare`].
+/// Clones share the function, and only they compare equal.
+#[derive(Clone)]
+pub struct KeyCompare(Arc<CompareFn>);
+
+type CompareFn = dyn Fn(&str, &str) -> Ordering + Send + Sync;
+
+impl KeyCompare {
+    pub fn new<F: Fn(&str, &str) -> Ordering + Send + Sync + 'static>(compare: F) -> Self {
+        KeyCompare(Arc::new(compare))
+    }
+}
+
+impl fmt::Debug for KeyCompare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("KeyCompare(..)")
+    }
+}
+
+impl PartialEq for KeyCompare {
+    fn eq(&self, other: &KeyCompare) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for KeyCompare {}
+
+/// The line ending of indented output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+impl SerializeOptions {
+    /// Two-space indentation, as used by [`to_string_pretty`].
+    pub fn pretty() -> Self {
+        SerializeOptions {
+            indent: Some("  ".to_string()),
+            ..SerializeOptions::default()
+        }
+    }
+
+    fn key_order(&self) -> KeyOrder<'_> {
+        if !self.key_priority.is_empty() || self.key_compare.is_some() {
+            KeyOrder::Custom(self)
+        } else if self.sort_keys {
+            KeyOrder::Sorted
+        } else {
+            KeyOrder::Map
+        }
+    }
+}
+
+/// Hooks for the pieces of serialized output, in the style of serde_json's
+/// formatter. The serializer walks the value and calls these in document
+/// order; every method has a default producing compact JSON, so a custom
+/// style only overrides what it changes.
+///
+/// `first` tells the per-element hooks whether a separator is needed.
+pub trait Formatter {
+    fn write_null<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str("null")
+    }
+
+    fn write_bool<W: fmt::Write + ?Sized>(&mut self, writer: &mut W, value: bool) -> fmt::Result {
+        writer.write_str(if value { "true" } else { "false" })
+    }
+
+    /// Writes a number; NaN and the infinities have no JSON form and are
+    /// written as `null` by default.
+    fn write_number<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        value: Number,
+    ) -> fmt::Result {
+        write!(writer, "{}", value)
+    }
+
+    /// Writes a string value or object key, including the quotes.
+    fn write_string<W: fmt::Write + ?Sized>(&mut self, writer: &mut W, value: &str) -> fmt::Result {
+        write_string(writer, value, false, false)
+    }
+
+    fn begin_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str("[")
+    }
+
+    fn end_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str("]")
+    }
+
+    fn begin_array_value<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> fmt::Result {
+        if first {
+            Ok(())
+        } else {
+            writer.write_str(",")
+        }
+    }
+
+    fn end_array_value<W: fmt::Write + ?Sized>(&mut self, _writer: &mut W) -> fmt::Result {
+        Ok(())
+    }
+
+    fn begin_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str("{")
+    }
+
+    fn end_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str("}")
+    }
+
+    fn begin_object_key<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> fmt::Result {
+        if first {
+            Ok(())
+        } else {
+            writer.write_str(",")
+        }
+    }
+
+    /// Writes what separates a key from its value.
+    fn begin_object_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str(":")
+    }
+
+    fn end_object_value<W: fmt::Write + ?Sized>(&mut self, _writer: &mut W) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// Compact output with no whitespace, as produced by [`to_string`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompactFormatter;
+
+impl Formatter for CompactFormatter {}
+
+/// One member or element per line, each nesting level indented, as produced
+/// by [`to_string_pretty`]. Empty containers stay on one line unless
+/// [`expand_empty`](PrettyFormatter::expand_empty) is set.
+#[derive(Debug, Clone)]
+pub struct PrettyFormatter<'a> {
+    indent: &'a str,
+    line_ending: LineEnding,
+    expand_empty: bool,
+    depth: usize,
+    has_value: bool,
+}
+
+impl<'a> PrettyFormatter<'a> {
+    /// Indents with two spaces.
+    pub fn new() -> Self {
+        PrettyFormatter::with_indent("  ")
+    }
+
+    pub fn with_indent(indent: &'a str) -> Self {
+        PrettyFormatter {
+            indent,
+            line_ending: LineEnding::Lf,
+            expand_empty: false,
+            depth: 0,
+            has_value: false,
+        }
+    }
+
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Whether empty arrays and objects are written over two lines.
+    pub fn expand_empty(mut self, expand_empty: bool) -> Self {
+        self.expand_empty = expand_empty;
+        self
+    }
+
+    fn begin<W: fmt::Write + ?Sized>(&mut self, writer: &mut W, open: &str) -> fmt::Result {
+        self.depth += 1;
+        self.has_value = false;
+        writer.write_str(open)
+    }
+
+    fn end<W: fmt::Write + ?Sized>(&mut self, writer: &mut W, close: &str) -> fmt::Result {
+        self.depth -= 1;
+        if self.has_value || self.expand_empty {
+            self.newline(writer)?;
+        }
+        self.has_value = true;
+        writer.write_str(close)
+    }
+
+    fn item<W: fmt::Write + ?Sized>(&mut self, writer: &mut W, first: bool) -> fmt::Result {
+        if !first {
+            writer.write_str(",")?;
+        }
+        self.newline(writer)
+    }
+
+    fn newline<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        writer.write_str(self.line_ending.as_str())?;
+        for _ in 0..self.depth {
+            writer.write_str(self.indent)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for PrettyFormatter<'_> {
+    fn default() -> Self {
+        PrettyFormatter::new()
+    }
+}
+
+impl Formatter for PrettyFormatter<'_> {
+    fn begin_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        self.begin(writer, "[")
+    }
+
+    fn end_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        self.end(writer, "]")
+    }
+
+    fn begin_array_value<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> fmt::Result {
+        self.item(writer, first)
+    }
+
+    fn end_array_value<W: fmt::Write + ?Sized>(&mut self, _writer: &mut W) -> fmt::Result {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn begin_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        self.begin(writer, "{")
+    }
+
+    fn end_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        self.end(writer, "}")
+    }
+
+    fn begin_object_key<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> fmt::Result {
+        self.item(writer, first)
+    }
+
+    fn begin_object_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        writer.write_str(": ")
+    }
+
+    fn end_object_value<W: fmt::Write + ?Sized>(&mut self, _writer: &mut W) -> fmt::Result {
+        self.has_value = true;
+        Ok(())
+    }
+}
+
+/// The formatter behind [`SerializeOptions`]: compact or pretty layout, with
+/// the string and number settings applied on top.
+struct OptionsFormatter<'a> {
+    pretty: Option<PrettyFormatter<'a>>,
+    ensure_ascii: bool,
+    escape_html: bool,
+    float_format: FloatFormat,
+    /// Format numbers as RFC 8785 requires.
+    canonical: bool,
+}
+
+impl<'a> OptionsFormatter<'a> {
+    fn new(options: &'a SerializeOptions) -> Self {
+        OptionsFormatter {
+            pretty: options.indent.as_deref().map(|indent| {
+                PrettyFormatter::with_indent(indent)
+                    .with_line_ending(options.line_ending)
+                    .expand_empty(options.expand_empty)
+            }),
+            ensure_ascii: options.ensure_ascii,
+            escape_html: options.escape_html,
+            float_format: options.float_format,
+            canonical: false,
+        }
+    }
+}
+
+impl Formatter for OptionsFormatter<'_> {
+    fn write_number<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        value: Number,
+    ) -> fmt::Result {
+        let n = value.to_f64();
+        if self.canonical {
+            write_canonical_number(writer, n)
+        } else if value.is_integer() || !n.is_finite() {
+            write!(writer, "{}", value)
+        } else {
+            match self.float_format {
+                FloatFormat::Shortest => write!(writer, "{}", value),
+                FloatFormat::Significant(digits) => {
+                    let rounded = format!("{:.*e}", digits.max(1) - 1, n);
+                    let rounded: f64 = rounded.parse().expect("`{:e}` output parses");
+                    write!(writer, "{}", Number::from(rounded))
+                }
+                FloatFormat::Fixed(decimals) => write!(writer, "{:.*}", decimals, n),
+            }
+        }
+    }
+
+    fn write_string<W: fmt::Write + ?Sized>(&mut self, writer: &mut W, value: &str) -> fmt::Result {
+        write_string(writer, value, self.ensure_ascii, self.escape_html)
+    }
+
+    fn begin_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.begin_array(writer),
+            None => CompactFormatter.begin_array(writer),
+        }
+    }
+
+    fn end_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.end_array(writer),
+            None => CompactFormatter.end_array(writer),
+        }
+    }
+
+    fn begin_array_value<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.begin_array_value(writer, first),
+            None => CompactFormatter.begin_array_value(writer, first),
+        }
+    }
+
+    fn end_array_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.end_array_value(writer),
+            None => CompactFormatter.end_array_value(writer),
+        }
+    }
+
+    fn begin_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.begin_object(writer),
+            None => CompactFormatter.begin_object(writer),
+        }
+    }
+
+    fn end_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.end_object(writer),
+            None => CompactFormatter.end_object(writer),
+        }
+    }
+
+    fn begin_object_key<W: fmt::Write + ?Sized>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.begin_object_key(writer, first),
+            None => CompactFormatter.begin_object_key(writer, first),
+        }
+    }
+
+    fn begin_object_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.begin_object_value(writer),
+            None => CompactFormatter.begin_object_value(writer),
+        }
+    }
+
+    fn end_object_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        match &mut self.pretty {
+            Some(pretty) => pretty.end_object_value(writer),
+            None => CompactFormatter.end_object_value(writer),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyOrder<'a> {
+    /// Whatever order the map iterates in.
+    Map,
+    Sorted,
+    /// By UTF-16 code units, as RFC 8785 requires.
+    Utf16,
+    /// By `key_priority`, then `key_compare` or `sort_keys`.
+    Custom(&'a SerializeOptions),
+}
+
+struct Serializer<'a, W: fmt::Write, F: Formatter> {
+    writer: W,
+    formatter: F,
+    order: KeyOrder<'a>,
+}
+
+impl<'a, W: fmt::Write, F: Formatter> Serializer<'a, W, F> {
+    fn new(writer: W, formatter: F, order: KeyOrder<'a>) -> Self {
+        Serializer {
+            writer,
+            formatter,
+            order,
+        }
+    }
+
+    fn write_value(&mut self, value: &Type) -> fmt::Result {
+        let writer = &mut self.writer;
+        match value {
+            Type::Null => self.formatter.write_null(writer),
+            Type::Boolean(b) => self.formatter.write_bool(writer, *b),
+            Type::Number(n) => self.formatter.write_number(writer, *n),
+            Type::String(s) => self.formatter.write_string(writer, s),
+            Type::Array(items) => {
+                self.formatter.begin_array(writer)?;
+                for (i, item) in items.iter().enumerate() {
+                    self.formatter.begin_array_value(&mut self.writer, i == 0)?;
+                    self.write_value(item)?;
+                    self.formatter.end_array_value(&mut self.writer)?;
+                }
+                self.formatter.end_array(&mut self.writer)
+            }
+            Type::Object(members) => {
+                self.formatter.begin_object(writer)?;
+                let mut members: Vec<_> = members.iter().collect();
+                match self.order {
+                    KeyOrder::Map => {}
+                    KeyOrder::Sorted => members.sort_by(|a, b| a.0.cmp(b.0)),
+                    KeyOrder::Utf16 => {
+                        members.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()))
+                    }
+                    KeyOrder::Custom(options) => {
+                        let priority = &options.key_priority;
+                        let rank = |key: &str| {
+                            priority
+                                .iter()
+                                .position(|first| first == key)
+                                .unwrap_or(priority.len())
+                        };
+                        members.sort_by(|a, b| {
+                            rank(a.0)
+                                .cmp(&rank(b.0))
+                                .then_with(|| match &options.key_compare {
+                                    Some(compare) => (compare.0)(a.0, b.0),
+                                    None if options.sort_keys => a.0.cmp(b.0),
+                                    None => Ordering::Equal,
+                                })
+                        });
+                    }
+                }
+                for (i, (key, value)) in members.into_iter().enumerate() {
+                    self.formatter.begin_object_key(&mut self.writer, i == 0)?;
+                    self.formatter.write_string(&mut self.writer, key)?;
+                    self.formatter.begin_object_value(&mut self.writer)?;
+                    self.write_value(value)?;
+                    self.formatter.end_object_value(&mut self.writer)?;
+                }
+                self.formatter.end_object(&mut self.writer)
+            }
+        }
+    }
+}
+
+/// Formats a number the way ECMAScript's `Number.prototype.toString` does,
+/// as RFC 8785 requires: the shortest digits that round-trip, in plain
+/// notation for decimal exponents from -6 to 20 and scientific otherwise.
+fn write_canonical_number<W: fmt::Write + ?Sized>(writer: &mut W, n: f64) -> fmt::Result {
+    if !n.is_finite() {
+        return writer.write_str("null");
+    }
+    if n == 0.0 {
+        return writer.write_str("0");
+    }
+    if n < 0.0 {
+        writer.write_str("-")?;
+    }
+    let scientific = format!("{:e}", n.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("`{:e}` always writes an exponent");
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let point = exponent.parse::<i32>().expect("exponent is an integer") + 1;
+    if k <= point && point <= 21 {
+        writer.write_str(&digits)?;
+        for _ in k..point {
+            writer.write_str("0")?;
+        }
+        Ok(())
+    } else if 0 < point && point <= 21 {
+        let (int, frac) = digits.split_at(point as usize);
+        write!(writer, "{}.{}", int, frac)
+    } else if -6 < point && point <= 0 {
+        writer.write_str("0.")?;
+        for _ in point..0 {
+            writer.write_str("0")?;
+        }
+        writer.write_str(&digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        writer.write_str(first)?;
+        if !rest.is_empty() {
+            write!(writer, ".{}", rest)?;
+        }
+        let sign = if point > 0 { "+" } else { "-" };
+        write!(writer, "e{}{}", sign, (point - 1).abs())
+    }
+}
+
+pub(crate) fn write_string<W: fmt::Write + ?Sized>(
+    writer: &mut W,
+    s: &str,
+    ensure_ascii: bool,
+    escape_html: bool,
+) -> fmt::Result {
+    writer.write_str("\"")?;
+    let mut start = 0;
+    for (i, byte) in s.bytes().enumerate() {
+        let escape = match byte {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0x08 => "\\b",
+            0x0c => "\\f",
+            0x00..=0x1f => {
+                writer.write_str(&s[start..i])?;
+                write!(writer, "\\u{:04x}", byte)?;
+                start = i + 1;
+                continue;
+            }
+            b'<' | b'>' | b'&' if escape_html => {
+                writer.write_str(&s[start..i])?;
+                write!(writer, "\\u{:04x}", byte)?;
+                start = i + 1;
+                continue;
+            }
+            0xe2 if escape_html
+                && (s[i..].starts_with('\u{2028}') || s[i..].starts_with('\u{2029}')) =>
+            {
+                writer.write_str(&s[start..i])?;
+                let ch = s[i..].chars().next().expect("a lead byte starts a char");
+                write!(writer, "\\u{:04x}", ch as u32)?;
+                start = i + ch.len_utf8();
+                continue;
+            }
+            0xc0..=0xff if ensure_ascii => {
+                let ch = s[i..].chars().next().expect("a lead byte starts a char");
+                writer.write_str(&s[start..i])?;
+                for unit in ch.encode_utf16(&mut [0; 2]) {
+                    write!(writer, "\\u{:04x}", unit)?;
+                }
+                start = i + ch.len_utf8();
+                continue;
+            }
+            _ => continue,
+        };
+        writer.write_str(&s[start..i])?;
+        writer.write_str(escape)?;
+        start = i + 1;
+    }
+    writer.write_str(&s[start..])?;
+    writer.write_str("\"")
+}
+
+/// Bridges the `fmt::Write`-based serializer to an `io::Write`, keeping the
+/// underlying I/O error that `fmt::Error` cannot carry.
+#[cfg(feature = "std")]
+pub(crate) struct IoWriter<W: io::Write> {
+    pub(crate) inner: W,
+    pub(crate) error: Option<io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+fn write_io<W: io::Write, F: Formatter>(
+    value: &Type,
+    writer: W,
+    formatter: F,
+    order: KeyOrder<'_>,
+) -> io::Result<()> {
+    let mut writer = IoWriter {
+        inner: writer,
+        error: None,
+    };
+    Serializer::new(&mut writer, formatter, order)
+        .write_value(value)
+        .map_err(|_| {
+            writer
+                .error
+                .take()
+                .unwrap_or_else(|| io::Error::other("formatter error"))
+        })
+}
+
+#[cfg(feature = "std")]
+impl Type {
+    /// Serializes the value as compact JSON into `writer`.
+    ///
+    /// Output is produced piece by piece, so wrap unbuffered writers such as
+    /// `File` in an `io::BufWriter`.
+    pub fn write_to<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        write_io(self, writer, CompactFormatter, KeyOrder::Map)
+    }
+
+    /// Serializes the value as JSON indented with two spaces into `writer`.
+    pub fn write_to_pretty<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        write_io(self, writer, PrettyFormatter::new(), KeyOrder::Map)
+    }
+
+    /// Serializes the value into `writer` as configured by `options`.
+    pub fn write_to_with<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerializeOptions,
+    ) -> io::Result<()> {
+        let mut writer = writer;
+        write_io(
+            self,
+            &mut writer,
+            OptionsFormatter::new(options),
+            options.key_order(),
+        )?;
+        if options.trailing_newline {
+            writer.write_all(options.line_ending.as_str().as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Serializes the value into `writer`, leaving every piece of the output
+    /// to `formatter`.
+    pub fn write_with_formatter<W: io::Write, F: Formatter>(
+        &self,
+        writer: W,
+        formatter: F,
+    ) -> io::Result<()> {
+        write_io(self, writer, formatter, KeyOrder::Map)
+    }
+}
+
+impl Type {
+    /// Serializes the value as compact JSON into any `fmt::Write`, such as a
+    /// pre-allocated `String` or a `fmt::Formatter`.
+    pub fn write_to_fmt<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        Serializer::new(writer, CompactFormatter, KeyOrder::Map).write_value(self)
+    }
+
+    /// Like [`Type::write_to_fmt`], indenting with two spaces.
+    pub fn write_to_fmt_pretty<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        Serializer::new(writer, PrettyFormatter::new(), KeyOrder::Map).write_value(self)
+    }
+
+    /// The exact length in bytes of [`to_string`] of the value, counted
+    /// without building the output.
+    pub fn serialized_size(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        Serializer::new(&mut counter, CompactFormatter, KeyOrder::Map)
+            .write_value(self)
+            .expect("counting never fails");
+        counter.0
+    }
+
+    /// Like [`Type::serialized_size`], for [`to_string_pretty`].
+    pub fn serialized_size_pretty(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        Serializer::new(&mut counter, PrettyFormatter::new(), KeyOrder::Map)
+            .write_value(self)
+            .expect("counting never fails");
+        counter.0
+    }
+
+    /// Like [`Type::serialized_size`], for [`to_string_with`].
+    pub fn serialized_size_with(&self, options: &SerializeOptions) -> usize {
+        let mut counter = ByteCounter(0);
+        Serializer::new(
+            &mut counter,
+            OptionsFormatter::new(options),
+            options.key_order(),
+        )
+        .write_value(self)
+        .expect("counting never fails");
+        if options.trailing_newline {
+            counter.0 += options.line_ending.as_str().len();
+        }
+        counter.0
+    }
+}
+
+/// Compact JSON, as from [`to_string`]; the alternate form, `{:#}`, is
+/// indented as by [`to_string_pretty`].
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            self.write_to_fmt_pretty(f)
+        } else {
+            self.write_to_fmt(f)
+        }
+    }
+}
+
+/// A `fmt::Write` that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+pub fn to_string(value: &Type) -> String {
+    let mut out = String::new();
+    value
+        .write_to_fmt(&mut out)
+        .expect("writing to a String never fails");
+    out
+}
+
+/// Serializes `value` in the JSON Canonicalization Scheme (RFC 8785): compact,
+/// with object keys sorted and numbers formatted as ECMAScript prints them,
+/// so equal values always produce identical bytes for hashing or signing.
+///
+/// JCS has no representation for NaN or the infinities; they are written as
+/// `null`, as in the other modes.
+pub fn to_canonical_string(value: &Type) -> String {
+    let mut out = String::new();
+    let options = SerializeOptions::default();
+    let formatter = OptionsFormatter {
+        canonical: true,
+        ..OptionsFormatter::new(&options)
+    };
+    Serializer::new(&mut out, formatter, KeyOrder::Utf16)
+        .write_value(value)
+        .expect("writing to a String never fails");
+    out
+}
+
+pub fn to_string_pretty(value: &Type) -> String {
+    let mut out = String::new();
+    value
+        .write_to_fmt_pretty(&mut out)
+        .expect("writing to a String never fails");
+    out
+}
+
+pub fn to_string_with(value: &Type, options: &SerializeOptions) -> String {
+    let mut out = String::new();
+    Serializer::new(
+        &mut out,
+        OptionsFormatter::new(options),
+        options.key_order(),
+    )
+    .write_value(value)
+    .expect("writing to a String never fails");
+    if options.trailing_newline {
+        out.push_str(options.line_ending.as_str());
+    }
+    out
+}
+
+/// Serializes `value` into a string, leaving every piece of the output to
+/// `formatter`. Writing to a string never fails, so an error is one the
+/// formatter returned.
+pub fn to_string_with_formatter<F: Formatter>(
+    value: &Type,
+    formatter: F,
+) -> Result<String, fmt::Error> {
+    let mut out = String::new();
+    Serializer::new(&mut out, formatter, KeyOrder::Map).write_value(value)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parse, to_canonical_string, to_string, to_string_pretty, to_string_with,
+        to_string_with_formatter, FloatFormat, Formatter, KeyCompare, LineEnding, Map,
+        PrettyFormatter, SerializeOptions, Type,
+    };
+    use std::fmt;
+
+    #[test]
+    fn write_to() {
+        let value = Type::Array(vec![
+            Type::Null,
+            Type::Boolean(true),
+            Type::Number((-1.5).into()),
+            Type::String("a\"b\\c\n\u{1}".into()),
+            Type::Array(vec![]),
+            Type::Object(Box::default()),
+        ]);
+        let mut out = Vec::new();
+        value.write_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            r#"[null,true,-1.5,"a\"b\\c\n\u0001",[],{}]"#
+        );
+        assert_eq!(to_string(&Type::Number(f64::NAN.into())), "null");
+    }
+
+    #[test]
+    fn write_to_fmt() {
+        let value = Type::Array(vec![Type::String("ü".into()), Type::Null]);
+        let mut out = String::with_capacity(16);
+        out.push_str("value=");
+        value.write_to_fmt(&mut out).unwrap();
+        assert_eq!(out, "value=[\"ü\",null]");
+
+        let mut pretty = String::new();
+        value.write_to_fmt_pretty(&mut pretty).unwrap();
+        assert_eq!(pretty, "[\n  \"ü\",\n  null\n]");
+    }
+
+    #[test]
+    fn display() {
+        let value = parse(r#"[{"a": [1, null]}, "x"]"#).unwrap();
+        assert_eq!(format!("{}", value), to_string(&value));
+        assert_eq!(format!("{:#}", value), to_string_pretty(&value));
+        assert_eq!(value.to_string(), r#"[{"a":[1,null]},"x"]"#);
+    }
+
+    #[test]
+    fn serialized_size() {
+        let value = parse(r#"{"a": ["ü\n", 1.5, null, {}], "b": {"c": [true]}}"#).unwrap();
+        assert_eq!(value.serialized_size(), to_string(&value).len());
+        assert_eq!(
+            value.serialized_size_pretty(),
+            to_string_pretty(&value).len()
+        );
+        let options = SerializeOptions {
+            ensure_ascii: true,
+            ..SerializeOptions::pretty()
+        };
+        assert_eq!(
+            value.serialized_size_with(&options),
+            to_string_with(&value, &options).len()
+        );
+    }
+
+    #[test]
+    fn write_to_pretty() {
+        let value = Type::Object(Box::new(Map::from_iter(vec![(
+            "list".into(),
+            Type::Array(vec![Type::Number(1.into()), Type::Number(2.into())]),
+        )])));
+        assert_eq!(
+            to_string_pretty(&value),
+            "{\n  \"list\": [\n    1,\n    2\n  ]\n}"
+        );
+        assert_eq!(parse(&to_string_pretty(&value)).unwrap(), value);
+    }
+
+    #[test]
+    fn canonical() {
+        let value = parse(r#"{"b": [1E2, 0.000001, 1e-7, -0, 1e21, 123456789012345680000], "a\u00e9": "\u001f/", "\u20ac": 1, "\ufb01": 3, "\ud83d\ude00": 2, "a": 4.5}"#).unwrap();
+        assert_eq!(
+            to_canonical_string(&value),
+            r#"{"a":4.5,"aé":"\u001f/","b":[100,0.000001,1e-7,0,1e+21,123456789012345680000],"€":1,"😀":2,"ﬁ":3}"#
+        );
+        assert_eq!(
+            to_canonical_string(&Type::Number(1.5e-300.into())),
+            "1.5e-300"
+        );
+    }
+
+    #[test]
+    fn sorted_keys() {
+        let value = parse(r#"{"b": 1, "a": {"z": [], "y": null}, "c": 2}"#).unwrap();
+        let options = SerializeOptions {
+            sort_keys: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            to_string_with(&value, &options),
+            r#"{"a":{"y":null,"z":[]},"b":1,"c":2}"#
+        );
+
+        let options = SerializeOptions {
+            sort_keys: true,
+            ..SerializeOptions::pretty()
+        };
+        let mut out = Vec::new();
+        value.write_to_with(&mut out, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\n  \"a\": {\n    \"y\": null,\n    \"z\": []\n  },\n  \"b\": 1,\n  \"c\": 2\n}"
+        );
+    }
+
+    #[test]
+    fn prioritized_keys() {
+        let value =
+            parse(r#"{"b": 1, "name": "x", "a": {"id": 2, "c": null, "b": []}, "id": 3}"#).unwrap();
+        let options = SerializeOptions {
+            key_priority: vec!["id".into(), "name".into()],
+            sort_keys: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            to_string_with(&value, &options),
+            r#"{"id":3,"name":"x","a":{"id":2,"b":[],"c":null},"b":1}"#
+        );
+
+        let options = SerializeOptions {
+            key_priority: vec!["name".into()],
+            key_compare: Some(KeyCompare::new(|a, b| b.cmp(a))),
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            to_string_with(&value, &options),
+            r#"{"name":"x","id":3,"b":1,"a":{"id":2,"c":null,"b":[]}}"#
+        );
+        assert_eq!(
+            value.serialized_size_with(&options),
+            to_string_with(&value, &options).len()
+        );
+        assert_eq!(options, options.clone());
+        assert_ne!(
+            options,
+            SerializeOptions {
+                key_compare: Some(KeyCompare::new(|a, b| b.cmp(a))),
+                ..options.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn ensure_ascii() {
+        let value = Type::Array(vec![Type::String("aé€😀\n".into())]);
+        let options = SerializeOptions {
+            ensure_ascii: true,
+            ..SerializeOptions::default()
+        };
+        let ascii = to_string_with(&value, &options);
+        assert_eq!(ascii, r#"["a\u00e9\u20ac\ud83d\ude00\n"]"#);
+        assert_eq!(parse(&ascii).unwrap(), value);
+    }
+
+    #[test]
+    fn line_layout() {
+        let value = parse(r#"{"a": [], "b": [{}]}"#).unwrap();
+        let options = SerializeOptions {
+            sort_keys: true,
+            line_ending: LineEnding::CrLf,
+            trailing_newline: true,
+            ..SerializeOptions::pretty()
+        };
+        let expected = "{\r\n  \"a\": [],\r\n  \"b\": [\r\n    {}\r\n  ]\r\n}\r\n";
+        assert_eq!(to_string_with(&value, &options), expected);
+        let mut out = Vec::new();
+        value.write_to_with(&mut out, &options).unwrap();
+        assert_eq!(out, expected.as_bytes());
+        assert_eq!(value.serialized_size_with(&options), expected.len());
+
+        let options = SerializeOptions {
+            sort_keys: true,
+            expand_empty: true,
+            ..SerializeOptions::pretty()
+        };
+        assert_eq!(
+            to_string_with(&value, &options),
+            "{\n  \"a\": [\n  ],\n  \"b\": [\n    {\n    }\n  ]\n}"
+        );
+        let options = SerializeOptions {
+            trailing_newline: true,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(to_string_with(&Type::Null, &options), "null\n");
+    }
+
+    #[test]
+    fn float_format() {
+        let value = parse("[3.14159, -0.000123456, 2.5, 12345.678, 7, 1e300]").unwrap();
+        let with = |float_format| {
+            let options = SerializeOptions {
+                float_format,
+                ..SerializeOptions::default()
+            };
+            to_string_with(&value, &options)
+        };
+        assert_eq!(with(FloatFormat::Shortest), to_string(&value));
+        assert_eq!(
+            with(FloatFormat::Significant(3)),
+            "[3.14,-0.000123,2.5,12300,7,1e300]"
+        );
+        assert_eq!(
+            with(FloatFormat::Significant(0)),
+            "[3,-0.0001,2,10000,7,1e300]"
+        );
+        assert_eq!(
+            with(FloatFormat::Fixed(2)),
+            format!("[3.14,-0.00,2.50,12345.68,7,{:.2}]", 1e300)
+        );
+        let rounded = with(FloatFormat::Fixed(0));
+        assert!(rounded.starts_with("[3,-0,2,12346,7,"), "{}", rounded);
+    }
+
+    #[test]
+    fn escape_html() {
+        let value = Type::String("</script><b>&\u{2028}\u{2029}é".into());
+        let options = SerializeOptions {
+            escape_html: true,
+            ..SerializeOptions::default()
+        };
+        let safe = to_string_with(&value, &options);
+        assert_eq!(
+            safe,
+            r#""\u003c/script\u003e\u003cb\u003e\u0026\u2028\u2029é""#
+        );
+        assert_eq!(parse(&safe).unwrap(), value);
+        let options = SerializeOptions {
+            ensure_ascii: true,
+            ..options
+        };
+        assert_eq!(
+            to_string_with(&value, &options),
+            r#""\u003c/script\u003e\u003cb\u003e\u0026\u2028\u2029\u00e9""#
+        );
+    }
+
+    /// Keeps arrays on one line inside otherwise pretty output.
+    struct InlineArrays<'a> {
+        pretty: PrettyFormatter<'a>,
+        arrays: usize,
+    }
+
+    impl Formatter for InlineArrays<'_> {
+        fn begin_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+            self.arrays += 1;
+            writer.write_str("[")
+        }
+
+        fn end_array<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+            self.arrays -= 1;
+            writer.write_str("]")
+        }
+
+        fn begin_array_value<W: fmt::Write + ?Sized>(
+            &mut self,
+            writer: &mut W,
+            first: bool,
+        ) -> fmt::Result {
+            writer.write_str(if first { "" } else { ", " })
+        }
+
+        fn begin_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+            self.pretty.begin_object(writer)
+        }
+
+        fn end_object<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+            self.pretty.end_object(writer)
+        }
+
+        fn begin_object_key<W: fmt::Write + ?Sized>(
+            &mut self,
+            writer: &mut W,
+            first: bool,
+        ) -> fmt::Result {
+            self.pretty.begin_object_key(writer, first)
+        }
+
+        fn begin_object_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+            self.pretty.begin_object_value(writer)
+        }
+
+        fn end_object_value<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+            self.pretty.end_object_value(writer)
+        }
+    }
+
+    #[test]
+    fn custom_formatter() {
+        let value = parse(r#"{"xs": [1, 2, []]}"#).unwrap();
+        let formatter = InlineArrays {
+            pretty: PrettyFormatter::with_indent("\t"),
+            arrays: 0,
+        };
+        assert_eq!(
+            to_string_with_formatter(&value, formatter).unwrap(),
+            "{\n\t\"xs\": [1, 2, []]\n}"
+        );
+
+        let mut out = Vec::new();
+        value
+            .write_with_formatter(&mut out, PrettyFormatter::with_indent(" "))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\n \"xs\": [\n  1,\n  2,\n  []\n ]\n}"
+        );
+    }
+
+    /// Refuses to write `null`.
+    struct NoNulls;
+
+    impl Formatter for NoNulls {
+        fn write_null<W: fmt::Write + ?Sized>(&mut self, _: &mut W) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    #[test]
+    fn formatter_errors() {
+        let value = parse("[1, null]").unwrap();
+        assert_eq!(to_string_with_formatter(&value, NoNulls), Err(fmt::Error));
+        assert_eq!(
+            to_string_with_formatter(&parse("[1]").unwrap(), NoNulls).unwrap(),
+            "[1]"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::{fmt, ops};
+
+use bytes::Bytes;
+
+use crate::lexer::{decode_number, decode_string, Lexer, TokenKind};
+use crate::parser::Grammar;
+use crate::{Expected, JsonError, Map, Number, Type};
+
+/// Text held in a [`Bytes`] buffer, known to be UTF-8. Cloning it shares
+/// the buffer.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedStr(Bytes);
+
+impl SharedStr {
+    pub fn as_str(&self) -> &str {
+        // SAFETY: a `SharedStr` is only made from bytes checked to be UTF-8.
+        unsafe { core::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// The buffer the text is a slice of.
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.0
+    }
+}
+
+impl ops::Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// A JSON value parsed by [`parse_shared`], whose strings and keys are
+/// slices of the buffer it was parsed from where they have no escapes.
+/// Object members keep their document order, duplicates included.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedType {
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(SharedStr),
+    Array(Vec<SharedType>),
+    Object(Vec<(SharedStr, SharedType)>),
+}
+
+impl SharedType {
+    /// Looks up a member of an object. As when building a [`Type`], the last
+    /// of several equal keys wins.
+    pub fn get(&self, key: &str) -> Option<&SharedType> {
+        match self {
+            SharedType::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(name, _)| name.as_str() == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Copies the value into a [`Type`], which owns its strings.
+    pub fn to_type(&self) -> Type {
+        match self {
+            SharedType::Null => Type::Null,
+            SharedType::Boolean(b) => Type::Boolean(*b),
+            SharedType::Number(n) => Type::Number(*n),
+            SharedType::String(s) => Type::String(s.as_str().into()),
+            SharedType::Array(items) => Type::Array(items.iter().map(Self::to_type).collect()),
+            SharedType::Object(members) => {
+                let mut map = Map::default();
+                for (key, value) in members {
+                    map.insert(key.as_str().into(), value.to_type());
+                }
+                Type::Object(Box::new(map))
+            }
+        }
+    }
+}
+
+enum Frame {
+    Array(Vec<SharedType>),
+    Object(Vec<(SharedStr, SharedType)>, Option<SharedStr>),
+}
+
+/// Parses the JSON in `input`, a request body from `hyper` say, without
+/// copying its strings: those without escapes become slices of `input`,
+/// which share its buffer, so the buffer lives as long as any of them. A
+/// leading UTF-8 byte-order mark is skipped.
+pub fn parse_shared(input: &Bytes) -> Result<SharedType, JsonError> {
+    let mut lexer = Lexer::new(input).skip_bom();
+    let mut grammar = Grammar::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root = None;
+    while let Some(token) = lexer.next_token()? {
+        let is_key = grammar.expects_key();
+        grammar.accept(token)?;
+        let value = match token.kind {
+            TokenKind::BeginArray => {
+                stack.push(Frame::Array(Vec::new()));
+                continue;
+            }
+            TokenKind::BeginObject => {
+                stack.push(Frame::Object(Vec::new(), None));
+                continue;
+            }
+            TokenKind::Colon | TokenKind::Comma => continue,
+            TokenKind::EndArray | TokenKind::EndObject => match stack.pop() {
+                Some(Frame::Array(items)) => SharedType::Array(items),
+                Some(Frame::Object(members, _)) => SharedType::Object(members),
+                None => unreachable!("the grammar balances containers"),
+            },
+            TokenKind::String => {
+                let (start, end) = (token.start + 1, token.end - 1);
+                let raw = &input[start..end];
+                let s = if raw.contains(&b'\\') {
+                    SharedStr(Bytes::from(decode_string(raw, start)?))
+                } else {
+                    if let Err(err) = core::str::from_utf8(raw) {
+                        return Err(JsonError::UnexpectToken {
+                            offset: start + err.valid_up_to(),
+                            expected: Expected::NONE,
+                        });
+                    }
+                    SharedStr(input.slice(start..end))
+                };
+                if is_key {
+                    if let Some(Frame::Object(_, pending)) = stack.last_mut() {
+                        *pending = Some(s);
+                    }
+                    continue;
+                }
+                SharedType::String(s)
+            }
+            TokenKind::Number => {
+                SharedType::Number(decode_number(&input[token.start..token.end], token.start)?)
+            }
+            TokenKind::True => SharedType::Boolean(true),
+            TokenKind::False => SharedType::Boolean(false),
+            TokenKind::Null => SharedType::Null,
+        };
+        match stack.last_mut() {
+            None => root = Some(value),
+            Some(Frame::Array(items)) => items.push(value),
+            Some(Frame::Object(members, pending)) => {
+                if let Some(key) = pending.take() {
+                    members.push((key, value));
+                }
+            }
+        }
+    }
+    root.ok_or(JsonError::UnexpectEof {
+        offset: input.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{parse_shared, SharedType};
+    use crate::{parse, Expected, JsonError};
+
+    #[test]
+    fn slices_the_input() {
+        let json = r#"{"plain": "text", "escaped": "a\nb", "list": [1, true, null], "plain": 2}"#;
+        let input = Bytes::from(json);
+        let value = parse_shared(&input).unwrap();
+
+        let SharedType::Object(members) = &value else {
+            panic!("expected an object");
+        };
+        assert_eq!(members.len(), 4);
+        assert_eq!(members[0].0, "plain");
+        let SharedType::String(text) = &members[0].1 else {
+            panic!("expected a string");
+        };
+        // Unescaped strings point into `input` itself.
+        assert!(input.as_ptr_range().contains(&text.as_ptr()));
+        assert_eq!(&**text, "text");
+        assert_eq!(
+            value.get("escaped").map(|s| s.to_type()),
+            Some(crate::Type::String("a\nb".into()))
+        );
+        assert_eq!(value.get("plain"), Some(&SharedType::Number(2.into())));
+        assert_eq!(value.to_type(), parse(json).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse_shared(&Bytes::from_static(b"[1,]")).unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::VALUE
+            }
+        );
+        assert_eq!(
+            parse_shared(&Bytes::from_static(b"{\"a\": [")).unwrap_err(),
+            JsonError::UnexpectEof { offset: 7 }
+        );
+        assert_eq!(
+            parse_shared(&Bytes::from_static(b"[\"a\xffb\"]")).unwrap_err(),
+            JsonError::UnexpectToken {
+                offset: 3,
+                expected: Expected::NONE
+            }
+        );
+    }
+
+    #[test]
+    fn skips_bom() {
+        let value = parse_shared(&Bytes::from_static(b"\xef\xbb\xbf[\"a\"]")).unwrap();
+        assert_eq!(value.to_type(), parse(r#"["a"]"#).unwrap());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::lexer::is_whitespace;
+
+/// Returns the position of the first non-whitespace byte at or after `pos`.
+pub(crate) fn skip_whitespace(input: &[u8], mut pos: usize) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        pos = sse2::skip_whitespace(input, pos);
+    }
+    while pos < input.len() && is_whitespace(input[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Returns the position of the first byte at or after `pos` that ends a run
+/// of plain string content: a quote, a backslash or a control character.
+pub(crate) fn skip_string_body(input: &[u8], mut pos: usize) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    {
+        pos = sse2::skip_string_body(input, pos);
+    }
+    while pos < input.len() && !matches!(input[pos], b'"' | b'\\' | 0x00..=0x1f) {
+        pos += 1;
+    }
+    pos
+}
+
+/// The `simd` fast paths, testing 16 bytes per step with SSE2, which every
+/// x86_64 CPU has. Other targets use the byte-at-a-time loops alone.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod sse2 {
+    use core::arch::x86_64::*;
+
+    /// Skips whole 16-byte blocks of whitespace, stopping inside the first
+    /// block that contains anything else or when fewer than 16 bytes remain.
+    pub fn skip_whitespace(input: &[u8], mut pos: usize) -> usize {
+        while pos + 16 <= input.len() {
+            // SAFETY: SSE2 is part of the x86_64 baseline, and the unaligned
+            // load reads `input[pos..pos + 16]`, which is in bounds.
+            let mask = unsafe {
+                let block = _mm_loadu_si128(input.as_ptr().add(pos) as *const __m128i);
+                let space = _mm_or_si128(
+                    _mm_cmpeq_epi8(block, _mm_set1_epi8(b' ' as i8)),
+                    _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\t' as i8)),
+                );
+                let newline = _mm_or_si128(
+                    _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\n' as i8)),
+                    _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\r' as i8)),
+                );
+                _mm_movemask_epi8(_mm_or_si128(space, newline)) as u32
+            };
+            if mask != 0xffff {
+                return pos + mask.trailing_ones() as usize;
+            }
+            pos += 16;
+        }
+        pos
+    }
+
+    /// Skips string content 16 bytes at a time up to the first quote,
+    /// backslash or control character.
+    pub fn skip_string_body(input: &[u8], mut pos: usize) -> usize {
+        while pos + 16 <= input.len() {
+            // SAFETY: as in `skip_whitespace`.
+            let mask = unsafe {
+                let block = _mm_loadu_si128(input.as_ptr().add(pos) as *const __m128i);
+                let quote = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'"' as i8));
+                let backslash = _mm_cmpeq_epi8(block, _mm_set1_epi8(b'\\' as i8));
+                // Unsigned `byte <= 0x1f`, as `max(byte, 0x1f) == 0x1f`.
+                let limit = _mm_set1_epi8(0x1f);
+                let control = _mm_cmpeq_epi8(_mm_max_epu8(block, limit), limit);
+                _mm_movemask_epi8(_mm_or_si128(_mm_or_si128(quote, backslash), control)) as u32
+            };
+            if mask != 0 {
+                return pos + mask.trailing_zeros() as usize;
+            }
+            pos += 16;
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{skip_string_body, skip_whitespace};
+
+    #[test]
+    fn stops_at_the_first_interesting_byte() {
+        // Put the stop byte at every offset across a couple of blocks.
+        for len in 0..40 {
+            let mut input = vec![b' '; len];
+            input.extend_from_slice(b"\t\r\nx   ");
+            assert_eq!(skip_whitespace(&input, 0), len + 3);
+
+            for stop in [b'"', b'\\', b'\n', 0x00, 0x1f] {
+                let mut input: Vec<u8> = "aé😀~".bytes().cycle().take(len).collect();
+                input.push(stop);
+                input.extend_from_slice(&[b'a'; 20]);
+                assert_eq!(skip_string_body(&input, 0), len);
+                assert_eq!(skip_string_body(&input, len + 1), input.len());
+            }
+        }
+        assert_eq!(skip_whitespace(&[b' '; 64], 3), 64);
+    }
+}
@@ -0,0 +1,433 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+use core::{mem, slice};
+use std::collections::{hash_map, HashMap};
+
+use crate::MapHasher;
+
+/// The most members kept in the vector before it becomes a `HashMap`.
+const SMALL: usize = 8;
+
+/// A map that holds up to eight members in a vector searched linearly, and
+/// moves them into a `HashMap` when it grows past that. Most JSON objects are
+/// small, and for them the scan is faster than hashing and the vector much
+/// smaller than a table. Insertion order is kept only while the map is small.
+#[derive(Clone)]
+pub struct SmallMap<K, V>(Repr<K, V>);
+
+#[derive(Clone)]
+enum Repr<K, V> {
+    Small(Vec<(K, V)>),
+    Large(HashMap<K, V, MapHasher>),
+}
+
+impl<K, V> SmallMap<K, V> {
+    pub fn new() -> Self {
+        SmallMap(Repr::Small(Vec::new()))
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Repr::Small(members) => members.len(),
+            Repr::Large(members) => members.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// An estimate of the heap bytes of the vector or table itself, not
+    /// counting what the keys and values own.
+    #[cfg(not(feature = "indexmap"))]
+    pub(crate) fn table_bytes(&self) -> usize {
+        let entry = mem::size_of::<(K, V)>();
+        match &self.0 {
+            Repr::Small(members) => members.capacity() * entry,
+            Repr::Large(members) => members.capacity() * (entry + 1),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        match &self.0 {
+            Repr::Small(members) => Iter(IterRepr::Small(members.iter())),
+            Repr::Large(members) => Iter(IterRepr::Large(members.iter())),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        match &mut self.0 {
+            Repr::Small(members) => IterMut(IterMutRepr::Small(members.iter_mut())),
+            Repr::Large(members) => IterMut(IterMutRepr::Large(members.iter_mut())),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, value)| value)
+    }
+
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        match &mut self.0 {
+            Repr::Small(members) => members.retain_mut(|(key, value)| keep(key, value)),
+            Repr::Large(members) => members.retain(|key, value| keep(key, value)),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0 = Repr::Small(Vec::new());
+    }
+}
+
+impl<K: Hash + Eq, V> SmallMap<K, V> {
+    /// A map with room for `capacity` members; above eight it starts out as
+    /// a `HashMap`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= SMALL {
+            SmallMap(Repr::Small(Vec::with_capacity(capacity)))
+        } else {
+            SmallMap(Repr::Large(HashMap::with_capacity_and_hasher(
+                capacity,
+                MapHasher::default(),
+            )))
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match &self.0 {
+            Repr::Small(members) => members
+                .iter()
+                .find(|(k, _)| k.borrow() == key)
+                .map(|(k, v)| (k, v)),
+            Repr::Large(members) => members.get_key_value(key),
+        }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match &mut self.0 {
+            Repr::Small(members) => members
+                .iter_mut()
+                .find(|(k, _)| (*k).borrow() == key)
+                .map(|(_, v)| v),
+            Repr::Large(members) => members.get_mut(key),
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Inserts `value` under `key`, returning the value it replaces.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Repr::Small(members) = &mut self.0 {
+            if let Some((_, old)) = members.iter_mut().find(|(k, _)| *k == key) {
+                return Some(mem::replace(old, value));
+            }
+            if members.len() < SMALL {
+                members.push((key, value));
+                return None;
+            }
+        }
+        self.large().insert(key, value)
+    }
+
+    /// Removes `key`, returning its value. While the map is small the
+    /// remaining members keep their order.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match &mut self.0 {
+            Repr::Small(members) => {
+                let index = members.iter().position(|(k, _)| k.borrow() == key)?;
+                Some(members.remove(index).1)
+            }
+            Repr::Large(members) => members.remove(key),
+        }
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        Entry { map: self, key }
+    }
+
+    /// The members as a `HashMap`, moving them there first if the map is
+    /// still small.
+    fn large(&mut self) -> &mut HashMap<K, V, MapHasher> {
+        if let Repr::Small(members) = &mut self.0 {
+            let members = mem::take(members);
+            self.0 = Repr::Large(members.into_iter().collect());
+        }
+        match &mut self.0 {
+            Repr::Large(members) => members,
+            Repr::Small(_) => unreachable!("the members were just moved"),
+        }
+    }
+}
+
+/// A place in a [`SmallMap`] for one key, as returned by
+/// [`SmallMap::entry`].
+pub struct Entry<'a, K, V> {
+    map: &'a mut SmallMap<K, V>,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq, V> Entry<'a, K, V> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        let Entry { map, key } = self;
+        if let Repr::Small(members) = &mut map.0 {
+            if let Some(index) = members.iter().position(|(k, _)| *k == key) {
+                let Repr::Small(members) = &mut map.0 else {
+                    unreachable!()
+                };
+                return &mut members[index].1;
+            }
+            if members.len() < SMALL {
+                members.push((key, default()));
+                let Repr::Small(members) = &mut map.0 else {
+                    unreachable!()
+                };
+                return &mut members.last_mut().expect("just pushed").1;
+            }
+        }
+        map.large().entry(key).or_insert_with(default)
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<K, V> Default for SmallMap<K, V> {
+    fn default() -> Self {
+        SmallMap::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for SmallMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Equal when both hold the same members, in any order.
+impl<K: Hash + Eq, V: PartialEq> PartialEq for SmallMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for SmallMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = SmallMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash + Eq, V, const N: usize> From<[(K, V); N]> for SmallMap<K, V> {
+    fn from(members: [(K, V); N]) -> Self {
+        members.into_iter().collect()
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for SmallMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+pub struct Iter<'a, K, V>(IterRepr<'a, K, V>);
+
+enum IterRepr<'a, K, V> {
+    Small(slice::Iter<'a, (K, V)>),
+    Large(hash_map::Iter<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterRepr::Small(members) => members.next().map(|(k, v)| (k, v)),
+            IterRepr::Large(members) => members.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterRepr::Small(members) => members.size_hint(),
+            IterRepr::Large(members) => members.size_hint(),
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V>(IterMutRepr<'a, K, V>);
+
+enum IterMutRepr<'a, K, V> {
+    Small(slice::IterMut<'a, (K, V)>),
+    Large(hash_map::IterMut<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterMutRepr::Small(members) => members.next().map(|(k, v)| (&*k, v)),
+            IterMutRepr::Large(members) => members.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterMutRepr::Small(members) => members.size_hint(),
+            IterMutRepr::Large(members) => members.size_hint(),
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+pub struct IntoIter<K, V>(IntoIterRepr<K, V>);
+
+enum IntoIterRepr<K, V> {
+    Small(alloc::vec::IntoIter<(K, V)>),
+    Large(hash_map::IntoIter<K, V>),
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IntoIterRepr::Small(members) => members.next(),
+            IntoIterRepr::Large(members) => members.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IntoIterRepr::Small(members) => members.size_hint(),
+            IntoIterRepr::Large(members) => members.size_hint(),
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> IntoIterator for SmallMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        match self.0 {
+            Repr::Small(members) => IntoIter(IntoIterRepr::Small(members.into_iter())),
+            Repr::Large(members) => IntoIter(IntoIterRepr::Large(members.into_iter())),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SmallMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut SmallMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Repr, SmallMap};
+
+    #[test]
+    fn grows_into_a_hash_map() {
+        let mut map = SmallMap::new();
+        for i in 0..8 {
+            assert_eq!(map.insert(i, i), None);
+        }
+        assert!(matches!(map.0, Repr::Small(_)));
+        assert_eq!(map.insert(3, 30), Some(3));
+        assert_eq!(
+            map.keys().copied().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6, 7]
+        );
+        *map.entry(8).or_insert(0) += 80;
+        assert!(matches!(map.0, Repr::Large(_)));
+        assert_eq!(
+            (map.len(), map.get(&8), map.get(&3)),
+            (9, Some(&80), Some(&30))
+        );
+        assert_eq!(map.remove(&0), Some(0));
+        assert!(!map.contains_key(&0));
+        let small: SmallMap<_, _> = map
+            .iter()
+            .filter(|(&k, _)| k > 5)
+            .map(|(&k, &v)| (k, v))
+            .collect();
+        assert!(matches!(small.0, Repr::Small(_)));
+        assert_eq!(small, SmallMap::from([(8, 80), (7, 7), (6, 6)]));
+    }
+
+    #[test]
+    fn entries_keep_their_slot() {
+        let mut map = SmallMap::from([("a", 1), ("b", 2)]);
+        *map.entry("a").or_default() += 10;
+        *map.entry("c").or_insert_with(|| 3) += 30;
+        map.retain(|&key, _| key != "b");
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [("a", 11), ("c", 33)]);
+    }
+}
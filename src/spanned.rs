@@ -0,0 +1,463 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::lexer::{decode_number, decode_string, Lexer, TokenKind};
+use crate::parser::Grammar;
+use crate::pointer::{parse_index, JsonPointer};
+use crate::{JsonError, Map, Number, Type};
+
+/// A value together with the byte range of the source text it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub start: usize,
+    pub end: usize,
+    /// Comments before the node, kept by [`parse_spanned_relaxed`].
+    pub comments: Vec<String>,
+}
+
+/// A JSON value from [`parse_spanned`], with a span on every node.
+///
+/// Object members keep their document order, duplicates included, and each
+/// key has a span of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpannedType {
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Spanned<SpannedType>>),
+    Object(Vec<(Spanned<String>, Spanned<SpannedType>)>),
+}
+
+impl Spanned<SpannedType> {
+    /// Looks up a member of an object. As when building a [`Type`], the last
+    /// of several equal keys wins.
+    pub fn get(&self, key: &str) -> Option<&Spanned<SpannedType>> {
+        match &self.value {
+            SpannedType::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(name, _)| name.value == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Looks up a node by JSON Pointer, to find where a value sits in the
+    /// source; `None` if the pointer is malformed or addresses nothing.
+    pub fn pointer(&self, pointer: &str) -> Option<&Spanned<SpannedType>> {
+        let pointer: JsonPointer = pointer.parse().ok()?;
+        pointer
+            .tokens()
+            .iter()
+            .try_fold(self, |node, token| match &node.value {
+                SpannedType::Object(_) => node.get(token),
+                SpannedType::Array(items) => items.get(parse_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Drops the spans.
+    pub fn to_type(&self) -> Type {
+        match &self.value {
+            SpannedType::Null => Type::Null,
+            SpannedType::Boolean(b) => Type::Boolean(*b),
+            SpannedType::Number(n) => Type::Number(*n),
+            SpannedType::String(s) => Type::String(s.as_str().into()),
+            SpannedType::Array(items) => Type::Array(items.iter().map(Self::to_type).collect()),
+            SpannedType::Object(members) => {
+                let mut map = Map::default();
+                for (key, value) in members {
+                    map.insert(key.value.as_str().into(), value.to_type());
+                }
+                Type::Object(Box::new(map))
+            }
+        }
+    }
+}
+
+impl Spanned<SpannedType> {
+    /// Brings a tree from [`parse_spanned`] of `source` up to date after the
+    /// bytes in `edit` are replaced with `text`, and returns the new source.
+    ///
+    /// Only the innermost value holding the whole edit is parsed again, an
+    /// array or object only when the edit is strictly inside its brackets,
+    /// and the spans after it are moved; if that value no longer parses on
+    /// its own, the whole document is. On an error the tree is unchanged.
+    ///
+    /// `JsonError::InvalidPath` if `edit` is not a range of `source` that
+    /// starts and ends on character boundaries.
+    pub fn reparse(
+        &mut self,
+        source: &str,
+        edit: Range<usize>,
+        text: &str,
+    ) -> Result<String, JsonError> {
+        self.reparse_with(source, edit, text, false)
+    }
+
+    /// [`reparse`](Self::reparse) for a tree from [`parse_spanned_relaxed`].
+    pub fn reparse_relaxed(
+        &mut self,
+        source: &str,
+        edit: Range<usize>,
+        text: &str,
+    ) -> Result<String, JsonError> {
+        self.reparse_with(source, edit, text, true)
+    }
+
+    fn reparse_with(
+        &mut self,
+        source: &str,
+        edit: Range<usize>,
+        text: &str,
+        comments: bool,
+    ) -> Result<String, JsonError> {
+        if edit.start > edit.end
+            || !source.is_char_boundary(edit.start)
+            || !source.is_char_boundary(edit.end)
+        {
+            return Err(JsonError::InvalidPath);
+        }
+        let mut new = String::with_capacity(source.len() - edit.len() + text.len());
+        new.push_str(&source[..edit.start]);
+        new.push_str(text);
+        new.push_str(&source[edit.end..]);
+        if encloses(self, &edit) {
+            let path = innermost(self, &edit);
+            let node = node_at(self, &path);
+            let end = node.end - edit.len() + text.len();
+            if let Ok(mut value) = spanned(&new[node.start..end], comments) {
+                move_spans(&mut value, 0, node.start, 0);
+                move_spans(self, edit.end, text.len(), edit.len());
+                let node = node_at(self, &path);
+                let mut before = core::mem::take(&mut node.comments);
+                before.append(&mut value.comments);
+                value.comments = before;
+                *node = value;
+                return Ok(new);
+            }
+        }
+        *self = spanned(&new, comments)?;
+        Ok(new)
+    }
+}
+
+/// Whether the edit can be parsed again as part of `node` alone.
+fn encloses(node: &Spanned<SpannedType>, edit: &Range<usize>) -> bool {
+    match node.value {
+        SpannedType::Array(_) | SpannedType::Object(_) => {
+            node.start < edit.start && edit.end < node.end
+        }
+        _ => node.start <= edit.start && edit.end <= node.end,
+    }
+}
+
+/// The path of child indices to the innermost node under `node` that
+/// encloses the edit.
+fn innermost(node: &Spanned<SpannedType>, edit: &Range<usize>) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut node = node;
+    loop {
+        let child = match &node.value {
+            SpannedType::Array(items) => items
+                .iter()
+                .enumerate()
+                .find(|(_, item)| encloses(item, edit)),
+            SpannedType::Object(members) => members
+                .iter()
+                .map(|(_, value)| value)
+                .enumerate()
+                .find(|(_, value)| encloses(value, edit)),
+            _ => None,
+        };
+        match child {
+            Some((i, child)) => {
+                path.push(i);
+                node = child;
+            }
+            None => return path,
+        }
+    }
+}
+
+fn node_at<'a>(node: &'a mut Spanned<SpannedType>, path: &[usize]) -> &'a mut Spanned<SpannedType> {
+    path.iter().fold(node, |node, &i| match &mut node.value {
+        SpannedType::Array(items) => &mut items[i],
+        SpannedType::Object(members) => &mut members[i].1,
+        _ => unreachable!("the path came from this tree"),
+    })
+}
+
+/// Moves every span boundary at or after `from` by `added - removed` bytes.
+fn move_spans(node: &mut Spanned<SpannedType>, from: usize, added: usize, removed: usize) {
+    let shift = |offset: &mut usize| {
+        if *offset >= from {
+            *offset = *offset + added - removed;
+        }
+    };
+    shift(&mut node.start);
+    shift(&mut node.end);
+    match &mut node.value {
+        SpannedType::Array(items) => {
+            for item in items {
+                move_spans(item, from, added, removed);
+            }
+        }
+        SpannedType::Object(members) => {
+            for (key, value) in members {
+                shift(&mut key.start);
+                shift(&mut key.end);
+                move_spans(value, from, added, removed);
+            }
+        }
+        _ => {}
+    }
+}
+
+type Member = (Spanned<String>, Spanned<SpannedType>);
+
+enum Frame {
+    Array(Vec<Spanned<SpannedType>>),
+    Object(Vec<Member>, Option<Spanned<String>>),
+}
+
+/// Parses `json` like [`parse`](crate::parse), but records where each node
+/// starts and ends, so that later checks on the values can point at the
+/// source they came from.
+pub fn parse_spanned(json: &str) -> Result<Spanned<SpannedType>, JsonError> {
+    spanned(json, false)
+}
+
+/// Like [`parse_spanned`], but accepts `//` and `/* */` comments and keeps
+/// each one, delimiters included, in the `comments` of the key or value that
+/// follows it. Comments with nothing after them in their container, or
+/// after the root value, are dropped; use [`parse_cst`](crate::parse_cst)
+/// to keep every byte.
+pub fn parse_spanned_relaxed(json: &str) -> Result<Spanned<SpannedType>, JsonError> {
+    spanned(json, true)
+}
+
+fn spanned(json: &str, comments: bool) -> Result<Spanned<SpannedType>, JsonError> {
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    if comments {
+        lexer = lexer.with_comments();
+    }
+    let mut grammar = Grammar::new();
+    // Each open container with where it starts and the comments before it.
+    let mut stack: Vec<(usize, Vec<String>, Frame)> = Vec::new();
+    let mut root = None;
+    // Comments waiting for the next node, and the end of the previous token.
+    let mut pending = Vec::new();
+    let mut prev = 0;
+    while let Some(token) = lexer.next_token()? {
+        let is_key = grammar.expects_key();
+        grammar.accept(token)?;
+        if comments {
+            collect_comments(&json[prev..token.start], &mut pending);
+            prev = token.end;
+        }
+        let (start, comments, value) = match token.kind {
+            TokenKind::BeginArray => {
+                let comments = core::mem::take(&mut pending);
+                stack.push((token.start, comments, Frame::Array(Vec::new())));
+                continue;
+            }
+            TokenKind::BeginObject => {
+                let comments = core::mem::take(&mut pending);
+                stack.push((token.start, comments, Frame::Object(Vec::new(), None)));
+                continue;
+            }
+            TokenKind::Colon | TokenKind::Comma => continue,
+            TokenKind::EndArray | TokenKind::EndObject => {
+                pending.clear();
+                match stack.pop() {
+                    Some((start, comments, Frame::Array(items))) => {
+                        (start, comments, SpannedType::Array(items))
+                    }
+                    Some((start, comments, Frame::Object(members, _))) => {
+                        (start, comments, SpannedType::Object(members))
+                    }
+                    None => unreachable!("the grammar balances containers"),
+                }
+            }
+            TokenKind::String => {
+                let s = decode_string(
+                    &json.as_bytes()[token.start + 1..token.end - 1],
+                    token.start + 1,
+                )?;
+                if is_key {
+                    if let Some((_, _, Frame::Object(_, key))) = stack.last_mut() {
+                        *key = Some(Spanned {
+                            value: s,
+                            start: token.start,
+                            end: token.end,
+                            comments: core::mem::take(&mut pending),
+                        });
+                    }
+                    continue;
+                }
+                (
+                    token.start,
+                    core::mem::take(&mut pending),
+                    SpannedType::String(s),
+                )
+            }
+            TokenKind::Number => (
+                token.start,
+                core::mem::take(&mut pending),
+                SpannedType::Number(decode_number(
+                    &json.as_bytes()[token.start..token.end],
+                    token.start,
+                )?),
+            ),
+            TokenKind::True => (
+                token.start,
+                core::mem::take(&mut pending),
+                SpannedType::Boolean(true),
+            ),
+            TokenKind::False => (
+                token.start,
+                core::mem::take(&mut pending),
+                SpannedType::Boolean(false),
+            ),
+            TokenKind::Null => (
+                token.start,
+                core::mem::take(&mut pending),
+                SpannedType::Null,
+            ),
+        };
+        let node = Spanned {
+            value,
+            start,
+            end: token.end,
+            comments,
+        };
+        match stack.last_mut() {
+            None => root = Some(node),
+            Some((_, _, Frame::Array(items))) => items.push(node),
+            Some((_, _, Frame::Object(members, key))) => {
+                if let Some(key) = key.take() {
+                    members.push((key, node));
+                }
+            }
+        }
+    }
+    root.ok_or(JsonError::UnexpectEof { offset: json.len() })
+}
+
+/// Appends the comments in `trivia`, a run of whitespace and comments the
+/// lexer has already checked, to `out`.
+fn collect_comments(trivia: &str, out: &mut Vec<String>) {
+    let mut rest = trivia.trim_start();
+    while !rest.is_empty() {
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else {
+            rest.find("*/").map_or(rest.len(), |end| end + 2)
+        };
+        out.push(rest[..len].to_string());
+        rest = rest[len..].trim_start();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_spanned, parse_spanned_relaxed, SpannedType};
+    use crate::{parse, JsonError};
+
+    #[test]
+    fn records_spans() {
+        let json = "{\n  \"name\": \"demo\",\n  \"ports\": [80, 443],\n  \"name\": null\n}";
+        let root = parse_spanned(json).unwrap();
+        assert_eq!((root.start, root.end), (0, json.len()));
+        assert_eq!(root.to_type(), parse(json).unwrap());
+
+        let ports = root.pointer("/ports").unwrap();
+        assert_eq!(&json[ports.start..ports.end], "[80, 443]");
+        let port = root.pointer("/ports/1").unwrap();
+        assert_eq!(&json[port.start..port.end], "443");
+        assert_eq!(port.value, SpannedType::Number(443.into()));
+        assert_eq!(root.get("name").unwrap().value, SpannedType::Null);
+
+        let SpannedType::Object(members) = &root.value else {
+            panic!("expected an object");
+        };
+        let first = &members[0];
+        assert_eq!(&json[first.0.start..first.0.end], "\"name\"");
+        assert_eq!(&json[first.1.start..first.1.end], "\"demo\"");
+        assert!(root.pointer("/missing").is_none());
+    }
+
+    #[test]
+    fn errors() {
+        for json in ["[1,]", "{\"a\" 1}", "[1] 2", "\"\\x\"", "", "[[]"] {
+            assert_eq!(parse_spanned(json).map(|_| ()), parse(json).map(|_| ()));
+        }
+        assert_eq!(
+            parse_spanned(" [").unwrap_err(),
+            JsonError::UnexpectEof { offset: 2 }
+        );
+    }
+
+    #[test]
+    fn reparses_edits() {
+        let json = r#"{"a": [1, 2, "x"], "b": {"c": true}, "d": null}"#;
+        for (find, text) in [
+            ("2", "20"),
+            ("x", "xyz"),
+            ("2", "2, 3"),
+            ("true", "[false]"),
+            (r#""c""#, r#""e""#),
+            (r#", "d": null"#, ""),
+            ("[1", "[0, 1"),
+        ] {
+            let start = json.find(find).unwrap();
+            let mut root = parse_spanned(json).unwrap();
+            let new = root.reparse(json, start..start + find.len(), text).unwrap();
+            assert_eq!(new, json.replacen(find, text, 1));
+            assert_eq!(root, parse_spanned(&new).unwrap(), "{}", new);
+        }
+        let mut root = parse_spanned(json).unwrap();
+        assert!(root.reparse(json, 7..8, "1 1").is_err());
+        assert!(root.reparse(json, 0..1, "").is_err());
+        #[allow(clippy::reversed_empty_ranges)]
+        for edit in [2..9, 200..300, 3..2] {
+            assert_eq!(root.reparse("[1]", edit, ""), Err(JsonError::InvalidPath));
+        }
+        assert_eq!(
+            root.reparse("[\"é\"]", 3..4, ""),
+            Err(JsonError::InvalidPath)
+        );
+        assert_eq!(root, parse_spanned(json).unwrap());
+
+        let json = "// list\n[1, /* two */ 2]";
+        let mut root = parse_spanned_relaxed(json).unwrap();
+        let new = root.reparse_relaxed(json, 22..23, "/* 3 */ 3").unwrap();
+        assert_eq!(root, parse_spanned_relaxed(&new).unwrap());
+        assert_eq!(
+            root.pointer("/1").unwrap().comments,
+            ["/* two */", "/* 3 */"]
+        );
+    }
+
+    #[test]
+    fn keeps_comments() {
+        let json = "// settings\n{\n  /* the port */ \"port\": // http\n 80,\n  \"on\": true // dropped\n}";
+        let root = parse_spanned_relaxed(json).unwrap();
+        assert_eq!(root.comments, ["// settings"]);
+        let SpannedType::Object(members) = &root.value else {
+            panic!("expected an object");
+        };
+        assert_eq!(members[0].0.comments, ["/* the port */"]);
+        assert_eq!(members[0].1.comments, ["// http"]);
+        assert!(members[1].0.comments.is_empty());
+        assert!(members[1].1.comments.is_empty());
+
+        assert!(parse_spanned(json).is_err());
+        assert!(parse_spanned("[1]").unwrap().comments.is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::hash::subtree_hash;
+use crate::visit::{walk_array, walk_object, Visitor};
+use crate::{JsonPointer, Key, Map, Number, Str, Type};
+
+/// The shape of a value, as measured by [`Type::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// How many arrays and objects are nested inside one another at the
+    /// deepest point; 0 for a scalar.
+    pub max_depth: usize,
+    pub nulls: usize,
+    pub booleans: usize,
+    pub numbers: usize,
+    pub strings: usize,
+    pub arrays: usize,
+    pub objects: usize,
+    /// The UTF-8 length of all strings, keys excluded.
+    pub string_bytes: usize,
+    /// The UTF-8 length of all object keys.
+    pub key_bytes: usize,
+    /// The most elements in any one array.
+    pub largest_array: usize,
+    /// The most members in any one object.
+    pub largest_object: usize,
+}
+
+impl Stats {
+    /// All values counted, containers included.
+    pub fn nodes(&self) -> usize {
+        self.nulls + self.booleans + self.numbers + self.strings + self.arrays + self.objects
+    }
+}
+
+struct Counter {
+    stats: Stats,
+    depth: usize,
+}
+
+impl Counter {
+    fn enter(&mut self) {
+        self.depth += 1;
+        self.stats.max_depth = self.stats.max_depth.max(self.depth);
+    }
+}
+
+impl Visitor for Counter {
+    fn visit_null(&mut self) {
+        self.stats.nulls += 1;
+    }
+
+    fn visit_bool(&mut self, _value: bool) {
+        self.stats.booleans += 1;
+    }
+
+    fn visit_number(&mut self, _value: Number) {
+        self.stats.numbers += 1;
+    }
+
+    fn visit_string(&mut self, value: &str) {
+        self.stats.strings += 1;
+        self.stats.string_bytes += value.len();
+    }
+
+    fn visit_array(&mut self, items: &[Type]) {
+        self.stats.arrays += 1;
+        self.stats.largest_array = self.stats.largest_array.max(items.len());
+        self.enter();
+        walk_array(self, items);
+        self.depth -= 1;
+    }
+
+    fn visit_object(&mut self, members: &Map<Key, Type>) {
+        self.stats.objects += 1;
+        self.stats.largest_object = self.stats.largest_object.max(members.len());
+        self.enter();
+        walk_object(self, members);
+        self.depth -= 1;
+    }
+
+    fn visit_member(&mut self, key: &str, value: &Type) {
+        self.stats.key_bytes += key.len();
+        self.visit_value(value);
+    }
+}
+
+impl Type {
+    /// Measures the value: its depth, how many values of each kind it holds,
+    /// and its largest strings and containers. Useful for deciding whether a
+    /// kind of document is better streamed than built.
+    pub fn stats(&self) -> Stats {
+        let mut counter = Counter {
+            stats: Stats::default(),
+            depth: 0,
+        };
+        self.accept(&mut counter);
+        counter.stats
+    }
+
+    /// An estimate of the heap bytes the value holds: string buffers, the
+    /// capacity of arrays, and object maps with their keys. The value
+    /// itself, `size_of::<Type>()`, is not counted. For a memory budget on
+    /// cached documents, say.
+    ///
+    /// Map overhead is approximated, and a shared `arc-keys` key is counted
+    /// once for every object holding it.
+    pub fn deep_size_of(&self) -> usize {
+        match self {
+            Type::String(s) => str_bytes(s),
+            Type::Array(items) => {
+                items.capacity() * mem::size_of::<Type>()
+                    + items.iter().map(Type::deep_size_of).sum::<usize>()
+            }
+            Type::Object(members) => {
+                mem::size_of::<Map<Key, Type>>()
+                    + table_bytes(members)
+                    + members
+                        .iter()
+                        .map(|(key, member)| key_bytes(key) + member.deep_size_of())
+                        .sum::<usize>()
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// What repeats in a value, as found by [`Type::repetitions`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Repetitions {
+    /// Object keys used more than once, with how often, the most used
+    /// first.
+    pub keys: Vec<(String, usize)>,
+    /// Arrays and objects that occur more than once, the ones whose copies
+    /// take the most bytes first.
+    pub subtrees: Vec<Repeated>,
+}
+
+/// An array or object that occurs more than once in a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repeated {
+    /// The JSON Pointer of one occurrence.
+    pub pointer: String,
+    pub count: usize,
+    /// The length of one occurrence as compact JSON.
+    pub bytes: usize,
+}
+
+impl Repeated {
+    /// The bytes that storing the subtree once would save.
+    pub fn redundant_bytes(&self) -> usize {
+        (self.count - 1) * self.bytes
+    }
+}
+
+impl Type {
+    /// Finds the object keys and the subtrees that repeat in the value,
+    /// reporting the `top` most repeated of each, to judge whether sharing
+    /// them (with the `arc-keys` feature, or
+    /// [`dedup_subtrees`](Type::dedup_subtrees)) is worth it for a kind of
+    /// document.
+    ///
+    /// Subtrees are equal as under `==`. The copies of a repeated subtree
+    /// count towards whatever repeats inside them.
+    pub fn repetitions(&self, top: usize) -> Repetitions {
+        let mut finder = RepetitionFinder {
+            keys: BTreeMap::new(),
+            subtrees: BTreeMap::new(),
+            pointer: JsonPointer::root(),
+        };
+        finder.hash(self);
+
+        let mut keys: Vec<(String, usize)> = finder
+            .keys
+            .into_iter()
+            .filter(|&(_, count)| count > 1)
+            .map(|(key, count)| (key.to_string(), count))
+            .collect();
+        keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        keys.truncate(top);
+
+        let mut subtrees: Vec<Repeated> = finder
+            .subtrees
+            .into_values()
+            .flatten()
+            .filter(|group| group.count > 1)
+            .map(|group| Repeated {
+                pointer: group.pointer,
+                count: group.count,
+                bytes: group.value.serialized_size(),
+            })
+            .collect();
+        subtrees.sort_by(|a, b| {
+            b.redundant_bytes()
+                .cmp(&a.redundant_bytes())
+                .then_with(|| b.count.cmp(&a.count))
+                .then_with(|| a.pointer.cmp(&b.pointer))
+        });
+        subtrees.truncate(top);
+        Repetitions { keys, subtrees }
+    }
+}
+
+/// Equal subtrees seen so far, one representative for each.
+struct Group<'a> {
+    value: &'a Type,
+    pointer: String,
+    count: usize,
+}
+
+struct RepetitionFinder<'a> {
+    keys: BTreeMap<&'a str, usize>,
+    /// Groups by subtree hash.
+    subtrees: BTreeMap<u64, Vec<Group<'a>>>,
+    pointer: JsonPointer,
+}
+
+impl<'a> RepetitionFinder<'a> {
+    /// Hashes the value from its children's hashes, so that each node is
+    /// hashed once, and counts it if it is a non-empty container.
+    fn hash(&mut self, value: &'a Type) -> u64 {
+        let hash = subtree_hash(value, |i, key, child| {
+            match key {
+                Some(key) => {
+                    *self.keys.entry(key).or_default() += 1;
+                    self.pointer.push(key);
+                }
+                None => self.pointer.push(i.to_string()),
+            }
+            let hash = self.hash(child);
+            self.pointer.pop();
+            hash
+        });
+        let is_counted = match value {
+            Type::Array(items) => !items.is_empty(),
+            Type::Object(members) => !members.is_empty(),
+            _ => false,
+        };
+        if !is_counted {
+            return hash;
+        }
+        let bucket = self.subtrees.entry(hash).or_default();
+        match bucket.iter_mut().find(|group| group.value == value) {
+            Some(group) => group.count += 1,
+            None => bucket.push(Group {
+                value,
+                pointer: self.pointer.to_string(),
+                count: 1,
+            }),
+        }
+        hash
+    }
+}
+
+#[cfg(not(feature = "compact-str"))]
+fn str_bytes(s: &Str) -> usize {
+    s.capacity()
+}
+
+#[cfg(feature = "compact-str")]
+fn str_bytes(s: &Str) -> usize {
+    if s.is_heap_allocated() {
+        s.capacity()
+    } else {
+        0
+    }
+}
+
+#[cfg(not(feature = "arc-keys"))]
+fn key_bytes(key: &Key) -> usize {
+    str_bytes(key)
+}
+
+/// The text and the two reference counts.
+#[cfg(feature = "arc-keys")]
+fn key_bytes(key: &Key) -> usize {
+    key.len() + 2 * mem::size_of::<usize>()
+}
+
+/// Each entry, and its index and control byte.
+#[cfg(feature = "indexmap")]
+fn table_bytes(members: &Map<Key, Type>) -> usize {
+    let entry = mem::size_of::<(usize, Key, Type)>();
+    members.capacity() * (entry + mem::size_of::<usize>() + 1)
+}
+
+#[cfg(all(feature = "small-map", not(feature = "indexmap")))]
+fn table_bytes(members: &Map<Key, Type>) -> usize {
+    members.table_bytes()
+}
+
+/// Each entry and its control byte.
+#[cfg(all(
+    feature = "std",
+    not(any(feature = "btree-map", feature = "small-map", feature = "indexmap"))
+))]
+fn table_bytes(members: &Map<Key, Type>) -> usize {
+    members.capacity() * (mem::size_of::<(Key, Type)>() + 1)
+}
+
+/// B-tree nodes run about two thirds full.
+#[cfg(all(
+    any(not(feature = "std"), feature = "btree-map"),
+    not(any(feature = "small-map", feature = "indexmap"))
+))]
+fn table_bytes(members: &Map<Key, Type>) -> usize {
+    members.len() * mem::size_of::<(Key, Type)>() * 3 / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Repeated, Stats};
+    use crate::{into_str, parse, Type};
+
+    #[test]
+    fn measures_values() {
+        let value = parse(r#"{"ab": [1, "xyz", null, true], "c": {"d": [[]], "é": "ü"}}"#).unwrap();
+        let stats = value.stats();
+        assert_eq!(
+            stats,
+            Stats {
+                max_depth: 4,
+                nulls: 1,
+                booleans: 1,
+                numbers: 1,
+                strings: 2,
+                arrays: 3,
+                objects: 2,
+                string_bytes: 5,
+                key_bytes: 6,
+                largest_array: 4,
+                largest_object: 2,
+            }
+        );
+        assert_eq!(stats.nodes(), 10);
+        assert_eq!(
+            Type::Number(1.into()).stats(),
+            Stats {
+                numbers: 1,
+                ..Stats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn estimates_heap_size() {
+        assert_eq!(Type::Number(1.into()).deep_size_of(), 0);
+        assert_eq!(Type::Null.deep_size_of(), 0);
+        let long = Type::String(into_str("x".repeat(100)));
+        assert!(long.deep_size_of() >= 100);
+
+        let items = vec![long.clone(), long.clone(), Type::Null];
+        let array = Type::Array(items);
+        assert!(array.deep_size_of() >= 200 + 3 * std::mem::size_of::<Type>());
+
+        let object =
+            parse(r#"{"a": [1, 2, 3], "b": "this string is long enough to allocate"}"#).unwrap();
+        let members = object.deep_size_of();
+        let a = object.pointer("/a").unwrap().deep_size_of();
+        let b = object.pointer("/b").unwrap().deep_size_of();
+        assert!(a >= 3 * std::mem::size_of::<Type>());
+        assert!(members > a + b);
+    }
+
+    #[test]
+    fn finds_repetitions() {
+        let value = parse(
+            r#"{"users": [{"id": 1, "role": {"name": "admin", "level": 3}}, {"id": 2, "role": {"level": 3, "name": "admin"}}, {"id": 3, "role": {"name": "guest"}}], "owner": {"name": "admin", "level": 3}, "tags": [[], []]}"#,
+        )
+        .unwrap();
+        let repetitions = value.repetitions(10);
+        let keys: Vec<(&str, usize)> = repetitions
+            .keys
+            .iter()
+            .map(|(key, count)| (key.as_str(), *count))
+            .collect();
+        assert_eq!(keys, [("name", 4), ("id", 3), ("level", 3), ("role", 3)]);
+
+        // Empty containers are too small to be worth sharing.
+        let [role]: [Repeated; 1] = repetitions.subtrees.try_into().unwrap();
+        assert_eq!(role.count, 3);
+        assert_eq!(role.bytes, r#"{"name":"admin","level":3}"#.len());
+        assert_eq!(role.redundant_bytes(), 2 * role.bytes);
+        assert_eq!(value.pointer(&role.pointer), value.pointer("/owner"));
+
+        assert_eq!(value.repetitions(1).keys.len(), 1);
+        assert_eq!(parse("[1, 1]").unwrap().repetitions(10), Default::default());
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::{self, Write};
+
+use crate::Type;
+
+/// Writes a JSON array one element at a time, straight to `writer`, so a
+/// large result set, such as rows read from a database cursor, can be sent
+/// without building the array or its text in memory first.
+///
+/// The array is only valid JSON once [`finish`](ArraySerializer::finish)
+/// has written the closing bracket.
+pub struct ArraySerializer<W> {
+    writer: W,
+    /// Whether the opening bracket has been written.
+    started: bool,
+}
+
+impl<W: Write> ArraySerializer<W> {
+    pub fn new(writer: W) -> Self {
+        ArraySerializer {
+            writer,
+            started: false,
+        }
+    }
+
+    /// Writes `value` as the next element, compactly.
+    pub fn write(&mut self, value: &Type) -> io::Result<()> {
+        self.writer
+            .write_all(if self.started { b"," } else { b"[" })?;
+        self.started = true;
+        value.write_to(&mut self.writer)
+    }
+
+    pub fn write_all<'a, I>(&mut self, values: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Type>,
+    {
+        for value in values {
+            self.write(value)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Closes the array, writing `[]` if no element was written, and
+    /// returns the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.started {
+            self.writer.write_all(b"[")?;
+        }
+        self.writer.write_all(b"]")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArraySerializer;
+    use crate::{parse_slice, Type};
+
+    #[test]
+    fn streams_elements() {
+        let mut array = ArraySerializer::new(Vec::new());
+        for i in 0..3 {
+            array
+                .write(&Type::Array(vec![Type::Number(i.into()), Type::Null]))
+                .unwrap();
+        }
+        array.write_all(&[Type::String("a\"b".into())]).unwrap();
+        let out = array.finish().unwrap();
+        assert_eq!(out, b"[[0,null],[1,null],[2,null],\"a\\\"b\"]");
+        assert!(parse_slice(&out).is_ok());
+
+        let empty = ArraySerializer::new(Vec::new()).finish().unwrap();
+        assert_eq!(empty, b"[]");
+    }
+}
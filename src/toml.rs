@@ -0,0 +1,210 @@
+use std::fmt;
+
+use ::toml::{Table, Value};
+
+use crate::{into_str, JsonPointer, Map, Type};
+
+/// An error from [`from_toml`] or [`to_toml`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TomlError {
+    /// The text is not valid TOML; `offset` is the byte where it goes wrong,
+    /// when known.
+    Syntax {
+        offset: Option<usize>,
+        message: String,
+    },
+    /// The value at `pointer` has no TOML equivalent, such as `null` or a
+    /// document that is not an object.
+    Unrepresentable {
+        pointer: JsonPointer,
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TomlError::Syntax {
+                offset: Some(offset),
+                message,
+            } => write!(f, "invalid TOML at byte {}: {}", offset, message),
+            TomlError::Syntax {
+                offset: None,
+                message,
+            } => write!(f, "invalid TOML: {}", message),
+            TomlError::Unrepresentable { pointer, message } => {
+                write!(f, "'{}': {}", pointer, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TomlError {}
+
+/// Parses a TOML document into an object. Datetimes become their RFC 3339
+/// text, and non-finite floats such as `inf` become `null`.
+pub fn from_toml(text: &str) -> Result<Type, TomlError> {
+    let table: Table = text
+        .parse()
+        .map_err(|err: ::toml::de::Error| TomlError::Syntax {
+            offset: err.span().map(|span| span.start),
+            message: err.message().trim_end().into(),
+        })?;
+    Ok(convert(Value::Table(table)))
+}
+
+fn convert(value: Value) -> Type {
+    match value {
+        Value::String(s) => Type::String(into_str(s)),
+        Value::Integer(n) => Type::Number(n.into()),
+        Value::Float(n) if n.is_finite() => Type::Number(n.into()),
+        Value::Float(_) => Type::Null,
+        Value::Boolean(b) => Type::Boolean(b),
+        Value::Datetime(datetime) => Type::String(into_str(datetime.to_string())),
+        Value::Array(items) => Type::Array(items.into_iter().map(convert).collect()),
+        Value::Table(table) => {
+            let members: Map<_, _> = table
+                .into_iter()
+                .map(|(key, value)| (key.as_str().into(), convert(value)))
+                .collect();
+            Type::Object(Box::new(members))
+        }
+    }
+}
+
+/// Writes `value`, which must be an object, as a TOML document with keys in
+/// sorted order.
+///
+/// TOML has no `null`, so a `null` anywhere is `TomlError::Unrepresentable`,
+/// as is a document that is not an object. Numbers that are integers in the `i64` range are written as integers.
+pub fn to_toml(value: &Type) -> Result<String, TomlError> {
+    let mut pointer = JsonPointer::root();
+    match to_value(value, &mut pointer)? {
+        Value::Table(table) => Ok(table.to_string()),
+        _ => Err(TomlError::Unrepresentable {
+            pointer,
+            message: "a TOML document must be an object",
+        }),
+    }
+}
+
+fn to_value(value: &Type, pointer: &mut JsonPointer) -> Result<Value, TomlError> {
+    Ok(match value {
+        Type::Null => {
+            return Err(TomlError::Unrepresentable {
+                pointer: pointer.clone(),
+                message: "TOML has no null",
+            })
+        }
+        Type::Boolean(b) => Value::Boolean(*b),
+        Type::Number(n) => match n.as_i64() {
+            Some(n) => Value::Integer(n),
+            None => Value::Float(n.to_f64()),
+        },
+        Type::String(s) => Value::String(s.as_str().into()),
+        Type::Array(items) => {
+            let mut array = Vec::with_capacity(items.len());
+            for (i, item) in items.iter().enumerate() {
+                pointer.push(i.to_string());
+                array.push(to_value(item, pointer)?);
+                pointer.pop();
+            }
+            Value::Array(array)
+        }
+        Type::Object(members) => {
+            let mut table = Table::new();
+            for (key, member) in members.iter() {
+                pointer.push(&**key);
+                table.insert(String::from(&**key), to_value(member, pointer)?);
+                pointer.pop();
+            }
+            Value::Table(table)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_toml, to_toml, TomlError};
+    use crate::parse;
+
+    #[test]
+    fn reads_toml() {
+        let toml = r#"
+title = "demo"
+ports = [80, 443]
+mixed = [1, "a"]
+ratio = 0.5
+forever = inf
+started = 1979-05-27T07:32:00Z
+
+[database]
+enabled = true
+
+[[servers]]
+name = "alpha"
+
+[[servers]]
+name = "beta"
+"#;
+        let expected = parse(
+            r#"{
+                "title": "demo",
+                "ports": [80, 443],
+                "mixed": [1, "a"],
+                "ratio": 0.5,
+                "forever": null,
+                "started": "1979-05-27T07:32:00Z",
+                "database": {"enabled": true},
+                "servers": [{"name": "alpha"}, {"name": "beta"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(from_toml(toml), Ok(expected));
+        match from_toml("a = ") {
+            Err(TomlError::Syntax {
+                offset: Some(4), ..
+            }) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn writes_toml() {
+        let value = parse(
+            r#"{"name": "svc", "port": 8080, "ratio": 1.5, "db": {"host": "h", "tls": true}, "users": [{"id": 1}, {"id": 2}]}"#,
+        )
+        .unwrap();
+        let toml = to_toml(&value).unwrap();
+        assert_eq!(
+            toml,
+            r#"name = "svc"
+port = 8080
+ratio = 1.5
+
+[db]
+host = "h"
+tls = true
+
+[[users]]
+id = 1
+
+[[users]]
+id = 2
+"#
+        );
+        assert_eq!(from_toml(&toml), Ok(value));
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        let error = |json: &str| match to_toml(&parse(json).unwrap()) {
+            Err(err) => err.to_string(),
+            Ok(toml) => panic!("{}", toml),
+        };
+        assert_eq!(error("[1, 2]"), "'': a TOML document must be an object");
+        assert_eq!(error(r#"{"a": [1, null]}"#), "'/a/1': TOML has no null");
+        assert_eq!(to_toml(&parse("{}").unwrap()), Ok(String::new()));
+        assert_eq!(from_toml(""), Ok(parse("{}").unwrap()));
+    }
+}
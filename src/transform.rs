@@ -0,0 +1,254 @@
+use alloc::boxed::Box;
+use alloc::string::ToString;
+
+use crate::{JsonError, JsonPointer, Type};
+
+/// Where a value sits in its container, as passed to [`Type::retain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl Type {
+    /// Rebuilds the value with `f` applied to every scalar in it; arrays and
+    /// objects keep their shape.
+    pub fn map_values<F: FnMut(Type) -> Type>(self, mut f: F) -> Type {
+        map_values(self, &mut f)
+    }
+
+    /// Like [`map_values`](Type::map_values), also passing `f` the pointer
+    /// to each scalar.
+    pub fn map_values_with_path<F>(self, mut f: F) -> Type
+    where
+        F: FnMut(&JsonPointer, Type) -> Type,
+    {
+        map_values_with_path(self, &mut JsonPointer::root(), &mut f)
+    }
+
+    /// Rebuilds the value keeping only the array elements and object members,
+    /// at any depth, for which `keep` returns true. A dropped value's
+    /// children are not looked at; the root itself is always kept.
+    pub fn filter<F: FnMut(&Type) -> bool>(self, mut keep: F) -> Type {
+        filter(self, &mut keep)
+    }
+
+    /// Rebuilds the value replacing every node `matches` accepts with `f` of
+    /// it, searching from the root down. The children of a replaced node are
+    /// not searched.
+    pub fn transform<P, F>(self, mut matches: P, mut f: F) -> Type
+    where
+        P: FnMut(&Type) -> bool,
+        F: FnMut(Type) -> Type,
+    {
+        transform(self, &mut matches, &mut f)
+    }
+
+    /// Keeps only the direct children of an array or object for which `keep`
+    /// returns true, in place, like `Vec::retain` and `HashMap::retain`.
+    /// Array elements are passed their index before any removal. Scalars are
+    /// left alone.
+    pub fn retain<F: FnMut(Segment, &mut Type) -> bool>(&mut self, mut keep: F) {
+        match self {
+            Type::Array(items) => {
+                let mut index = 0;
+                items.retain_mut(|item| {
+                    index += 1;
+                    keep(Segment::Index(index - 1), item)
+                });
+            }
+            Type::Object(members) => members.retain(|key, value| keep(Segment::Key(key), value)),
+            _ => {}
+        }
+    }
+
+    /// Replaces the value at the JSON Pointer `pointer` with `f` of it;
+    /// `JsonError::InvalidPath` if the pointer is malformed or addresses
+    /// nothing.
+    pub fn transform_at<F: FnOnce(Type) -> Type>(
+        mut self,
+        pointer: &str,
+        f: F,
+    ) -> Result<Type, JsonError> {
+        let target = self.pointer_mut(pointer).ok_or(JsonError::InvalidPath)?;
+        *target = f(core::mem::replace(target, Type::Null));
+        Ok(self)
+    }
+}
+
+fn map_values<F: FnMut(Type) -> Type>(value: Type, f: &mut F) -> Type {
+    match value {
+        Type::Array(items) => Type::Array(items.into_iter().map(|v| map_values(v, f)).collect()),
+        Type::Object(members) => Type::Object(Box::new(
+            members
+                .into_iter()
+                .map(|(k, v)| (k, map_values(v, f)))
+                .collect(),
+        )),
+        leaf => f(leaf),
+    }
+}
+
+fn map_values_with_path<F>(value: Type, path: &mut JsonPointer, f: &mut F) -> Type
+where
+    F: FnMut(&JsonPointer, Type) -> Type,
+{
+    match value {
+        Type::Array(items) => Type::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    path.push(i.to_string());
+                    let v = map_values_with_path(v, path, f);
+                    path.pop();
+                    v
+                })
+                .collect(),
+        ),
+        Type::Object(members) => Type::Object(Box::new(
+            members
+                .into_iter()
+                .map(|(k, v)| {
+                    path.push(&*k);
+                    let v = map_values_with_path(v, path, f);
+                    path.pop();
+                    (k, v)
+                })
+                .collect(),
+        )),
+        leaf => f(path, leaf),
+    }
+}
+
+fn filter<F: FnMut(&Type) -> bool>(value: Type, keep: &mut F) -> Type {
+    match value {
+        Type::Array(items) => Type::Array(
+            items
+                .into_iter()
+                .filter_map(|v| keep(&v).then(|| filter(v, keep)))
+                .collect(),
+        ),
+        Type::Object(members) => Type::Object(Box::new(
+            members
+                .into_iter()
+                .filter_map(|(k, v)| keep(&v).then(|| (k, filter(v, keep))))
+                .collect(),
+        )),
+        leaf => leaf,
+    }
+}
+
+fn transform<P, F>(value: Type, matches: &mut P, f: &mut F) -> Type
+where
+    P: FnMut(&Type) -> bool,
+    F: FnMut(Type) -> Type,
+{
+    if matches(&value) {
+        return f(value);
+    }
+    match value {
+        Type::Array(items) => Type::Array(
+            items
+                .into_iter()
+                .map(|v| transform(v, matches, f))
+                .collect(),
+        ),
+        Type::Object(members) => Type::Object(Box::new(
+            members
+                .into_iter()
+                .map(|(k, v)| (k, transform(v, matches, f)))
+                .collect(),
+        )),
+        leaf => leaf,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Segment;
+    use crate::{into_str, parse, JsonError, Type};
+
+    const DOC: &str = r#"{"a": 1.26, "b": [null, 2.5, {"c": null, "d": " x "}], "e": null}"#;
+
+    #[test]
+    fn maps_and_filters() {
+        let rounded = parse(DOC).unwrap().map_values(|v| match v {
+            Type::Number(n) => Type::Number(n.to_f64().round().into()),
+            v => v,
+        });
+        assert_eq!(
+            rounded,
+            parse(r#"{"a": 1, "b": [null, 3, {"c": null, "d": " x "}], "e": null}"#).unwrap()
+        );
+        let filtered = parse(DOC).unwrap().filter(|v| *v != Type::Null);
+        assert_eq!(
+            filtered,
+            parse(r#"{"a": 1.26, "b": [2.5, {"d": " x "}]}"#).unwrap()
+        );
+        assert_eq!(Type::Null.filter(|_| false), Type::Null);
+    }
+
+    #[test]
+    fn maps_with_paths() {
+        let mut paths = Vec::new();
+        let value = parse(DOC).unwrap().map_values_with_path(|path, v| {
+            paths.push(path.to_string());
+            match v {
+                Type::Number(n) => Type::String(into_str(n.to_string())),
+                v => v,
+            }
+        });
+        assert_eq!(
+            value,
+            parse(r#"{"a": "1.26", "b": [null, "2.5", {"c": null, "d": " x "}], "e": null}"#)
+                .unwrap()
+        );
+        paths.sort();
+        assert_eq!(paths, ["/a", "/b/0", "/b/1", "/b/2/c", "/b/2/d", "/e"]);
+        let root =
+            Type::Null.map_values_with_path(|path, _| Type::String(into_str(path.to_string())));
+        assert_eq!(root, Type::String("".into()));
+    }
+
+    #[test]
+    fn transforms() {
+        let value = parse(DOC).unwrap().transform(
+            |v| matches!(v, Type::Object(m) if m.contains_key("c")),
+            |_| Type::String("hidden".into()),
+        );
+        assert_eq!(
+            value,
+            parse(r#"{"a": 1.26, "b": [null, 2.5, "hidden"], "e": null}"#).unwrap()
+        );
+        let value = parse(DOC)
+            .unwrap()
+            .transform_at("/b/2/d", |v| match v {
+                Type::String(s) => Type::String(s.trim().into()),
+                v => v,
+            })
+            .unwrap();
+        assert_eq!(value.pointer("/b/2/d"), Some(&Type::String("x".into())));
+        assert_eq!(
+            parse(DOC).unwrap().transform_at("/b/9", |v| v),
+            Err(JsonError::InvalidPath)
+        );
+    }
+
+    #[test]
+    fn retains() {
+        let mut value = parse(r#"[1, 2, 3, 4, {"a": 1, "b": [], "c": 3}]"#).unwrap();
+        value.retain(|segment, _| segment != Segment::Index(1));
+        value.retain(|_, v| *v != Type::Number(3.into()));
+        if let Some(object) = value.pointer_mut("/2") {
+            object.retain(|segment, v| {
+                *v = Type::Boolean(true);
+                segment != Segment::Key("b")
+            });
+        }
+        assert_eq!(value, parse(r#"[1, 4, {"a": true, "c": true}]"#).unwrap());
+        let mut scalar = Type::Number(1.into());
+        scalar.retain(|_, _| false);
+        assert_eq!(scalar, Type::Number(1.into()));
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{into_str, to_string, Map, Type};
+
+/// Compact JSON of `value` cut down for logging, still valid JSON: strings
+/// keep their first `max_string_len` characters, arrays and objects their
+/// first `max_elements` elements or members, and arrays and objects nested
+/// `max_depth` levels deep are replaced whole, each with a marker saying
+/// what was left out, such as `"…(+4321 items)"`.
+///
+/// An object's marker is a last member with the key `…`. Which members an
+/// object keeps follows the map's order.
+pub fn to_string_truncated(
+    value: &Type,
+    max_depth: usize,
+    max_string_len: usize,
+    max_elements: usize,
+) -> String {
+    let limits = Limits {
+        max_depth,
+        max_string_len,
+        max_elements,
+    };
+    to_string(&truncate(value, &limits, 0))
+}
+
+impl Type {
+    /// A preview of the value: each array keeps only its first `n`
+    /// elements and each object its first `n` members, in map order, at
+    /// every depth. Unlike [`to_string_truncated`], nothing marks what was
+    /// left out.
+    pub fn sample(&self, n: usize) -> Type {
+        match self {
+            Type::Array(items) => {
+                Type::Array(items.iter().take(n).map(|item| item.sample(n)).collect())
+            }
+            Type::Object(members) => Type::Object(Box::new(
+                members
+                    .iter()
+                    .take(n)
+                    .map(|(key, member)| (key.clone(), member.sample(n)))
+                    .collect(),
+            )),
+            _ => self.clone(),
+        }
+    }
+}
+
+struct Limits {
+    max_depth: usize,
+    max_string_len: usize,
+    max_elements: usize,
+}
+
+fn truncate(value: &Type, limits: &Limits, depth: usize) -> Type {
+    let marker = |text: String| Type::String(into_str(text));
+    match value {
+        Type::String(s) => match s.char_indices().nth(limits.max_string_len) {
+            Some((end, _)) => {
+                let rest = s[end..].chars().count();
+                marker(format!("{}…(+{} chars)", &s[..end], rest))
+            }
+            None => value.clone(),
+        },
+        Type::Array(items) if depth >= limits.max_depth && !items.is_empty() => {
+            marker(format!("…({} items)", items.len()))
+        }
+        Type::Object(members) if depth >= limits.max_depth && !members.is_empty() => {
+            marker(format!("…({} members)", members.len()))
+        }
+        Type::Array(items) => {
+            let mut out: Vec<Type> = items
+                .iter()
+                .take(limits.max_elements)
+                .map(|item| truncate(item, limits, depth + 1))
+                .collect();
+            if items.len() > limits.max_elements {
+                out.push(marker(format!(
+                    "…(+{} items)",
+                    items.len() - limits.max_elements
+                )));
+            }
+            Type::Array(out)
+        }
+        Type::Object(members) => {
+            let mut out = Map::default();
+            for (key, member) in members.iter().take(limits.max_elements) {
+                out.insert(key.clone(), truncate(member, limits, depth + 1));
+            }
+            if members.len() > limits.max_elements {
+                out.insert(
+                    "…".into(),
+                    marker(format!("+{} members", members.len() - limits.max_elements)),
+                );
+            }
+            Type::Object(Box::new(out))
+        }
+        _ => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_string_truncated;
+    use crate::parse;
+
+    #[test]
+    fn elides_large_content() {
+        let value = parse(r#"[1, "héllo wörld", [2, [3]], {"a": {"b": 1}}, 5, 6]"#).unwrap();
+        assert_eq!(
+            to_string_truncated(&value, 2, 5, 4),
+            r#"[1,"héllo…(+6 chars)",[2,"…(1 items)"],{"a":"…(1 members)"},"…(+2 items)"]"#
+        );
+        assert_eq!(to_string_truncated(&value, 0, 5, 4), r#""…(6 items)""#);
+        assert_eq!(
+            to_string_truncated(&value, 10, 100, 100),
+            crate::to_string(&value)
+        );
+        assert_eq!(
+            to_string_truncated(&parse("[[]]").unwrap(), 1, 1, 1),
+            "[[]]"
+        );
+
+        let value = parse(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap();
+        let out = parse(&to_string_truncated(&value, 1, 10, 1)).unwrap();
+        assert_eq!(out.len(), Some(2));
+        assert_eq!(out.get_str("/…"), Ok("+2 members"));
+    }
+
+    #[test]
+    fn samples() {
+        let value = parse(r#"[[1, 2, 3], {"a": [4, 5, 6]}, 7]"#).unwrap();
+        assert_eq!(
+            value.sample(2),
+            parse(r#"[[1, 2], {"a": [4, 5]}]"#).unwrap()
+        );
+        assert_eq!(value.sample(0), parse("[]").unwrap());
+        assert_eq!(value.sample(3), value);
+        let value = parse(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap();
+        assert_eq!(value.sample(2).len(), Some(2));
+        assert_eq!(
+            parse(r#""long""#).unwrap().sample(1),
+            parse(r#""long""#).unwrap()
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use alloc::vec::Vec;
+
+use crate::builder::TreeBuilder;
+use crate::lexer::{decode_number, decode_string, BOM};
+use crate::parser::Event;
+use crate::simd::{skip_string_body, skip_whitespace};
+use crate::{Expected, JsonError, Type, MAX_DEPTH};
+
+/// Parses JSON this process produced itself, such as a cache file written
+/// by [`to_string`](crate::to_string), faster than [`parse`](crate::parse)
+/// by skipping most of validation: the grammar between tokens is not
+/// checked, nor are numbers beyond what reading them takes, nor anything
+/// after the root value. Nesting is still limited to
+/// [`MAX_DEPTH`](crate::MAX_DEPTH), and a leading UTF-8 byte-order mark is
+/// skipped.
+///
+/// Only for well-formed input. Malformed input never causes undefined
+/// behavior or a panic, but may be read as some other value rather than
+/// rejected; an error is returned only where no value can be read at all.
+pub fn parse_trusted(json: &str) -> Result<Type, JsonError> {
+    let input = json.as_bytes();
+    let mut builder = TreeBuilder::new();
+    // Whether each open container is an object.
+    let mut objects: Vec<bool> = Vec::new();
+    let mut expect_key = false;
+    let mut pos = if input.starts_with(BOM) { BOM.len() } else { 0 };
+    loop {
+        pos = skip_whitespace(input, pos);
+        let Some(&byte) = input.get(pos) else {
+            return Err(JsonError::UnexpectEof { offset: pos });
+        };
+        if matches!(byte, b'{' | b'[') && objects.len() == MAX_DEPTH {
+            return Err(JsonError::LimitExceeded { offset: pos });
+        }
+        let start = pos;
+        let event = match byte {
+            b'{' => {
+                objects.push(true);
+                expect_key = true;
+                pos += 1;
+                Event::StartObject
+            }
+            b'[' => {
+                objects.push(false);
+                pos += 1;
+                Event::StartArray
+            }
+            b'}' | b']' => {
+                objects.pop();
+                pos += 1;
+                if byte == b'}' {
+                    Event::EndObject
+                } else {
+                    Event::EndArray
+                }
+            }
+            b',' => {
+                expect_key = objects.last() == Some(&true);
+                pos += 1;
+                continue;
+            }
+            b':' => {
+                pos += 1;
+                continue;
+            }
+            b'"' => {
+                pos = string_end(input, pos + 1)?;
+                let s = decode_string(&input[start + 1..pos], start + 1)?;
+                pos += 1;
+                if expect_key {
+                    expect_key = false;
+                    Event::Key(s)
+                } else {
+                    Event::String(s)
+                }
+            }
+            b't' | b'n' => {
+                pos += 4;
+                if byte == b't' {
+                    Event::Boolean(true)
+                } else {
+                    Event::Null
+                }
+            }
+            b'f' => {
+                pos += 5;
+                Event::Boolean(false)
+            }
+            b'-' | b'0'..=b'9' => {
+                while input
+                    .get(pos)
+                    .is_some_and(|b| matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+                {
+                    pos += 1;
+                }
+                Event::Number(decode_number(&input[start..pos], start)?)
+            }
+            _ => {
+                return Err(JsonError::UnexpectToken {
+                    offset: pos,
+                    expected: Expected::NONE,
+                })
+            }
+        };
+        if let Some(root) = builder.push(event) {
+            return Ok(root);
+        }
+    }
+}
+
+/// The position of the quote closing a string whose contents start at
+/// `pos`.
+fn string_end(input: &[u8], mut pos: usize) -> Result<usize, JsonError> {
+    loop {
+        pos = skip_string_body(input, pos);
+        match input.get(pos) {
+            Some(b'"') => return Ok(pos),
+            Some(b'\\') => pos += 2,
+            Some(_) => pos += 1,
+            None => {
+                return Err(JsonError::UnexpectEof {
+                    offset: input.len(),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_trusted;
+    use crate::{parse, to_string, to_string_pretty};
+
+    #[test]
+    fn parses_own_output() {
+        let value = parse(
+            r#"{"a": [1, -2.5e-3, 18446744073709551615, true, false, null], "b\"\\": "x\nyé😀", "c": {}, "d": [[], {"e": ""}]}"#,
+        )
+        .unwrap();
+        assert_eq!(parse_trusted(&to_string(&value)).unwrap(), value);
+        assert_eq!(parse_trusted(&to_string_pretty(&value)).unwrap(), value);
+        assert_eq!(parse_trusted(" 3 ").unwrap(), parse("3").unwrap());
+
+        // Malformed input is an error only where nothing can be read.
+        assert!(parse_trusted(r#"{"a": "#).is_err());
+        assert!(parse_trusted(r#"["abc"#).is_err());
+        assert!(parse_trusted("[x]").is_err());
+        assert!(parse_trusted("[tru").is_err());
+        assert_eq!(parse_trusted("[1 2,]").unwrap(), parse("[1, 2]").unwrap());
+    }
+
+    #[test]
+    fn skips_bom() {
+        assert_eq!(
+            parse_trusted("\u{feff}{\"a\": [1]}").unwrap(),
+            parse(r#"{"a": [1]}"#).unwrap()
+        );
+        assert!(parse_trusted("\u{feff}").is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Key, Map, Type};
+
+/// The value at a pointer given to one of the `Type::get_*` methods was
+/// missing or of the wrong type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetError {
+    pub pointer: String,
+    /// What the getter wanted, e.g. `a string`.
+    pub expected: &'static str,
+    /// The kind of value found; `None` if the pointer is malformed or
+    /// addresses nothing.
+    pub found: Option<ValueKind>,
+}
+
+impl fmt::Display for GetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                f,
+                "'{}': expected {}, found {}",
+                self.pointer, self.expected, found
+            ),
+            None => write!(
+                f,
+                "'{}': expected {}, found nothing",
+                self.pointer, self.expected
+            ),
+        }
+    }
+}
+
+impl core::error::Error for GetError {}
+
+/// Which of the JSON types a value is, without its contents; from
+/// [`Type::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl ValueKind {
+    /// The type's name as JSON calls it: `null`, `boolean`, `number`,
+    /// `string`, `array` or `object`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ValueKind::Null => "null",
+            ValueKind::Bool => "boolean",
+            ValueKind::Number => "number",
+            ValueKind::String => "string",
+            ValueKind::Array => "array",
+            ValueKind::Object => "object",
+        }
+    }
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Type {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Type::Null => ValueKind::Null,
+            Type::Boolean(_) => ValueKind::Bool,
+            Type::Number(_) => ValueKind::Number,
+            Type::String(_) => ValueKind::String,
+            Type::Array(_) => ValueKind::Array,
+            Type::Object(_) => ValueKind::Object,
+        }
+    }
+
+    /// The number of bytes in a string, elements in an array or members in
+    /// an object; `None` for the other kinds.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Type::String(s) => Some(s.len()),
+            Type::Array(items) => Some(items.len()),
+            Type::Object(members) => Some(members.len()),
+            _ => None,
+        }
+    }
+
+    /// Whether a string, array or object is empty; `None` for the other
+    /// kinds.
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Type::Null)
+    }
+
+    pub fn is_boolean(&self) -> bool {
+        matches!(self, Type::Boolean(_))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Type::Number(_))
+    }
+
+    /// Whether the value is an integer in the `i64` range.
+    pub fn is_i64(&self) -> bool {
+        matches!(self, Type::Number(n) if n.as_i64().is_some())
+    }
+
+    /// Whether the value is an integer in the `u64` range.
+    pub fn is_u64(&self) -> bool {
+        matches!(self, Type::Number(n) if n.as_u64().is_some())
+    }
+
+    /// Whether the value is a number held as an `f64`, i.e. one that is not
+    /// [an integer](crate::Number::is_integer).
+    pub fn is_f64(&self) -> bool {
+        matches!(self, Type::Number(n) if !n.is_integer())
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, Type::String(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Type::Array(_))
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Type::Object(_))
+    }
+
+    /// The string at the JSON Pointer `pointer`.
+    pub fn get_str(&self, pointer: &str) -> Result<&str, GetError> {
+        self.get_as(pointer, "a string", |value| match value {
+            Type::String(s) => Some(&**s),
+            _ => None,
+        })
+    }
+
+    pub fn get_bool(&self, pointer: &str) -> Result<bool, GetError> {
+        self.get_as(pointer, "a boolean", |value| match value {
+            Type::Boolean(b) => Some(*b),
+            _ => None,
+        })
+    }
+
+    /// The integer at `pointer`; a number outside the `i64` range or with a
+    /// fraction is an error.
+    pub fn get_i64(&self, pointer: &str) -> Result<i64, GetError> {
+        self.get_as(pointer, "an i64 integer", |value| match value {
+            Type::Number(n) => n.as_i64(),
+            _ => None,
+        })
+    }
+
+    pub fn get_u64(&self, pointer: &str) -> Result<u64, GetError> {
+        self.get_as(pointer, "a u64 integer", |value| match value {
+            Type::Number(n) => n.as_u64(),
+            _ => None,
+        })
+    }
+
+    /// The number at `pointer`, as the nearest `f64`.
+    pub fn get_f64(&self, pointer: &str) -> Result<f64, GetError> {
+        self.get_as(pointer, "a number", |value| match value {
+            Type::Number(n) => Some(n.to_f64()),
+            _ => None,
+        })
+    }
+
+    pub fn get_array(&self, pointer: &str) -> Result<&Vec<Type>, GetError> {
+        self.get_as(pointer, "an array", |value| match value {
+            Type::Array(items) => Some(items),
+            _ => None,
+        })
+    }
+
+    pub fn get_object(&self, pointer: &str) -> Result<&Map<Key, Type>, GetError> {
+        self.get_as(pointer, "an object", |value| match value {
+            Type::Object(members) => Some(&**members),
+            _ => None,
+        })
+    }
+
+    fn get_as<'a, T>(
+        &'a self,
+        pointer: &str,
+        expected: &'static str,
+        cast: impl FnOnce(&'a Type) -> Option<T>,
+    ) -> Result<T, GetError> {
+        let value = self.pointer(pointer);
+        let found = value.map(Type::kind);
+        value.and_then(cast).ok_or_else(|| GetError {
+            pointer: pointer.into(),
+            expected,
+            found,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueKind;
+    use crate::{parse, Type};
+
+    #[test]
+    fn gets_typed_values() {
+        let value = parse(
+            r#"{"user": {"name": "Ada", "admin": true, "age": 36, "tags": []}, "score": -1.5}"#,
+        )
+        .unwrap();
+        assert_eq!(value.get_str("/user/name"), Ok("Ada"));
+        assert_eq!(value.get_bool("/user/admin"), Ok(true));
+        assert_eq!(value.get_i64("/user/age"), Ok(36));
+        assert_eq!(value.get_u64("/user/age"), Ok(36));
+        assert_eq!(value.get_f64("/score"), Ok(-1.5));
+        assert!(value.get_array("/user/tags").unwrap().is_empty());
+        assert_eq!(value.get_object("/user").unwrap().len(), 4);
+
+        let err = value.get_str("/user/age").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'/user/age': expected a string, found number"
+        );
+        assert_eq!(
+            value.get_u64("/score").unwrap_err().to_string(),
+            "'/score': expected a u64 integer, found number"
+        );
+        assert_eq!(
+            value.get_bool("/user/missing").unwrap_err().to_string(),
+            "'/user/missing': expected a boolean, found nothing"
+        );
+        assert_eq!(value.get_object("").unwrap().len(), 2);
+        assert_eq!(err.found, Some(ValueKind::Number));
+        assert_eq!(value.kind(), ValueKind::Object);
+        assert_eq!(ValueKind::Bool.to_string(), "boolean");
+    }
+
+    #[test]
+    fn predicates() {
+        let value = parse(r#"[null, true, -1, 18446744073709551615, 0.5, "s", [], {}]"#).unwrap();
+        let Type::Array(items) = &value else {
+            unreachable!()
+        };
+        let checks: [fn(&Type) -> bool; 9] = [
+            Type::is_null,
+            Type::is_boolean,
+            Type::is_i64,
+            Type::is_u64,
+            Type::is_f64,
+            Type::is_string,
+            Type::is_array,
+            Type::is_object,
+            Type::is_number,
+        ];
+        let table: Vec<Vec<bool>> = checks
+            .iter()
+            .map(|check| items.iter().map(check).collect())
+            .collect();
+        let t = true;
+        let f = false;
+        assert_eq!(
+            table,
+            [
+                [t, f, f, f, f, f, f, f],
+                [f, t, f, f, f, f, f, f],
+                [f, f, t, f, f, f, f, f],
+                [f, f, f, t, f, f, f, f],
+                [f, f, f, f, t, f, f, f],
+                [f, f, f, f, f, t, f, f],
+                [f, f, f, f, f, f, t, f],
+                [f, f, f, f, f, f, f, t],
+                [f, f, t, t, t, f, f, f],
+            ]
+        );
+    }
+
+    #[test]
+    fn lengths() {
+        let value = parse(r#"{"s": "héllo", "a": [1, 2], "o": {}, "n": 3}"#).unwrap();
+        assert_eq!(value.len(), Some(4));
+        assert_eq!(value.pointer("/s").unwrap().len(), Some(6));
+        assert_eq!(value.pointer("/a").unwrap().len(), Some(2));
+        assert_eq!(value.pointer("/o").unwrap().is_empty(), Some(true));
+        assert_eq!(value.pointer("/a").unwrap().is_empty(), Some(false));
+        assert_eq!(value.pointer("/n").unwrap().len(), None);
+        assert_eq!(value.pointer("/n").unwrap().is_empty(), None);
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::string::String;
+use core::mem;
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::Type;
+
+/// `s` in Normalization Form C, reusing its allocation when it already is.
+pub(crate) fn nfc(s: String) -> String {
+    if is_nfc(&s) {
+        s
+    } else {
+        s.nfc().collect()
+    }
+}
+
+impl Type {
+    /// Puts every string and object key, at any depth, in Unicode
+    /// Normalization Form C, so that text which differs only in how its
+    /// characters are composed compares equal. Keys that become equal are
+    /// merged, the one iterated last winning.
+    pub fn normalize_unicode(&mut self) {
+        match self {
+            Type::String(s) if !is_nfc(s) => *s = s.nfc().collect(),
+            Type::Array(items) => {
+                for item in items {
+                    item.normalize_unicode();
+                }
+            }
+            Type::Object(members) => {
+                for (key, mut value) in mem::take(&mut **members) {
+                    value.normalize_unicode();
+                    members.insert(nfc(String::from(&*key)).as_str().into(), value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse, parse_with, JsonError, ParseOptions, Type};
+
+    #[test]
+    fn normalizes_values() {
+        let mut value = parse(r#"{"cafe\u0301": ["e\u0301te\u0301", 1], "x": "\u00e9"}"#).unwrap();
+        value.normalize_unicode();
+        assert_eq!(
+            value,
+            parse(r#"{"caf\u00e9": ["\u00e9t\u00e9", 1], "x": "\u00e9"}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_option() {
+        let doc = r#"{"caf\u00e9": 1, "cafe\u0301": 2}"#;
+        let options = ParseOptions {
+            normalize_unicode: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(parse_with(doc, &options), parse(r#"{"caf\u00e9": 2}"#));
+        let Ok(Type::Object(members)) = parse_with(doc, &ParseOptions::default()) else {
+            unreachable!()
+        };
+        assert_eq!(members.len(), 2);
+
+        // The decomposed form is three bytes, the composed one two.
+        let limited = |normalize_unicode| ParseOptions {
+            max_string_len: Some(2),
+            normalize_unicode,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            parse_with(r#""e\u0301""#, &limited(true)),
+            Ok(Type::String("\u{e9}".into()))
+        );
+        assert_eq!(
+            parse_with(r#""e\u0301""#, &limited(false)),
+            Err(JsonError::LimitExceeded { offset: 0 })
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use alloc::string::ToString;
+
+use ::uuid::Uuid;
+
+use crate::{into_str, DecodeError, FromJson, ToJson, Type};
+
+impl Type {
+    /// Parses a string value as a UUID, in the hyphenated, simple, braced or
+    /// URN form. `None` for other values and for strings that are not UUIDs.
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        match self {
+            Type::String(s) => Uuid::parse_str(s).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// The lowercase hyphenated form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+impl From<Uuid> for Type {
+    fn from(uuid: Uuid) -> Self {
+        Type::String(into_str(uuid.hyphenated().to_string()))
+    }
+}
+
+impl ToJson for Uuid {
+    fn to_json(&self) -> Type {
+        Type::from(*self)
+    }
+}
+
+impl FromJson for Uuid {
+    fn from_json(value: &Type) -> Result<Self, DecodeError> {
+        value
+            .as_uuid()
+            .ok_or_else(|| DecodeError::new("expected a UUID"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::uuid::Uuid;
+
+    use crate::{parse, DecodeError, FromJson, ToJson, Type};
+
+    #[test]
+    fn converts_uuids() {
+        let uuid = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+        let value = Type::from(uuid);
+        assert_eq!(
+            value,
+            Type::String("67e55044-10b1-426f-9247-bb680e5fe0c8".into())
+        );
+        assert_eq!(value.as_uuid(), Some(uuid));
+        for text in [
+            r#""67E55044-10B1-426F-9247-BB680E5FE0C8""#,
+            r#""67e5504410b1426f9247bb680e5fe0c8""#,
+            r#""{67e55044-10b1-426f-9247-bb680e5fe0c8}""#,
+            r#""urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8""#,
+        ] {
+            assert_eq!(parse(text).unwrap().as_uuid(), Some(uuid), "{}", text);
+        }
+        assert_eq!(Uuid::from_json(&uuid.to_json()), Ok(uuid));
+        assert_eq!(Type::String("67e55044-10b1".into()).as_uuid(), None);
+        assert_eq!(
+            Uuid::from_json(&Type::Number(1.into())),
+            Err(DecodeError::new("expected a UUID"))
+        );
+    }
+}
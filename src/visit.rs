@@ -0,0 +1,194 @@
+use alloc::vec::Vec;
+
+use crate::{Key, Map, Number, Str, Type};
+
+/// A pass over a [`Type`] tree, driven by [`Type::accept`].
+///
+/// Every callback has a default: leaves do nothing, and containers descend
+/// into their children through [`walk_array`] and [`walk_object`]. Override
+/// the ones a pass cares about; an override that still wants the children
+/// visited calls the matching `walk_` function itself.
+pub trait Visitor {
+    /// Called for every value before dispatching on its kind.
+    fn visit_value(&mut self, value: &Type) {
+        walk_value(self, value)
+    }
+
+    fn visit_null(&mut self) {}
+
+    fn visit_bool(&mut self, _value: bool) {}
+
+    fn visit_number(&mut self, _value: Number) {}
+
+    fn visit_string(&mut self, _value: &str) {}
+
+    fn visit_array(&mut self, items: &[Type]) {
+        walk_array(self, items)
+    }
+
+    fn visit_object(&mut self, members: &Map<Key, Type>) {
+        walk_object(self, members)
+    }
+
+    /// Called for each member of an object, in map order.
+    fn visit_member(&mut self, _key: &str, value: &Type) {
+        self.visit_value(value)
+    }
+}
+
+/// Calls the [`Visitor`] callback for the kind of `value`.
+pub fn walk_value<V: Visitor + ?Sized>(visitor: &mut V, value: &Type) {
+    match value {
+        Type::Null => visitor.visit_null(),
+        Type::Boolean(value) => visitor.visit_bool(*value),
+        Type::Number(value) => visitor.visit_number(*value),
+        Type::String(value) => visitor.visit_string(value),
+        Type::Array(items) => visitor.visit_array(items),
+        Type::Object(members) => visitor.visit_object(members),
+    }
+}
+
+pub fn walk_array<V: Visitor + ?Sized>(visitor: &mut V, items: &[Type]) {
+    for item in items {
+        visitor.visit_value(item);
+    }
+}
+
+pub fn walk_object<V: Visitor + ?Sized>(visitor: &mut V, members: &Map<Key, Type>) {
+    for (key, value) in members {
+        visitor.visit_member(key, value);
+    }
+}
+
+/// Like [`Visitor`], with mutable access to the tree, driven by
+/// [`Type::accept_mut`]. `visit_value_mut` may replace a value outright.
+pub trait VisitorMut {
+    fn visit_value_mut(&mut self, value: &mut Type) {
+        walk_value_mut(self, value)
+    }
+
+    fn visit_null_mut(&mut self) {}
+
+    fn visit_bool_mut(&mut self, _value: &mut bool) {}
+
+    fn visit_number_mut(&mut self, _value: &mut Number) {}
+
+    fn visit_string_mut(&mut self, _value: &mut Str) {}
+
+    fn visit_array_mut(&mut self, items: &mut Vec<Type>) {
+        walk_array_mut(self, items)
+    }
+
+    fn visit_object_mut(&mut self, members: &mut Map<Key, Type>) {
+        walk_object_mut(self, members)
+    }
+
+    fn visit_member_mut(&mut self, _key: &str, value: &mut Type) {
+        self.visit_value_mut(value)
+    }
+}
+
+pub fn walk_value_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value: &mut Type) {
+    match value {
+        Type::Null => visitor.visit_null_mut(),
+        Type::Boolean(value) => visitor.visit_bool_mut(value),
+        Type::Number(value) => visitor.visit_number_mut(value),
+        Type::String(value) => visitor.visit_string_mut(value),
+        Type::Array(items) => visitor.visit_array_mut(items),
+        Type::Object(members) => visitor.visit_object_mut(members),
+    }
+}
+
+pub fn walk_array_mut<V: VisitorMut + ?Sized>(visitor: &mut V, items: &mut [Type]) {
+    for item in items {
+        visitor.visit_value_mut(item);
+    }
+}
+
+pub fn walk_object_mut<V: VisitorMut + ?Sized>(visitor: &mut V, members: &mut Map<Key, Type>) {
+    for (key, value) in members.iter_mut() {
+        visitor.visit_member_mut(key, value);
+    }
+}
+
+impl Type {
+    /// Runs `visitor` over the value and everything inside it.
+    pub fn accept<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_value(self)
+    }
+
+    pub fn accept_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        visitor.visit_value_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_object, Visitor, VisitorMut};
+    use crate::{parse, Key, Map, Number, Str, Type};
+
+    #[derive(Default)]
+    struct Stats {
+        strings: usize,
+        sum: f64,
+        keys: usize,
+        depth: usize,
+        max_depth: usize,
+    }
+
+    impl Visitor for Stats {
+        fn visit_number(&mut self, value: Number) {
+            self.sum += value.to_f64();
+        }
+
+        fn visit_string(&mut self, _value: &str) {
+            self.strings += 1;
+        }
+
+        fn visit_object(&mut self, members: &Map<Key, Type>) {
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+            self.keys += members.len();
+            walk_object(self, members);
+            self.depth -= 1;
+        }
+    }
+
+    #[test]
+    fn visits_every_node() {
+        let value = parse(r#"{"a": [1, "x", {"b": 2, "c": "y"}], "d": {"e": 3}}"#).unwrap();
+        let mut stats = Stats::default();
+        value.accept(&mut stats);
+        assert_eq!(stats.strings, 2);
+        assert_eq!(stats.sum, 6.0);
+        assert_eq!(stats.keys, 5);
+        assert_eq!(stats.max_depth, 2);
+    }
+
+    struct Redact;
+
+    impl VisitorMut for Redact {
+        fn visit_member_mut(&mut self, key: &str, value: &mut Type) {
+            if key == "password" {
+                *value = Type::Null;
+            } else {
+                self.visit_value_mut(value);
+            }
+        }
+
+        fn visit_string_mut(&mut self, value: &mut Str) {
+            value.make_ascii_uppercase();
+        }
+    }
+
+    #[test]
+    fn visits_mutably() {
+        let mut value =
+            parse(r#"[{"user": "ann", "password": "x"}, "b", {"password": {"k": 1}}]"#).unwrap();
+        value.accept_mut(&mut Redact);
+        assert_eq!(
+            value,
+            parse(r#"[{"user": "ANN", "password": null}, "B", {"password": null}]"#).unwrap()
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::lexer::Lexer;
+use crate::ser::SerializeOptions;
+use crate::{into_str, parse_lexer, to_string_with, JsonError, Key, Map, Type};
+
+/// Parses `json` into plain JS objects, arrays and primitives. With
+/// `relaxed`, `//` and `/* */` comments are accepted and ignored. A leading
+/// UTF-8 byte-order mark is skipped. Syntax errors are thrown as a JS
+/// `Error` with the [`JsonError`] message.
+#[wasm_bindgen(js_name = parse)]
+pub fn parse_js(json: &str, relaxed: Option<bool>) -> Result<JsValue, JsError> {
+    let mut lexer = Lexer::new(json.as_bytes()).skip_bom();
+    if relaxed == Some(true) {
+        lexer = lexer.with_comments();
+    }
+    Ok(to_js(&parse_lexer(lexer).map_err(js_error)?))
+}
+
+/// Serializes a JS value as JSON, indenting each level with `indent` if it
+/// is given. `undefined` is written as `null`; functions and symbols are
+/// rejected.
+#[wasm_bindgen]
+pub fn stringify(value: JsValue, indent: Option<String>) -> Result<String, JsError> {
+    let options = SerializeOptions {
+        indent,
+        ..SerializeOptions::default()
+    };
+    Ok(to_string_with(&from_js(&value)?, &options))
+}
+
+/// Parses `json` and returns the value at the JSON Pointer `pointer`, or
+/// `undefined` if there is none.
+#[wasm_bindgen(js_name = getPointer)]
+pub fn get_pointer(json: &str, pointer: &str) -> Result<JsValue, JsError> {
+    let value = crate::parse(json).map_err(js_error)?;
+    Ok(value.pointer(pointer).map_or(JsValue::UNDEFINED, to_js))
+}
+
+fn js_error(err: JsonError) -> JsError {
+    JsError::new(&err.to_string())
+}
+
+fn to_js(value: &Type) -> JsValue {
+    match value {
+        Type::Null => JsValue::NULL,
+        Type::Boolean(b) => JsValue::from_bool(*b),
+        Type::Number(n) => JsValue::from_f64(n.to_f64()),
+        Type::String(s) => JsValue::from_str(s),
+        Type::Array(items) => items.iter().map(to_js).collect::<Array>().into(),
+        Type::Object(members) => {
+            let object = Object::new();
+            for (key, value) in members.iter() {
+                Reflect::set(&object, &JsValue::from_str(key), &to_js(value))
+                    .expect("setting a property on a plain object succeeds");
+            }
+            object.into()
+        }
+    }
+}
+
+fn from_js(value: &JsValue) -> Result<Type, JsError> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(Type::Null);
+    }
+    if let Some(b) = value.as_bool() {
+        return Ok(Type::Boolean(b));
+    }
+    if let Some(n) = value.as_f64() {
+        return Ok(Type::Number(n.into()));
+    }
+    if let Some(s) = value.as_string() {
+        return Ok(Type::String(into_str(s)));
+    }
+    if Array::is_array(value) {
+        let array: &Array = value.unchecked_ref();
+        return array
+            .iter()
+            .map(|item| from_js(&item))
+            .collect::<Result<_, _>>()
+            .map(Type::Array);
+    }
+    if value.is_object() {
+        let mut members = Map::default();
+        let object: &Object = value.unchecked_ref();
+        for key in Object::keys(object).iter() {
+            let member =
+                Reflect::get(value, &key).map_err(|_| JsError::new("unreadable property"))?;
+            members.insert(
+                Key::from(key.as_string().unwrap_or_default()),
+                from_js(&member)?,
+            );
+        }
+        return Ok(Type::Object(Box::new(members)));
+    }
+    Err(JsError::new("value has no JSON representation"))
+}
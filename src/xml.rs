@@ -0,0 +1,588 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{into_str, to_string, JsonPointer, Key, Map, Type, MAX_DEPTH};
+
+/// How [`from_xml`] and [`to_xml`] map XML onto objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlOptions {
+    /// Prepended to an attribute's name to make its key; `"@"` by default.
+    pub attribute_prefix: String,
+    /// The key for the text of an element that also has attributes or
+    /// children; `"#text"` by default.
+    pub text_key: String,
+}
+
+impl Default for XmlOptions {
+    fn default() -> Self {
+        XmlOptions {
+            attribute_prefix: "@".into(),
+            text_key: "#text".into(),
+        }
+    }
+}
+
+/// An error from [`from_xml`] or [`to_xml`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlError {
+    /// The text is not well-formed XML at byte `offset`.
+    Syntax {
+        offset: usize,
+        message: &'static str,
+    },
+    /// The value at `pointer` has no XML form under the options.
+    Unrepresentable {
+        pointer: JsonPointer,
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            XmlError::Syntax { offset, message } => {
+                write!(f, "invalid XML at byte {}: {}", offset, message)
+            }
+            XmlError::Unrepresentable { pointer, message } => {
+                write!(f, "'{}': {}", pointer, message)
+            }
+        }
+    }
+}
+
+impl core::error::Error for XmlError {}
+
+/// Converts an XML document into an object with one member, named after the
+/// root element.
+///
+/// An element becomes an object holding its attributes, under their names
+/// with [`XmlOptions::attribute_prefix`], and its child elements, where a
+/// child name seen more than once holds an array. Its text, trimmed, goes
+/// under [`XmlOptions::text_key`]. An element with neither attributes nor
+/// children is just its text, or `null` when it has none. All values are
+/// strings; comments, processing instructions and the document type are
+/// skipped.
+pub fn from_xml(text: &str, options: &XmlOptions) -> Result<Type, XmlError> {
+    let mut reader = Reader {
+        text,
+        pos: 0,
+        depth: 0,
+        options,
+    };
+    reader.misc()?;
+    if !reader.rest().starts_with('<') {
+        return Err(reader.error("expected the root element"));
+    }
+    let (name, value) = reader.element()?;
+    reader.misc()?;
+    if reader.pos < text.len() {
+        return Err(reader.error("content after the root element"));
+    }
+    let mut root = Map::default();
+    root.insert(name.as_str().into(), value);
+    Ok(Type::Object(Box::new(root)))
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+    /// How many elements enclose the current one.
+    depth: usize,
+    options: &'a XmlOptions,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, message: &'static str) -> XmlError {
+        XmlError::Syntax {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Moves past the next `end`, or fails with `message` if there is none.
+    fn skip_past(&mut self, end: &str, message: &'static str) -> Result<usize, XmlError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                let start = self.pos;
+                self.pos += i + end.len();
+                Ok(start + i)
+            }
+            None => Err(self.error(message)),
+        }
+    }
+
+    /// Skips whitespace, comments, processing instructions and a document
+    /// type declaration, as may surround the root element.
+    fn misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_past("-->", "unterminated comment")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>", "unterminated processing instruction")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                // The internal subset, if any, is bracketed and may hold `>`.
+                let mut depth = 0;
+                let Some(end) = rest.char_indices().find_map(|(i, ch)| {
+                    match ch {
+                        '[' => depth += 1,
+                        ']' => depth -= 1,
+                        '>' if depth == 0 => return Some(i),
+                        _ => {}
+                    }
+                    None
+                }) else {
+                    return Err(self.error("unterminated document type"));
+                };
+                self.pos += end + 1;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|ch: char| ch.is_whitespace() || "/>=".contains(ch))
+            .unwrap_or(rest.len());
+        if !is_name(&rest[..len]) {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].into())
+    }
+
+    /// Decodes character data up to the first of `ends`, resolving
+    /// references.
+    fn chars(&mut self, ends: &[char], out: &mut String) -> Result<(), XmlError> {
+        loop {
+            let rest = self.rest();
+            let len = rest
+                .find(|ch| ch == '&' || ends.contains(&ch))
+                .unwrap_or(rest.len());
+            out.push_str(&rest[..len]);
+            self.pos += len;
+            if !self.rest().starts_with('&') {
+                return Ok(());
+            }
+            let Some(end) = self.rest().find(';') else {
+                return Err(self.error("unterminated reference"));
+            };
+            let reference = &self.rest()[1..end];
+            let ch = match reference {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => reference.strip_prefix('#').and_then(|n| n.parse().ok()),
+                }
+                .and_then(char::from_u32)
+                .filter(|&ch| ch != '\0'),
+            };
+            match ch {
+                Some(ch) => out.push(ch),
+                None => return Err(self.error("unknown reference")),
+            }
+            self.pos += end + 1;
+        }
+    }
+
+    /// Reads the element at `<`, returning its name and value. Elements
+    /// may nest no deeper than [`MAX_DEPTH`].
+    fn element(&mut self) -> Result<(String, Type), XmlError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("elements nested too deep"));
+        }
+        self.depth += 1;
+        let element = self.element_contents()?;
+        self.depth -= 1;
+        Ok(element)
+    }
+
+    fn element_contents(&mut self) -> Result<(String, Type), XmlError> {
+        self.pos += 1;
+        let name = self.name()?;
+        let mut members = Map::default();
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok((name, finish(members, String::new(), self.options)));
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected '='"));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.error("expected a quoted attribute value")),
+            };
+            self.pos += 1;
+            let mut value = String::new();
+            self.chars(&[quote, '<'], &mut value)?;
+            if !self.rest().starts_with(quote) {
+                return Err(self.error("unterminated attribute value"));
+            }
+            self.pos += 1;
+            let key = format!("{}{}", self.options.attribute_prefix, attribute);
+            members.insert(key.as_str().into(), Type::String(into_str(value)));
+        }
+
+        let mut text = String::new();
+        loop {
+            self.chars(&['<'], &mut text)?;
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(self.error("unclosed element"));
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let start = self.pos;
+                if self.name()? != name {
+                    self.pos = start;
+                    return Err(self.error("mismatched end tag"));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error("expected '>'"));
+                }
+                self.pos += 1;
+                return Ok((name, finish(members, text, self.options)));
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "unterminated comment")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                let start = self.pos;
+                let end = self.skip_past("]]>", "unterminated CDATA section")?;
+                text.push_str(&self.text[start..end]);
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>", "unterminated processing instruction")?;
+            } else {
+                let (child, value) = self.element()?;
+                match members.get_mut(child.as_str()) {
+                    Some(Type::Array(items)) => items.push(value),
+                    Some(existing) => {
+                        let first = core::mem::replace(existing, Type::Null);
+                        *existing = Type::Array(alloc::vec![first, value]);
+                    }
+                    None => {
+                        members.insert(child.as_str().into(), value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Builds the value of an element from its attributes and children and
+/// its text.
+fn finish(mut members: Map<Key, Type>, text: String, options: &XmlOptions) -> Type {
+    let text = text.trim();
+    if members.is_empty() {
+        return if text.is_empty() {
+            Type::Null
+        } else {
+            Type::String(text.into())
+        };
+    }
+    if !text.is_empty() {
+        members.insert(options.text_key.as_str().into(), Type::String(text.into()));
+    }
+    Type::Object(Box::new(members))
+}
+
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == ':')
+        && chars.all(|ch| ch.is_alphanumeric() || "-_.:".contains(ch))
+}
+
+/// Converts an object with one member, the root element, back into XML
+/// under the same mapping as [`from_xml`], with members in key order.
+///
+/// An array holds repeated elements of one name; a scalar under the
+/// attribute prefix is an attribute and one under the text key is text.
+/// Keys that are not XML names, arrays nested directly in arrays, and
+/// containers used as attributes or text are `XmlError::Unrepresentable`.
+pub fn to_xml(value: &Type, options: &XmlOptions) -> Result<String, XmlError> {
+    let mut pointer = JsonPointer::root();
+    let unrepresentable = |pointer: &JsonPointer, message| XmlError::Unrepresentable {
+        pointer: pointer.clone(),
+        message,
+    };
+    let root = match value {
+        Type::Object(members) if members.len() == 1 => members.iter().next().unwrap(),
+        _ => {
+            return Err(unrepresentable(
+                &pointer,
+                "an XML document must be an object with one member",
+            ))
+        }
+    };
+    if let Type::Array(_) = root.1 {
+        pointer.push(&**root.0);
+        return Err(unrepresentable(&pointer, "an XML document has one root"));
+    }
+    let mut out = String::new();
+    pointer.push(&**root.0);
+    write_element(&mut out, root.0, root.1, options, &mut pointer)?;
+    Ok(out)
+}
+
+fn scalar_text(value: &Type) -> Option<String> {
+    match value {
+        Type::Null => Some(String::new()),
+        Type::Boolean(b) => Some(b.to_string()),
+        Type::Number(_) => Some(to_string(value)),
+        Type::String(s) => Some(s.as_str().into()),
+        Type::Array(_) | Type::Object(_) => None,
+    }
+}
+
+fn escape(text: &str, out: &mut String) {
+    for ch in text.chars() {
+        match ch {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            ch => out.push(ch),
+        }
+    }
+}
+
+fn write_element(
+    out: &mut String,
+    name: &str,
+    value: &Type,
+    options: &XmlOptions,
+    pointer: &mut JsonPointer,
+) -> Result<(), XmlError> {
+    let unrepresentable = |pointer: &JsonPointer, message| XmlError::Unrepresentable {
+        pointer: pointer.clone(),
+        message,
+    };
+    if !is_name(name) {
+        return Err(unrepresentable(pointer, "not a valid XML name"));
+    }
+    if let Type::Array(items) = value {
+        for (i, item) in items.iter().enumerate() {
+            pointer.push(i.to_string());
+            if let Type::Array(_) = item {
+                return Err(unrepresentable(
+                    pointer,
+                    "an array cannot directly hold an array",
+                ));
+            }
+            write_element(out, name, item, options, pointer)?;
+            pointer.pop();
+        }
+        return Ok(());
+    }
+    out.push('<');
+    out.push_str(name);
+    let Type::Object(members) = value else {
+        if let Type::Null = value {
+            out.push_str("/>");
+        } else {
+            out.push('>');
+            escape(&scalar_text(value).unwrap(), out);
+            out.push_str("</");
+            out.push_str(name);
+            out.push('>');
+        }
+        return Ok(());
+    };
+
+    let mut members: Vec<_> = members.iter().collect();
+    members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    let mut text = None;
+    let mut children = Vec::new();
+    for (key, member) in members {
+        pointer.push(&**key);
+        if **key == *options.text_key {
+            text = Some(scalar_text(member).ok_or_else(|| {
+                unrepresentable(pointer, "text must be a string, number, boolean or null")
+            })?);
+        } else if let Some(attribute) = key.strip_prefix(options.attribute_prefix.as_str()) {
+            let value = scalar_text(member).ok_or_else(|| {
+                unrepresentable(
+                    pointer,
+                    "an attribute must be a string, number, boolean or null",
+                )
+            })?;
+            if !is_name(attribute) {
+                return Err(unrepresentable(pointer, "not a valid XML name"));
+            }
+            out.push(' ');
+            out.push_str(attribute);
+            out.push_str("=\"");
+            escape(&value, out);
+            out.push('"');
+        } else {
+            children.push((key, member));
+        }
+        pointer.pop();
+    }
+    let text = text.filter(|text| !text.is_empty());
+    if children.is_empty() && text.is_none() {
+        out.push_str("/>");
+        return Ok(());
+    }
+    out.push('>');
+    if let Some(text) = text {
+        escape(&text, out);
+    }
+    for (key, member) in children {
+        pointer.push(&**key);
+        write_element(out, key, member, options, pointer)?;
+        pointer.pop();
+    }
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_xml, to_xml, XmlError, XmlOptions};
+    use crate::{parse, MAX_DEPTH};
+
+    #[test]
+    fn reads_xml() {
+        let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE order [<!ELEMENT order ANY>]>
+<!-- an order -->
+<order id="7" status='new'>
+  <item sku="a1">Widget &amp; co</item>
+  <item sku="b2"/>
+  <note><![CDATA[<fragile>]]> &#233;&#x41;</note>
+  <empty></empty>
+  <gift/>
+</order>
+"#;
+        let expected = parse(
+            r##"{"order": {
+                "@id": "7",
+                "@status": "new",
+                "item": [{"@sku": "a1", "#text": "Widget & co"}, {"@sku": "b2"}],
+                "note": "<fragile> éA",
+                "empty": null,
+                "gift": null
+            }}"##,
+        )
+        .unwrap();
+        assert_eq!(from_xml(xml, &XmlOptions::default()), Ok(expected));
+
+        let options = XmlOptions {
+            attribute_prefix: "-".into(),
+            text_key: "$".into(),
+        };
+        assert_eq!(
+            from_xml(r#"<a b="1">x<c/></a>"#, &options),
+            Ok(parse(r#"{"a": {"-b": "1", "$": "x", "c": null}}"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let options = XmlOptions::default();
+        for (xml, offset, message) in [
+            ("", 0, "expected the root element"),
+            ("<a>", 3, "unclosed element"),
+            ("<a></b>", 5, "mismatched end tag"),
+            ("<a x=1/>", 5, "expected a quoted attribute value"),
+            ("<a>&nbsp;</a>", 3, "unknown reference"),
+            ("<a/><b/>", 4, "content after the root element"),
+            ("<1/>", 1, "expected a name"),
+            (
+                &"<a>".repeat(200_000),
+                3 * MAX_DEPTH,
+                "elements nested too deep",
+            ),
+        ] {
+            assert_eq!(
+                from_xml(xml, &options),
+                Err(XmlError::Syntax { offset, message }),
+                "{}",
+                xml
+            );
+        }
+        let deepest = "<a>".repeat(MAX_DEPTH) + &"</a>".repeat(MAX_DEPTH);
+        assert!(from_xml(&deepest, &options).is_ok());
+    }
+
+    #[test]
+    fn writes_xml() {
+        let options = XmlOptions::default();
+        let value = parse(
+            r##"{"order": {"@id": 7, "item": [{"@sku": "a\"1", "#text": "W & co"}, {"@sku": "b2"}], "note": "<x>", "gift": null, "paid": true}}"##,
+        )
+        .unwrap();
+        let xml = to_xml(&value, &options).unwrap();
+        assert_eq!(
+            xml,
+            r#"<order id="7"><gift/><item sku="a&quot;1">W &amp; co</item><item sku="b2"/><note>&lt;x&gt;</note><paid>true</paid></order>"#
+        );
+        assert_eq!(
+            from_xml(&xml, &options),
+            Ok(parse(
+                r##"{"order": {"@id": "7", "item": [{"@sku": "a\"1", "#text": "W & co"}, {"@sku": "b2"}], "note": "<x>", "gift": null, "paid": "true"}}"##
+            )
+            .unwrap())
+        );
+
+        let error = |json: &str| {
+            to_xml(&parse(json).unwrap(), &options)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("[1]"),
+            "'': an XML document must be an object with one member"
+        );
+        assert_eq!(
+            error(r#"{"a": [1, 2]}"#),
+            "'/a': an XML document has one root"
+        );
+        assert_eq!(
+            error(r#"{"a": {"b c": 1}}"#),
+            "'/a/b c': not a valid XML name"
+        );
+        assert_eq!(
+            error(r#"{"a": {"b": [[1]]}}"#),
+            "'/a/b/0': an array cannot directly hold an array"
+        );
+        assert_eq!(
+            error(r#"{"a": {"@b": {}}}"#),
+            "'/a/@b': an attribute must be a string, number, boolean or null"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use yaml_rust2::parser::{EventReceiver, Parser};
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::{Event, ScanError, Yaml, YamlEmitter, YamlLoader};
+
+use crate::{into_str, to_string, Map, Type, MAX_DEPTH};
+
+/// An error from [`from_yaml`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YamlError {
+    /// The line and column of the error, both counted from one, when the
+    /// YAML is malformed there.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "{}:{}: {}", line, column, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+/// How many nodes aliases may add to a document in all, so that a small
+/// document of nested aliases cannot expand to billions of nodes.
+const MAX_ALIASED_NODES: usize = 1 << 20;
+
+/// Parses a YAML document into a value, resolving anchors and aliases.
+/// A document whose aliases add more than 2^20 nodes in all is an error, as
+/// is one nested deeper than [`MAX_DEPTH`](crate::MAX_DEPTH), aliases
+/// expanded.
+///
+/// Mapping keys that are not strings become their compact JSON
+/// serialization, and non-finite floats such as `.inf` become `null`. An
+/// empty document is `null`; a stream of several documents is an error.
+pub fn from_yaml(text: &str) -> Result<Type, YamlError> {
+    let error = |message: &str| YamlError {
+        position: None,
+        message: message.into(),
+    };
+    let scan_error = |err: ScanError| YamlError {
+        // The scanner counts lines from one but columns from zero.
+        position: Some((err.marker().line(), err.marker().col() + 1)),
+        message: err.info().into(),
+    };
+    // The loader copies the node of each alias, so measure first.
+    let mut sizes = NodeSizes::default();
+    Parser::new_from_str(text)
+        .load(&mut sizes, true)
+        .map_err(scan_error)?;
+    if sizes.aliased > MAX_ALIASED_NODES {
+        return Err(error("aliases expand to too many nodes"));
+    }
+    let mut documents = YamlLoader::load_from_str(text).map_err(scan_error)?;
+    match documents.len() {
+        0 => Ok(Type::Null),
+        1 => match documents.pop().unwrap() {
+            Yaml::BadValue => Ok(Type::Null),
+            document => convert(document, 0).map_err(error),
+        },
+        _ => Err(error("expected a single document")),
+    }
+}
+
+/// Counts the nodes aliases add to a stream of parse events.
+#[derive(Default)]
+struct NodeSizes {
+    /// The number of nodes in each anchored node, aliases expanded.
+    anchors: HashMap<usize, usize>,
+    /// The number of nodes so far in each open sequence and mapping, with
+    /// its anchor, or 0 if it has none.
+    open: Vec<(usize, usize)>,
+    aliased: usize,
+}
+
+impl NodeSizes {
+    fn close(&mut self, anchor: usize, size: usize) {
+        if anchor != 0 {
+            self.anchors.insert(anchor, size);
+        }
+        if let Some((_, parent)) = self.open.last_mut() {
+            *parent = parent.saturating_add(size);
+        }
+    }
+}
+
+impl EventReceiver for NodeSizes {
+    fn on_event(&mut self, event: Event) {
+        match event {
+            Event::Scalar(_, _, anchor, _) => self.close(anchor, 1),
+            Event::SequenceStart(anchor, _) | Event::MappingStart(anchor, _) => {
+                self.open.push((anchor, 1));
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                let (anchor, size) = self.open.pop().expect("the parser balances nodes");
+                self.close(anchor, size);
+            }
+            Event::Alias(anchor) => {
+                let size = self.anchors.get(&anchor).copied().unwrap_or(1);
+                self.aliased = self.aliased.saturating_add(size);
+                self.close(0, size);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts a loaded node inside `depth` sequences and mappings. A scalar
+/// whose explicit tag does not match its text, such as `!!int x`, is an
+/// error, as is nesting deeper than [`MAX_DEPTH`].
+fn convert(node: Yaml, depth: usize) -> Result<Type, &'static str> {
+    if matches!(node, Yaml::Array(_) | Yaml::Hash(_)) && depth == MAX_DEPTH {
+        return Err("nesting too deep");
+    }
+    Ok(match node {
+        Yaml::Null => Type::Null,
+        Yaml::Boolean(b) => Type::Boolean(b),
+        Yaml::Integer(n) => Type::Number(n.into()),
+        Yaml::Real(ref text) => match node.as_f64() {
+            Some(n) if n.is_finite() => Type::Number(n.into()),
+            Some(_) => Type::Null,
+            None => Type::String(text.as_str().into()),
+        },
+        Yaml::String(s) => Type::String(into_str(s)),
+        Yaml::Array(items) => Type::Array(
+            items
+                .into_iter()
+                .map(|item| convert(item, depth + 1))
+                .collect::<Result<_, _>>()?,
+        ),
+        Yaml::Hash(entries) => {
+            let mut members = Map::default();
+            for (key, value) in entries {
+                let key = match convert(key, depth + 1)? {
+                    Type::String(key) => key,
+                    key => into_str(to_string(&key)),
+                };
+                members.insert(key.as_str().into(), convert(value, depth + 1)?);
+            }
+            Type::Object(Box::new(members))
+        }
+        Yaml::Alias(_) | Yaml::BadValue => return Err("invalid tagged scalar"),
+    })
+}
+
+/// Writes `value` as a YAML document in block style, with object keys
+/// sorted so that equal values give equal text.
+pub fn to_yaml(value: &Type) -> String {
+    let mut out = String::new();
+    YamlEmitter::new(&mut out)
+        .dump(&to_node(value))
+        .expect("writing to a String cannot fail");
+    // Drop the `---` document start marker the emitter always writes.
+    let mut out = out.split_off(out.find('\n').map_or(0, |i| i + 1));
+    out.push('\n');
+    out
+}
+
+fn to_node(value: &Type) -> Yaml {
+    match value {
+        Type::Null => Yaml::Null,
+        Type::Boolean(b) => Yaml::Boolean(*b),
+        Type::Number(n) => match n.as_i64() {
+            Some(n) => Yaml::Integer(n),
+            None => Yaml::Real(to_string(value)),
+        },
+        Type::String(s) => Yaml::String(s.as_str().into()),
+        Type::Array(items) => Yaml::Array(items.iter().map(to_node).collect()),
+        Type::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            let mut hash = Hash::new();
+            for (key, member) in members {
+                hash.insert(Yaml::String(String::from(&**key)), to_node(member));
+            }
+            Yaml::Hash(hash)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_yaml, to_yaml, YamlError};
+    use crate::{parse, Type, MAX_DEPTH};
+
+    #[test]
+    fn reads_yaml() {
+        let yaml = "
+defaults: &defaults
+  adapter: postgres
+  pool: 5
+development:
+  <<: *defaults
+  database: dev
+ports: [80, 443]
+ratio: 0.5
+flags: {on: yes, off: false, none: ~}
+1: one
+names:
+  - a
+  - 'quoted: text'
+inf: .inf
+";
+        let expected = parse(
+            r#"{
+                "defaults": {"adapter": "postgres", "pool": 5},
+                "development": {"<<": {"adapter": "postgres", "pool": 5}, "database": "dev"},
+                "ports": [80, 443],
+                "ratio": 0.5,
+                "flags": {"on": "yes", "off": false, "none": null},
+                "1": "one",
+                "names": ["a", "quoted: text"],
+                "inf": null
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(from_yaml(yaml), Ok(expected));
+        assert_eq!(from_yaml(""), Ok(Type::Null));
+        assert_eq!(from_yaml("--- 1\n"), Ok(Type::Number(1.into())));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            from_yaml("a: [1, 2\n"),
+            Err(YamlError {
+                position: Some((2, 1)),
+                message: "while parsing a flow sequence, expected ',' or ']'".into()
+            })
+        );
+        assert_eq!(
+            from_yaml("--- 1\n--- 2\n"),
+            Err(YamlError {
+                position: None,
+                message: "expected a single document".into()
+            })
+        );
+        assert_eq!(
+            from_yaml("a: !!int x").unwrap_err().message,
+            "invalid tagged scalar"
+        );
+        assert!(from_yaml("a: *missing").is_err());
+
+        let mut laughs = String::from("a: &a [lol, lol, lol, lol, lol, lol, lol, lol, lol]\n");
+        for (name, prev) in ["ba", "cb", "dc", "ed", "fe", "gf", "hg", "ih"]
+            .iter()
+            .map(|pair| (&pair[..1], &pair[1..]))
+        {
+            let aliases = vec![format!("*{}", prev); 9].join(", ");
+            laughs.push_str(&format!("{}: &{} [{}]\n", name, name, aliases));
+        }
+        assert_eq!(
+            from_yaml(&laughs),
+            Err(YamlError {
+                position: None,
+                message: "aliases expand to too many nodes".into()
+            })
+        );
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert_eq!(
+            from_yaml(&nested(MAX_DEPTH + 1)),
+            Err(YamlError {
+                position: None,
+                message: "nesting too deep".into()
+            })
+        );
+        assert!(from_yaml(&nested(MAX_DEPTH)).is_ok());
+        let few = "a: &a [1, 2]\nb: [*a, *a]\n";
+        assert_eq!(
+            from_yaml(few),
+            Ok(parse(r#"{"a": [1, 2], "b": [[1, 2], [1, 2]]}"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn writes_yaml() {
+        let value = parse(
+            r#"{"name": "svc", "port": 8080, "ratio": 1.5, "tags": ["a", "b: c"], "db": {"host": null, "tls": true}, "empty": []}"#,
+        )
+        .unwrap();
+        let yaml = to_yaml(&value);
+        assert_eq!(
+            yaml,
+            "db:
+  host: ~
+  tls: true
+empty: []
+name: svc
+port: 8080
+ratio: 1.5
+tags:
+  - a
+  - \"b: c\"
+"
+        );
+        assert_eq!(from_yaml(&yaml), Ok(value));
+        assert_eq!(to_yaml(&Type::String("true".into())), "\"true\"\n");
+    }
+}
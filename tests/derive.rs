@@ -0,0 +1,125 @@
+// `#[derive(ToJson, FromJson)]`, which needs the `derive` feature:
+//
+//     cargo test --features derive --test derive
+
+#![cfg(feature = "derive")]
+
+use json_rs::{parse, DecodeError, FromJson, ToJson, Type};
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct User {
+    name: String,
+    #[json(rename = "userAge")]
+    age: u8,
+    email: Option<String>,
+    #[json(default)]
+    roles: Vec<Role>,
+    #[json(default = "default_limit")]
+    limit: u32,
+    #[json(skip)]
+    session: Option<u64>,
+}
+
+fn default_limit() -> u32 {
+    10
+}
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+enum Role {
+    Admin,
+    #[json(rename = "guest")]
+    Guest,
+    Member(u32),
+    Pair(u8, String),
+    Scoped {
+        scope: String,
+    },
+}
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct Id(u64);
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct Point(f64, f64);
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct Marker;
+
+#[derive(Debug, PartialEq, ToJson, FromJson)]
+struct Page<T> {
+    items: Vec<T>,
+}
+
+fn json(text: &str) -> Type {
+    parse(text).unwrap()
+}
+
+#[test]
+fn round_trips_structs_and_enums() {
+    let user = User {
+        name: "Ada".into(),
+        age: 36,
+        email: None,
+        roles: vec![
+            Role::Admin,
+            Role::Guest,
+            Role::Member(7),
+            Role::Pair(1, "x".into()),
+            Role::Scoped {
+                scope: "repo".into(),
+            },
+        ],
+        limit: 5,
+        session: Some(99),
+    };
+    let value = user.to_json();
+    assert_eq!(
+        value,
+        json(
+            r#"{
+                "name": "Ada", "userAge": 36, "email": null, "limit": 5,
+                "roles": ["Admin", "guest", {"Member": 7}, {"Pair": [1, "x"]}, {"Scoped": {"scope": "repo"}}]
+            }"#
+        )
+    );
+    let back = User::from_json(&value).unwrap();
+    assert_eq!(back.session, None);
+    assert_eq!(back.roles, user.roles);
+
+    assert_eq!(Id(3).to_json(), json("3"));
+    assert_eq!(Point::from_json(&json("[1, 2.5]")), Ok(Point(1.0, 2.5)));
+    assert_eq!(Marker::from_json(&Marker.to_json()), Ok(Marker));
+    let page = Page { items: vec![Id(1)] };
+    assert_eq!(Page::<Id>::from_json(&page.to_json()), Ok(page));
+}
+
+#[test]
+fn fills_missing_fields() {
+    let user = User::from_json(&json(r#"{"name": "Bo", "userAge": 1, "extra": true}"#)).unwrap();
+    assert_eq!(user.email, None);
+    assert_eq!(user.roles, []);
+    assert_eq!(user.limit, 10);
+}
+
+#[test]
+fn reports_where_decoding_failed() {
+    let error = |text: &str| User::from_json(&json(text)).unwrap_err().to_string();
+    assert_eq!(error("[]"), "'': expected an object, found array");
+    assert_eq!(error(r#"{"userAge": 1}"#), "'': missing field `name`");
+    assert_eq!(
+        error(r#"{"name": "x", "userAge": 1, "roles": ["Admin", {"Pair": [1, 2]}]}"#),
+        "'/roles/1/Pair/1': expected a string, found number"
+    );
+    assert_eq!(
+        Role::from_json(&json(r#""Owner""#)),
+        Err(DecodeError::new("unknown variant"))
+    );
+    assert_eq!(
+        Point::from_json(&json("[1]")).unwrap_err().to_string(),
+        "'': expected an array of 2 elements"
+    );
+    assert_eq!(
+        Point::from_json(&json("{}")).unwrap_err().to_string(),
+        "'': expected an array of 2 elements, found object"
+    );
+}
@@ -0,0 +1,63 @@
+// Conformance against the JSONTestSuite parsing corpus
+// (https://github.com/nst/JSONTestSuite). Files named `y_*` must be accepted,
+// `n_*` rejected, and `i_*` may go either way but must not panic.
+//
+// `tests/test_parsing` holds a subset of the corpus. Point `JSON_TEST_SUITE`
+// at a checkout's `test_parsing` directory to run the full suite:
+//
+//     JSON_TEST_SUITE=../JSONTestSuite/test_parsing cargo test --test json_test_suite -- --nocapture
+
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+
+use json_rs::parse;
+
+fn corpus_dir() -> PathBuf {
+    env::var_os("JSON_TEST_SUITE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/test_parsing"))
+}
+
+fn accepts(data: &[u8]) -> bool {
+    match std::str::from_utf8(data) {
+        Ok(text) => parse(text).is_ok(),
+        // JSON text must be UTF-8.
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn json_test_suite() {
+    let mut names: Vec<String> = fs::read_dir(corpus_dir())
+        .expect("corpus directory is readable")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".json"))
+        .collect();
+    names.sort();
+
+    let mut failures = Vec::new();
+    for name in &names {
+        let data = fs::read(corpus_dir().join(name)).unwrap();
+        let outcome = match (name.as_bytes()[0], panic::catch_unwind(|| accepts(&data))) {
+            (_, Err(_)) => "PANIC",
+            (b'y', Ok(true)) | (b'n', Ok(false)) => "pass",
+            (b'i', Ok(true)) => "accepted",
+            (b'i', Ok(false)) => "rejected",
+            _ => "FAIL",
+        };
+        println!("{:<8} {}", outcome, name);
+        if outcome == "PANIC" || outcome == "FAIL" {
+            failures.push(name);
+        }
+    }
+    assert!(!names.is_empty(), "no cases in {}", corpus_dir().display());
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases failed: {:?}",
+        failures.len(),
+        names.len(),
+        failures
+    );
+}
@@ -0,0 +1,112 @@
+// No input makes the parsers panic or overflow the stack: truncated and
+// mutated documents, and nesting of any depth, are errors like any other.
+// The targets under `fuzz/` explore further; this is the deterministic
+// part, run with every `cargo test`.
+
+use json_rs::{
+    parse, parse_lenient, parse_slice, parse_trusted, parse_with, validate, JsonError,
+    ParseOptions, PushParser, MAX_DEPTH,
+};
+
+const SEEDS: &[&str] = &[
+    r#"{"a": [1, -2.5e-3, true, false, null], "b": {"c": "x\né😀"}}"#,
+    r#"[[], {}, [{"k": [0, 1e400, -0, 123456789012345678901234567890]}], "\\\"/"]"#,
+    "  \u{feff}\"tail\"  ",
+    "-0.5E+10",
+];
+
+/// Runs every entry point over `json`; any panic fails the test.
+fn check(json: &str) {
+    let parsed = parse(json);
+    assert_eq!(validate(json).is_ok(), parsed.is_ok(), "{:?}", json);
+    assert_eq!(parse_slice(json.as_bytes()), parsed, "{:?}", json);
+    assert_eq!(
+        parse_with(json, &ParseOptions::default()),
+        parsed,
+        "{:?}",
+        json
+    );
+    let (_, errors) = parse_lenient(json);
+    assert_eq!(errors.first(), parsed.as_ref().err(), "{:?}", json);
+    let _ = parse_trusted(json);
+    let mut push = PushParser::new();
+    let (a, b) = json.as_bytes().split_at(json.len() / 2);
+    let pushed = push
+        .feed(a)
+        .and_then(|_| push.feed(b))
+        .and_then(|_| push.finish());
+    assert_eq!(pushed.ok(), parsed.ok(), "{:?}", json);
+}
+
+#[test]
+fn truncated_input() {
+    for case in [
+        "{", "[1,", "-", "[-]", "1e", "1.", "\"\\u12", "\"\\", "{\"a\"", "{\"a\":", "tru", "nul",
+    ] {
+        assert!(parse(case).is_err(), "{:?}", case);
+        check(case);
+    }
+    for seed in SEEDS {
+        for (end, _) in seed.char_indices() {
+            check(&seed[..end]);
+        }
+        check(seed);
+    }
+}
+
+#[test]
+fn mutated_input() {
+    let alphabet = b"{}[],:\"\\-+.eE0123456789tfnul \n\xc3\xa9x";
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..20_000 {
+        let seed = SEEDS[next() as usize % SEEDS.len()];
+        let mut bytes = seed.as_bytes().to_vec();
+        for _ in 0..1 + next() % 4 {
+            let at = next() as usize % (bytes.len() + 1);
+            let byte = alphabet[next() as usize % alphabet.len()];
+            match next() % 3 {
+                0 if at < bytes.len() => bytes[at] = byte,
+                1 if at < bytes.len() => {
+                    bytes.remove(at);
+                }
+                _ => bytes.insert(at, byte),
+            }
+        }
+        match String::from_utf8(bytes) {
+            Ok(json) => check(&json),
+            Err(err) => {
+                assert!(parse_slice(err.as_bytes()).is_err());
+            }
+        }
+    }
+}
+
+#[test]
+fn deep_nesting() {
+    let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+    assert!(parse(&nested(MAX_DEPTH)).is_ok());
+    check(&nested(MAX_DEPTH));
+    let too_deep = Err(JsonError::LimitExceeded { offset: MAX_DEPTH });
+    assert_eq!(parse(&nested(MAX_DEPTH + 1)), too_deep);
+    assert_eq!(
+        parse(&"{\"a\":".repeat(MAX_DEPTH + 1))
+            .unwrap_err()
+            .offset(),
+        Some(5 * MAX_DEPTH)
+    );
+    for json in [
+        nested(1_000_000),
+        "[".repeat(1_000_000),
+        "{\"a\":[".repeat(100_000),
+    ] {
+        check(&json);
+        assert!(parse_trusted(&json).is_err());
+        assert!(parse_lenient(&json).1[0].offset().is_some());
+    }
+}